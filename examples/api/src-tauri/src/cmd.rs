@@ -2,11 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use serde::Deserialize;
-use tauri::command;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Runtime};
+use tauri_plugin_http::{client::Url, Body, HttpExt, HttpRequestBuilder, ResponseData};
 
-#[derive(Debug, Deserialize)]
-#[allow(unused)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct RequestBody {
     id: i32,
     name: String,
@@ -17,8 +17,18 @@ pub fn log_operation(event: String, payload: Option<String>) {
     log::info!("{} {:?}", event, payload);
 }
 
+/// Posts the body to the endpoint with the http plugin, so the request goes through its scope
+/// and default headers like the ones of the frontend.
 #[command]
-pub fn perform_request(endpoint: String, body: RequestBody) -> String {
-    println!("{} {:?}", endpoint, body);
-    "message response".into()
+pub async fn perform_request<R: Runtime>(
+    app: AppHandle<R>,
+    endpoint: Url,
+    body: RequestBody,
+) -> Result<ResponseData, tauri_plugin_http::Error> {
+    let body = serde_json::to_value(body)?;
+    let request = HttpRequestBuilder {
+        body: Some(Body::Json(body)),
+        ..HttpRequestBuilder::new("POST", endpoint)
+    };
+    app.http().request(request).await
 }
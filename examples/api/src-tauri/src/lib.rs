@@ -9,6 +9,8 @@
 
 mod cmd;
 #[cfg(desktop)]
+mod server;
+#[cfg(desktop)]
 mod tray;
 
 use serde::Serialize;
@@ -77,29 +79,7 @@ pub fn run() {
             window.open_devtools();
 
             #[cfg(desktop)]
-            std::thread::spawn(|| {
-                let server = match tiny_http::Server::http("localhost:3003") {
-                    Ok(s) => s,
-                    Err(e) => {
-                        eprintln!("{}", e);
-                        std::process::exit(1);
-                    }
-                };
-                loop {
-                    if let Ok(mut request) = server.recv() {
-                        let mut body = Vec::new();
-                        let _ = request.as_reader().read_to_end(&mut body);
-                        let response = tiny_http::Response::new(
-                            tiny_http::StatusCode(200),
-                            request.headers().to_vec(),
-                            std::io::Cursor::new(body),
-                            request.body_length(),
-                            None,
-                        );
-                        let _ = request.respond(response);
-                    }
-                }
-            });
+            server::start(&app.handle());
 
             Ok(())
         })
@@ -127,6 +107,8 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             cmd::log_operation,
             cmd::perform_request,
+            #[cfg(desktop)]
+            server::server_status,
         ])
         .build(tauri::tauri_build_context!())
         .expect("error while building tauri application");
@@ -136,10 +118,14 @@ pub fn run() {
 
     app.run(move |_app_handle, _event| {
         #[cfg(desktop)]
-        if let RunEvent::ExitRequested { api, .. } = &_event {
-            // Keep the event loop running even if all windows are closed
-            // This allow us to catch system tray events when there is no window
-            api.prevent_exit();
+        match &_event {
+            RunEvent::ExitRequested { api, .. } => {
+                // Keep the event loop running even if all windows are closed
+                // This allow us to catch system tray events when there is no window
+                api.prevent_exit();
+            }
+            RunEvent::Exit => server::shutdown(_app_handle),
+            _ => {}
         }
     })
 }
@@ -0,0 +1,165 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The local server the http and upload examples make their requests to.
+//!
+//! - `/download` serves a payload of [`DOWNLOAD_SIZE`] bytes.
+//! - `/upload` replies with a JSON summary of the received body.
+//! - Any other path mirrors the request: its body and end-to-end headers are sent back.
+
+use std::{
+    io::{self, Read},
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+};
+
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Manager, Runtime, State};
+use tiny_http::{Header, Request, Response, Server};
+
+/// The port of the server, an available one is picked when not set.
+const PORT_ENV: &str = "TAURI_API_SERVER_PORT";
+/// Event emitted to the windows with the [`Status`] of the server once it started.
+const STARTED_EVENT: &str = "api-server://started";
+/// The size of the `/download` payload.
+const DOWNLOAD_SIZE: usize = 1024 * 1024;
+/// The headers that only apply to a single connection, and the ones describing the request
+/// itself, which are not mirrored.
+const SKIPPED_HEADERS: [&str; 11] = [
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "host",
+    "expect",
+    "content-length",
+];
+
+/// Whether the server is listening.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum Status {
+    Listening { url: String },
+    Failed { error: String },
+}
+
+/// The running server, stopped with [`shutdown`].
+pub struct ApiServer {
+    status: Status,
+    server: Option<Arc<Server>>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// Starts the server on the port of the [`PORT_ENV`] variable.
+///
+/// A port that is taken fails the server, not the app.
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    let port = std::env::var(PORT_ENV)
+        .ok()
+        .and_then(|port| port.parse::<u16>().ok())
+        .unwrap_or(0);
+    let (status, server, thread) = match Server::http(("localhost", port)) {
+        Ok(server) => {
+            let server = Arc::new(server);
+            let url = format!("http://localhost:{}", server.server_addr().port());
+            let incoming = server.clone();
+            let thread = std::thread::spawn(move || {
+                // ends once the server is unblocked
+                for request in incoming.incoming_requests() {
+                    if let Err(e) = respond(request) {
+                        eprintln!("failed to respond: {e}");
+                    }
+                }
+            });
+            (Status::Listening { url }, Some(server), Some(thread))
+        }
+        Err(e) => {
+            eprintln!("failed to start the api server on port {port}: {e}");
+            let error = e.to_string();
+            (Status::Failed { error }, None, None)
+        }
+    };
+
+    let _ = app.emit_all(STARTED_EVENT, &status);
+    app.manage(ApiServer {
+        status,
+        server,
+        thread: Mutex::new(thread),
+    });
+}
+
+/// Stops the server, waiting for the request being handled.
+pub fn shutdown<R: Runtime>(app: &AppHandle<R>) {
+    let Some(api_server) = app.try_state::<ApiServer>() else {
+        return;
+    };
+    if let Some(server) = &api_server.server {
+        server.unblock();
+    }
+    if let Some(thread) = api_server.thread.lock().unwrap().take() {
+        let _ = thread.join();
+    }
+}
+
+/// The status of the server, for the windows loaded after it started.
+#[tauri::command]
+pub fn server_status(api_server: State<'_, ApiServer>) -> Status {
+    api_server.status.clone()
+}
+
+fn respond(mut request: Request) -> io::Result<()> {
+    let mut body = Vec::new();
+    request.as_reader().read_to_end(&mut body)?;
+
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    match path.as_str() {
+        "/download" => {
+            let payload = (0..DOWNLOAD_SIZE).map(|i| i as u8).collect::<Vec<_>>();
+            let response = Response::from_data(payload)
+                .with_header(header("Content-Type", "application/octet-stream"));
+            request.respond(response)
+        }
+        "/upload" => {
+            let content_type = request
+                .headers()
+                .iter()
+                .find(|h| h.field.equiv("content-type"))
+                .map(|h| h.value.to_string());
+            let summary = json!({
+                "method": request.method().as_str(),
+                "bytes": body.len(),
+                "contentType": content_type,
+            });
+            let response = Response::from_data(summary.to_string())
+                .with_header(header("Content-Type", "application/json"));
+            request.respond(response)
+        }
+        _ => {
+            let mut response = Response::from_data(body);
+            for h in request.headers() {
+                if !SKIPPED_HEADERS
+                    .iter()
+                    .any(|skipped| h.field.equiv(*skipped))
+                {
+                    response.add_header(h.clone());
+                }
+            }
+            request.respond(response)
+        }
+    }
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field, value).expect("invalid header")
+}
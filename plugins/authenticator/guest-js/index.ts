@@ -2,22 +2,149 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+
 declare global {
   interface Window {
     __TAURI_INVOKE__: <T>(cmd: string, args?: unknown) => Promise<T>;
   }
 }
 
+/**
+ * Whether the device must verify the user, e.g. with a PIN or biometrics, besides a touch.
+ *
+ * The U2F devices can not verify the user, so `required` fails right away
+ * with `UserVerificationUnavailable` and `preferred` only asks for a touch.
+ * The `userPresent` and `userVerified` fields of the results report what the device did.
+ */
+type UserVerification = "discouraged" | "preferred" | "required";
+
+/**
+ * The deadlines of a ceremony, in milliseconds.
+ *
+ * `timeout` is the default of `discoveryTimeout`, itself defaulting to the
+ * timeout set on the plugin builder. `discoveryTimeout` is the time for a
+ * device to be connected, and `userPresenceTimeout` the time for the user to
+ * touch it once connected. Without `userPresenceTimeout`, the touch must happen
+ * before the discovery deadline, so a single timeout covers the whole ceremony.
+ */
+interface Timeouts {
+  timeout?: number;
+  discoveryTimeout?: number;
+  userPresenceTimeout?: number;
+}
+
+/**
+ * The phase a ceremony entered, with its remaining time in milliseconds,
+ * e.g. to show "insert your key" and then "touch your key".
+ */
+type CeremonyPhase =
+  | { phase: "discovery"; timeout: number }
+  | { phase: "userPresence"; timeout: number };
+
+/**
+ * The WebAuthn extensions requested for a ceremony.
+ *
+ * The U2F transports can not carry authenticator extensions, so none is
+ * honored and all are listed in the `unsupportedExtensions` of the result. A
+ * registration requiring `largeBlob` support fails.
+ */
+interface ExtensionInputs {
+  largeBlob?: {
+    support?: "required" | "preferred";
+  };
+  [extension: string]: unknown;
+}
+
+/**
+ * A connected security key.
+ *
+ * `claimable` is `false` when the device can not be opened, e.g. because
+ * another application uses it or the permissions do not allow it.
+ */
+interface DeviceInfo {
+  productName: string | null;
+  manufacturer: string | null;
+  vendorId: number;
+  productId: number;
+  claimable: boolean;
+}
+
+type ErrorKind =
+  | "deviceNotFound"
+  | "timeout"
+  | "badChallenge"
+  | "verificationFailed"
+  | "unknownKeyHandle"
+  | "unsupported"
+  | "io"
+  | "permissionDenied"
+  | "other";
+
+/**
+ * The error the commands reject with.
+ *
+ * @example
+ * ```typescript
+ * try {
+ *   await auth.register(challenge, application);
+ * } catch (e) {
+ *   if ((e as AuthenticatorError).kind === "timeout") {
+ *     // ask the user to touch the key again
+ *   }
+ * }
+ * ```
+ */
+interface AuthenticatorError {
+  kind: ErrorKind;
+  message: string;
+}
+
 export class Authenticator {
+  /**
+   * Starts the USB transports.
+   *
+   * Rejects with the `permissionDenied` kind when a connected security key
+   * can not be opened, e.g. on Linux without a udev rule for its hidraw device.
+   */
   async init(): Promise<void> {
     return await window.__TAURI_INVOKE__("plugin:authenticator|init_auth");
   }
 
-  async register(challenge: string, application: string): Promise<string> {
+  /**
+   * Replaces the hardware transports with a software device derived from `seed`.
+   *
+   * Only available when the plugin is built with the `virtual-authenticator` feature,
+   * meant for tests that can not rely on a physical security key.
+   */
+  async initVirtual(seed: string): Promise<void> {
+    return await window.__TAURI_INVOKE__(
+      "plugin:authenticator|init_virtual_auth",
+      { seed }
+    );
+  }
+
+  /**
+   * Lists the connected security keys without starting a ceremony,
+   * e.g. to disable the register button when none is connected.
+   */
+  async listDevices(): Promise<DeviceInfo[]> {
+    return await window.__TAURI_INVOKE__("plugin:authenticator|list_devices");
+  }
+
+  async register(
+    challenge: string,
+    application: string,
+    userVerification: UserVerification = "preferred",
+    timeouts: Timeouts = {},
+    extensions: ExtensionInputs = {}
+  ): Promise<string> {
     return await window.__TAURI_INVOKE__("plugin:authenticator|register", {
-      timeout: 10000,
+      ...timeouts,
       challenge,
       application,
+      userVerification,
+      extensions,
     });
   }
 
@@ -38,16 +165,27 @@ export class Authenticator {
     );
   }
 
+  /**
+   * Signs with the first of the key handles registered on the connected
+   * device, reported in the `keyHandle` of the signature.
+   *
+   * Rejects when the device accepts none of them.
+   */
   async sign(
     challenge: string,
     application: string,
-    keyHandle: string
+    keyHandles: string | string[],
+    userVerification: UserVerification = "preferred",
+    timeouts: Timeouts = {},
+    extensions: ExtensionInputs = {}
   ): Promise<string> {
     return await window.__TAURI_INVOKE__("plugin:authenticator|sign", {
-      timeout: 10000,
+      ...timeouts,
       challenge,
       application,
-      keyHandle,
+      keyHandles: typeof keyHandles === "string" ? [keyHandles] : keyHandles,
+      userVerification,
+      extensions,
     });
   }
 
@@ -71,4 +209,25 @@ export class Authenticator {
       }
     );
   }
+
+  /**
+   * Listens to the phases of the ceremonies started by this window.
+   */
+  async onPhase(
+    handler: (phase: CeremonyPhase) => void
+  ): Promise<UnlistenFn> {
+    return await listen<CeremonyPhase>("authenticator://phase", (event) =>
+      handler(event.payload)
+    );
+  }
 }
+
+export type {
+  UserVerification,
+  Timeouts,
+  CeremonyPhase,
+  ExtensionInputs,
+  DeviceInfo,
+  ErrorKind,
+  AuthenticatorError,
+};
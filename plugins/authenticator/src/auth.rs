@@ -3,26 +3,69 @@
 // SPDX-License-Identifier: MIT
 
 use authenticator::{
-    authenticatorservice::AuthenticatorService, statecallback::StateCallback,
-    AuthenticatorTransports, KeyHandle, RegisterFlags, SignFlags, StatusUpdate,
+    authenticatorservice::AuthenticatorService,
+    errors::{AuthenticatorError, U2FTokenError},
+    AuthenticatorTransports, KeyHandle, RegisterFlags, SignFlags, U2FManager,
 };
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use once_cell::sync::Lazy;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::io;
-use std::sync::mpsc::channel;
 use std::{convert::Into, sync::Mutex};
 
+use crate::ceremony::{self, Phase, Timeouts};
+use crate::extensions::Extensions;
+use crate::Error;
+
+/// User presence flag of the authenticator data.
+pub(crate) const USER_PRESENT: u8 = 0x01;
+/// User verification flag of the authenticator data.
+pub(crate) const USER_VERIFIED: u8 = 0x04;
+
 static MANAGER: Lazy<Mutex<AuthenticatorService>> = Lazy::new(|| {
     let manager = AuthenticatorService::new().expect("The auth service should initialize safely");
     Mutex::new(manager)
 });
 
-pub fn init_usb() {
-    let mut manager = MANAGER.lock().unwrap();
-    // theres also "add_detected_transports()" in the docs?
-    manager.add_u2f_usb_hid_platform_transports();
+/// Adds the USB HID transports, failing if they can not start or a connected security key
+/// can not be opened for lack of permissions.
+pub fn init_usb() -> crate::Result<()> {
+    // the transports of the service log their errors instead of returning them
+    let transport = U2FManager::new().map_err(Error::UsbInit)?;
+    MANAGER.lock().unwrap().add_transport(Box::new(transport));
+    #[cfg(target_os = "linux")]
+    crate::devices::check_permissions()?;
+    Ok(())
+}
+
+/// Whether the device must verify the user, e.g. with a PIN or biometrics, besides a touch.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UserVerification {
+    /// A touch is enough.
+    Discouraged,
+    /// Verify the user if the device supports it, a touch is enough otherwise.
+    ///
+    /// The U2F devices can not verify the user, so this is the same as `Discouraged`.
+    #[default]
+    Preferred,
+    /// Fail unless the device verified the user, which the U2F devices can not do.
+    Required,
+}
+
+impl UserVerification {
+    /// Fails when the user must be verified, which the U2F devices can not do.
+    ///
+    /// The U2F transports only let the devices verifying the user answer a ceremony with the
+    /// user verification flag, so setting it would wait for no device until the timeout.
+    fn check(self) -> crate::Result<()> {
+        if self == Self::Required {
+            Err(Error::UserVerificationUnavailable)
+        } else {
+            Ok(())
+        }
+    }
 }
 
 #[derive(Serialize, Clone)]
@@ -32,67 +75,53 @@ pub struct Registration {
     pub pubkey: String,
     pub register_data: String,
     pub client_data: String,
+    /// Whether the user touched the device.
+    pub user_present: bool,
+    /// Whether the device verified the user.
+    pub user_verified: bool,
+    /// The requested extensions, none of which is honored by U2F devices.
+    pub unsupported_extensions: Vec<String>,
 }
 
-pub fn register(application: String, timeout: u64, challenge: String) -> crate::Result<String> {
+pub(crate) fn register(
+    application: String,
+    timeouts: Timeouts,
+    challenge: String,
+    user_verification: UserVerification,
+    extensions: Extensions,
+    on_phase: &dyn Fn(Phase),
+) -> crate::Result<String> {
     let (chall_bytes, app_bytes, client_data_string) =
         format_client_data(application.as_str(), challenge.as_str());
 
-    // log the status rx?
-    let (status_tx, _status_rx) = channel::<StatusUpdate>();
-
-    let mut manager = MANAGER.lock().unwrap();
+    user_verification.check()?;
 
-    let (register_tx, register_rx) = channel();
-    let callback = StateCallback::new(Box::new(move |rv| {
-        register_tx.send(rv).unwrap();
-    }));
-
-    let res = manager.register(
+    let (status_tx, callback, progress_rx) = ceremony::channels();
+    // the lock is released while waiting so a passed deadline can cancel the ceremony
+    MANAGER.lock().unwrap().register(
         RegisterFlags::empty(),
-        timeout,
+        timeouts.total_millis(),
         chall_bytes,
         app_bytes,
         vec![],
         status_tx,
         callback,
-    );
-
-    match res {
-        Ok(_r) => {
-            let register_result = register_rx
-                .recv()
-                .expect("Problem receiving, unable to continue");
-
-            if let Err(e) = register_result {
-                return Err(e.into());
-            }
-
-            let (register_data, device_info) = register_result.unwrap(); // error already has been checked
-
-            // println!("Register result: {}", base64::encode(&register_data));
-            println!("Device info: {}", &device_info);
-
-            let (key_handle, public_key) =
-                _u2f_get_key_handle_and_public_key_from_register_response(&register_data).unwrap();
-            let key_handle_base64 = URL_SAFE_NO_PAD.encode(key_handle);
-            let public_key_base64 = URL_SAFE_NO_PAD.encode(public_key);
-            let register_data_base64 = URL_SAFE_NO_PAD.encode(&register_data);
-            println!("Key Handle: {}", &key_handle_base64);
-            println!("Public Key: {}", &public_key_base64);
-
-            // Ok(base64::encode(&register_data))
-            // Ok(key_handle_base64)
-            let res = serde_json::to_string(&Registration {
-                key_handle: key_handle_base64,
-                pubkey: public_key_base64,
-                register_data: register_data_base64,
-                client_data: client_data_string,
-            })?;
-            Ok(res)
-        }
-        Err(e) => Err(e.into()),
-    }
+    )?;
+    let (register_data, _device_info) = ceremony::wait(progress_rx, timeouts, on_phase, cancel)??;
+
+    let (key_handle, public_key) =
+        _u2f_get_key_handle_and_public_key_from_register_response(&register_data).unwrap();
+    let res = serde_json::to_string(&Registration {
+        key_handle: URL_SAFE_NO_PAD.encode(key_handle),
+        pubkey: URL_SAFE_NO_PAD.encode(public_key),
+        register_data: URL_SAFE_NO_PAD.encode(&register_data),
+        client_data: client_data_string,
+        // registering requires a touch, which is all a U2F device checks
+        user_present: true,
+        user_verified: false,
+        unsupported_extensions: extensions.unsupported,
+    })?;
+    Ok(res)
 }
 
 #[derive(Serialize, Clone)]
@@ -100,76 +129,86 @@ pub fn register(application: String, timeout: u64, challenge: String) -> crate::
 pub struct Signature {
     pub key_handle: String,
     pub sign_data: String,
+    /// Whether the user touched the device, as reported in the signed data.
+    pub user_present: bool,
+    /// Whether the device verified the user, as reported in the signed data.
+    pub user_verified: bool,
+    /// The requested extensions, none of which is honored by U2F devices.
+    pub unsupported_extensions: Vec<String>,
+}
+
+impl Signature {
+    pub(crate) fn new(key_handle: &[u8], sign_data: &[u8], extensions: Extensions) -> Self {
+        let flags = sign_data.first().copied().unwrap_or_default();
+        Self {
+            key_handle: URL_SAFE_NO_PAD.encode(key_handle),
+            sign_data: URL_SAFE_NO_PAD.encode(sign_data),
+            user_present: flags & USER_PRESENT != 0,
+            user_verified: flags & USER_VERIFIED != 0,
+            unsupported_extensions: extensions.unsupported,
+        }
+    }
 }
 
-pub fn sign(
+pub(crate) fn sign(
     application: String,
-    timeout: u64,
+    timeouts: Timeouts,
     challenge: String,
-    key_handle: String,
+    key_handles: Vec<String>,
+    user_verification: UserVerification,
+    extensions: Extensions,
+    on_phase: &dyn Fn(Phase),
 ) -> crate::Result<String> {
-    let credential = match URL_SAFE_NO_PAD.decode(key_handle) {
-        Ok(v) => v,
-        Err(e) => {
-            return Err(e.into());
-        }
-    };
-    let key_handle = KeyHandle {
-        credential,
-        transports: AuthenticatorTransports::empty(),
-    };
+    if key_handles.is_empty() {
+        return Err(Error::NoMatchingKeyHandle);
+    }
+    let key_handles = key_handles
+        .into_iter()
+        .map(|key_handle| {
+            Ok(KeyHandle {
+                credential: URL_SAFE_NO_PAD.decode(key_handle)?,
+                transports: AuthenticatorTransports::empty(),
+            })
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
 
     let (chall_bytes, app_bytes, _) = format_client_data(application.as_str(), challenge.as_str());
 
-    let (sign_tx, sign_rx) = channel();
-    let callback = StateCallback::new(Box::new(move |rv| {
-        sign_tx.send(rv).unwrap();
-    }));
-
-    // log the status rx?
-    let (status_tx, _status_rx) = channel::<StatusUpdate>();
+    user_verification.check()?;
 
-    let mut manager = MANAGER.lock().unwrap();
-
-    let res = manager.sign(
+    let (status_tx, callback, progress_rx) = ceremony::channels();
+    // the transports check each handle with a check-only request, then sign with the first
+    // one the device accepts
+    MANAGER.lock().unwrap().sign(
         SignFlags::empty(),
-        timeout,
+        timeouts.total_millis(),
         chall_bytes,
         vec![app_bytes],
-        vec![key_handle],
+        key_handles,
         status_tx,
         callback,
-    );
-    match res {
-        Ok(_v) => {
-            let sign_result = sign_rx
-                .recv()
-                .expect("Problem receiving, unable to continue");
-
-            if let Err(e) = sign_result {
-                return Err(e.into());
+    )?;
+    let (_, handle_used, sign_data, _device_info) =
+        match ceremony::wait(progress_rx, timeouts, on_phase, cancel)? {
+            Ok(signed) => signed,
+            // a device accepting none of the handles blinks until touched, then fails
+            Err(AuthenticatorError::U2FToken(U2FTokenError::InvalidState)) => {
+                return Err(Error::NoMatchingKeyHandle)
             }
+            Err(e) => return Err(e.into()),
+        };
 
-            let (_, handle_used, sign_data, device_info) = sign_result.unwrap();
-
-            let sig = URL_SAFE_NO_PAD.encode(sign_data);
-
-            println!("Sign result: {sig}");
-            println!("Key handle used: {}", URL_SAFE_NO_PAD.encode(&handle_used));
-            println!("Device info: {}", &device_info);
-            println!("Done.");
+    let signature = Signature::new(&handle_used, &sign_data, extensions);
+    let res = serde_json::to_string(&signature)?;
+    Ok(res)
+}
 
-            let res = serde_json::to_string(&Signature {
-                sign_data: sig,
-                key_handle: URL_SAFE_NO_PAD.encode(&handle_used),
-            })?;
-            Ok(res)
-        }
-        Err(e) => Err(e.into()),
-    }
+/// Cancels the running ceremony, releasing the device it is polling.
+pub(crate) fn cancel() {
+    let _ = MANAGER.lock().unwrap().cancel();
 }
 
-fn format_client_data(application: &str, challenge: &str) -> (Vec<u8>, Vec<u8>, String) {
+pub(crate) fn format_client_data(application: &str, challenge: &str) -> (Vec<u8>, Vec<u8>, String) {
     let d =
         format!(r#"{{"challenge": "{challenge}", "version": "U2F_V2", "appId": "{application}"}}"#);
     let mut challenge = Sha256::new();
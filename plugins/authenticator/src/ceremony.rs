@@ -0,0 +1,141 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The two deadlines of a register or sign ceremony: one for a device to be connected,
+//! then one for the user to touch it.
+
+use authenticator::{errors::AuthenticatorError, statecallback::StateCallback, StatusUpdate};
+use serde::Serialize;
+
+use std::{
+    sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::Error;
+
+/// Event emitted to the calling window when the ceremony enters a phase.
+pub const PHASE_EVENT: &str = "authenticator://phase";
+
+/// The phase of a ceremony, with its remaining time in milliseconds.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(tag = "phase", rename_all = "camelCase")]
+pub enum Phase {
+    /// Waiting for a device to be connected.
+    Discovery { timeout: u64 },
+    /// A device is connected, waiting for the user to touch it.
+    UserPresence { timeout: u64 },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Timeouts {
+    pub(crate) discovery: Duration,
+    /// `None` keeps the discovery deadline, so a single timeout covers the whole ceremony.
+    pub(crate) user_presence: Option<Duration>,
+}
+
+impl Timeouts {
+    /// The time both phases may take at most.
+    pub(crate) fn total(&self) -> Duration {
+        self.discovery + self.user_presence.unwrap_or_default()
+    }
+
+    /// The timeout given to the transports, which must outlast both phases.
+    pub(crate) fn total_millis(&self) -> u64 {
+        millis(self.total())
+    }
+}
+
+pub(crate) enum Progress<T> {
+    Status(StatusUpdate),
+    Done(Result<T, AuthenticatorError>),
+}
+
+/// The status sender and the result callback to start a ceremony with,
+/// and the receiver to [`wait`] on.
+pub(crate) fn channels<T: Send + 'static>() -> (
+    Sender<StatusUpdate>,
+    StateCallback<Result<T, AuthenticatorError>>,
+    Receiver<Progress<T>>,
+) {
+    let (progress_tx, progress_rx) = channel();
+
+    let (status_tx, status_rx) = channel::<StatusUpdate>();
+    let forward = progress_tx.clone();
+    thread::spawn(move || {
+        for status in status_rx {
+            if forward.send(Progress::Status(status)).is_err() {
+                break;
+            }
+        }
+    });
+
+    let callback = StateCallback::new(Box::new(move |rv| {
+        let _ = progress_tx.send(Progress::Done(rv));
+    }));
+
+    (status_tx, callback, progress_rx)
+}
+
+/// Waits for the result of a started ceremony, calling `cancel` when a deadline passes.
+pub(crate) fn wait<T>(
+    progress: Receiver<Progress<T>>,
+    timeouts: Timeouts,
+    on_phase: &dyn Fn(Phase),
+    cancel: impl FnOnce(),
+) -> crate::Result<Result<T, AuthenticatorError>> {
+    let mut deadline = Instant::now() + timeouts.discovery;
+    let mut device_present = false;
+    on_phase(Phase::Discovery {
+        timeout: millis(timeouts.discovery),
+    });
+
+    loop {
+        match progress.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(Progress::Status(StatusUpdate::DeviceAvailable { .. })) if !device_present => {
+                device_present = true;
+                if let Some(timeout) = timeouts.user_presence {
+                    deadline = Instant::now() + timeout;
+                }
+                on_phase(Phase::UserPresence {
+                    timeout: millis(deadline.saturating_duration_since(Instant::now())),
+                });
+            }
+            Ok(Progress::Status(_)) => {}
+            Ok(Progress::Done(result)) => return Ok(result),
+            Err(RecvTimeoutError::Timeout) => {
+                cancel();
+                return Err(if device_present {
+                    Error::UserPresenceTimeout
+                } else {
+                    Error::DiscoveryTimeout
+                });
+            }
+            // the transports dropped the callback without calling it
+            Err(RecvTimeoutError::Disconnected) => return Err(Error::CeremonyAborted),
+        }
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dropped_callback_aborts_the_ceremony() {
+        let (progress_tx, progress_rx) = channel::<Progress<()>>();
+        drop(progress_tx);
+        let timeouts = Timeouts {
+            discovery: Duration::from_secs(5),
+            user_presence: None,
+        };
+        let result = wait(progress_rx, timeouts, &|_| {}, || {});
+        assert!(matches!(result, Err(Error::CeremonyAborted)));
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The signature counters of the credentials, checked to detect cloned keys.
+//!
+//! A device increments the counter of a credential on every signature, so a signature whose
+//! counter is not greater than the last one verified comes from another device holding the
+//! same key. Some devices do not implement the counter and always report zero, which is only
+//! accepted with [`CounterStore::allow_zero`].
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::{Error, Result};
+
+/// The last verified signature counter of each credential, indexed by key handle.
+///
+/// Given to [`init_with_counter_store`](crate::init_with_counter_store), the store is managed
+/// by the app and consulted by `verify_signature`. Get it with `app.state::<CounterStore>()`
+/// to back up or restore the counters.
+#[derive(Debug, Default)]
+pub struct CounterStore {
+    counters: Mutex<HashMap<String, u32>>,
+    allow_zero: bool,
+    /// The file the counters are saved to after every change, if any.
+    path: Option<PathBuf>,
+}
+
+impl CounterStore {
+    /// A store kept in memory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A store saved to the JSON file at `path`, loading the counters it holds.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let counters = match fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(Error::CounterStore(e)),
+        };
+        Ok(Self {
+            counters: Mutex::new(counters),
+            allow_zero: false,
+            path: Some(path),
+        })
+    }
+
+    /// Whether to accept the signatures of the devices that always report a zero counter.
+    ///
+    /// The signatures of those devices can not be told apart from the ones of a cloned key.
+    /// A credential that reported a counter once must keep increasing it.
+    pub fn allow_zero(mut self, allow: bool) -> Self {
+        self.allow_zero = allow;
+        self
+    }
+
+    /// The last verified counter of the credential.
+    pub fn get(&self, key_handle: &str) -> Option<u32> {
+        self.counters.lock().unwrap().get(key_handle).copied()
+    }
+
+    /// The counters of all the credentials, e.g. to back them up.
+    pub fn export(&self) -> HashMap<String, u32> {
+        self.counters.lock().unwrap().clone()
+    }
+
+    /// Replaces the counters of all the credentials, e.g. with a backup.
+    pub fn import(&self, counters: HashMap<String, u32>) -> Result<()> {
+        let mut stored = self.counters.lock().unwrap();
+        *stored = counters;
+        self.save(&stored)
+    }
+
+    /// Forgets the counter of a credential, e.g. once it was revoked.
+    pub fn remove(&self, key_handle: &str) -> Result<()> {
+        let mut counters = self.counters.lock().unwrap();
+        if counters.remove(key_handle).is_some() {
+            self.save(&counters)?;
+        }
+        Ok(())
+    }
+
+    /// Records the counter of a verified signature, failing unless it is greater than the
+    /// last one recorded for the credential.
+    pub(crate) fn check(&self, key_handle: &str, counter: u32) -> Result<()> {
+        let mut counters = self.counters.lock().unwrap();
+        let stored = counters.get(key_handle).copied();
+        if counter == 0 && self.allow_zero && stored.unwrap_or_default() == 0 {
+            return Ok(());
+        }
+        if let Some(stored) = stored.filter(|stored| counter <= *stored) {
+            return Err(Error::CounterNotIncreased { stored, counter });
+        }
+        counters.insert(key_handle.into(), counter);
+        self.save(&counters)
+    }
+
+    fn save(&self, counters: &HashMap<String, u32>) -> Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(Error::CounterStore)?;
+            }
+            fs::write(path, serde_json::to_vec(counters)?).map_err(Error::CounterStore)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_counter_not_increased() {
+        let store = CounterStore::new();
+        store.check("key", 1).unwrap();
+        store.check("key", 5).unwrap();
+        assert_eq!(store.get("key"), Some(5));
+        for counter in [5, 4, 0] {
+            assert!(matches!(
+                store.check("key", counter),
+                Err(Error::CounterNotIncreased { stored: 5, .. })
+            ));
+        }
+        // the counters are kept per credential
+        store.check("other", 1).unwrap();
+    }
+
+    #[test]
+    fn zero_allowance() {
+        assert!(CounterStore::new().check("key", 0).is_ok());
+        let store = CounterStore::new();
+        store.check("key", 0).unwrap();
+        assert!(store.check("key", 0).is_err());
+
+        let store = CounterStore::new().allow_zero(true);
+        store.check("key", 0).unwrap();
+        store.check("key", 0).unwrap();
+        assert_eq!(store.get("key"), None);
+        store.check("key", 3).unwrap();
+        assert!(store.check("key", 0).is_err());
+    }
+
+    #[test]
+    fn persists_counters() {
+        let path = std::env::temp_dir()
+            .join(format!("tauri-authenticator-{}", std::process::id()))
+            .join("counters.json");
+        let store = CounterStore::load(&path).unwrap();
+        store.check("key", 7).unwrap();
+        assert_eq!(CounterStore::load(&path).unwrap().get("key"), Some(7));
+
+        store.import(HashMap::from([("other".into(), 2)])).unwrap();
+        let loaded = CounterStore::load(&path).unwrap();
+        assert_eq!(loaded.export(), HashMap::from([("other".into(), 2)]));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
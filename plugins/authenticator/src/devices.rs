@@ -0,0 +1,76 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The connected security keys, listed without starting a ceremony.
+//!
+//! The `authenticator` crate does not expose the devices its transports poll, so the HID
+//! devices are enumerated directly, keeping the ones of the FIDO usage page.
+
+use hidapi::{DeviceInfo as HidDeviceInfo, HidApi};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use std::sync::Mutex;
+
+/// The HID usage page of the FIDO Alliance.
+const FIDO_USAGE_PAGE: u16 = 0xf1d0;
+/// The usage of the U2F HID interface of a device.
+const FIDO_USAGE_U2FHID: u16 = 0x01;
+
+/// The HID library is initialized once per process.
+static HID: Lazy<Mutex<()>> = Lazy::new(Default::default);
+
+/// A connected security key.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceInfo {
+    pub product_name: Option<String>,
+    pub manufacturer: Option<String>,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    /// Whether the device could be opened, i.e. it is not used by another application and
+    /// the permissions allow it. A ceremony waiting for a touch may keep it.
+    pub claimable: bool,
+}
+
+/// Lists the connected security keys, opening each of them briefly to check it is claimable.
+pub(crate) fn list() -> crate::Result<Vec<DeviceInfo>> {
+    let _lock = HID.lock().unwrap();
+    let api = HidApi::new()?;
+    let devices = api
+        .device_list()
+        .filter(|device| is_security_key(device))
+        .map(|device| DeviceInfo {
+            product_name: device.product_string().map(Into::into),
+            manufacturer: device.manufacturer_string().map(Into::into),
+            vendor_id: device.vendor_id(),
+            product_id: device.product_id(),
+            claimable: device.open_device(&api).is_ok(),
+        })
+        .collect();
+    Ok(devices)
+}
+
+/// Fails with [`crate::Error::HidrawPermissionDenied`] if a connected security key can not be
+/// opened for lack of permissions, which the transports would only log.
+#[cfg(target_os = "linux")]
+pub(crate) fn check_permissions() -> crate::Result<()> {
+    let _lock = HID.lock().unwrap();
+    let api = HidApi::new()?;
+    for device in api.device_list().filter(|device| is_security_key(device)) {
+        let path = device.path().to_string_lossy();
+        let opened = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&*path);
+        if matches!(opened, Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied) {
+            return Err(crate::Error::HidrawPermissionDenied(path.into_owned()));
+        }
+    }
+    Ok(())
+}
+
+fn is_security_key(device: &HidDeviceInfo) -> bool {
+    device.usage_page() == FIDO_USAGE_PAGE && device.usage() == FIDO_USAGE_U2FHID
+}
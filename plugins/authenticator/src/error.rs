@@ -2,18 +2,117 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use serde::{Serialize, Serializer};
+use authenticator::errors::{AuthenticatorError, U2FTokenError};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+/// The kind of an [`Error`], serialized with its message so the frontend can handle each kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorKind {
+    /// No device was connected before the discovery timeout.
+    DeviceNotFound,
+    /// The device was not touched in time, or the ceremony did not complete in time.
+    Timeout,
+    /// The challenge is not valid base64url.
+    BadChallenge,
+    /// The registration or the signature does not verify, or the signature counter did not
+    /// increase.
+    VerificationFailed,
+    /// The device accepted none of the key handles.
+    UnknownKeyHandle,
+    /// The device can not verify the user or honor a required extension.
+    Unsupported,
+    /// The device could not be reached.
+    Io,
+    /// The device can not be opened, e.g. without a udev rule on Linux.
+    PermissionDenied,
+    /// Any other failure, e.g. a malformed argument.
+    Other,
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error(transparent)]
     Base64Decode(#[from] base64::DecodeError),
+    #[error("invalid challenge: {0}")]
+    BadChallenge(base64::DecodeError),
     #[error(transparent)]
     JSON(#[from] serde_json::Error),
     #[error(transparent)]
     U2F(#[from] u2f::u2ferror::U2fError),
     #[error(transparent)]
-    Auth(#[from] authenticator::errors::AuthenticatorError),
+    Auth(#[from] AuthenticatorError),
+    #[error(transparent)]
+    Hid(#[from] hidapi::HidError),
+    #[error("no device was connected before the discovery timeout")]
+    DiscoveryTimeout,
+    #[error("the device was not touched before the user presence timeout")]
+    UserPresenceTimeout,
+    /// The transports stopped without reporting a result.
+    #[error("the ceremony ended without a result from the device")]
+    CeremonyAborted,
+    /// The device kept the ceremony blocked past its timeout.
+    #[error("the ceremony did not complete before the timeout")]
+    Timeout,
+    #[error(transparent)]
+    Tauri(#[from] tauri::Error),
+    /// The device accepted none of the key handles.
+    #[error("none of the key handles was registered on the device")]
+    NoMatchingKeyHandle,
+    #[error("user verification is required but no device verified the user")]
+    UserVerificationUnavailable,
+    #[error("the `{0}` extension is required but not supported by the device")]
+    ExtensionUnsupported(String),
+    /// The signature may come from a cloned key.
+    #[error("the signature counter {counter} did not increase past {stored}")]
+    CounterNotIncreased { stored: u32, counter: u32 },
+    #[error("failed to save the signature counters: {0}")]
+    CounterStore(std::io::Error),
+    #[error("failed to start the USB transports: {0}")]
+    UsbInit(std::io::Error),
+    /// The hidraw device of a security key can not be opened by the user.
+    #[error(
+        "permission denied on {0}, add a udev rule giving access to the security keys, e.g. \
+         `KERNEL==\"hidraw*\", SUBSYSTEM==\"hidraw\", MODE=\"0660\", TAG+=\"uaccess\"`"
+    )]
+    HidrawPermissionDenied(String),
+    /// The application is not one of the allowed facets of the plugin builder.
+    #[error("the application `{0}` is not an allowed facet")]
+    FacetNotAllowed(String),
+    #[cfg(feature = "virtual-authenticator")]
+    #[error("the key handle was not issued by the virtual authenticator for this application")]
+    UnknownKeyHandle,
+}
+
+impl Error {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::DiscoveryTimeout => ErrorKind::DeviceNotFound,
+            // the transports give up with `NotAllowed` once their timeout passed
+            Self::UserPresenceTimeout
+            | Self::Timeout
+            | Self::Auth(AuthenticatorError::U2FToken(U2FTokenError::NotAllowed)) => {
+                ErrorKind::Timeout
+            }
+            Self::BadChallenge(_) => ErrorKind::BadChallenge,
+            Self::U2F(_) | Self::CounterNotIncreased { .. } => ErrorKind::VerificationFailed,
+            Self::NoMatchingKeyHandle => ErrorKind::UnknownKeyHandle,
+            #[cfg(feature = "virtual-authenticator")]
+            Self::UnknownKeyHandle => ErrorKind::UnknownKeyHandle,
+            Self::UserVerificationUnavailable | Self::ExtensionUnsupported(_) => {
+                ErrorKind::Unsupported
+            }
+            Self::Auth(_) | Self::Hid(_) | Self::UsbInit(_) | Self::CeremonyAborted => {
+                ErrorKind::Io
+            }
+            Self::HidrawPermissionDenied(_) => ErrorKind::PermissionDenied,
+            Self::Base64Decode(_)
+            | Self::JSON(_)
+            | Self::Tauri(_)
+            | Self::CounterStore(_)
+            | Self::FacetNotAllowed(_) => ErrorKind::Other,
+        }
+    }
 }
 
 impl Serialize for Error {
@@ -21,6 +120,9 @@ impl Serialize for Error {
     where
         S: Serializer,
     {
-        serializer.serialize_str(self.to_string().as_ref())
+        let mut error = serializer.serialize_struct("Error", 2)?;
+        error.serialize_field("kind", &self.kind())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
     }
 }
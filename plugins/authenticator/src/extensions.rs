@@ -0,0 +1,115 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The WebAuthn extensions of the ceremonies.
+//!
+//! The U2F transports can not carry authenticator extensions, so none is honored and no
+//! result is reported for them: every requested extension is listed as unsupported, like a
+//! browser does for the extensions it does not know. A registration that requires `largeBlob`
+//! support fails, since U2F devices can not store blobs.
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use std::collections::BTreeMap;
+
+use crate::Error;
+
+/// The requested extensions, as in the `extensions` of the WebAuthn request options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionInputs {
+    pub large_blob: Option<LargeBlobInputs>,
+    /// The other extensions, by identifier.
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+/// The inputs of the `largeBlob` extension.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LargeBlobInputs {
+    /// Whether the device must be able to store blobs, when registering.
+    pub support: Option<LargeBlobSupport>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LargeBlobSupport {
+    /// Fail the registration unless the device can store blobs.
+    Required,
+    /// Register the device even if it can not store blobs.
+    Preferred,
+}
+
+/// The outcome of the requested extensions.
+#[derive(Debug, Default, Clone)]
+pub struct Extensions {
+    /// The identifiers of the requested extensions, none of which is honored.
+    pub(crate) unsupported: Vec<String>,
+}
+
+impl ExtensionInputs {
+    /// Processes the extensions of a registration, before the ceremony so a required
+    /// extension fails without asking for a touch.
+    pub(crate) fn registration(self) -> crate::Result<Extensions> {
+        if let Some(LargeBlobSupport::Required) = self.large_blob.as_ref().and_then(|l| l.support) {
+            return Err(Error::ExtensionUnsupported("largeBlob".into()));
+        }
+        Ok(self.unsupported())
+    }
+
+    /// Processes the extensions of a signature.
+    pub(crate) fn authentication(self) -> Extensions {
+        self.unsupported()
+    }
+
+    fn unsupported(self) -> Extensions {
+        let mut unsupported: Vec<String> = self.other.into_keys().collect();
+        if self.large_blob.is_some() {
+            unsupported.push("largeBlob".into());
+        }
+        unsupported.sort();
+        Extensions { unsupported }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(value: Value) -> ExtensionInputs {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn registration_reports_all_extensions_unsupported() {
+        let extensions = inputs(serde_json::json!({
+            "credProps": true,
+            "largeBlob": { "support": "preferred" },
+            "hmacCreateSecret": true
+        }))
+        .registration()
+        .unwrap();
+        assert_eq!(
+            extensions.unsupported,
+            vec!["credProps", "hmacCreateSecret", "largeBlob"]
+        );
+    }
+
+    #[test]
+    fn registration_fails_on_required_large_blob() {
+        assert!(matches!(
+            inputs(serde_json::json!({ "largeBlob": { "support": "required" } })).registration(),
+            Err(Error::ExtensionUnsupported(_))
+        ));
+    }
+
+    #[test]
+    fn authentication_reports_all_extensions_unsupported() {
+        let extensions =
+            inputs(serde_json::json!({ "largeBlob": { "write": "AAEC" } })).authentication();
+        assert_eq!(extensions.unsupported, vec!["largeBlob"]);
+    }
+}
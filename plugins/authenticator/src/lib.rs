@@ -5,25 +5,152 @@
 #![cfg(not(any(target_os = "android", target_os = "ios")))]
 
 mod auth;
+mod ceremony;
+mod counter;
+mod devices;
 mod error;
+mod extensions;
 mod u2f;
+#[cfg(feature = "virtual-authenticator")]
+mod virtual_device;
 
 use tauri::{
     plugin::{Builder as PluginBuilder, TauriPlugin},
-    Runtime,
+    AppHandle, Manager, Runtime, State, Window,
 };
 
-pub use error::Error;
+use std::time::Duration;
+
+use ceremony::Timeouts;
+
+pub use auth::UserVerification;
+pub use ceremony::{Phase, PHASE_EVENT};
+pub use counter::CounterStore;
+pub use devices::DeviceInfo;
+pub use error::{Error, ErrorKind};
+pub use extensions::{ExtensionInputs, LargeBlobInputs, LargeBlobSupport};
 type Result<T> = std::result::Result<T, Error>;
 
+/// The timeout of the ceremonies that set none, in milliseconds.
+const DEFAULT_TIMEOUT: u64 = 10_000;
+
+/// The settings of the [`Builder`], managed by the app.
+struct Settings {
+    default_timeout: u64,
+    allowed_facets: Option<Vec<String>>,
+}
+
+impl Settings {
+    /// Rejects the applications missing from the allowed facets.
+    fn check_facet(&self, application: &str) -> Result<()> {
+        match &self.allowed_facets {
+            Some(facets) if !facets.iter().any(|facet| facet == application) => {
+                Err(Error::FacetNotAllowed(application.into()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The split timeouts in milliseconds, or the single `timeout` for the whole ceremony.
+fn timeouts(
+    timeout: u64,
+    discovery_timeout: Option<u64>,
+    user_presence_timeout: Option<u64>,
+) -> Timeouts {
+    Timeouts {
+        discovery: Duration::from_millis(discovery_timeout.unwrap_or(timeout)),
+        user_presence: user_presence_timeout.map(Duration::from_millis),
+    }
+}
+
+fn emit_phase<R: Runtime>(window: &Window<R>) -> impl Fn(Phase) + '_ {
+    move |phase| {
+        let _ = window.emit(PHASE_EVENT, phase);
+    }
+}
+
+/// How long a ceremony may outlast its timeouts before it is abandoned.
+const CANCEL_GRACE: Duration = Duration::from_secs(1);
+
+/// Runs a blocking ceremony on a thread of its own, failing with [`Error::Timeout`] once
+/// its timeouts and the [`CANCEL_GRACE`] passed.
+///
+/// The ceremony enforces its deadlines itself, but polling a device may block past them.
+/// The transports are then cancelled, so the device is released for the next ceremony.
+async fn run_ceremony<T: Send + 'static>(
+    timeouts: Timeouts,
+    ceremony: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    let task = tauri::async_runtime::spawn_blocking(ceremony);
+    match tokio::time::timeout(timeouts.total() + CANCEL_GRACE, task).await {
+        Ok(result) => result?,
+        Err(_) => {
+            auth::cancel();
+            Err(Error::Timeout)
+        }
+    }
+}
+
+/// Starts the USB transports, failing if a connected security key can not be opened.
+#[tauri::command]
+async fn init_auth() -> Result<()> {
+    tauri::async_runtime::spawn_blocking(auth::init_usb).await?
+}
+
+/// Replaces the USB transports with a software device derived from `seed`.
+#[cfg(feature = "virtual-authenticator")]
+#[tauri::command]
+fn init_virtual_auth(seed: String) {
+    virtual_device::DEVICE
+        .lock()
+        .unwrap()
+        .replace(virtual_device::VirtualAuthenticator::new(&seed));
+}
+
+/// Lists the connected security keys without starting a ceremony, or the virtual device if selected.
 #[tauri::command]
-fn init_auth() {
-    auth::init_usb();
+async fn list_devices() -> Result<Vec<DeviceInfo>> {
+    #[cfg(feature = "virtual-authenticator")]
+    if let Some(device) = virtual_device::DEVICE.lock().unwrap().as_ref() {
+        return Ok(vec![device.device_info()]);
+    }
+    tauri::async_runtime::spawn_blocking(devices::list).await?
 }
 
 #[tauri::command]
-fn register(timeout: u64, challenge: String, application: String) -> crate::Result<String> {
-    auth::register(application, timeout, challenge)
+#[allow(clippy::too_many_arguments)]
+async fn register<R: Runtime>(
+    window: Window<R>,
+    settings: State<'_, Settings>,
+    timeout: Option<u64>,
+    discovery_timeout: Option<u64>,
+    user_presence_timeout: Option<u64>,
+    challenge: String,
+    application: String,
+    user_verification: Option<UserVerification>,
+    extensions: Option<ExtensionInputs>,
+) -> crate::Result<String> {
+    settings.check_facet(&application)?;
+    let user_verification = user_verification.unwrap_or_default();
+    let extensions = extensions.unwrap_or_default().registration()?;
+    #[cfg(feature = "virtual-authenticator")]
+    if let Some(device) = virtual_device::DEVICE.lock().unwrap().as_mut() {
+        return device.register(application, challenge, user_verification, extensions);
+    }
+    let timeout = timeout.unwrap_or(settings.default_timeout);
+    let timeouts = timeouts(timeout, discovery_timeout, user_presence_timeout);
+    run_ceremony(timeouts, move || {
+        auth::register(
+            application,
+            timeouts,
+            challenge,
+            user_verification,
+            extensions,
+            &emit_phase(&window),
+        )
+    })
+    .await
 }
 
 #[tauri::command]
@@ -36,18 +163,55 @@ fn verify_registration(
     u2f::verify_registration(application, challenge, register_data, client_data)
 }
 
+/// Signs with the first of the key handles registered on the connected device,
+/// reported in the `keyHandle` of the signature.
 #[tauri::command]
-fn sign(
-    timeout: u64,
+#[allow(clippy::too_many_arguments)]
+async fn sign<R: Runtime>(
+    window: Window<R>,
+    settings: State<'_, Settings>,
+    timeout: Option<u64>,
+    discovery_timeout: Option<u64>,
+    user_presence_timeout: Option<u64>,
     challenge: String,
     application: String,
-    key_handle: String,
+    key_handles: Vec<String>,
+    user_verification: Option<UserVerification>,
+    extensions: Option<ExtensionInputs>,
 ) -> crate::Result<String> {
-    auth::sign(application, timeout, challenge, key_handle)
+    settings.check_facet(&application)?;
+    let user_verification = user_verification.unwrap_or_default();
+    let extensions = extensions.unwrap_or_default().authentication();
+    #[cfg(feature = "virtual-authenticator")]
+    if let Some(device) = virtual_device::DEVICE.lock().unwrap().as_mut() {
+        return device.sign(
+            application,
+            challenge,
+            key_handles,
+            user_verification,
+            extensions,
+        );
+    }
+    let timeout = timeout.unwrap_or(settings.default_timeout);
+    let timeouts = timeouts(timeout, discovery_timeout, user_presence_timeout);
+    run_ceremony(timeouts, move || {
+        auth::sign(
+            application,
+            timeouts,
+            challenge,
+            key_handles,
+            user_verification,
+            extensions,
+            &emit_phase(&window),
+        )
+    })
+    .await
 }
 
+/// Verifies the signature, recording its counter in the [`CounterStore`] if the app has one.
 #[tauri::command]
-fn verify_signature(
+fn verify_signature<R: Runtime>(
+    app: AppHandle<R>,
     challenge: String,
     application: String,
     sign_data: String,
@@ -55,25 +219,95 @@ fn verify_signature(
     key_handle: String,
     pubkey: String,
 ) -> crate::Result<u32> {
-    u2f::verify_signature(
+    let counter = u2f::verify_signature(
         application,
         challenge,
         sign_data,
         client_data,
-        key_handle,
+        key_handle.clone(),
         pubkey,
-    )
+    )?;
+    if let Some(counters) = app.try_state::<CounterStore>() {
+        counters.check(&key_handle, counter)?;
+    }
+    Ok(counter)
 }
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    PluginBuilder::new("authenticator")
-        .js_init_script(include_str!("api-iife.js").to_string())
-        .invoke_handler(tauri::generate_handler![
-            init_auth,
-            register,
-            verify_registration,
-            sign,
-            verify_signature
-        ])
-        .build()
+    Builder::new().build()
+}
+
+/// Initializes the plugin with a store of the signature counters, so `verify_signature`
+/// rejects the signatures whose counter did not increase.
+pub fn init_with_counter_store<R: Runtime>(counters: CounterStore) -> TauriPlugin<R> {
+    Builder::new().counter_store(counters).build()
+}
+
+/// The authenticator plugin builder.
+pub struct Builder {
+    default_timeout: u64,
+    allowed_facets: Option<Vec<String>>,
+    counters: Option<CounterStore>,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            default_timeout: DEFAULT_TIMEOUT,
+            allowed_facets: None,
+            counters: None,
+        }
+    }
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the timeout in milliseconds of the ceremonies that set none, 10 seconds by default.
+    pub fn default_timeout(mut self, timeout: u64) -> Self {
+        self.default_timeout = timeout;
+        self
+    }
+
+    /// Restricts the `application` of the registrations and signatures to the given AppIDs,
+    /// failing the others with [`Error::FacetNotAllowed`]. All of them are allowed by default.
+    pub fn allowed_facets(mut self, facets: Vec<String>) -> Self {
+        self.allowed_facets = Some(facets);
+        self
+    }
+
+    /// Sets the store of the signature counters, so `verify_signature` rejects the signatures
+    /// whose counter did not increase.
+    pub fn counter_store(mut self, counters: CounterStore) -> Self {
+        self.counters = Some(counters);
+        self
+    }
+
+    pub fn build<R: Runtime>(self) -> TauriPlugin<R> {
+        PluginBuilder::new("authenticator")
+            .js_init_script(include_str!("api-iife.js").to_string())
+            .invoke_handler(tauri::generate_handler![
+                init_auth,
+                #[cfg(feature = "virtual-authenticator")]
+                init_virtual_auth,
+                list_devices,
+                register,
+                verify_registration,
+                sign,
+                verify_signature
+            ])
+            .setup(move |app, _api| {
+                app.manage(Settings {
+                    default_timeout: self.default_timeout,
+                    allowed_facets: self.allowed_facets,
+                });
+                if let Some(counters) = self.counters {
+                    app.manage(counters);
+                }
+                Ok(())
+            })
+            .build()
+    }
 }
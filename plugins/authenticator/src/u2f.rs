@@ -35,7 +35,9 @@ pub fn verify_registration(
     register_data: String,
     client_data: String,
 ) -> crate::Result<String> {
-    let challenge_bytes = URL_SAFE_NO_PAD.decode(challenge)?;
+    let challenge_bytes = URL_SAFE_NO_PAD
+        .decode(challenge)
+        .map_err(crate::Error::BadChallenge)?;
     let challenge = make_challenge(&app_id, challenge_bytes);
     let client_data_bytes: Vec<u8> = client_data.as_bytes().into();
     let client_data_base64 = URL_SAFE_NO_PAD.encode(client_data_bytes);
@@ -74,7 +76,9 @@ pub fn verify_signature(
     key_handle: String,
     pub_key: String,
 ) -> crate::Result<u32> {
-    let challenge_bytes = URL_SAFE_NO_PAD.decode(challenge)?;
+    let challenge_bytes = URL_SAFE_NO_PAD
+        .decode(challenge)
+        .map_err(crate::Error::BadChallenge)?;
     let chal = make_challenge(&app_id, challenge_bytes);
     let client_data_bytes: Vec<u8> = client_data.as_bytes().into();
     let client_data_base64 = URL_SAFE_NO_PAD.encode(client_data_bytes);
@@ -0,0 +1,395 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! A software U2F device for testing without hardware.
+//!
+//! Credentials are derived from the seed, so the same seed always produces the same
+//! key handles and keys for a given application and challenge. Key handles wrap the
+//! credential like most hardware keys do: no state besides the signature counter is kept.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use p256::ecdsa::{signature::Signer, Signature as EcdsaSignature, SigningKey};
+use sha2::{Digest, Sha256};
+
+use std::sync::Mutex;
+
+use crate::{
+    auth::{format_client_data, Registration, Signature, UserVerification, USER_PRESENT},
+    devices::DeviceInfo,
+    extensions::Extensions,
+    Error,
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Self-signed attestation certificate shared by every virtual device.
+static ATTESTATION_CERT: &[u8] = include_bytes!("./virtual_device/attestation.der");
+/// Raw P-256 scalar of the attestation certificate key.
+static ATTESTATION_KEY: &[u8] = include_bytes!("./virtual_device/attestation.key");
+
+/// The virtual device used instead of the USB transports, if selected.
+pub static DEVICE: Lazy<Mutex<Option<VirtualAuthenticator>>> = Lazy::new(Default::default);
+
+pub struct VirtualAuthenticator {
+    seed: [u8; 32],
+    counter: u32,
+}
+
+impl VirtualAuthenticator {
+    pub fn new(seed: &str) -> Self {
+        Self {
+            seed: Sha256::digest(seed.as_bytes()).into(),
+            counter: 0,
+        }
+    }
+
+    /// The device listed by `list_devices` while the virtual device is selected.
+    pub fn device_info(&self) -> DeviceInfo {
+        DeviceInfo {
+            product_name: Some("Virtual authenticator".into()),
+            manufacturer: None,
+            vendor_id: 0,
+            product_id: 0,
+            claimable: true,
+        }
+    }
+
+    fn mac(&self, parts: &[&[u8]]) -> Vec<u8> {
+        let mut mac = HmacSha256::new_from_slice(&self.seed).expect("HMAC accepts any key size");
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    fn credential_key(&self, app_bytes: &[u8], nonce: &[u8]) -> crate::Result<SigningKey> {
+        SigningKey::from_slice(&self.mac(&[b"key", app_bytes, nonce]))
+            .map_err(|_| Error::UnknownKeyHandle)
+    }
+
+    /// Whether the key handle was issued by this device for the application.
+    fn is_issued(&self, app_bytes: &[u8], key_handle: &[u8]) -> bool {
+        if key_handle.len() != 64 {
+            return false;
+        }
+        let (nonce, tag) = key_handle.split_at(32);
+        self.mac(&[b"tag", app_bytes, nonce]) == tag
+    }
+
+    pub fn register(
+        &mut self,
+        application: String,
+        challenge: String,
+        user_verification: UserVerification,
+        extensions: Extensions,
+    ) -> crate::Result<String> {
+        // like a plain U2F key, the virtual device can only test for user presence
+        if user_verification == UserVerification::Required {
+            return Err(Error::UserVerificationUnavailable);
+        }
+        let (chall_bytes, app_bytes, client_data_string) =
+            format_client_data(application.as_str(), challenge.as_str());
+
+        // key handle: nonce || HMAC(app || nonce), so `sign` can check it was issued for the app
+        let nonce = self.mac(&[b"nonce", &app_bytes, &chall_bytes]);
+        let mut key_handle = nonce.clone();
+        key_handle.extend(self.mac(&[b"tag", &app_bytes, &nonce]));
+
+        let credential_key = self.credential_key(&app_bytes, &nonce)?;
+        let public_key = credential_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec();
+
+        let attestation_key =
+            SigningKey::from_slice(ATTESTATION_KEY).expect("invalid attestation key");
+        let mut signed_data = vec![0x00];
+        signed_data.extend(&app_bytes);
+        signed_data.extend(&chall_bytes);
+        signed_data.extend(&key_handle);
+        signed_data.extend(&public_key);
+        let signature: EcdsaSignature = attestation_key.sign(&signed_data);
+
+        // 1: reserved
+        // 65: public key
+        // 1: key handle length
+        // key handle
+        // x.509 cert
+        // sig
+        let mut register_data = vec![0x05];
+        register_data.extend(&public_key);
+        register_data.push(key_handle.len() as u8);
+        register_data.extend(&key_handle);
+        register_data.extend(ATTESTATION_CERT);
+        register_data.extend(signature.to_der().as_bytes());
+
+        let res = serde_json::to_string(&Registration {
+            key_handle: URL_SAFE_NO_PAD.encode(&key_handle),
+            pubkey: URL_SAFE_NO_PAD.encode(&public_key),
+            register_data: URL_SAFE_NO_PAD.encode(&register_data),
+            client_data: client_data_string,
+            user_present: true,
+            user_verified: false,
+            unsupported_extensions: extensions.unsupported,
+        })?;
+        Ok(res)
+    }
+
+    pub fn sign(
+        &mut self,
+        application: String,
+        challenge: String,
+        key_handles: Vec<String>,
+        user_verification: UserVerification,
+        extensions: Extensions,
+    ) -> crate::Result<String> {
+        if user_verification == UserVerification::Required {
+            return Err(Error::UserVerificationUnavailable);
+        }
+        let (chall_bytes, app_bytes, _) =
+            format_client_data(application.as_str(), challenge.as_str());
+
+        let mut key_handle = None;
+        for handle in key_handles {
+            let handle = URL_SAFE_NO_PAD.decode(handle)?;
+            if self.is_issued(&app_bytes, &handle) {
+                key_handle.replace(handle);
+                break;
+            }
+        }
+        let key_handle = key_handle.ok_or(Error::NoMatchingKeyHandle)?;
+        let credential_key = self.credential_key(&app_bytes, &key_handle[..32])?;
+
+        self.counter += 1;
+        let user_presence = USER_PRESENT;
+
+        let mut signed_data = app_bytes;
+        signed_data.push(user_presence);
+        signed_data.extend(self.counter.to_be_bytes());
+        signed_data.extend(&chall_bytes);
+        let signature: EcdsaSignature = credential_key.sign(&signed_data);
+
+        let mut sign_data = vec![user_presence];
+        sign_data.extend(self.counter.to_be_bytes());
+        sign_data.extend(signature.to_der().as_bytes());
+
+        let res = serde_json::to_string(&Signature::new(&key_handle, &sign_data, extensions))?;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::u2f;
+
+    const APPLICATION: &str = "https://tauri.app";
+
+    fn random_challenge() -> String {
+        URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>())
+    }
+
+    #[test]
+    fn register_and_sign() {
+        let mut device = VirtualAuthenticator::new("seed");
+
+        let challenge = random_challenge();
+        let registration = device
+            .register(
+                APPLICATION.into(),
+                challenge.clone(),
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+        let registration: serde_json::Value = serde_json::from_str(&registration).unwrap();
+        let verification: serde_json::Value = serde_json::from_str(
+            &u2f::verify_registration(
+                APPLICATION.into(),
+                challenge,
+                registration["registerData"].as_str().unwrap().into(),
+                registration["clientData"].as_str().unwrap().into(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let key_handle = verification["keyHandle"].as_str().unwrap().to_string();
+        let pubkey = verification["pubkey"].as_str().unwrap().to_string();
+        assert_eq!(registration["keyHandle"], verification["keyHandle"]);
+
+        let challenge = random_challenge();
+        let signature = device
+            .sign(
+                APPLICATION.into(),
+                challenge.clone(),
+                vec![key_handle.clone()],
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+        let signature: serde_json::Value = serde_json::from_str(&signature).unwrap();
+        let (_, _, client_data) = format_client_data(APPLICATION, &challenge);
+        let counter = u2f::verify_signature(
+            APPLICATION.into(),
+            challenge,
+            signature["signData"].as_str().unwrap().into(),
+            client_data,
+            key_handle,
+            pubkey,
+        )
+        .unwrap();
+        assert_eq!(counter, 1);
+        assert_eq!(signature["userPresent"], true);
+        assert_eq!(signature["userVerified"], false);
+    }
+
+    #[test]
+    fn requires_user_verification() {
+        let mut device = VirtualAuthenticator::new("seed");
+        assert!(matches!(
+            device.register(
+                APPLICATION.into(),
+                random_challenge(),
+                UserVerification::Required,
+                Default::default()
+            ),
+            Err(Error::UserVerificationUnavailable)
+        ));
+    }
+
+    #[test]
+    fn deterministic_from_seed() {
+        let challenge = random_challenge();
+        let first = VirtualAuthenticator::new("seed")
+            .register(
+                APPLICATION.into(),
+                challenge.clone(),
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+        let second = VirtualAuthenticator::new("seed")
+            .register(
+                APPLICATION.into(),
+                challenge.clone(),
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+        let other = VirtualAuthenticator::new("other")
+            .register(
+                APPLICATION.into(),
+                challenge,
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn rejects_foreign_key_handle() {
+        let mut device = VirtualAuthenticator::new("seed");
+        let registration = device
+            .register(
+                APPLICATION.into(),
+                random_challenge(),
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+        let registration: serde_json::Value = serde_json::from_str(&registration).unwrap();
+        let key_handle = registration["keyHandle"].as_str().unwrap().to_string();
+
+        assert!(matches!(
+            device.sign(
+                "https://example.com".into(),
+                random_challenge(),
+                vec![key_handle],
+                Default::default(),
+                Default::default()
+            ),
+            Err(Error::NoMatchingKeyHandle)
+        ));
+    }
+
+    #[test]
+    fn serializes_error_kind() {
+        let registration: serde_json::Value = serde_json::from_str(
+            &VirtualAuthenticator::new("seed")
+                .register(
+                    APPLICATION.into(),
+                    random_challenge(),
+                    Default::default(),
+                    Default::default(),
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        let error = u2f::verify_registration(
+            APPLICATION.into(),
+            "not base64url!".into(),
+            registration["registerData"].as_str().unwrap().into(),
+            registration["clientData"].as_str().unwrap().into(),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind(), crate::ErrorKind::BadChallenge);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap()["kind"],
+            "badChallenge"
+        );
+    }
+
+    #[test]
+    fn signs_with_matching_key_handle() {
+        let mut device = VirtualAuthenticator::new("seed");
+        let registration = device
+            .register(
+                APPLICATION.into(),
+                random_challenge(),
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+        let registration: serde_json::Value = serde_json::from_str(&registration).unwrap();
+        let key_handle = registration["keyHandle"].as_str().unwrap().to_string();
+        let foreign = VirtualAuthenticator::new("other")
+            .register(
+                APPLICATION.into(),
+                random_challenge(),
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+        let foreign: serde_json::Value = serde_json::from_str(&foreign).unwrap();
+        let foreign = foreign["keyHandle"].as_str().unwrap().to_string();
+
+        let signature = device
+            .sign(
+                APPLICATION.into(),
+                random_challenge(),
+                vec![foreign.clone(), key_handle.clone()],
+                Default::default(),
+                Default::default(),
+            )
+            .unwrap();
+        let signature: serde_json::Value = serde_json::from_str(&signature).unwrap();
+        assert_eq!(signature["keyHandle"], key_handle);
+
+        assert!(matches!(
+            device.sign(
+                APPLICATION.into(),
+                random_challenge(),
+                vec![foreign],
+                Default::default(),
+                Default::default()
+            ),
+            Err(Error::NoMatchingKeyHandle)
+        ));
+    }
+}
@@ -21,13 +21,17 @@ interface ArgMatch {
   /**
    * string if takes value
    * boolean if flag
-   * string[] or null if takes multiple values
+   * string[] or null if takes multiple values or has a value delimiter
    */
   value: string | boolean | string[] | null;
   /**
    * Number of occurrences
    */
   occurrences: number;
+  /**
+   * Whether the value was read from the environment variable of the argument
+   */
+  fromEnv: boolean;
 }
 
 /**
@@ -38,12 +42,37 @@ interface SubcommandMatch {
   matches: CliMatches;
 }
 
+/**
+ * What the parsing of the arguments resulted in.
+ * With `--help` or `--version`, `text` is what a terminal would have printed.
+ *
+ * @since 2.0.0
+ */
+type Outcome =
+  | { kind: "parsed" }
+  | { kind: "help"; text: string }
+  | { kind: "version"; text: string };
+
 /**
  * @since 2.0.0
  */
 interface CliMatches {
   args: Record<string, ArgMatch>;
   subcommand: SubcommandMatch | null;
+  /** Always `parsed` for the subcommands. */
+  outcome: Outcome;
+}
+
+/**
+ * @since 2.0.0
+ */
+interface MatchesOptions {
+  /**
+   * Print the parse error and usage to stderr and exit the process with code 2
+   * instead of rejecting the promise when the arguments are invalid.
+   * Defaults to `false`.
+   */
+  exitOnError?: boolean;
 }
 
 /**
@@ -67,10 +96,18 @@ interface CliMatches {
  *
  * @since 2.0.0
  */
-async function getMatches(): Promise<CliMatches> {
-  return await window.__TAURI_INVOKE__("plugin:cli|cli_matches");
+async function getMatches(options?: MatchesOptions): Promise<CliMatches> {
+  return await window.__TAURI_INVOKE__("plugin:cli|cli_matches", {
+    ...options,
+  });
 }
 
-export type { ArgMatch, SubcommandMatch, CliMatches };
+export type {
+  ArgMatch,
+  SubcommandMatch,
+  CliMatches,
+  MatchesOptions,
+  Outcome,
+};
 
 export { getMatches };
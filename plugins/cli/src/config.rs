@@ -2,10 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use serde::Deserialize;
 
+use crate::Error;
+
+/// Argument names generated by the parser for every command.
+const RESERVED_NAMES: [&str; 2] = ["help", "version"];
+/// Short flags generated by the parser for every command.
+const RESERVED_SHORTS: [char; 2] = ['h', 'V'];
+
 /// A CLI argument definition.
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
@@ -38,6 +45,17 @@ pub struct Arg {
     /// NOTE: Setting this requires `takes_value` to be set to true.
     #[serde(default)]
     pub multiple: bool,
+    /// Splits each value of the argument on this character, e.g. `--tags a,b,c` with `,`
+    /// results in a list of three values. Combined with `multiple`, the values of every
+    /// occurrence are listed together.
+    ///
+    /// The delimiter stays literal when escaped with a `\` or inside double quotes,
+    /// e.g. `a\,b` or `"a,b"` is a single `a,b` value.
+    ///
+    /// NOTE: Setting this requires `takes_value` to be set to true. The possible values are
+    /// checked for every split value, while the value counts apply to the values as given.
+    #[serde(alias = "value-delimiter")]
+    pub value_delimiter: Option<char>,
     /// Specifies how many values are required to satisfy this argument. For example, if you had a
     /// `-f <file>` argument where you wanted exactly 3 'files' you would set
     /// `number_of_values = 3`, and this argument wouldn't be satisfied unless the user provided
@@ -50,10 +68,17 @@ pub struct Arg {
     /// **NOTE:** implicitly sets `takes_value = true` and `multiple_values = true`.
     #[serde(alias = "number-of-values")]
     pub number_of_values: Option<usize>,
-    /// Specifies a list of possible values for this argument.
+    /// Specifies a list of possible values for this argument, also named `choices`.
     /// At runtime, the CLI verifies that only one of the specified values was used, or fails with an error message.
-    #[serde(alias = "possible-values")]
+    #[serde(alias = "possible-values", alias = "choices")]
     pub possible_values: Option<Vec<String>>,
+    /// Restricts the value to an integer in this inclusive range, e.g. `[1, 65535]` for a port.
+    /// The matched value is still a string.
+    ///
+    /// NOTE: Setting this requires `takes_value` to be set to true. With a `valueDelimiter`,
+    /// every split value is checked.
+    #[serde(alias = "number-range")]
+    pub number_range: Option<(i64, i64)>,
     /// Specifies the minimum number of values for this argument.
     /// For example, if you had a -f `<file>` argument where you wanted at least 2 'files',
     /// you would set `minValues: 2`, and this argument would be satisfied if the user provided, 2 or more values.
@@ -108,6 +133,23 @@ pub struct Arg {
     /// i.e. an equals between the option and associated value.
     #[serde(alias = "requires-equals")]
     pub require_equals: Option<bool>,
+    /// The environment variable read when the argument is not given,
+    /// e.g. `APP_TOKEN` for `--token`.
+    ///
+    /// A value given on the command line takes precedence. The matches report whether the value
+    /// was read from the environment.
+    ///
+    /// NOTE: Setting this requires `takes_value` to be set to true.
+    pub env: Option<String>,
+    /// Reads the value of the argument from stdin when it is present, so a secret is not
+    /// exposed in the process listings, e.g. `echo $TOKEN | app --token-stdin`.
+    ///
+    /// The whole input is read, without its trailing line ending. Only one argument of an
+    /// invocation can read stdin.
+    ///
+    /// NOTE: The argument is a flag, so it can not take a value nor be positional.
+    #[serde(default, alias = "from-stdin")]
+    pub from_stdin: bool,
     /// The positional argument index, starting at 1.
     ///
     /// The index refers to position according to other positional argument.
@@ -116,6 +158,37 @@ pub struct Arg {
     pub index: Option<usize>,
 }
 
+impl Arg {
+    /// The names of the other arguments this argument refers to.
+    fn references(&self) -> impl Iterator<Item = &str> {
+        [
+            &self.required_unless_present,
+            &self.conflicts_with,
+            &self.requires,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(
+            [
+                &self.required_unless_present_all,
+                &self.required_unless_present_any,
+                &self.conflicts_with_all,
+                &self.requires_all,
+            ]
+            .into_iter()
+            .flatten()
+            .flatten(),
+        )
+        .chain(
+            [&self.requires_if, &self.required_if_eq]
+                .into_iter()
+                .flatten()
+                .map(|(arg, _)| arg),
+        )
+        .map(String::as_str)
+    }
+}
+
 /// describes a CLI configuration
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
@@ -139,6 +212,11 @@ pub struct Config {
     pub args: Option<Vec<Arg>>,
     /// List of subcommands of this command
     pub subcommands: Option<HashMap<String, Config>>,
+    /// The subcommand used when none is given and the arguments are valid for it.
+    ///
+    /// `--help` and `--version` still apply to this command.
+    #[serde(alias = "default-subcommand")]
+    pub default_subcommand: Option<String>,
 }
 
 impl Config {
@@ -152,6 +230,11 @@ impl Config {
         self.subcommands.as_ref()
     }
 
+    /// The subcommand used when none is given and the arguments are valid for it.
+    pub fn default_subcommand(&self) -> Option<&String> {
+        self.default_subcommand.as_ref()
+    }
+
     /// Command description which will be shown on the help information.
     pub fn description(&self) -> Option<&String> {
         self.description.as_ref()
@@ -175,4 +258,173 @@ impl Config {
     pub fn after_help(&self) -> Option<&String> {
         self.after_help.as_ref()
     }
+
+    /// Checks the configuration for structural problems the parser would otherwise
+    /// reject at runtime or silently misinterpret.
+    ///
+    /// Reports duplicate argument names, short flags and positional indexes, references to
+    /// undefined arguments, empty names, inconsistent value counts and empty number ranges,
+    /// for every subcommand.
+    /// Subcommands are nested by value, so the configuration can not describe a cycle.
+    pub fn validate(&self) -> crate::Result<()> {
+        let mut problems = Vec::new();
+        self.validate_command("<root>", &mut problems);
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidConfig(problems))
+        }
+    }
+
+    fn validate_command(&self, path: &str, problems: &mut Vec<String>) {
+        let mut report = |problem: String| problems.push(format!("`{path}`: {problem}"));
+        let args = self.args().map(Vec::as_slice).unwrap_or_default();
+
+        let mut names = HashSet::new();
+        let mut shorts = HashMap::new();
+        let mut indexes = HashMap::new();
+        for arg in args {
+            if arg.name.is_empty() {
+                report("argument with an empty name".into());
+                continue;
+            }
+            if RESERVED_NAMES.contains(&arg.name.as_str()) {
+                report(format!("argument name `{}` is reserved", arg.name));
+            }
+            if !names.insert(arg.name.as_str()) {
+                report(format!("duplicate argument name `{}`", arg.name));
+            }
+
+            match (arg.index, arg.short) {
+                (Some(0), _) => report(format!(
+                    "argument `{}` has index 0, positional indexes start at 1",
+                    arg.name
+                )),
+                (Some(index), _) => {
+                    if let Some(other) = indexes.insert(index, &arg.name) {
+                        report(format!(
+                            "arguments `{other}` and `{}` have the same index {index}",
+                            arg.name
+                        ));
+                    }
+                }
+                (None, Some(short)) => {
+                    if RESERVED_SHORTS.contains(&short) {
+                        report(format!(
+                            "short flag `-{short}` of argument `{}` is reserved",
+                            arg.name
+                        ));
+                    } else if let Some(other) = shorts.insert(short, &arg.name) {
+                        report(format!(
+                            "arguments `{other}` and `{}` have the same short flag `-{short}`",
+                            arg.name
+                        ));
+                    }
+                }
+                (None, None) => {}
+            }
+
+            if arg.multiple && !arg.takes_value && arg.number_of_values.is_none() {
+                report(format!(
+                    "argument `{}` is `multiple` but does not take a value",
+                    arg.name
+                ));
+            }
+            if let Some(env) = &arg.env {
+                if !arg.takes_value {
+                    report(format!(
+                        "argument `{}` has an `env` but does not take a value",
+                        arg.name
+                    ));
+                }
+                if env.is_empty() || env.contains('=') {
+                    report(format!(
+                        "argument `{}` has an invalid `env` name `{env}`",
+                        arg.name
+                    ));
+                }
+            }
+            if arg.from_stdin && (arg.takes_value || arg.index.is_some()) {
+                report(format!(
+                    "argument `{}` reads its value from stdin, so it can not take a value nor be positional",
+                    arg.name
+                ));
+            }
+            if let Some(delimiter) = arg.value_delimiter {
+                if !arg.takes_value {
+                    report(format!(
+                        "argument `{}` has a `valueDelimiter` but does not take a value",
+                        arg.name
+                    ));
+                }
+                if matches!(delimiter, '\\' | '"') {
+                    report(format!(
+                        "argument `{}` can not use `{delimiter}` as `valueDelimiter`, it is used for escaping",
+                        arg.name
+                    ));
+                }
+            }
+            if let (Some(min), Some(max)) = (arg.min_values, arg.max_values) {
+                if min > max {
+                    report(format!(
+                        "argument `{}` has `minValues` {min} greater than `maxValues` {max}",
+                        arg.name
+                    ));
+                }
+            }
+            if arg.possible_values.as_ref().map_or(false, Vec::is_empty) {
+                report(format!("argument `{}` has no possible values", arg.name));
+            }
+            if let Some((min, max)) = arg.number_range {
+                if !arg.takes_value {
+                    report(format!(
+                        "argument `{}` has a `numberRange` but does not take a value",
+                        arg.name
+                    ));
+                }
+                if min > max {
+                    report(format!(
+                        "argument `{}` has an empty `numberRange` from {min} to {max}",
+                        arg.name
+                    ));
+                }
+            }
+        }
+
+        for arg in args {
+            for reference in arg.references() {
+                if !names.contains(reference) {
+                    report(format!(
+                        "argument `{}` references the undefined argument `{reference}`",
+                        arg.name
+                    ));
+                }
+            }
+        }
+
+        if let Some(default) = self.default_subcommand() {
+            let defined = self
+                .subcommands()
+                .map_or(false, |s| s.contains_key(default));
+            if !defined {
+                report(format!("default subcommand `{default}` is not defined"));
+            }
+        }
+
+        for (name, subcommand) in self.subcommands().into_iter().flatten() {
+            if name.is_empty() {
+                problems.push(format!("`{path}`: subcommand with an empty name"));
+                continue;
+            }
+            if name == "help" {
+                problems.push(format!("`{path}`: subcommand name `help` is reserved"));
+            }
+            let path = if path == "<root>" {
+                name.clone()
+            } else {
+                format!("{path} {name}")
+            };
+            subcommand.validate_command(&path, problems);
+        }
+    }
 }
@@ -8,6 +8,19 @@ use serde::{Serialize, Serializer};
 pub enum Error {
     #[error("failed to parse arguments: {0}")]
     ParseCli(#[from] clap::Error),
+    /// A value rejected by the possible values or the number range of its argument.
+    #[error("invalid value `{value}` for `{arg}`: {reason}")]
+    InvalidArgValue {
+        /// The argument, as shown in the usage, e.g. `--port <port>`.
+        arg: String,
+        /// The rejected value, or the rejected part of a delimited value.
+        value: String,
+        reason: String,
+    },
+    #[error("invalid cli configuration: {}", .0.join("; "))]
+    InvalidConfig(Vec<String>),
+    #[error("the generated completion script is not valid UTF-8: {0}")]
+    Completions(#[from] std::string::FromUtf8Error),
 }
 
 impl Serialize for Error {
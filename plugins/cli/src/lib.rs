@@ -11,15 +11,60 @@ mod config;
 mod error;
 mod parser;
 
+pub use clap_complete::Shell;
 use config::{Arg, Config};
 pub use error::Error;
+pub use parser::Outcome;
 type Result<T> = std::result::Result<T, Error>;
 
-pub struct Cli<R: Runtime>(PluginApi<R, Config>);
+pub struct Cli<R: Runtime>(PluginApi<R, Config>, parser::Stdin);
 
 impl<R: Runtime> Cli<R> {
+    /// Parses the process arguments, returning an error if they do not match the configuration.
+    ///
+    /// The value of a `fromStdin` argument is read on the first call and kept for the next ones.
     pub fn matches(&self) -> Result<parser::Matches> {
-        parser::get_matches(self.0.config(), self.0.app().package_info())
+        parser::get_matches(self.0.config(), self.0.app().package_info(), &self.1, false)
+    }
+
+    /// Parses `args` instead of the process arguments, returning an error if they do not match
+    /// the configuration.
+    ///
+    /// The first argument is the binary name, as in [`std::env::args`], e.g. the arguments
+    /// of another instance of the app received by the single-instance plugin.
+    /// The `fromStdin` arguments are not read from the stdin of this process.
+    pub fn matches_from(&self, args: Vec<String>) -> Result<parser::Matches> {
+        parser::get_matches_from(self.0.config(), self.0.app().package_info(), args, false)
+    }
+
+    /// Generates the completion script of the configured arguments and subcommands for `shell`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use tauri_plugin_cli::{CliExt, Shell};
+    /// tauri::Builder::default()
+    ///   .setup(|app| {
+    ///     // e.g. `./app --generate-completions bash > /etc/bash_completion.d/app`
+    ///     let matches = app.cli().matches()?;
+    ///     if let Some(shell) = matches.args["generate-completions"].value.as_str() {
+    ///       if let Ok(shell) = shell.parse::<Shell>() {
+    ///         print!("{}", app.cli().generate_completions(shell)?);
+    ///         std::process::exit(0);
+    ///       }
+    ///     }
+    ///     Ok(())
+    ///   });
+    /// ```
+    pub fn generate_completions(&self, shell: Shell) -> Result<String> {
+        parser::generate_completions(self.0.config(), self.0.app().package_info(), shell)
+    }
+
+    /// Parses the process arguments, printing the error and usage to stderr and exiting
+    /// the process with code 2 if they do not match the configuration.
+    pub fn matches_or_exit(&self) -> parser::Matches {
+        parser::get_matches(self.0.config(), self.0.app().package_info(), &self.1, true)
+            .expect("parse errors exit the process")
     }
 }
 
@@ -34,8 +79,16 @@ impl<R: Runtime, T: Manager<R>> CliExt<R> for T {
 }
 
 #[tauri::command]
-fn cli_matches<R: Runtime>(_app: AppHandle<R>, cli: State<'_, Cli<R>>) -> Result<parser::Matches> {
-    cli.matches()
+fn cli_matches<R: Runtime>(
+    _app: AppHandle<R>,
+    cli: State<'_, Cli<R>>,
+    exit_on_error: Option<bool>,
+) -> Result<parser::Matches> {
+    if exit_on_error.unwrap_or_default() {
+        Ok(cli.matches_or_exit())
+    } else {
+        cli.matches()
+    }
 }
 
 pub fn init<R: Runtime>() -> TauriPlugin<R, Config> {
@@ -43,7 +96,8 @@ pub fn init<R: Runtime>() -> TauriPlugin<R, Config> {
         .js_init_script(include_str!("api-iife.js").to_string())
         .invoke_handler(tauri::generate_handler![cli_matches])
         .setup(|app, api| {
-            app.manage(Cli(api));
+            api.config().validate()?;
+            app.manage(Cli(api, Default::default()));
             Ok(())
         })
         .build()
@@ -4,32 +4,44 @@
 
 use clap::{
     builder::{PossibleValue, PossibleValuesParser},
-    error::ErrorKind,
+    error::{ContextKind, ContextValue, ErrorKind},
+    parser::ValueSource,
     Arg as ClapArg, ArgAction, ArgMatches, Command,
 };
+use clap_complete::Shell;
 use serde::Serialize;
 use serde_json::Value;
 use tauri::PackageInfo;
 
-use crate::{Arg, Config};
+use crate::{Arg, Config, Error};
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    error::Error as _,
+    ffi::OsString,
+    io::{self, Read},
+    sync::Mutex,
+};
 
 #[macro_use]
 mod macros;
 
 /// The resolution of a argument match.
 #[derive(Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub struct ArgData {
     /// - [`Value::Bool`] if it's a flag,
-    /// - [`Value::Array`] if it's multiple,
-    /// - [`Value::String`] if it has value,
+    /// - [`Value::Array`] if it's multiple or has a value delimiter,
+    /// - [`Value::String`] if it has value or reads its value from stdin,
     /// - [`Value::Null`] otherwise.
     pub value: Value,
     /// The number of occurrences of the argument.
     /// e.g. `./app --arg 1 --arg 2 --arg 2 3 4` results in three occurrences.
     pub occurrences: u8,
+    /// Whether the value was read from the environment variable of the argument,
+    /// e.g. to warn about a deprecated variable.
+    pub from_env: bool,
 }
 
 /// The matched subcommand.
@@ -42,6 +54,22 @@ pub struct SubcommandMatches {
     pub matches: Matches,
 }
 
+/// What the parsing of the arguments resulted in.
+///
+/// The parser never prints the help or the version, so an app without a terminal can
+/// show them in a window instead.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "text", rename_all = "camelCase")]
+pub enum Outcome {
+    /// The arguments matched the configuration.
+    #[default]
+    Parsed,
+    /// `--help` was given, with the rendered help of the command.
+    Help(String),
+    /// `--version` was given, with the rendered version.
+    Version(String),
+}
+
 /// The argument matches of a command.
 #[derive(Default, Debug, Serialize)]
 #[non_exhaustive]
@@ -50,9 +78,25 @@ pub struct Matches {
     pub args: HashMap<String, ArgData>,
     /// The matched subcommand if found.
     pub subcommand: Option<Box<SubcommandMatches>>,
+    /// Whether the arguments were parsed or asked for the help or the version,
+    /// always [`Outcome::Parsed`] for the subcommands.
+    pub outcome: Outcome,
 }
 
 impl Matches {
+    /// The names of the matched subcommands, from the outermost to the one that ran.
+    ///
+    /// e.g. `./app db migrate` results in `["db", "migrate"]`, and `./app` in an empty list.
+    pub fn active_subcommand_path(&self) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut subcommand = self.subcommand.as_deref();
+        while let Some(s) = subcommand {
+            path.push(s.name.clone());
+            subcommand = s.matches.subcommand.as_deref();
+        }
+        path
+    }
+
     /// Set a arg match.
     pub(crate) fn set_arg(&mut self, name: String, value: ArgData) {
         self.args.insert(name, value);
@@ -66,6 +110,9 @@ impl Matches {
 
 /// Gets the argument matches of the CLI definition.
 ///
+/// If `exit_on_error` is `true`, a parse failure prints the error and the usage generated from
+/// the configuration to stderr and exits the process with code 2 instead of returning an error.
+///
 /// This is a low level API. If the application has been built,
 /// prefer [`App::get_cli_matches`](`crate::App#method.get_cli_matches`).
 ///
@@ -79,44 +126,430 @@ impl Matches {
 ///     Ok(())
 ///   });
 /// ```
-pub fn get_matches(cli: &Config, package_info: &PackageInfo) -> crate::Result<Matches> {
+pub fn get_matches(
+    cli: &Config,
+    package_info: &PackageInfo,
+    stdin: &Stdin,
+    exit_on_error: bool,
+) -> crate::Result<Matches> {
+    parse_matches(
+        cli,
+        package_info,
+        std::env::args_os(),
+        Some(stdin),
+        exit_on_error,
+    )
+}
+
+/// Gets the argument matches of `args`, the first one being the binary name,
+/// like [`get_matches`] does for the process arguments.
+///
+/// The `fromStdin` arguments are not read from the stdin of this process, their value is `true`
+/// when present.
+pub fn get_matches_from<I, T>(
+    cli: &Config,
+    package_info: &PackageInfo,
+    args: I,
+    exit_on_error: bool,
+) -> crate::Result<Matches>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    parse_matches(cli, package_info, args, None, exit_on_error)
+}
+
+fn parse_matches<I, T>(
+    cli: &Config,
+    package_info: &PackageInfo,
+    args: I,
+    stdin: Option<&Stdin>,
+    exit_on_error: bool,
+) -> crate::Result<Matches>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    // with `wrap_help`, clap wraps to the `COLUMNS` variable or the terminal width
+    let app = command(cli, package_info);
+    let result = try_get_matches_from(app, cli, args).and_then(|mut matches| {
+        if let Some(stdin) = stdin {
+            read_stdin_value(cli, &mut matches, stdin, io::stdin())?;
+        }
+        Ok(matches)
+    });
+    match result {
+        Ok(matches) => Ok(matches),
+        Err(e) => match outcome_matches(&e) {
+            Some(matches) => Ok(matches),
+            None if exit_on_error => e.exit(),
+            None => Err(invalid_value(&e).unwrap_or_else(|| e.into())),
+        },
+    }
+}
+
+/// The matches of `--help` and `--version`, which clap reports as errors.
+fn outcome_matches(error: &clap::Error) -> Option<Matches> {
+    let mut matches = Matches::default();
+    let text = error.to_string();
+    match error.kind() {
+        ErrorKind::DisplayHelp => {
+            matches.args.insert(
+                "help".to_string(),
+                ArgData {
+                    value: Value::String(text.clone()),
+                    ..Default::default()
+                },
+            );
+            matches.outcome = Outcome::Help(text);
+        }
+        ErrorKind::DisplayVersion => {
+            matches
+                .args
+                .insert("version".to_string(), Default::default());
+            matches.outcome = Outcome::Version(text);
+        }
+        _ => return None,
+    }
+    Some(matches)
+}
+
+/// The error of a value rejected by the possible values or the number range of its argument.
+fn invalid_value(error: &clap::Error) -> Option<Error> {
+    if !matches!(
+        error.kind(),
+        ErrorKind::InvalidValue | ErrorKind::ValueValidation
+    ) {
+        return None;
+    }
+    let context = |kind| match error.get(kind) {
+        Some(ContextValue::String(value)) => Some(value.clone()),
+        _ => None,
+    };
+    let arg = context(ContextKind::InvalidArg)?;
+    if let Some(invalid) = error
+        .source()
+        .and_then(|source| source.downcast_ref::<InvalidValue>())
+    {
+        return Some(Error::InvalidArgValue {
+            arg,
+            value: invalid.value.clone(),
+            reason: invalid.reason.clone(),
+        });
+    }
+    // rejected by the possible values parser
+    let Some(ContextValue::Strings(possible_values)) = error.get(ContextKind::ValidValue) else {
+        return None;
+    };
+    Some(Error::InvalidArgValue {
+        arg,
+        value: context(ContextKind::InvalidValue)?,
+        reason: format!("possible values: {}", possible_values.join(", ")),
+    })
+}
+
+/// Generates the completion script of the CLI definition for `shell`.
+pub fn generate_completions(
+    cli: &Config,
+    package_info: &PackageInfo,
+    shell: Shell,
+) -> crate::Result<String> {
+    // the script completes the binary the shell runs, not the product name
+    let bin_name = tauri::utils::platform::current_exe()
+        .ok()
+        .and_then(|exe| {
+            exe.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| package_info.name.clone());
+    completions(command(cli, package_info), &bin_name, shell)
+}
+
+fn completions(mut app: Command, bin_name: &str, shell: Shell) -> crate::Result<String> {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut app, bin_name, &mut script);
+    String::from_utf8(script).map_err(Into::into)
+}
+
+/// The clap command of the CLI definition, described with the package info.
+fn command(cli: &Config, package_info: &PackageInfo) -> Command {
     let about = cli
         .description()
         .unwrap_or(&package_info.description.to_string())
         .to_string();
     let version = package_info.version.to_string();
-    let app = get_app(
-        package_info,
+    get_app(
+        package_info.authors,
         version,
         package_info.name.clone(),
         Some(&about),
         cli,
-    );
-    match app.try_get_matches() {
-        Ok(matches) => Ok(get_matches_internal(cli, &matches)),
-        Err(e) => match e.kind() {
-            ErrorKind::DisplayHelp => {
-                let mut matches = Matches::default();
-                let help_text = e.to_string();
-                matches.args.insert(
-                    "help".to_string(),
-                    ArgData {
-                        value: Value::String(help_text),
-                        occurrences: 0,
-                    },
-                );
-                Ok(matches)
+    )
+}
+
+/// Parses `args`, the first one being the binary name.
+///
+/// On top of the forms clap accepts, `-abc` for boolean flags, `-ofile`, `-o=file` and `-o file` for
+/// flags taking a value and `--long=value` and `--long value` for long flags, a flag taking a value
+/// must be the first or the last one of a group of short flags, e.g. `-abo file`.
+fn try_get_matches_from<I, T>(
+    mut app: Command,
+    cli: &Config,
+    args: I,
+) -> std::result::Result<Matches, clap::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+    parse(&mut app, cli, args)
+}
+
+/// Parses `args`, applying the default subcommand of the matched command while it has
+/// no subcommand and the arguments are valid for the default one.
+fn parse(
+    app: &mut Command,
+    cli: &Config,
+    mut args: Vec<OsString>,
+) -> std::result::Result<Matches, clap::Error> {
+    check_short_flag_groups(app, cli, &args)?;
+    let mut result = app.try_get_matches_from_mut(args.clone());
+    if let Err(e) = &result {
+        // `--help` and `--version` take precedence over the default subcommand
+        if matches!(e.kind(), ErrorKind::DisplayHelp | ErrorKind::DisplayVersion) {
+            return result.map(|matches| get_matches_internal(cli, &matches));
+        }
+    }
+
+    loop {
+        let depth = result.as_ref().map_or(0, subcommand_depth);
+        let defaulted = with_default_subcommand(cli, result.as_ref().ok(), &args)
+            .into_iter()
+            .find_map(|args| {
+                check_short_flag_groups(app, cli, &args).ok()?;
+                let matches = app.try_get_matches_from_mut(args.clone()).ok()?;
+                // the default must be matched as a subcommand, not as a value
+                (subcommand_depth(&matches) > depth).then_some((args, matches))
+            });
+        match defaulted {
+            Some((defaulted_args, matches)) => {
+                args = defaulted_args;
+                result = Ok(matches);
             }
-            ErrorKind::DisplayVersion => {
-                let mut matches = Matches::default();
-                matches
-                    .args
-                    .insert("version".to_string(), Default::default());
-                Ok(matches)
+            None => break,
+        }
+    }
+    result.map(|matches| get_matches_internal(cli, &matches))
+}
+
+/// The arguments with the default subcommand of the matched command, given right after
+/// the matched subcommands or, when the arguments matched, after all of them.
+fn with_default_subcommand(
+    cli: &Config,
+    matches: Option<&ArgMatches>,
+    args: &[OsString],
+) -> Vec<Vec<OsString>> {
+    let mut config = cli;
+    let mut position = 1;
+    let mut subcommand = matches.and_then(ArgMatches::subcommand);
+    while let Some((name, subcommand_matches)) = subcommand {
+        let Some(found) = args
+            .get(position..)
+            .and_then(|args| args.iter().position(|arg| arg == name))
+        else {
+            return Vec::new();
+        };
+        position += found + 1;
+        config = match config.subcommands().and_then(|s| s.get(name)) {
+            Some(config) => config,
+            None => return Vec::new(),
+        };
+        subcommand = subcommand_matches.subcommand();
+    }
+
+    let Some(default) = config.default_subcommand() else {
+        return Vec::new();
+    };
+    let position = position.min(args.len());
+    let mut positions = vec![position];
+    if matches.is_some() && position < args.len() {
+        positions.push(args.len());
+    }
+    positions
+        .into_iter()
+        .map(|position| {
+            let mut args = args.to_vec();
+            args.insert(position, default.into());
+            args
+        })
+        .collect()
+}
+
+fn subcommand_depth(matches: &ArgMatches) -> usize {
+    let mut depth = 0;
+    let mut subcommand = matches.subcommand();
+    while let Some((_, matches)) = subcommand {
+        depth += 1;
+        subcommand = matches.subcommand();
+    }
+    depth
+}
+
+/// Sets the value of the present flag reading stdin, if any, to the input read from `stdin`.
+/// The stdin of the process, read at most once so every parse gets the same value.
+#[derive(Default)]
+pub struct Stdin(Mutex<Option<std::result::Result<String, String>>>);
+
+impl Stdin {
+    /// Reads `reader` on the first call, returning the same input on the next ones.
+    fn read(&self, mut reader: impl Read) -> io::Result<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| {
+                let mut input = String::new();
+                reader
+                    .read_to_string(&mut input)
+                    .map(|_| input)
+                    .map_err(|e| e.to_string())
+            })
+            .clone()
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+}
+
+fn read_stdin_value(
+    cli: &Config,
+    matches: &mut Matches,
+    stdin: &Stdin,
+    reader: impl Read,
+) -> std::result::Result<(), clap::Error> {
+    let mut flags = Vec::new();
+    stdin_flags(cli, matches, &mut flags);
+    let (name, data) = match flags.len() {
+        0 => return Ok(()),
+        1 => flags.pop().unwrap(),
+        _ => {
+            let names = flags
+                .iter()
+                .map(|(name, _)| format!("`--{name}`"))
+                .collect::<Vec<_>>();
+            return Err(clap::Error::raw(
+                ErrorKind::ArgumentConflict,
+                format!("only one of {} can read stdin", names.join(", ")),
+            ));
+        }
+    };
+
+    let input = stdin.read(reader).map_err(|e| {
+        clap::Error::raw(
+            ErrorKind::Io,
+            format!("failed to read the value of `--{name}` from stdin: {e}"),
+        )
+    })?;
+    let value = input
+        .strip_suffix('\n')
+        .map(|value| value.strip_suffix('\r').unwrap_or(value))
+        .unwrap_or(&input);
+    // a closed stdin or a terminal ended right away
+    if value.is_empty() {
+        return Err(clap::Error::raw(
+            ErrorKind::EmptyValue,
+            format!("`--{name}` reads its value from stdin, but nothing was piped to it"),
+        ));
+    }
+    data.value = Value::String(value.into());
+    Ok(())
+}
+
+/// The present flags reading stdin, for the matched command and its subcommands.
+fn stdin_flags<'a>(
+    config: &Config,
+    matches: &'a mut Matches,
+    flags: &mut Vec<(String, &'a mut ArgData)>,
+) {
+    let Matches {
+        args, subcommand, ..
+    } = matches;
+    for (name, data) in args.iter_mut() {
+        let from_stdin = config
+            .args()
+            .into_iter()
+            .flatten()
+            .any(|arg| arg.from_stdin && &arg.name == name);
+        if from_stdin && data.occurrences > 0 {
+            flags.push((name.clone(), data));
+        }
+    }
+    if let Some(subcommand) = subcommand.as_deref_mut() {
+        if let Some(config) = config.subcommands().and_then(|s| s.get(&subcommand.name)) {
+            stdin_flags(config, &mut subcommand.matches, flags);
+        }
+    }
+}
+
+/// Rejects the groups of short flags where a flag taking a value is in the middle, e.g. `-aob`,
+/// which clap would otherwise read as `-a -o b`.
+fn check_short_flag_groups(
+    app: &mut Command,
+    cli: &Config,
+    args: &[OsString],
+) -> std::result::Result<(), clap::Error> {
+    let mut config = cli;
+    let mut args = args.iter().skip(1).filter_map(|arg| arg.to_str());
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+
+        if let Some(long) = arg.strip_prefix("--") {
+            if find_flag(config, |a| a.name == long).map_or(false, takes_separate_value) {
+                args.next();
             }
-            _ => Err(e.into()),
-        },
+        } else if let Some(group) = arg.strip_prefix('-').filter(|g| !g.is_empty()) {
+            for (i, short) in group.char_indices() {
+                if short == '=' {
+                    break;
+                }
+                // unknown flags are reported by clap
+                let Some(flag) = find_flag(config, |a| a.short == Some(short)) else {
+                    continue;
+                };
+                if !flag.takes_value {
+                    continue;
+                }
+
+                let rest = &group[i + short.len_utf8()..];
+                if rest.is_empty() && takes_separate_value(flag) {
+                    args.next();
+                }
+                if i == 0 || rest.is_empty() || rest.starts_with('=') {
+                    break;
+                }
+                return Err(app.error(
+                    ErrorKind::ArgumentConflict,
+                    format!(
+                        "`-{short}` takes a value, so it must be the first or the last flag of `{arg}`"
+                    ),
+                ));
+            }
+        } else if let Some(subcommand) = config.subcommands().and_then(|s| s.get(arg)) {
+            config = subcommand;
+        }
     }
+    Ok(())
+}
+
+fn find_flag(config: &Config, predicate: impl Fn(&Arg) -> bool) -> Option<&Arg> {
+    config
+        .args()?
+        .iter()
+        .find(|arg| arg.index.is_none() && predicate(arg))
+}
+
+/// Whether the value of the flag can be the next argument.
+fn takes_separate_value(arg: &Arg) -> bool {
+    arg.takes_value && !arg.require_equals.unwrap_or_default()
 }
 
 fn get_matches_internal(config: &Config, matches: &ArgMatches) -> Matches {
@@ -142,7 +575,21 @@ fn get_matches_internal(config: &Config, matches: &ArgMatches) -> Matches {
 fn map_matches(config: &Config, matches: &ArgMatches, cli_matches: &mut Matches) {
     if let Some(args) = config.args() {
         for arg in args {
-            let (occurrences, value) = if arg.takes_value {
+            let delimiter = arg.value_delimiter.filter(|_| arg.takes_value);
+            let (occurrences, value) = if let Some(delimiter) = delimiter {
+                matches
+                    .get_many::<String>(&arg.name)
+                    .map(|v| {
+                        let given = v.collect::<Vec<_>>();
+                        let values = given
+                            .iter()
+                            .flat_map(|value| split_value(value, delimiter))
+                            .map(Value::String)
+                            .collect();
+                        (given.len() as u8, Value::Array(values))
+                    })
+                    .unwrap_or((0, Value::Null))
+            } else if arg.takes_value {
                 if arg.multiple {
                     matches
                         .get_many::<String>(&arg.name)
@@ -165,20 +612,99 @@ fn map_matches(config: &Config, matches: &ArgMatches, cli_matches: &mut Matches)
                 (occurrences, Value::Bool(occurrences > 0))
             };
 
-            cli_matches.set_arg(arg.name.clone(), ArgData { value, occurrences });
+            let from_env = matches.value_source(&arg.name) == Some(ValueSource::EnvVariable);
+            cli_matches.set_arg(
+                arg.name.clone(),
+                ArgData {
+                    value,
+                    occurrences,
+                    from_env,
+                },
+            );
+        }
+    }
+}
+
+/// Splits the value on the delimiter, except where it is escaped with a `\` or inside
+/// double quotes. The quotes and the escaping backslashes are removed.
+fn split_value(value: &str, delimiter: char) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // other backslashes are kept, e.g. in Windows paths
+            '\\' => match chars.next_if(|&next| next == delimiter || next == '"' || next == '\\') {
+                Some(escaped) => current.push(escaped),
+                None => current.push(c),
+            },
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => values.push(std::mem::take(&mut current)),
+            c => current.push(c),
         }
     }
+    values.push(current);
+    values
+}
+
+/// A value rejected by [`validate_values`].
+#[derive(Debug)]
+struct InvalidValue {
+    value: String,
+    reason: String,
+}
+
+impl std::fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid value `{}`, {}", self.value, self.reason)
+    }
+}
+
+impl std::error::Error for InvalidValue {}
+
+/// Checks every split value against the possible values and the number range, keeping the
+/// value as given.
+fn validate_values(
+    delimiter: Option<char>,
+    possible_values: Option<Vec<String>>,
+    number_range: Option<(i64, i64)>,
+) -> impl Fn(&str) -> std::result::Result<String, InvalidValue> + Clone + Send + Sync + 'static {
+    move |given| {
+        let values = match delimiter {
+            Some(delimiter) => split_value(given, delimiter),
+            None => vec![given.to_string()],
+        };
+        for value in values {
+            let reason = if let Some(possible_values) = possible_values
+                .as_ref()
+                .filter(|possible_values| !possible_values.contains(&value))
+            {
+                format!("possible values: {}", possible_values.join(", "))
+            } else if let Some((min, max)) = number_range.filter(|(min, max)| {
+                !value
+                    .parse::<i64>()
+                    .map_or(false, |number| (*min..=*max).contains(&number))
+            }) {
+                format!("expected an integer from {min} to {max}")
+            } else {
+                continue;
+            };
+            return Err(InvalidValue { value, reason });
+        }
+        Ok(given.into())
+    }
 }
 
 fn get_app(
-    package_info: &PackageInfo,
+    authors: &'static str,
     version: String,
     command_name: String,
     about: Option<&String>,
     config: &Config,
 ) -> Command {
     let mut app = Command::new(command_name)
-        .author(package_info.authors)
+        .author(authors)
         .version(version.clone());
 
     if let Some(about) = about {
@@ -203,7 +729,7 @@ fn get_app(
     if let Some(subcommands) = config.subcommands() {
         for (subcommand_name, subcommand) in subcommands {
             let clap_subcommand = get_app(
-                package_info,
+                authors,
                 version.clone(),
                 subcommand_name.to_string(),
                 subcommand.description(),
@@ -241,13 +767,24 @@ fn get_arg(arg_name: String, arg: &Arg) -> ClapArg {
 
     clap_arg = bind_value_arg!(arg, clap_arg, number_of_values);
 
-    if let Some(values) = &arg.possible_values {
-        clap_arg = clap_arg.value_parser(PossibleValuesParser::new(
-            values
-                .iter()
-                .map(PossibleValue::new)
-                .collect::<Vec<PossibleValue>>(),
-        ));
+    match (&arg.possible_values, arg.value_delimiter, arg.number_range) {
+        // the possible values parser lists the values in the help
+        (Some(values), None, None) => {
+            clap_arg = clap_arg.value_parser(PossibleValuesParser::new(
+                values
+                    .iter()
+                    .map(PossibleValue::new)
+                    .collect::<Vec<PossibleValue>>(),
+            ));
+        }
+        (Some(_), Some(_), _) | (_, _, Some(_)) => {
+            clap_arg = clap_arg.value_parser(validate_values(
+                arg.value_delimiter,
+                arg.possible_values.clone(),
+                arg.number_range,
+            ));
+        }
+        (None, _, None) => {}
     }
 
     clap_arg = match (arg.min_values, arg.max_values) {
@@ -277,6 +814,402 @@ fn get_arg(arg_name: String, arg: &Arg) -> ClapArg {
     clap_arg = bind_if_arg!(arg, clap_arg, required_if_eq);
     clap_arg = bind_value_arg!(arg, clap_arg, require_equals);
     clap_arg = bind_value_arg!(arg, clap_arg, index);
+    if let Some(env) = &arg.env {
+        // the help would show the value, possibly a secret
+        clap_arg = clap_arg.env(env.clone()).hide_env_values(true);
+    }
 
     clap_arg
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(config: &Config, args: &[&str]) -> std::result::Result<Matches, clap::Error> {
+        let app = get_app("", "1.0.0".into(), "app".into(), None, config);
+        try_get_matches_from(app, config, std::iter::once(&"app").chain(args))
+    }
+
+    fn flags_config() -> Config {
+        serde_json::from_value(serde_json::json!({
+            "args": [
+                { "name": "all", "short": "a" },
+                { "name": "brief", "short": "b" },
+                { "name": "color", "short": "c" },
+                { "name": "output", "short": "o", "takesValue": true },
+                { "name": "level", "short": "l", "takesValue": true, "requireEquals": true }
+            ],
+            "subcommands": {
+                "run": {
+                    "args": [
+                        { "name": "quiet", "short": "q" },
+                        { "name": "target", "short": "t", "takesValue": true }
+                    ]
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn value<'a>(matches: &'a Matches, name: &str) -> &'a Value {
+        &matches.args[name].value
+    }
+
+    #[test]
+    fn grouped_booleans() {
+        let matches = parse(&flags_config(), &["-abc"]).unwrap();
+        for name in ["all", "brief", "color"] {
+            assert_eq!(value(&matches, name), &Value::Bool(true));
+        }
+
+        let matches = parse(&flags_config(), &["-ab", "-c"]).unwrap();
+        assert_eq!(value(&matches, "color"), &Value::Bool(true));
+        assert_eq!(value(&matches, "output"), &Value::Null);
+    }
+
+    #[test]
+    fn short_values() {
+        for args in [&["-ofile"][..], &["-o=file"], &["-o", "file"]] {
+            let matches = parse(&flags_config(), args).unwrap();
+            assert_eq!(value(&matches, "output"), &Value::String("file".into()));
+        }
+    }
+
+    #[test]
+    fn long_values() {
+        for args in [&["--output=file"][..], &["--output", "file"]] {
+            let matches = parse(&flags_config(), args).unwrap();
+            assert_eq!(value(&matches, "output"), &Value::String("file".into()));
+        }
+        let matches = parse(&flags_config(), &["--level=2"]).unwrap();
+        assert_eq!(value(&matches, "level"), &Value::String("2".into()));
+    }
+
+    #[test]
+    fn value_ends_group() {
+        for args in [&["-abo", "file"][..], &["-abo=file"]] {
+            let matches = parse(&flags_config(), args).unwrap();
+            assert_eq!(value(&matches, "all"), &Value::Bool(true));
+            assert_eq!(value(&matches, "brief"), &Value::Bool(true));
+            assert_eq!(value(&matches, "output"), &Value::String("file".into()));
+        }
+
+        let matches = parse(&flags_config(), &["-abl=2"]).unwrap();
+        assert_eq!(value(&matches, "level"), &Value::String("2".into()));
+    }
+
+    #[test]
+    fn value_in_group_middle() {
+        let error = parse(&flags_config(), &["-aob"]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ArgumentConflict);
+        assert!(error.to_string().contains("`-o` takes a value"));
+
+        assert!(parse(&flags_config(), &["-aoc", "file"]).is_err());
+        assert!(parse(&flags_config(), &["-alb=2"]).is_err());
+    }
+
+    #[test]
+    fn subcommand_groups() {
+        let matches = parse(&flags_config(), &["-a", "run", "-qt", "x86"]).unwrap();
+        let subcommand = matches.subcommand.unwrap();
+        assert_eq!(subcommand.name, "run");
+        assert_eq!(
+            value(&subcommand.matches, "target"),
+            &Value::String("x86".into())
+        );
+
+        // `-t` takes `q` as its value
+        let matches = parse(&flags_config(), &["run", "-tq"]).unwrap();
+        assert_eq!(
+            value(&matches.subcommand.unwrap().matches, "target"),
+            &Value::String("q".into())
+        );
+        assert!(parse(&flags_config(), &["run", "-qtq"]).is_err());
+    }
+
+    fn delimited_config() -> Config {
+        serde_json::from_value(serde_json::json!({
+            "args": [
+                { "name": "tags", "takesValue": true, "valueDelimiter": "," },
+                { "name": "path", "takesValue": true, "multiple": true, "valueDelimiter": ";" },
+                {
+                    "name": "mode",
+                    "takesValue": true,
+                    "valueDelimiter": ",",
+                    "possibleValues": ["read", "write"]
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn strings(values: &[&str]) -> Value {
+        Value::Array(
+            values
+                .iter()
+                .map(|v| Value::String(v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn value_delimiter() {
+        let matches = parse(&delimited_config(), &["--tags", "a,b,c"]).unwrap();
+        assert_eq!(value(&matches, "tags"), &strings(&["a", "b", "c"]));
+        assert_eq!(matches.args["tags"].occurrences, 1);
+        assert_eq!(value(&matches, "path"), &Value::Null);
+
+        let matches = parse(&delimited_config(), &["--tags=a"]).unwrap();
+        assert_eq!(value(&matches, "tags"), &strings(&["a"]));
+
+        let matches = parse(&delimited_config(), &["--path", "a;b", "--path", "c"]).unwrap();
+        assert_eq!(value(&matches, "path"), &strings(&["a", "b", "c"]));
+        assert_eq!(matches.args["path"].occurrences, 2);
+    }
+
+    #[test]
+    fn escaped_value_delimiter() {
+        let matches = parse(&delimited_config(), &["--tags", r#"a\,b,"c,d",e\f"#]).unwrap();
+        assert_eq!(value(&matches, "tags"), &strings(&["a,b", "c,d", r"e\f"]));
+    }
+
+    #[test]
+    fn delimited_possible_values() {
+        let matches = parse(&delimited_config(), &["--mode", "read,write"]).unwrap();
+        assert_eq!(value(&matches, "mode"), &strings(&["read", "write"]));
+
+        let error = parse(&delimited_config(), &["--mode", "read,exec"]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+    }
+
+    fn validated_config() -> Config {
+        serde_json::from_value(serde_json::json!({
+            "args": [
+                { "name": "port", "takesValue": true, "numberRange": [1, 65535] },
+                { "name": "ids", "takesValue": true, "valueDelimiter": ",", "numberRange": [0, 9] },
+                { "name": "mode", "takesValue": true, "choices": ["fast", "safe"] }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn number_range_and_choices() {
+        let matches = parse(
+            &validated_config(),
+            &["--port", "8080", "--ids", "1,2", "--mode", "safe"],
+        )
+        .unwrap();
+        assert_eq!(value(&matches, "port"), &Value::String("8080".into()));
+        assert_eq!(value(&matches, "ids"), &strings(&["1", "2"]));
+
+        for (args, rejected) in [
+            (&["--port", "0"][..], "0"),
+            (&["--port", "http"], "http"),
+            (&["--ids", "1,10"], "10"),
+            (&["--mode", "slow"], "slow"),
+        ] {
+            let error = invalid_value(&parse(&validated_config(), args).unwrap_err());
+            assert!(
+                matches!(&error, Some(Error::InvalidArgValue { value, .. }) if value == rejected),
+                "{args:?}: {error:?}"
+            );
+        }
+        assert!(invalid_value(&parse(&validated_config(), &["--unknown"]).unwrap_err()).is_none());
+    }
+
+    fn with_default_config() -> Config {
+        serde_json::from_value(serde_json::json!({
+            "args": [{ "name": "all", "short": "a" }],
+            "defaultSubcommand": "run",
+            "subcommands": {
+                "run": {
+                    "args": [{ "name": "quiet", "short": "q" }],
+                    "defaultSubcommand": "fast",
+                    "subcommands": { "fast": {}, "slow": {} }
+                },
+                "build": {}
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn default_subcommand() {
+        let matches = parse(&with_default_config(), &[]).unwrap();
+        assert_eq!(matches.active_subcommand_path(), ["run", "fast"]);
+
+        let matches = parse(&with_default_config(), &["-q"]).unwrap();
+        assert_eq!(matches.active_subcommand_path(), ["run", "fast"]);
+        let run = &matches.subcommand.unwrap().matches;
+        assert_eq!(value(run, "quiet"), &Value::Bool(true));
+
+        let matches = parse(&with_default_config(), &["run", "slow"]).unwrap();
+        assert_eq!(matches.active_subcommand_path(), ["run", "slow"]);
+        let matches = parse(&with_default_config(), &["build"]).unwrap();
+        assert_eq!(matches.active_subcommand_path(), ["build"]);
+
+        // the arguments of the command are kept before its default subcommand
+        let matches = parse(&with_default_config(), &["-a"]).unwrap();
+        assert_eq!(matches.active_subcommand_path(), ["run", "fast"]);
+        assert_eq!(value(&matches, "all"), &Value::Bool(true));
+
+        assert!(parse(&with_default_config(), &["--unknown"]).is_err());
+    }
+
+    #[test]
+    fn active_subcommand_path() {
+        let matches = parse(&with_default_config(), &["run", "-q", "slow"]).unwrap();
+        assert_eq!(matches.active_subcommand_path(), ["run", "slow"]);
+        let run = &matches.subcommand.as_ref().unwrap().matches;
+        assert_eq!(run.active_subcommand_path(), ["slow"]);
+
+        let matches = parse(&flags_config(), &["-a"]).unwrap();
+        assert!(matches.active_subcommand_path().is_empty());
+    }
+
+    fn stdin_config() -> Config {
+        serde_json::from_value(serde_json::json!({
+            "args": [{ "name": "password-stdin", "fromStdin": true }],
+            "subcommands": {
+                "login": { "args": [{ "name": "token-stdin", "fromStdin": true }] }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn value_from_stdin() {
+        let config = stdin_config();
+        let parse_stdin = |args: &[&str], stdin: &[u8]| {
+            let mut matches = parse(&config, args)?;
+            read_stdin_value(&config, &mut matches, &Stdin::default(), stdin)?;
+            Ok::<_, clap::Error>(matches)
+        };
+        let matches = parse_stdin(&["--password-stdin"], b"secret\r\n").unwrap();
+        assert_eq!(
+            value(&matches, "password-stdin"),
+            &Value::String("secret".into())
+        );
+
+        let matches = parse_stdin(&["login", "--token-stdin"], b"multi\nline\n").unwrap();
+        let login = &matches.subcommand.unwrap().matches;
+        assert_eq!(
+            value(login, "token-stdin"),
+            &Value::String("multi\nline".into())
+        );
+
+        // stdin is not read without the flag
+        let matches = parse_stdin(&[], b"").unwrap();
+        assert_eq!(value(&matches, "password-stdin"), &Value::Bool(false));
+
+        let error = parse_stdin(&["--password-stdin"], b"").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::EmptyValue);
+        let error =
+            parse_stdin(&["--password-stdin", "login", "--token-stdin"], b"secret").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn stdin_is_read_once() {
+        let config = stdin_config();
+        let stdin = Stdin::default();
+        for input in [&b"secret\n"[..], &b""[..]] {
+            let mut matches = parse(&config, &["--password-stdin"]).unwrap();
+            read_stdin_value(&config, &mut matches, &stdin, input).unwrap();
+            assert_eq!(
+                value(&matches, "password-stdin"),
+                &Value::String("secret".into())
+            );
+        }
+    }
+
+    fn env_config() -> Config {
+        serde_json::from_value(serde_json::json!({
+            "args": [
+                { "name": "token", "takesValue": true, "env": "TAURI_PLUGIN_CLI_TEST_TOKEN" },
+                {
+                    "name": "tags",
+                    "takesValue": true,
+                    "valueDelimiter": ",",
+                    "env": "TAURI_PLUGIN_CLI_TEST_TAGS"
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn env_fallback() {
+        let matches = parse(&env_config(), &[]).unwrap();
+        assert_eq!(value(&matches, "token"), &Value::Null);
+        assert!(!matches.args["token"].from_env);
+
+        std::env::set_var("TAURI_PLUGIN_CLI_TEST_TOKEN", "from-env");
+        std::env::set_var("TAURI_PLUGIN_CLI_TEST_TAGS", "a,b");
+        let matches = parse(&env_config(), &[]).unwrap();
+        assert_eq!(value(&matches, "token"), &Value::String("from-env".into()));
+        assert!(matches.args["token"].from_env);
+        assert_eq!(value(&matches, "tags"), &strings(&["a", "b"]));
+
+        // the command line takes precedence
+        let matches = parse(&env_config(), &["--token", "given"]).unwrap();
+        assert_eq!(value(&matches, "token"), &Value::String("given".into()));
+        assert!(!matches.args["token"].from_env);
+        std::env::remove_var("TAURI_PLUGIN_CLI_TEST_TOKEN");
+        std::env::remove_var("TAURI_PLUGIN_CLI_TEST_TAGS");
+    }
+
+    #[test]
+    fn completions_cover_subcommands() {
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "args": [{ "name": "verbose", "short": "v" }],
+            "subcommands": {
+                "db": { "subcommands": { "migrate": {} } }
+            }
+        }))
+        .unwrap();
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let app = get_app("", "1.0.0".into(), "app".into(), None, &config);
+            let script = completions(app, "my-app", shell).unwrap();
+            assert!(script.contains("my-app"), "{shell}");
+            assert!(script.contains("migrate"), "{shell}");
+            assert!(script.contains("verbose"), "{shell}");
+        }
+    }
+
+    #[test]
+    fn help_and_version_outcomes() {
+        let matches = outcome_matches(&parse(&flags_config(), &["--help"]).unwrap_err()).unwrap();
+        let Outcome::Help(help) = &matches.outcome else {
+            panic!("expected the help, got {:?}", matches.outcome);
+        };
+        assert!(help.contains("--output"));
+        assert_eq!(value(&matches, "help"), &Value::String(help.clone()));
+
+        let matches =
+            outcome_matches(&parse(&flags_config(), &["run", "--help"]).unwrap_err()).unwrap();
+        assert!(matches!(&matches.outcome, Outcome::Help(help) if help.contains("--target")));
+
+        let matches =
+            outcome_matches(&parse(&flags_config(), &["--version"]).unwrap_err()).unwrap();
+        assert!(matches!(&matches.outcome, Outcome::Version(version) if version.contains("1.0.0")));
+
+        assert!(outcome_matches(&parse(&flags_config(), &["--unknown"]).unwrap_err()).is_none());
+        assert_eq!(
+            parse(&flags_config(), &["-a"]).unwrap().outcome,
+            Outcome::Parsed
+        );
+    }
+
+    #[test]
+    fn default_subcommand_help() {
+        for args in [&["--help"][..], &["-h"]] {
+            let error = parse(&with_default_config(), args).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::DisplayHelp);
+        }
+        let error = parse(&with_default_config(), &["--version"]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::DisplayVersion);
+    }
+}
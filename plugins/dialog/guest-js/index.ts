@@ -8,6 +8,20 @@ declare global {
   }
 }
 
+/**
+ * Shows a dialog with the command, which resolves with the ID of its request
+ * without waiting for the dialog, then waits for the response of that request.
+ *
+ * Several dialogs can be in flight, closed in any order.
+ */
+async function request<T>(cmd: string, args: unknown): Promise<T> {
+  const id = await window.__TAURI_INVOKE__<number>(
+    `plugin:dialog|${cmd}`,
+    args
+  );
+  return await window.__TAURI_INVOKE__<T>("plugin:dialog|response", { id });
+}
+
 interface FileResponse {
   base64Data?: string;
   duration?: number;
@@ -173,7 +187,7 @@ async function open(
     Object.freeze(options);
   }
 
-  return window.__TAURI_INVOKE__("plugin:dialog|open", { options });
+  return await request("open", { options });
 }
 
 /**
@@ -205,7 +219,7 @@ async function save(options: SaveDialogOptions = {}): Promise<string | null> {
     Object.freeze(options);
   }
 
-  return window.__TAURI_INVOKE__("plugin:dialog|save", { options });
+  return await request("save", { options });
 }
 
 /**
@@ -230,7 +244,7 @@ async function message(
   options?: string | MessageDialogOptions
 ): Promise<void> {
   const opts = typeof options === "string" ? { title: options } : options;
-  return window.__TAURI_INVOKE__("plugin:dialog|message", {
+  return await request("message", {
     message: message.toString(),
     title: opts?.title?.toString(),
     type_: opts?.type,
@@ -259,7 +273,7 @@ async function ask(
   options?: string | ConfirmDialogOptions
 ): Promise<boolean> {
   const opts = typeof options === "string" ? { title: options } : options;
-  return window.__TAURI_INVOKE__("plugin:dialog|ask", {
+  return await request("ask", {
     message: message.toString(),
     title: opts?.title?.toString(),
     type_: opts?.type,
@@ -289,7 +303,7 @@ async function confirm(
   options?: string | ConfirmDialogOptions
 ): Promise<boolean> {
   const opts = typeof options === "string" ? { title: options } : options;
-  return window.__TAURI_INVOKE__("plugin:dialog|confirm", {
+  return await request("confirm", {
     message: message.toString(),
     title: opts?.title?.toString(),
     type_: opts?.type,
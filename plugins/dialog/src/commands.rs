@@ -8,7 +8,10 @@ use serde::{Deserialize, Serialize};
 use tauri::{command, Manager, Runtime, State, Window};
 use tauri_plugin_fs::FsExt;
 
-use crate::{Dialog, FileDialogBuilder, FileResponse, MessageDialogKind, Result};
+use crate::{
+    request::{DialogRequests, RequestId},
+    Dialog, FileDialogBuilder, FileResponse, MessageDialogKind, Result,
+};
 
 #[derive(Serialize)]
 #[serde(untagged)]
@@ -86,12 +89,47 @@ fn set_default_path<R: Runtime>(
     }
 }
 
+/// Adds the selected files to the filesystem and asset protocol scopes.
+fn allow_files<'a, R: Runtime>(
+    window: &Window<R>,
+    files: impl IntoIterator<Item = &'a PathBuf>,
+) -> Result<()> {
+    for file in files {
+        if let Some(s) = window.try_fs_scope() {
+            s.allow_file(file)?;
+        }
+        window.state::<tauri::scope::Scopes>().allow_file(file)?;
+    }
+    Ok(())
+}
+
+/// Adds the selected directories to the filesystem scope.
+#[cfg(desktop)]
+fn allow_directories<'a, R: Runtime>(
+    window: &Window<R>,
+    directories: impl IntoIterator<Item = &'a PathBuf>,
+    recursive: bool,
+) -> Result<()> {
+    if let Some(s) = window.try_fs_scope() {
+        for directory in directories {
+            s.allow_directory(directory, recursive)?;
+        }
+    }
+    Ok(())
+}
+
 #[command]
 pub(crate) async fn open<R: Runtime>(
     window: Window<R>,
     dialog: State<'_, Dialog<R>>,
+    requests: State<'_, DialogRequests>,
     options: OpenDialogOptions,
-) -> Result<OpenResponse> {
+) -> Result<RequestId> {
+    #[cfg(mobile)]
+    if options.directory {
+        return Err(crate::Error::FolderPickerNotImplemented);
+    }
+
     let mut dialog_builder = dialog.file();
     #[cfg(any(windows, target_os = "macos"))]
     {
@@ -108,57 +146,28 @@ pub(crate) async fn open<R: Runtime>(
         dialog_builder = dialog_builder.add_filter(filter.name, &extensions);
     }
 
-    let res = if options.directory {
+    let (id, responder) = requests.start(&window);
+    match (options.directory, options.multiple) {
         #[cfg(desktop)]
-        {
-            if options.multiple {
-                let folders = dialog_builder.blocking_pick_folders();
-                if let Some(folders) = &folders {
-                    for folder in folders {
-                        if let Some(s) = window.try_fs_scope() {
-                            s.allow_directory(folder, options.recursive)?;
-                        }
-                    }
-                }
-                OpenResponse::Folders(folders)
-            } else {
-                let folder = dialog_builder.blocking_pick_folder();
-                if let Some(path) = &folder {
-                    if let Some(s) = window.try_fs_scope() {
-                        s.allow_directory(path, options.recursive)?;
-                    }
-                }
-                OpenResponse::Folder(folder)
-            }
-        }
-        #[cfg(mobile)]
-        return Err(crate::Error::FolderPickerNotImplemented);
-    } else if options.multiple {
-        let files = dialog_builder.blocking_pick_files();
-        if let Some(files) = &files {
-            for file in files {
-                if let Some(s) = window.try_fs_scope() {
-                    s.allow_file(&file.path)?;
-                }
-                window
-                    .state::<tauri::scope::Scopes>()
-                    .allow_file(&file.path)?;
-            }
-        }
-        OpenResponse::Files(files)
-    } else {
-        let file = dialog_builder.blocking_pick_file();
-        if let Some(file) = &file {
-            if let Some(s) = window.try_fs_scope() {
-                s.allow_file(&file.path)?;
-            }
-            window
-                .state::<tauri::scope::Scopes>()
-                .allow_file(&file.path)?;
-        }
-        OpenResponse::File(file)
-    };
-    Ok(res)
+        (true, true) => dialog_builder.pick_folders(move |folders| {
+            let allowed = allow_directories(&window, folders.iter().flatten(), options.recursive);
+            responder.respond(allowed.map(|()| OpenResponse::Folders(folders)));
+        }),
+        #[cfg(desktop)]
+        (true, false) => dialog_builder.pick_folder(move |folder| {
+            let allowed = allow_directories(&window, folder.iter(), options.recursive);
+            responder.respond(allowed.map(|()| OpenResponse::Folder(folder)));
+        }),
+        (_, true) => dialog_builder.pick_files(move |files| {
+            let allowed = allow_files(&window, files.iter().flatten().map(|file| &file.path));
+            responder.respond(allowed.map(|()| OpenResponse::Files(files)));
+        }),
+        (_, false) => dialog_builder.pick_file(move |file| {
+            let allowed = allow_files(&window, file.iter().map(|file| &file.path));
+            responder.respond(allowed.map(|()| OpenResponse::File(file)));
+        }),
+    }
+    Ok(id)
 }
 
 #[allow(unused_variables)]
@@ -166,8 +175,9 @@ pub(crate) async fn open<R: Runtime>(
 pub(crate) async fn save<R: Runtime>(
     window: Window<R>,
     dialog: State<'_, Dialog<R>>,
+    requests: State<'_, DialogRequests>,
     options: SaveDialogOptions,
-) -> Result<Option<PathBuf>> {
+) -> Result<RequestId> {
     #[cfg(mobile)]
     return Err(crate::Error::FileSaveDialogNotImplemented);
     #[cfg(desktop)]
@@ -188,27 +198,26 @@ pub(crate) async fn save<R: Runtime>(
             dialog_builder = dialog_builder.add_filter(filter.name, &extensions);
         }
 
-        let path = dialog_builder.blocking_save_file();
-        if let Some(p) = &path {
-            if let Some(s) = window.try_fs_scope() {
-                s.allow_file(p)?;
-            }
-            window.state::<tauri::scope::Scopes>().allow_file(p)?;
-        }
-
-        Ok(path)
+        let (id, responder) = requests.start(&window);
+        dialog_builder.save_file(move |path| {
+            let allowed = allow_files(&window, path.iter());
+            responder.respond(allowed.map(|()| path));
+        });
+        Ok(id)
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn message_dialog<R: Runtime>(
-    #[allow(unused_variables)] window: Window<R>,
+    window: Window<R>,
     dialog: State<'_, Dialog<R>>,
+    requests: State<'_, DialogRequests>,
     title: Option<String>,
     message: String,
     type_: Option<MessageDialogKind>,
     ok_button_label: Option<String>,
     cancel_button_label: Option<String>,
-) -> bool {
+) -> RequestId {
     let mut builder = dialog.message(message);
 
     if let Some(title) = title {
@@ -232,21 +241,25 @@ fn message_dialog<R: Runtime>(
         builder = builder.cancel_button_label(cancel);
     }
 
-    builder.blocking_show()
+    let (id, responder) = requests.start(&window);
+    builder.show(move |ok| responder.respond(Ok(ok)));
+    id
 }
 
 #[command]
 pub(crate) async fn message<R: Runtime>(
     window: Window<R>,
     dialog: State<'_, Dialog<R>>,
+    requests: State<'_, DialogRequests>,
     title: Option<String>,
     message: String,
     type_: Option<MessageDialogKind>,
     ok_button_label: Option<String>,
-) -> Result<bool> {
+) -> Result<RequestId> {
     Ok(message_dialog(
         window,
         dialog,
+        requests,
         title,
         message,
         type_,
@@ -256,18 +269,21 @@ pub(crate) async fn message<R: Runtime>(
 }
 
 #[command]
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn ask<R: Runtime>(
     window: Window<R>,
     dialog: State<'_, Dialog<R>>,
+    requests: State<'_, DialogRequests>,
     title: Option<String>,
     message: String,
     type_: Option<MessageDialogKind>,
     ok_button_label: Option<String>,
     cancel_button_label: Option<String>,
-) -> Result<bool> {
+) -> Result<RequestId> {
     Ok(message_dialog(
         window,
         dialog,
+        requests,
         title,
         message,
         type_,
@@ -277,18 +293,21 @@ pub(crate) async fn ask<R: Runtime>(
 }
 
 #[command]
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn confirm<R: Runtime>(
     window: Window<R>,
     dialog: State<'_, Dialog<R>>,
+    requests: State<'_, DialogRequests>,
     title: Option<String>,
     message: String,
     type_: Option<MessageDialogKind>,
     ok_button_label: Option<String>,
     cancel_button_label: Option<String>,
-) -> Result<bool> {
+) -> Result<RequestId> {
     Ok(message_dialog(
         window,
         dialog,
+        requests,
         title,
         message,
         type_,
@@ -296,3 +315,14 @@ pub(crate) async fn confirm<R: Runtime>(
         Some(cancel_button_label.unwrap_or_else(|| "Cancel".into())),
     ))
 }
+
+/// Waits for the dialog of a request returned by the other commands to close, resolving with
+/// its response.
+#[command]
+pub(crate) async fn response<R: Runtime>(
+    window: Window<R>,
+    requests: State<'_, DialogRequests>,
+    id: RequestId,
+) -> Result<serde_json::Value> {
+    requests.response(&window, id).await
+}
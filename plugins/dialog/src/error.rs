@@ -23,6 +23,13 @@ pub enum Error {
     FileSaveDialogNotImplemented,
     #[error(transparent)]
     Fs(#[from] tauri_plugin_fs::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// The request was already answered, or opened by another window.
+    #[error("unknown dialog request {0}")]
+    UnknownRequest(u32),
+    #[error("the dialog of request {0} closed without a response")]
+    NoResponse(u32),
 }
 
 impl Serialize for Error {
@@ -2,14 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+function showDialog(cmd, args) {
+  return window
+    .__TAURI_INVOKE__("plugin:dialog|" + cmd, args)
+    .then((id) => window.__TAURI_INVOKE__("plugin:dialog|response", { id }));
+}
+
 window.alert = function (message) {
-  window.__TAURI_INVOKE__("plugin:dialog|message", {
+  showDialog("message", {
     message: message.toString(),
   });
 };
 
 window.confirm = function (message) {
-  return window.__TAURI_INVOKE__("plugin:dialog|confirm", {
+  return showDialog("confirm", {
     message: message.toString(),
   });
 };
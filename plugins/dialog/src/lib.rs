@@ -23,8 +23,10 @@ mod mobile;
 mod commands;
 mod error;
 mod models;
+mod request;
 
 pub use error::{Error, Result};
+pub use request::RequestId;
 
 #[cfg(desktop)]
 use desktop::*;
@@ -90,7 +92,8 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             commands::save,
             commands::message,
             commands::ask,
-            commands::confirm
+            commands::confirm,
+            commands::response
         ])
         .setup(|app, api| {
             #[cfg(mobile)]
@@ -98,8 +101,19 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             #[cfg(desktop)]
             let dialog = desktop::init(app, api)?;
             app.manage(dialog);
+            app.manage(request::DialogRequests::default());
             Ok(())
         })
+        .on_event(|app, event| {
+            if let tauri::RunEvent::WindowEvent {
+                label,
+                event: tauri::WindowEvent::Destroyed,
+                ..
+            } = event
+            {
+                app.state::<request::DialogRequests>().remove(label);
+            }
+        })
         .build()
 }
 
@@ -0,0 +1,102 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The dialogs shown by the commands, each identified by a request ID.
+//!
+//! A command shows its dialog without waiting for it to close and returns the ID of the
+//! request, whose response is then awaited with the `response` command. No thread is blocked
+//! while a dialog is open, so several dialogs, from one window or many, can be in flight and
+//! closed in any order.
+
+use serde::Serialize;
+use tauri::{
+    async_runtime::{channel, Receiver, Sender},
+    Runtime, Window,
+};
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    },
+};
+
+use crate::{Error, Result};
+
+pub type RequestId = u32;
+
+type Response = Result<serde_json::Value>;
+
+struct Pending {
+    /// The label of the window that opened the dialog, the only one given its response.
+    window: String,
+    response: Receiver<Response>,
+}
+
+/// The requests whose response was not awaited yet.
+#[derive(Default)]
+pub(crate) struct DialogRequests {
+    next_id: AtomicU32,
+    pending: Mutex<HashMap<RequestId, Pending>>,
+}
+
+impl DialogRequests {
+    /// Starts a request of the window, returning its ID and the responder given to the dialog.
+    pub(crate) fn start<R: Runtime>(&self, window: &Window<R>) -> (RequestId, Responder) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        // the responder sends a single response, which never waits for capacity
+        let (tx, rx) = channel(1);
+        self.pending.lock().unwrap().insert(
+            id,
+            Pending {
+                window: window.label().into(),
+                response: rx,
+            },
+        );
+        (id, Responder(tx))
+    }
+
+    /// Waits for the dialog of the request to close.
+    pub(crate) async fn response<R: Runtime>(
+        &self,
+        window: &Window<R>,
+        id: RequestId,
+    ) -> Result<serde_json::Value> {
+        let pending = {
+            let mut pending = self.pending.lock().unwrap();
+            match pending.get(&id) {
+                Some(request) if request.window == window.label() => pending.remove(&id),
+                _ => None,
+            }
+        };
+        let Some(mut pending) = pending else {
+            return Err(Error::UnknownRequest(id));
+        };
+        pending
+            .response
+            .recv()
+            .await
+            .unwrap_or(Err(Error::NoResponse(id)))
+    }
+
+    /// Drops the requests of a destroyed window.
+    pub(crate) fn remove(&self, label: &str) {
+        self.pending
+            .lock()
+            .unwrap()
+            .retain(|_, request| request.window != label);
+    }
+}
+
+/// Resolves a request once its dialog closed.
+pub(crate) struct Responder(Sender<Response>);
+
+impl Responder {
+    pub(crate) fn respond<T: Serialize>(self, response: Result<T>) {
+        let response = response.and_then(|r| serde_json::to_value(r).map_err(Into::into));
+        // the window may be gone
+        let _ = self.0.try_send(response);
+    }
+}
@@ -46,6 +46,7 @@
  */
 
 import { BaseDirectory } from "@tauri-apps/api/path";
+import { Channel } from "@tauri-apps/api/tauri";
 
 declare global {
   interface Window {
@@ -168,6 +169,60 @@ interface FsOptions {
   // note that adding fields here needs a change in the writeBinaryFile check
 }
 
+/**
+ * @since 2.0.0
+ */
+interface FsWriteOptions extends FsOptions {
+  /**
+   * Append to the file instead of replacing its contents, creating it if
+   * missing. The contents are written at once, so concurrent appends are not
+   * interleaved. Defaults to `false`.
+   */
+  append?: boolean;
+}
+
+/**
+ * @since 2.0.0
+ */
+interface ExistsOptions extends FsOptions {
+  /** Whether a symbolic link to a missing path exists. Defaults to `false`. */
+  noFollowSymlinks?: boolean;
+}
+
+/**
+ * @since 2.0.0
+ */
+interface FsTextOptions extends FsOptions {
+  /**
+   * The [label](https://encoding.spec.whatwg.org/#names-and-labels) of the file's text encoding,
+   * e.g. `latin1` or `shift_jis`. Defaults to UTF-8.
+   *
+   * The UTF-16 byte order is given by the label: `utf-16be` or `utf-16le`, with `utf-16` meaning little endian.
+   */
+  encoding?: string;
+  /**
+   * When writing, append to the file instead of replacing its contents.
+   * Defaults to `false`.
+   */
+  append?: boolean;
+  /**
+   * When writing UTF-8 or UTF-16, start the file with a byte order mark.
+   * It is not written when appending to a non-empty file. Defaults to `false`.
+   */
+  bom?: boolean;
+}
+
+/**
+ * A text read with its malformed sequences replaced by U+FFFD.
+ *
+ * @since 2.0.0
+ */
+interface LossyText {
+  text: string;
+  /** The number of replaced sequences. */
+  replacements: number;
+}
+
 /**
  * @since 2.0.0
  */
@@ -217,19 +272,21 @@ interface FileEntry {
 }
 
 /**
- * Reads a file as an UTF-8 encoded string.
+ * Reads a file as a string, decoding it as UTF-8 unless another `encoding` is given.
  * @example
  * ```typescript
  * import { readTextFile, BaseDirectory } from '@tauri-apps/plugin-fs';
  * // Read the text file in the `$APPCONFIG/app.conf` path
  * const contents = await readTextFile('app.conf', { dir: BaseDirectory.AppConfig });
+ * // Read a legacy Shift-JIS file
+ * const legacy = await readTextFile('legacy.txt', { dir: BaseDirectory.AppData, encoding: 'shift_jis' });
  * ```
  *
  * @since 2.0.0
  */
 async function readTextFile(
   filePath: string,
-  options: FsOptions = {}
+  options: FsTextOptions = {}
 ): Promise<string> {
   return await window.__TAURI_INVOKE__("plugin:fs|read_text_file", {
     path: filePath,
@@ -237,6 +294,27 @@ async function readTextFile(
   });
 }
 
+/**
+ * Reads a file as a string like {@link readTextFile}, replacing the malformed sequences with U+FFFD
+ * instead of rejecting, and returns the number of replaced sequences.
+ * @example
+ * ```typescript
+ * import { readTextFileLossy, BaseDirectory } from '@tauri-apps/plugin-fs';
+ * const { text, replacements } = await readTextFileLossy('legacy.txt', { dir: BaseDirectory.AppData });
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function readTextFileLossy(
+  filePath: string,
+  options: FsTextOptions = {}
+): Promise<LossyText> {
+  return await window.__TAURI_INVOKE__("plugin:fs|read_text_file_lossy", {
+    path: filePath,
+    options,
+  });
+}
+
 /**
  * Reads a file as byte array.
  * @example
@@ -261,12 +339,14 @@ async function readBinaryFile(
 }
 
 /**
- * Writes a UTF-8 text file.
+ * Writes a text file, encoded as UTF-8 unless another `encoding` is given.
  * @example
  * ```typescript
  * import { writeTextFile, BaseDirectory } from '@tauri-apps/plugin-fs';
  * // Write a text file to the `$APPCONFIG/app.conf` path
  * await writeTextFile('app.conf', 'file contents', { dir: BaseDirectory.AppConfig });
+ * // Write a Latin-1 file
+ * await writeTextFile('legacy.txt', 'café', { dir: BaseDirectory.AppData, encoding: 'latin1' });
  * ```
  *
  * @since 2.0.0
@@ -274,7 +354,7 @@ async function readBinaryFile(
 async function writeTextFile(
   path: string,
   contents: string,
-  options?: FsOptions
+  options?: FsTextOptions
 ): Promise<void>;
 
 /**
@@ -291,7 +371,7 @@ async function writeTextFile(
  */
 async function writeTextFile(
   file: FsTextFileOption,
-  options?: FsOptions
+  options?: FsTextOptions
 ): Promise<void>;
 
 /**
@@ -303,8 +383,8 @@ async function writeTextFile(
  */
 async function writeTextFile(
   path: string | FsTextFileOption,
-  contents?: string | FsOptions,
-  options?: FsOptions
+  contents?: string | FsTextOptions,
+  options?: FsTextOptions
 ): Promise<void> {
   if (typeof options === "object") {
     Object.freeze(options);
@@ -314,7 +394,7 @@ async function writeTextFile(
   }
 
   const file: FsTextFileOption = { path: "", contents: "" };
-  let fileOptions: FsOptions | undefined = options;
+  let fileOptions: FsTextOptions | undefined = options;
   if (typeof path === "string") {
     file.path = path;
   } else {
@@ -328,9 +408,9 @@ async function writeTextFile(
     fileOptions = contents;
   }
 
-  return await window.__TAURI_INVOKE__("plugin:fs|write_file", {
+  return await window.__TAURI_INVOKE__("plugin:fs|write_text_file", {
     path: file.path,
-    contents: Array.from(new TextEncoder().encode(file.contents)),
+    contents: file.contents,
     options: fileOptions,
   });
 }
@@ -352,7 +432,7 @@ async function writeTextFile(
 async function writeBinaryFile(
   path: string,
   contents: BinaryFileContents,
-  options?: FsOptions
+  options?: FsWriteOptions
 ): Promise<void>;
 
 /**
@@ -372,7 +452,7 @@ async function writeBinaryFile(
  */
 async function writeBinaryFile(
   file: FsBinaryFileOption,
-  options?: FsOptions
+  options?: FsWriteOptions
 ): Promise<void>;
 
 /**
@@ -384,8 +464,8 @@ async function writeBinaryFile(
  */
 async function writeBinaryFile(
   path: string | FsBinaryFileOption,
-  contents?: BinaryFileContents | FsOptions,
-  options?: FsOptions
+  contents?: BinaryFileContents | FsWriteOptions,
+  options?: FsWriteOptions
 ): Promise<void> {
   if (typeof options === "object") {
     Object.freeze(options);
@@ -395,7 +475,7 @@ async function writeBinaryFile(
   }
 
   const file: FsBinaryFileOption = { path: "", contents: [] };
-  let fileOptions: FsOptions | undefined = options;
+  let fileOptions: FsWriteOptions | undefined = options;
   if (typeof path === "string") {
     file.path = path;
   } else {
@@ -403,7 +483,7 @@ async function writeBinaryFile(
     file.contents = path.contents;
   }
 
-  if (contents && "dir" in contents) {
+  if (contents && ("dir" in contents || "append" in contents)) {
     fileOptions = contents;
   } else if (typeof path === "string") {
     // @ts-expect-error in this case `contents` is always a BinaryFileContents
@@ -421,6 +501,30 @@ async function writeBinaryFile(
   });
 }
 
+/**
+ * Writes each line followed by the platform newline, e.g. to build a CSV
+ * file or a log.
+ * @example
+ * ```typescript
+ * import { writeLines, BaseDirectory } from '@tauri-apps/plugin-fs';
+ * await writeLines('app.log', ['started', 'ready'], { dir: BaseDirectory.AppLog, append: true });
+ * ```
+ * @returns A promise indicating the success or failure of the operation.
+ *
+ * @since 2.0.0
+ */
+async function writeLines(
+  path: string,
+  lines: string[],
+  options?: FsTextOptions
+): Promise<void> {
+  return await window.__TAURI_INVOKE__("plugin:fs|write_lines", {
+    path,
+    lines,
+    options,
+  });
+}
+
 /**
  * List directory files.
  * @example
@@ -476,24 +580,68 @@ async function createDir(
   });
 }
 
+/**
+ * Options for {@link removeDir}.
+ *
+ * @since 2.0.0
+ */
+interface RemoveDirOptions extends FsDirOptions {
+  /**
+   * Whether to only list the entries that would be removed. Defaults to `false`.
+   */
+  dryRun?: boolean;
+}
+
+/**
+ * An entry that could not be read or removed by {@link removeDir}.
+ *
+ * @since 2.0.0
+ */
+interface RemoveDirError {
+  path: string;
+  error: string;
+}
+
+/**
+ * The entries removed by {@link removeDir}, or that would be removed by a dry run.
+ *
+ * @since 2.0.0
+ */
+interface RemoveDirReport {
+  /**
+   * The removed entries, each directory listed after its contents and the root last.
+   */
+  entries: string[];
+  /**
+   * The entries that could not be read or removed, which are kept with their parents.
+   */
+  errors: RemoveDirError[];
+}
+
 /**
  * Removes a directory.
  * If the directory is not empty and the `recursive` option isn't set to true, the promise will be rejected.
+ *
+ * A recursive removal goes on when an entry can not be removed, reporting its error.
+ * With the `dryRun` option, the entries are only listed.
+ * The root of the file system, the home directory and the roots of the scope are never removed.
  * @example
  * ```typescript
  * import { removeDir, BaseDirectory } from '@tauri-apps/plugin-fs';
+ * // List the entries of the `$APPDATA/users` directory
+ * const { entries } = await removeDir('users', { dir: BaseDirectory.AppData, recursive: true, dryRun: true });
  * // Remove the directory `$APPDATA/users`
- * await removeDir('users', { dir: BaseDirectory.AppData });
+ * await removeDir('users', { dir: BaseDirectory.AppData, recursive: true });
  * ```
  *
- * @returns A promise indicating the success or failure of the operation.
+ * @returns A promise resolving to the removed entries.
  *
  * @since 2.0.0
  */
 async function removeDir(
   dir: string,
-  options: FsDirOptions = {}
-): Promise<void> {
+  options: RemoveDirOptions = {}
+): Promise<RemoveDirReport> {
   return await window.__TAURI_INVOKE__("plugin:fs|remove_dir", {
     path: dir,
     options,
@@ -548,8 +696,21 @@ async function removeFile(
   });
 }
 
+/**
+ * @since 2.0.0
+ */
+interface MoveProgress {
+  /** The number of bytes copied so far. */
+  progress: number;
+  /** The size of the file. */
+  total: number;
+}
+
 /**
  * Renames a file.
+ *
+ * If the paths are on different filesystems the file is copied, keeping its permissions and timestamps,
+ * and the source is only removed once the copy is complete.
  * @example
  * ```typescript
  * import { renameFile, BaseDirectory } from '@tauri-apps/plugin-fs';
@@ -557,6 +718,7 @@ async function removeFile(
  * await renameFile('avatar.png', 'deleted.png', { dir: BaseDirectory.AppData });
  * ```
  *
+ * @param onProgress Called with the copy progress when the file is moved to another filesystem.
  * @returns A promise indicating the success or failure of the operation.
  *
  * @since 2.0.0
@@ -564,17 +726,86 @@ async function removeFile(
 async function renameFile(
   oldPath: string,
   newPath: string,
-  options: FsOptions = {}
+  options: FsOptions = {},
+  onProgress?: (progress: MoveProgress) => void
 ): Promise<void> {
+  const channel = new Channel<MoveProgress>();
+  if (onProgress != null) {
+    channel.onmessage = onProgress;
+  }
   return await window.__TAURI_INVOKE__("plugin:fs|rename_file", {
     oldPath,
     newPath,
     options,
+    onProgress: channel,
+  });
+}
+
+/**
+ * @since 2.0.0
+ */
+interface DiskUsageOptions {
+  /** The base directory of the path. */
+  dir?: BaseDirectory;
+  /**
+   * Whether to walk the targets of the symbolic links, if they are in scope.
+   * Each directory is only counted once, even if several links lead to it. Defaults to `false`.
+   */
+  followSymlinks?: boolean;
+}
+
+/**
+ * The size and number of entries of a directory tree.
+ *
+ * @since 2.0.0
+ */
+interface DiskUsage {
+  /** The sum of the file sizes, in bytes. */
+  bytes: number;
+  /** The number of files. */
+  files: number;
+  /** The number of directories, not counting the root. */
+  dirs: number;
+  /** The number of entries that could not be read or are out of scope. */
+  skipped: number;
+}
+
+/**
+ * Computes the total size and the number of files and directories of a directory tree.
+ * @example
+ * ```typescript
+ * import { du, BaseDirectory } from '@tauri-apps/plugin-fs';
+ * // Compute the size of the `$APPDATA/cache` directory
+ * const usage = await du('cache', { dir: BaseDirectory.AppData }, (partial) => {
+ *   console.log(`${partial.files} files so far`);
+ * });
+ * console.log(`${usage.bytes} bytes in ${usage.files} files and ${usage.dirs} directories`);
+ * ```
+ *
+ * @param onProgress Called periodically with the totals computed so far.
+ * @returns A promise resolving to the totals of the tree.
+ *
+ * @since 2.0.0
+ */
+async function du(
+  path: string,
+  options: DiskUsageOptions = {},
+  onProgress?: (usage: DiskUsage) => void
+): Promise<DiskUsage> {
+  const channel = new Channel<DiskUsage>();
+  if (onProgress != null) {
+    channel.onmessage = onProgress;
+  }
+  return await window.__TAURI_INVOKE__("plugin:fs|du", {
+    path,
+    options,
+    onProgress: channel,
   });
 }
 
 /**
- * Check if a path exists.
+ * Check if a path exists, following symbolic links unless `noFollowSymlinks` is set.
+ * Resolves to `false` instead of rejecting when the path is missing.
  * @example
  * ```typescript
  * import { exists, BaseDirectory } from '@tauri-apps/plugin-fs';
@@ -584,8 +815,80 @@ async function renameFile(
  *
  * @since 2.0.0
  */
-async function exists(path: string): Promise<boolean> {
-  return await window.__TAURI_INVOKE__("plugin:fs|exists", { path });
+async function exists(
+  path: string,
+  options: ExistsOptions = {}
+): Promise<boolean> {
+  return await window.__TAURI_INVOKE__("plugin:fs|exists", { path, options });
+}
+
+/**
+ * Check if a path is an existing directory, following symbolic links.
+ * @example
+ * ```typescript
+ * import { isDir, BaseDirectory } from '@tauri-apps/plugin-fs';
+ * // Check if the `$APPDATA/db` directory exists
+ * await isDir('db', { dir: BaseDirectory.AppData });
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function isDir(path: string, options: FsOptions = {}): Promise<boolean> {
+  return await window.__TAURI_INVOKE__("plugin:fs|is_dir", { path, options });
+}
+
+/**
+ * Check if a path is an existing regular file, following symbolic links.
+ * @example
+ * ```typescript
+ * import { isFile, BaseDirectory } from '@tauri-apps/plugin-fs';
+ * // Check if the `$APPDATA/avatar.png` file exists
+ * await isFile('avatar.png', { dir: BaseDirectory.AppData });
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function isFile(path: string, options: FsOptions = {}): Promise<boolean> {
+  return await window.__TAURI_INVOKE__("plugin:fs|is_file", { path, options });
+}
+
+/**
+ * Resolves the absolute path of a base directory, e.g. to show where a file
+ * will be saved.
+ * Rejects when neither the directory nor its entries are allowed by the scope.
+ * @example
+ * ```typescript
+ * import { resolveDir, BaseDirectory } from '@tauri-apps/plugin-fs';
+ * const downloads = await resolveDir(BaseDirectory.Download);
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function resolveDir(base: BaseDirectory): Promise<string> {
+  return await window.__TAURI_INVOKE__("plugin:fs|resolve_dir", { base });
+}
+
+/**
+ * Guesses the content type of a file from its first bytes, then from its
+ * extension, e.g. to decide how to preview it.
+ * Resolves to `application/octet-stream` when the type is unknown.
+ * @example
+ * ```typescript
+ * import { mimeType, BaseDirectory } from '@tauri-apps/plugin-fs';
+ * // Get the content type of the `$APPDATA/avatar` file
+ * const type = await mimeType('avatar', { dir: BaseDirectory.AppData });
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function mimeType(
+  path: string,
+  options: FsOptions = {}
+): Promise<string> {
+  return await window.__TAURI_INVOKE__("plugin:fs|mime_type", {
+    path,
+    options,
+  });
 }
 
 /**
@@ -609,9 +912,163 @@ async function metadata(path: string): Promise<Metadata> {
     });
 }
 
+/**
+ * @since 2.0.0
+ */
+interface LockOptions extends FsOptions {
+  /** Take an exclusive lock instead of a shared one. */
+  exclusive?: boolean;
+  /**
+   * Wait until the lock is available.
+   * Otherwise the promise rejects right away if the lock is held by another handle.
+   */
+  blocking?: boolean;
+}
+
+/**
+ * Takes an advisory lock on a file, creating it if needed.
+ *
+ * The lock is only honored by processes that also lock the file,
+ * and is released by {@link unlock} or when the application exits.
+ * @example
+ * ```typescript
+ * import { lock, unlock, BaseDirectory } from '@tauri-apps/plugin-fs';
+ * await lock('downloads.lock', { dir: BaseDirectory.AppData, exclusive: true });
+ * // ...
+ * await unlock('downloads.lock', { dir: BaseDirectory.AppData });
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function lock(path: string, options: LockOptions = {}): Promise<void> {
+  return await window.__TAURI_INVOKE__("plugin:fs|lock", { path, options });
+}
+
+/**
+ * Releases a lock taken with {@link lock}.
+ *
+ * @since 2.0.0
+ */
+async function unlock(path: string, options: FsOptions = {}): Promise<void> {
+  return await window.__TAURI_INVOKE__("plugin:fs|unlock", { path, options });
+}
+
+/**
+ * How {@link open} opens a file.
+ * `write`, `readWrite` and `append` create the file if it does not exist.
+ *
+ * @since 2.0.0
+ */
+type OpenMode = "read" | "write" | "readWrite" | "append";
+
+/**
+ * The origin of a {@link seek} offset.
+ *
+ * @since 2.0.0
+ */
+type SeekMode = "start" | "current" | "end";
+
+/**
+ * Opens a file for positioned reads and writes, returning its handle.
+ *
+ * The handle stays open until {@link close} is called or the window is destroyed.
+ * @example
+ * ```typescript
+ * import { open, read, seek, close, BaseDirectory } from '@tauri-apps/plugin-fs';
+ * const handle = await open('data.bin', 'read', { dir: BaseDirectory.AppData });
+ * await seek(handle, 1024);
+ * const chunk = await read(handle, 512);
+ * await close(handle);
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function open(
+  path: string,
+  mode: OpenMode,
+  options: FsOptions = {}
+): Promise<number> {
+  return await window.__TAURI_INVOKE__("plugin:fs|open", {
+    path,
+    mode,
+    options,
+  });
+}
+
+/**
+ * Reads up to `len` bytes from the current position of the file.
+ * Fewer bytes are returned at the end of the file.
+ *
+ * @since 2.0.0
+ */
+async function read(handle: number, len: number): Promise<Uint8Array> {
+  const arr = await window.__TAURI_INVOKE__<number[]>("plugin:fs|read", {
+    handle,
+    len,
+  });
+
+  return Uint8Array.from(arr);
+}
+
+/**
+ * Writes the data at the current position of the file.
+ *
+ * @since 2.0.0
+ */
+async function write(
+  handle: number,
+  data: BinaryFileContents
+): Promise<void> {
+  return await window.__TAURI_INVOKE__("plugin:fs|write", {
+    handle,
+    data: Array.from(
+      data instanceof ArrayBuffer ? new Uint8Array(data) : data
+    ),
+  });
+}
+
+/**
+ * Moves the position of the file, returning the new position from the start of the file.
+ *
+ * @since 2.0.0
+ */
+async function seek(
+  handle: number,
+  offset: number,
+  whence: SeekMode = "start"
+): Promise<number> {
+  return await window.__TAURI_INVOKE__("plugin:fs|seek", {
+    handle,
+    offset,
+    whence,
+  });
+}
+
+/**
+ * Closes a file opened with {@link open}.
+ *
+ * @since 2.0.0
+ */
+async function close(handle: number): Promise<void> {
+  return await window.__TAURI_INVOKE__("plugin:fs|close", { handle });
+}
+
 export type {
+  OpenMode,
+  SeekMode,
+  LockOptions,
+  MoveProgress,
+  DiskUsageOptions,
+  DiskUsage,
   FsOptions,
+  FsWriteOptions,
+  ExistsOptions,
+  FsTextOptions,
+  LossyText,
   FsDirOptions,
+  RemoveDirOptions,
+  RemoveDirError,
+  RemoveDirReport,
   FsTextFileOption,
   BinaryFileContents,
   FsBinaryFileOption,
@@ -624,16 +1081,30 @@ export {
   BaseDirectory,
   BaseDirectory as Dir,
   readTextFile,
+  readTextFileLossy,
   readBinaryFile,
   writeTextFile,
   writeTextFile as writeFile,
   writeBinaryFile,
+  writeLines,
   readDir,
   createDir,
   removeDir,
   copyFile,
   removeFile,
   renameFile,
+  du,
   exists,
+  isDir,
+  isFile,
+  mimeType,
+  resolveDir,
   metadata,
+  lock,
+  unlock,
+  open,
+  read,
+  write,
+  seek,
+  close,
 };
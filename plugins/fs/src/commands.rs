@@ -6,6 +6,7 @@ use crate::Scope;
 use anyhow::Context;
 use serde::{Deserialize, Serialize, Serializer};
 use tauri::{
+    api::ipc::Channel,
     path::{BaseDirectory, SafePathBuf},
     Manager, Runtime, Window,
 };
@@ -15,13 +16,13 @@ use std::os::unix::fs::{MetadataExt, PermissionsExt};
 #[cfg(windows)]
 use std::os::windows::fs::MetadataExt;
 use std::{
-    fs::{self, symlink_metadata, File},
+    fs::{self, symlink_metadata, File, OpenOptions},
     io::Write,
     path::{Path, PathBuf},
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use crate::{Error, FsExt, Result};
+use crate::{disk_usage, encoding, mime, move_file, remove_dir, Error, FsExt, Result};
 
 #[derive(Debug, thiserror::Error)]
 pub enum CommandError {
@@ -61,7 +62,44 @@ pub struct FileOperationOptions {
     pub dir: Option<BaseDirectory>,
 }
 
-fn resolve_path<R: Runtime>(
+/// The options for the binary file writes on the file system API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WriteFileOptions {
+    /// The base directory of the operation.
+    /// The directory path of the BaseDirectory will be the prefix of the defined file path.
+    pub dir: Option<BaseDirectory>,
+    /// Whether to append to the file instead of replacing its contents.
+    #[serde(default)]
+    pub append: bool,
+}
+
+/// The options for the text file functions on the file system API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TextFileOperationOptions {
+    /// The base directory of the operation.
+    /// The directory path of the BaseDirectory will be the prefix of the defined file path.
+    pub dir: Option<BaseDirectory>,
+    /// The label of the text encoding, e.g. `latin1` or `shift_jis`. Defaults to UTF-8.
+    pub encoding: Option<String>,
+    /// Whether a write appends to the file instead of replacing its contents.
+    #[serde(default)]
+    pub append: bool,
+    /// Whether a write starts with the byte order mark of UTF-8 and UTF-16.
+    /// It is not written when appending to a non-empty file.
+    #[serde(default)]
+    pub bom: bool,
+}
+
+/// A text read with its malformed sequences replaced by U+FFFD.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LossyText {
+    pub text: String,
+    /// The number of replaced sequences.
+    pub replacements: usize,
+}
+
+pub(crate) fn resolve_path<R: Runtime>(
     window: &Window<R>,
     path: SafePathBuf,
     dir: Option<BaseDirectory>,
@@ -93,36 +131,124 @@ pub fn read_file<R: Runtime>(
         .map_err(Into::into)
 }
 
+fn read_text<R: Runtime>(
+    window: &Window<R>,
+    path: SafePathBuf,
+    options: Option<TextFileOperationOptions>,
+) -> CommandResult<(&'static encoding_rs::Encoding, LossyText)> {
+    let (dir, label) = match options {
+        Some(o) => (o.dir, o.encoding),
+        None => (None, None),
+    };
+    let encoding = encoding::lookup(label.as_deref())?;
+    let resolved_path = resolve_path(window, path, dir)?;
+    let bytes =
+        fs::read(&resolved_path).with_context(|| format!("path: {}", resolved_path.display()))?;
+
+    let (text, replacements) = encoding::decode(encoding, &bytes);
+    Ok((encoding, LossyText { text, replacements }))
+}
+
 #[tauri::command]
 pub fn read_text_file<R: Runtime>(
     window: Window<R>,
     path: SafePathBuf,
-    options: Option<FileOperationOptions>,
+    options: Option<TextFileOperationOptions>,
 ) -> CommandResult<String> {
-    let resolved_path = resolve_path(&window, path, options.and_then(|o| o.dir))?;
-    fs::read_to_string(&resolved_path)
-        .with_context(|| format!("path: {}", resolved_path.display()))
-        .map_err(Into::into)
+    let (encoding, read) = read_text(&window, path, options)?;
+    if read.replacements > 0 {
+        return Err(Error::Decode {
+            encoding: encoding.name(),
+            replacements: read.replacements,
+        }
+        .into());
+    }
+
+    Ok(read.text)
 }
 
+/// Reads a text file, replacing the malformed sequences instead of failing.
 #[tauri::command]
-pub fn write_file<R: Runtime>(
+pub fn read_text_file_lossy<R: Runtime>(
     window: Window<R>,
     path: SafePathBuf,
-    contents: Vec<u8>,
-    options: Option<FileOperationOptions>,
-) -> CommandResult<()> {
-    let resolved_path = resolve_path(&window, path, options.and_then(|o| o.dir))?;
-    File::create(&resolved_path)
-        .with_context(|| format!("path: {}", resolved_path.display()))
+    options: Option<TextFileOperationOptions>,
+) -> CommandResult<LossyText> {
+    read_text(&window, path, options).map(|(_, read)| read)
+}
+
+/// Writes the contents with a single call, so the appends of concurrent writers are not
+/// interleaved: the file is opened in append mode, where every write lands at the end.
+fn write_contents(path: &Path, contents: &[u8], append: bool) -> CommandResult<()> {
+    let file = if append {
+        OpenOptions::new().append(true).create(true).open(path)
+    } else {
+        File::create(path)
+    };
+    file.with_context(|| format!("path: {}", path.display()))
         .map_err(Into::into)
         .and_then(|mut f| {
-            f.write_all(&contents)
+            f.write_all(contents)
                 .map_err(|err| anyhow::anyhow!("{}", err))
                 .map_err(Into::into)
         })
 }
 
+#[tauri::command]
+pub fn write_file<R: Runtime>(
+    window: Window<R>,
+    path: SafePathBuf,
+    contents: Vec<u8>,
+    options: Option<WriteFileOptions>,
+) -> CommandResult<()> {
+    let (dir, append) = match options {
+        Some(o) => (o.dir, o.append),
+        None => (None, false),
+    };
+    let resolved_path = resolve_path(&window, path, dir)?;
+    write_contents(&resolved_path, &contents, append)
+}
+
+#[tauri::command]
+pub fn write_text_file<R: Runtime>(
+    window: Window<R>,
+    path: SafePathBuf,
+    contents: String,
+    options: Option<TextFileOperationOptions>,
+) -> CommandResult<()> {
+    let (dir, label, append, bom) = match options {
+        Some(o) => (o.dir, o.encoding, o.append, o.bom),
+        None => (None, None, false, false),
+    };
+    let encoding = encoding::lookup(label.as_deref())?;
+    let resolved_path = resolve_path(&window, path, dir)?;
+    // the mark only belongs at the start of the file
+    let bom = bom
+        && !(append
+            && fs::metadata(&resolved_path)
+                .map(|m| m.len() > 0)
+                .unwrap_or(false));
+    let bytes = encoding::encode(encoding, &contents, bom)?;
+    write_contents(&resolved_path, &bytes, append)
+}
+
+/// Writes each line followed by the platform newline.
+#[tauri::command]
+pub fn write_lines<R: Runtime>(
+    window: Window<R>,
+    path: SafePathBuf,
+    lines: Vec<String>,
+    options: Option<TextFileOperationOptions>,
+) -> CommandResult<()> {
+    let newline = if cfg!(windows) { "\r\n" } else { "\n" };
+    let contents = lines.iter().fold(String::new(), |mut contents, line| {
+        contents.push_str(line);
+        contents.push_str(newline);
+        contents
+    });
+    write_text_file(window, path, contents, options)
+}
+
 #[derive(Clone, Copy)]
 struct ReadDirOptions<'a> {
     pub scope: Option<&'a Scope>,
@@ -249,27 +375,77 @@ pub fn create_dir<R: Runtime>(
     Ok(())
 }
 
+/// The options for the [`remove_dir`] command.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveDirOptions {
+    /// Whether to remove the contents of the directory too.
+    #[serde(default)]
+    pub recursive: bool,
+    /// The base directory of the operation.
+    /// The directory path of the BaseDirectory will be the prefix of the defined directory path.
+    pub dir: Option<BaseDirectory>,
+    /// Whether to only list the entries that would be removed.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Removes a directory, reporting the removed entries.
+///
+/// A recursive removal goes on when an entry can not be removed, reporting its error.
+/// The root of the file system, the home directory and the roots of the scope are never removed.
 #[tauri::command]
-pub fn remove_dir<R: Runtime>(
+pub async fn remove_dir<R: Runtime>(
     window: Window<R>,
     path: SafePathBuf,
-    options: Option<DirOperationOptions>,
-) -> CommandResult<()> {
-    let (recursive, dir) = if let Some(options_value) = options {
-        (options_value.recursive, options_value.dir)
-    } else {
-        (false, None)
-    };
+    options: Option<RemoveDirOptions>,
+) -> CommandResult<remove_dir::RemoveDirReport> {
+    let (recursive, dir, dry_run) = options
+        .map(|o| (o.recursive, o.dir, o.dry_run))
+        .unwrap_or_default();
     let resolved_path = resolve_path(&window, path, dir)?;
-    if recursive {
-        fs::remove_dir_all(&resolved_path)
-            .with_context(|| format!("path: {}", resolved_path.display()))?;
-    } else {
-        fs::remove_dir(&resolved_path)
-            .with_context(|| format!("path: {} (non recursive)", resolved_path.display()))?;
+    if is_protected(&window, &resolved_path) {
+        return Err(Error::ProtectedPath(resolved_path).into());
     }
 
-    Ok(())
+    if !recursive {
+        if dry_run {
+            let mut entries = fs::read_dir(&resolved_path)
+                .with_context(|| format!("path: {} (non recursive)", resolved_path.display()))?;
+            if entries.next().is_some() {
+                return Err(Error::DirectoryNotEmpty(resolved_path).into());
+            }
+        } else {
+            fs::remove_dir(&resolved_path)
+                .with_context(|| format!("path: {} (non recursive)", resolved_path.display()))?;
+        }
+        return Ok(remove_dir::RemoveDirReport {
+            entries: vec![resolved_path],
+            errors: Vec::new(),
+        });
+    }
+
+    let scope = window.fs_scope().clone();
+    let report = tauri::async_runtime::spawn_blocking(move || {
+        remove_dir::remove_dir(&resolved_path, dry_run, |path| scope.is_allowed(path))
+            .with_context(|| format!("path: {}", resolved_path.display()))
+    })
+    .await
+    .context("the remove task panicked")??;
+    Ok(report)
+}
+
+/// Whether the directory is the root of the file system, the home directory or a root of the scope.
+fn is_protected<R: Runtime>(window: &Window<R>, path: &Path) -> bool {
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let home_dir = window
+        .path()
+        .home_dir()
+        .ok()
+        .map(|home| fs::canonicalize(&home).unwrap_or(home));
+    canonical.parent().is_none()
+        || home_dir.map_or(false, |home| home == canonical)
+        || window.fs_scope().is_root(&canonical)
 }
 
 #[tauri::command]
@@ -284,34 +460,163 @@ pub fn remove_file<R: Runtime>(
     Ok(())
 }
 
+/// Renames a file, falling back to copying it when the paths are on different filesystems.
+///
+/// `on_progress` only receives the copy progress if the fallback is used.
 #[tauri::command]
-pub fn rename_file<R: Runtime>(
+pub async fn rename_file<R: Runtime>(
     window: Window<R>,
     old_path: SafePathBuf,
     new_path: SafePathBuf,
     options: Option<FileOperationOptions>,
+    on_progress: Channel<R>,
 ) -> CommandResult<()> {
-    match options.and_then(|o| o.dir) {
-        Some(dir) => {
-            let old = resolve_path(&window, old_path, Some(dir))?;
-            let new = resolve_path(&window, new_path, Some(dir))?;
-            fs::rename(&old, &new)
-                .with_context(|| format!("old: {}, new: {}", old.display(), new.display()))?
-        }
-        None => fs::rename(&old_path, &new_path)
-            .with_context(|| format!("old: {}, new: {}", old_path.display(), new_path.display()))?,
-    }
+    let (old, new) = match options.and_then(|o| o.dir) {
+        Some(dir) => (
+            resolve_path(&window, old_path, Some(dir))?,
+            resolve_path(&window, new_path, Some(dir))?,
+        ),
+        None => (
+            old_path.as_ref().to_path_buf(),
+            new_path.as_ref().to_path_buf(),
+        ),
+    };
+    // copying a large file must not hold up the async runtime
+    tauri::async_runtime::spawn_blocking(move || {
+        move_file::move_file(&old, &new, |progress| {
+            let _ = on_progress.send(&progress);
+        })
+        .with_context(|| format!("old: {}, new: {}", old.display(), new.display()))
+    })
+    .await
+    .context("the move task panicked")??;
     Ok(())
 }
 
+/// The options for the [`du`] command.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsageOptions {
+    /// The base directory of the operation.
+    /// The directory path of the BaseDirectory will be the prefix of the defined directory path.
+    pub dir: Option<BaseDirectory>,
+    /// Whether to walk the targets of the symbolic links, if they are in scope.
+    #[serde(default)]
+    pub follow_symlinks: bool,
+}
+
+/// Computes the total size and the number of files and directories of a directory tree.
+#[tauri::command]
+pub async fn du<R: Runtime>(
+    window: Window<R>,
+    path: SafePathBuf,
+    options: Option<DiskUsageOptions>,
+    on_progress: Channel<R>,
+) -> CommandResult<disk_usage::DiskUsage> {
+    let (dir, follow_symlinks) = options
+        .map(|o| (o.dir, o.follow_symlinks))
+        .unwrap_or_default();
+    let resolved_path = resolve_path(&window, path, dir)?;
+    let scope = window.fs_scope().clone();
+    let usage = tauri::async_runtime::spawn_blocking(move || {
+        disk_usage::disk_usage(
+            &resolved_path,
+            follow_symlinks,
+            |path| scope.is_allowed(path),
+            |usage| {
+                let _ = on_progress.send(usage);
+            },
+        )
+        .with_context(|| format!("path: {}", resolved_path.display()))
+    })
+    .await
+    .context("the disk usage task panicked")??;
+    Ok(usage)
+}
+
+/// The options for the [`exists`] command.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExistsOptions {
+    /// The base directory of the operation.
+    /// The directory path of the BaseDirectory will be the prefix of the defined path.
+    pub dir: Option<BaseDirectory>,
+    /// Whether a symbolic link to a missing path exists. Defaults to `false`.
+    #[serde(default)]
+    pub no_follow_symlinks: bool,
+}
+
+/// Whether the path exists, following symbolic links unless disabled.
+///
+/// Paths that can not be read, e.g. for a missing permission, do not exist.
 #[tauri::command]
 pub fn exists<R: Runtime>(
+    window: Window<R>,
+    path: SafePathBuf,
+    options: Option<ExistsOptions>,
+) -> CommandResult<bool> {
+    let (dir, no_follow_symlinks) = options
+        .map(|o| (o.dir, o.no_follow_symlinks))
+        .unwrap_or_default();
+    let resolved_path = resolve_path(&window, path, dir)?;
+    Ok(if no_follow_symlinks {
+        symlink_metadata(&resolved_path).is_ok()
+    } else {
+        resolved_path.exists()
+    })
+}
+
+/// Whether the path is an existing directory, following symbolic links.
+#[tauri::command]
+pub fn is_dir<R: Runtime>(
     window: Window<R>,
     path: SafePathBuf,
     options: Option<FileOperationOptions>,
 ) -> CommandResult<bool> {
     let resolved_path = resolve_path(&window, path, options.and_then(|o| o.dir))?;
-    Ok(resolved_path.exists())
+    Ok(resolved_path.is_dir())
+}
+
+/// Whether the path is an existing regular file, following symbolic links.
+#[tauri::command]
+pub fn is_file<R: Runtime>(
+    window: Window<R>,
+    path: SafePathBuf,
+    options: Option<FileOperationOptions>,
+) -> CommandResult<bool> {
+    let resolved_path = resolve_path(&window, path, options.and_then(|o| o.dir))?;
+    Ok(resolved_path.is_file())
+}
+
+/// Resolves the absolute path of the base directory.
+///
+/// The directory, or the entries in it, must be allowed by the scope.
+#[tauri::command]
+pub fn resolve_dir<R: Runtime>(window: Window<R>, base: BaseDirectory) -> CommandResult<PathBuf> {
+    let path: PathBuf = window
+        .path()
+        .resolve("", base)
+        .map_err(Error::CannotResolvePath)?
+        .components()
+        .collect();
+    if window.fs_scope().is_directory_allowed(&path) {
+        Ok(path)
+    } else {
+        Err(Error::BaseDirectoryForbidden(base).into())
+    }
+}
+
+/// Guesses the content type of the file from its first bytes and its extension.
+#[tauri::command]
+pub fn mime_type<R: Runtime>(
+    window: Window<R>,
+    path: SafePathBuf,
+    options: Option<FileOperationOptions>,
+) -> CommandResult<&'static str> {
+    let resolved_path = resolve_path(&window, path, options.and_then(|o| o.dir))?;
+    mime::mime_type(&resolved_path)
+        .with_context(|| format!("path: {}", resolved_path.display()))
+        .map_err(Into::into)
 }
 
 #[derive(Serialize)]
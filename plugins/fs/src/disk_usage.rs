@@ -0,0 +1,144 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Serialize;
+
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+use std::{
+    collections::HashSet,
+    fs::{self, Metadata},
+    io,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// The minimum delay between two progress reports.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The size and number of entries of a directory tree.
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsage {
+    /// The sum of the file sizes, in bytes.
+    pub bytes: u64,
+    /// The number of files.
+    pub files: u64,
+    /// The number of directories, not counting the root.
+    pub dirs: u64,
+    /// The number of entries that could not be read or are out of scope.
+    pub skipped: u64,
+}
+
+/// Computes the disk usage of `root`.
+///
+/// Symbolic links are counted as files unless `follow_symlinks` is set. When following them,
+/// each directory is only walked once, so a link to one of its parents does not loop.
+/// On Unix, hard links to the same file are also only counted once.
+/// `is_allowed` filters the canonical paths of the followed symbolic links.
+pub fn disk_usage<A: Fn(&Path) -> bool, F: FnMut(&DiskUsage)>(
+    root: &Path,
+    follow_symlinks: bool,
+    is_allowed: A,
+    mut on_progress: F,
+) -> io::Result<DiskUsage> {
+    let mut usage = DiskUsage::default();
+    let mut visited = HashSet::new();
+    #[cfg(unix)]
+    let mut hard_links = HashSet::new();
+    let mut last_progress = Instant::now();
+
+    let root_metadata = entry_metadata(root, follow_symlinks)?;
+    if !root_metadata.is_dir() {
+        usage.files = 1;
+        usage.bytes = root_metadata.len();
+        return Ok(usage);
+    }
+    visited.insert(directory_id(root, &root_metadata)?);
+
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) if dir != root => {
+                usage.skipped += 1;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        for entry in entries {
+            let Ok(path) = entry.map(|e| e.path()) else {
+                usage.skipped += 1;
+                continue;
+            };
+            let path = if follow_symlinks && path.is_symlink() {
+                // the scope applies to the link target, which may be anywhere
+                match fs::canonicalize(&path) {
+                    Ok(target) if is_allowed(&target) => target,
+                    // e.g. a dangling link
+                    _ => {
+                        usage.skipped += 1;
+                        continue;
+                    }
+                }
+            } else {
+                path
+            };
+            let Ok(metadata) = entry_metadata(&path, follow_symlinks) else {
+                usage.skipped += 1;
+                continue;
+            };
+
+            if metadata.is_dir() {
+                match directory_id(&path, &metadata) {
+                    Ok(id) if visited.insert(id) => {
+                        usage.dirs += 1;
+                        pending.push(path);
+                    }
+                    Ok(_) => {}
+                    Err(_) => usage.skipped += 1,
+                }
+            } else {
+                #[cfg(unix)]
+                if metadata.nlink() > 1 && !hard_links.insert((metadata.dev(), metadata.ino())) {
+                    continue;
+                }
+                usage.files += 1;
+                usage.bytes += metadata.len();
+            }
+        }
+
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            on_progress(&usage);
+            last_progress = Instant::now();
+        }
+    }
+
+    Ok(usage)
+}
+
+fn entry_metadata(path: &Path, follow_symlinks: bool) -> io::Result<Metadata> {
+    if follow_symlinks {
+        fs::metadata(path)
+    } else {
+        fs::symlink_metadata(path)
+    }
+}
+
+/// Identifies a directory regardless of the links leading to it.
+#[cfg(unix)]
+type DirectoryId = (u64, u64);
+#[cfg(not(unix))]
+type DirectoryId = std::path::PathBuf;
+
+#[cfg(unix)]
+fn directory_id(_path: &Path, metadata: &Metadata) -> io::Result<DirectoryId> {
+    Ok((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn directory_id(path: &Path, _metadata: &Metadata) -> io::Result<DirectoryId> {
+    fs::canonicalize(path)
+}
@@ -0,0 +1,133 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use encoding_rs::{DecoderResult, Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+use crate::{Error, Result};
+
+/// Looks up an encoding by its [WHATWG label](https://encoding.spec.whatwg.org/#names-and-labels),
+/// defaulting to UTF-8 when no label is given.
+pub fn lookup(label: Option<&str>) -> Result<&'static Encoding> {
+    match label {
+        Some(label) => Encoding::for_label(label.trim().as_bytes())
+            .ok_or_else(|| Error::UnknownEncoding(label.into())),
+        None => Ok(UTF_8),
+    }
+}
+
+/// Decodes `bytes`, removing the BOM of the encoding and replacing malformed sequences with U+FFFD.
+///
+/// Returns the decoded text and the number of replaced sequences.
+pub fn decode(encoding: &'static Encoding, bytes: &[u8]) -> (String, usize) {
+    let mut decoder = encoding.new_decoder_with_bom_removal();
+    let mut text = String::with_capacity(
+        decoder
+            .max_utf8_buffer_length_without_replacement(bytes.len())
+            .unwrap_or(bytes.len()),
+    );
+    let mut replacements = 0;
+    let mut input = bytes;
+
+    loop {
+        let (result, read) = decoder.decode_to_string_without_replacement(input, &mut text, true);
+        input = &input[read..];
+        match result {
+            DecoderResult::InputEmpty => break,
+            DecoderResult::OutputFull => text.reserve(
+                decoder
+                    .max_utf8_buffer_length_without_replacement(input.len())
+                    .unwrap_or(input.len()),
+            ),
+            DecoderResult::Malformed(_, _) => {
+                replacements += 1;
+                text.push(char::REPLACEMENT_CHARACTER);
+            }
+        }
+    }
+
+    (text, replacements)
+}
+
+/// Encodes `text`, failing if it contains characters the encoding can not represent.
+///
+/// With `bom`, the text is prefixed with the byte order mark of the UTF-8 and UTF-16 encodings.
+pub fn encode(encoding: &'static Encoding, text: &str, bom: bool) -> Result<Vec<u8>> {
+    // the WHATWG encoders output UTF-8 for UTF-16, so it is encoded here
+    let utf16: Option<fn(u16) -> [u8; 2]> = if encoding == UTF_16LE {
+        Some(u16::to_le_bytes)
+    } else if encoding == UTF_16BE {
+        Some(u16::to_be_bytes)
+    } else {
+        None
+    };
+    if let Some(to_bytes) = utf16 {
+        return Ok(bom
+            .then_some(0xfeff)
+            .into_iter()
+            .chain(text.encode_utf16())
+            .flat_map(to_bytes)
+            .collect());
+    }
+
+    let (bytes, used, unmappable) = encoding.encode(text);
+    if unmappable {
+        return Err(Error::Encode(used.name()));
+    }
+    let mut contents = Vec::with_capacity(bytes.len() + 3);
+    if bom && encoding == UTF_8 {
+        contents.extend_from_slice(b"\xef\xbb\xbf");
+    }
+    contents.extend_from_slice(&bytes);
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_utf16_with_its_endianness() {
+        let le = lookup(Some("utf-16le")).unwrap();
+        let be = lookup(Some("utf-16be")).unwrap();
+        assert_eq!(
+            encode(le, "a\u{1f600}", false).unwrap(),
+            b"a\x00\x3d\xd8\x00\xde"
+        );
+        assert_eq!(encode(be, "a", true).unwrap(), b"\xfe\xff\x00a");
+        // the `utf-16` label means little endian
+        assert_eq!(
+            encode(lookup(Some("utf-16")).unwrap(), "a", true).unwrap(),
+            b"\xff\xfea\x00"
+        );
+    }
+
+    #[test]
+    fn round_trips_with_the_bom() {
+        for label in ["utf-8", "utf-16le", "utf-16be"] {
+            let encoding = lookup(Some(label)).unwrap();
+            let bytes = encode(encoding, "café", true).unwrap();
+            assert_eq!(decode(encoding, &bytes), ("café".into(), 0), "{label}");
+        }
+        assert_eq!(encode(UTF_8, "café", false).unwrap(), "café".as_bytes());
+    }
+
+    #[test]
+    fn counts_the_replacements() {
+        assert_eq!(
+            decode(UTF_8, b"a\xffb\xfe"),
+            ("a\u{fffd}b\u{fffd}".into(), 2)
+        );
+        assert_eq!(decode(UTF_8, b"ab"), ("ab".into(), 0));
+    }
+
+    #[test]
+    fn fails_on_unmappable_characters() {
+        let latin1 = lookup(Some("latin1")).unwrap();
+        assert_eq!(encode(latin1, "café", false).unwrap(), b"caf\xe9");
+        assert!(matches!(
+            encode(latin1, "\u{1f600}", false),
+            Err(Error::Encode(_))
+        ));
+    }
+}
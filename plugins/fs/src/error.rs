@@ -14,9 +14,39 @@ pub enum Error {
     PathForbidden(PathBuf),
     #[error("failed to resolve path: {0}")]
     CannotResolvePath(tauri::path::Error),
+    /// The base directory is not allowed by the scope.
+    #[error("base directory not allowed by the scope: {0:?}")]
+    BaseDirectoryForbidden(tauri::path::BaseDirectory),
     /// Invalid glob pattern.
     #[error("invalid glob pattern: {0}")]
     GlobPattern(#[from] glob::PatternError),
+    /// Unknown text encoding label.
+    #[error("unknown encoding: {0}")]
+    UnknownEncoding(String),
+    /// The file contents are not valid in the requested encoding.
+    #[error("failed to decode file as {encoding}: {replacements} malformed sequence(s)")]
+    Decode {
+        encoding: &'static str,
+        replacements: usize,
+    },
+    /// The text contains characters the requested encoding can not represent.
+    #[error("text can not be encoded as {0}")]
+    Encode(&'static str),
+    /// The lock is held by another handle.
+    #[error("the lock on {0} is held by another handle")]
+    WouldBlock(PathBuf),
+    /// The path is already locked by the application.
+    #[error("{0} is already locked")]
+    AlreadyLocked(PathBuf),
+    /// The file handle is unknown or was opened by another window.
+    #[error("invalid file handle: {0}")]
+    InvalidHandle(u32),
+    /// The directory is the root of the file system, the home directory or a root of the scope.
+    #[error("refusing to remove {0}")]
+    ProtectedPath(PathBuf),
+    /// The directory is not empty and was not removed recursively.
+    #[error("directory not empty: {0}")]
+    DirectoryNotEmpty(PathBuf),
     /// Watcher error.
     #[cfg(feature = "watch")]
     #[error(transparent)]
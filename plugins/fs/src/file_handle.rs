@@ -0,0 +1,183 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Deserialize;
+use tauri::{command, path::SafePathBuf, Runtime, State, Window};
+
+use crate::{
+    commands::{resolve_path, FileOperationOptions},
+    Error, Result,
+};
+
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+};
+
+type Id = u32;
+
+struct Handle {
+    /// The label of the window that opened the file.
+    window: String,
+    file: Arc<Mutex<File>>,
+}
+
+/// The files opened with [`open`], closed when their window is destroyed.
+#[derive(Default)]
+pub struct FileHandles {
+    next_id: AtomicU32,
+    handles: Mutex<HashMap<Id, Handle>>,
+}
+
+impl FileHandles {
+    fn get(&self, window: &str, id: Id) -> Result<Arc<Mutex<File>>> {
+        match self.handles.lock().unwrap().get(&id) {
+            Some(handle) if handle.window == window => Ok(handle.file.clone()),
+            _ => Err(Error::InvalidHandle(id)),
+        }
+    }
+
+    /// Closes the files opened by the window.
+    pub(crate) fn close_window(&self, window: &str) {
+        self.handles
+            .lock()
+            .unwrap()
+            .retain(|_, handle| handle.window != window);
+    }
+
+    pub(crate) fn close_all(&self) {
+        self.handles.lock().unwrap().clear();
+    }
+}
+
+/// How a file is opened.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OpenMode {
+    /// Read only.
+    Read,
+    /// Write only, creating the file if needed.
+    Write,
+    /// Read and write, creating the file if needed.
+    ReadWrite,
+    /// Write at the end of the file, creating it if needed.
+    Append,
+}
+
+impl OpenMode {
+    fn options(self) -> OpenOptions {
+        let mut options = OpenOptions::new();
+        match self {
+            Self::Read => options.read(true),
+            Self::Write => options.write(true).create(true),
+            Self::ReadWrite => options.read(true).write(true).create(true),
+            Self::Append => options.append(true).create(true),
+        };
+        options
+    }
+}
+
+/// The origin of a [`seek`] offset.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SeekMode {
+    #[default]
+    Start,
+    Current,
+    End,
+}
+
+#[command]
+pub async fn open<R: Runtime>(
+    window: Window<R>,
+    handles: State<'_, FileHandles>,
+    path: SafePathBuf,
+    mode: OpenMode,
+    options: Option<FileOperationOptions>,
+) -> Result<Id> {
+    let path = resolve_path(&window, path, options.and_then(|o| o.dir))?;
+    let file = mode.options().open(path)?;
+
+    let id = handles.next_id.fetch_add(1, Ordering::Relaxed);
+    handles.handles.lock().unwrap().insert(
+        id,
+        Handle {
+            window: window.label().into(),
+            file: Arc::new(Mutex::new(file)),
+        },
+    );
+    Ok(id)
+}
+
+/// Reads up to `len` bytes from the current position, fewer at the end of the file.
+#[command]
+pub async fn read<R: Runtime>(
+    window: Window<R>,
+    handles: State<'_, FileHandles>,
+    handle: Id,
+    len: u64,
+) -> Result<Vec<u8>> {
+    let file = handles.get(window.label(), handle)?;
+    let mut file = file.lock().unwrap();
+    let mut buffer = Vec::new();
+    (&mut *file).take(len).read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+#[command]
+pub async fn write<R: Runtime>(
+    window: Window<R>,
+    handles: State<'_, FileHandles>,
+    handle: Id,
+    data: Vec<u8>,
+) -> Result<()> {
+    let file = handles.get(window.label(), handle)?;
+    let mut file = file.lock().unwrap();
+    file.write_all(&data)?;
+    Ok(())
+}
+
+/// Moves the position of the file and returns it, counted from the start of the file.
+#[command]
+pub async fn seek<R: Runtime>(
+    window: Window<R>,
+    handles: State<'_, FileHandles>,
+    handle: Id,
+    offset: i64,
+    whence: Option<SeekMode>,
+) -> Result<u64> {
+    let position = match whence.unwrap_or_default() {
+        SeekMode::Start => SeekFrom::Start(u64::try_from(offset).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "can not seek before the start of the file",
+            )
+        })?),
+        SeekMode::Current => SeekFrom::Current(offset),
+        SeekMode::End => SeekFrom::End(offset),
+    };
+
+    let file = handles.get(window.label(), handle)?;
+    let mut file = file.lock().unwrap();
+    file.seek(position).map_err(Into::into)
+}
+
+/// Closes the file. Closing an unknown handle is a no-op.
+#[command]
+pub async fn close<R: Runtime>(
+    window: Window<R>,
+    handles: State<'_, FileHandles>,
+    handle: Id,
+) -> Result<()> {
+    let mut map = handles.handles.lock().unwrap();
+    if matches!(map.get(&handle), Some(h) if h.window == window.label()) {
+        map.remove(&handle);
+    }
+    Ok(())
+}
@@ -10,7 +10,14 @@ use tauri::{
 
 mod commands;
 mod config;
+mod disk_usage;
+mod encoding;
 mod error;
+mod file_handle;
+mod lock;
+mod mime;
+mod move_file;
+mod remove_dir;
 mod scope;
 #[cfg(feature = "watch")]
 mod watcher;
@@ -42,7 +49,10 @@ pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
         .invoke_handler(tauri::generate_handler![
             commands::read_file,
             commands::read_text_file,
+            commands::read_text_file_lossy,
             commands::write_file,
+            commands::write_text_file,
+            commands::write_lines,
             commands::read_dir,
             commands::copy_file,
             commands::create_dir,
@@ -50,7 +60,19 @@ pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
             commands::remove_file,
             commands::rename_file,
             commands::exists,
+            commands::is_dir,
+            commands::is_file,
+            commands::mime_type,
+            commands::resolve_dir,
+            commands::du,
             commands::metadata,
+            lock::lock,
+            lock::unlock,
+            file_handle::open,
+            file_handle::read,
+            file_handle::write,
+            file_handle::seek,
+            file_handle::close,
             #[cfg(feature = "watch")]
             watcher::watch,
             #[cfg(feature = "watch")]
@@ -65,19 +87,20 @@ pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
                     .map(|c| &c.scope)
                     .unwrap_or(&default_scope),
             )?);
+            app.manage(lock::FileLocks::default());
+            app.manage(file_handle::FileHandles::default());
 
             #[cfg(feature = "watch")]
             app.manage(watcher::WatcherCollection::default());
 
             Ok(())
         })
-        .on_event(|app, event| {
-            if let RunEvent::WindowEvent {
+        .on_event(|app, event| match event {
+            RunEvent::WindowEvent {
                 label: _,
                 event: WindowEvent::FileDrop(FileDropEvent::Dropped(paths)),
                 ..
-            } = event
-            {
+            } => {
                 let scope = app.fs_scope();
                 for path in paths {
                     if path.is_file() {
@@ -87,6 +110,15 @@ pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
                     }
                 }
             }
+            RunEvent::WindowEvent {
+                label,
+                event: WindowEvent::Destroyed,
+                ..
+            } => {
+                app.state::<file_handle::FileHandles>().close_window(label);
+            }
+            RunEvent::Exit => app.state::<file_handle::FileHandles>().close_all(),
+            _ => {}
         })
         .build()
 }
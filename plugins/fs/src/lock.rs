@@ -0,0 +1,124 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use fs4::FileExt;
+use serde::Deserialize;
+use tauri::{
+    command,
+    path::{BaseDirectory, SafePathBuf},
+    Runtime, State, Window,
+};
+
+use crate::{commands::resolve_path, Error, Result};
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fs::{File, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// The files locked by the application.
+///
+/// The locks are held by the open handles, so removing a handle releases its lock.
+/// A path is reserved with `None` while its lock is being taken.
+#[derive(Default)]
+pub struct FileLocks(Mutex<HashMap<PathBuf, Option<File>>>);
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LockOptions {
+    /// Whether to take an exclusive lock instead of a shared one.
+    #[serde(default)]
+    exclusive: bool,
+    /// Whether to wait for a contended lock instead of failing with [`Error::WouldBlock`].
+    #[serde(default)]
+    blocking: bool,
+    /// The base directory of the operation.
+    dir: Option<BaseDirectory>,
+}
+
+fn is_contended(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::WouldBlock
+        || error.raw_os_error() == fs4::lock_contended_error().raw_os_error()
+}
+
+#[command]
+pub async fn lock<R: Runtime>(
+    window: Window<R>,
+    locks: State<'_, FileLocks>,
+    path: SafePathBuf,
+    options: Option<LockOptions>,
+) -> Result<()> {
+    let options = options.unwrap_or_default();
+    let path = resolve_path(&window, path, options.dir)?;
+
+    // a second handle to the same file would contend with our own lock
+    match locks.0.lock().unwrap().entry(path.clone()) {
+        Entry::Occupied(_) => return Err(Error::AlreadyLocked(path)),
+        Entry::Vacant(entry) => {
+            entry.insert(None);
+        }
+    }
+
+    let result = take_lock(&path, options.exclusive, options.blocking).await;
+    let mut locks = locks.0.lock().unwrap();
+    match (result, locks.get_mut(&path)) {
+        (Ok(file), Some(reserved @ None)) => *reserved = Some(file),
+        // the path was unlocked meanwhile, dropping the handle releases the lock
+        (Ok(_), _) => (),
+        (Err(e), reserved) => {
+            if matches!(reserved, Some(None)) {
+                locks.remove(&path);
+            }
+            return Err(e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn take_lock(path: &Path, exclusive: bool, blocking: bool) -> Result<File> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .open(path)?;
+
+    // a blocking lock may wait indefinitely, so it must not hold up the async runtime
+    let file = tauri::async_runtime::spawn_blocking(move || {
+        match (exclusive, blocking) {
+            (true, true) => file.lock_exclusive(),
+            (true, false) => file.try_lock_exclusive(),
+            (false, true) => file.lock_shared(),
+            (false, false) => file.try_lock_shared(),
+        }
+        .map(|_| file)
+    })
+    .await
+    .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?
+    .map_err(|e| {
+        if is_contended(&e) {
+            Error::WouldBlock(path.to_path_buf())
+        } else {
+            e.into()
+        }
+    })?;
+
+    Ok(file)
+}
+
+#[command]
+pub async fn unlock<R: Runtime>(
+    window: Window<R>,
+    locks: State<'_, FileLocks>,
+    path: SafePathBuf,
+    options: Option<LockOptions>,
+) -> Result<()> {
+    let path = resolve_path(&window, path, options.and_then(|o| o.dir))?;
+    if let Some(Some(file)) = locks.0.lock().unwrap().remove(&path) {
+        file.unlock()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,256 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+/// The content type of the files that could not be identified.
+const OCTET_STREAM: &str = "application/octet-stream";
+
+const ZIP: &str = "application/zip";
+
+/// The number of bytes read from the start of the file to find its signature.
+const SNIFF_LEN: u64 = 512;
+
+/// File signatures: the offset of the magic bytes, the magic bytes and the content type.
+const SIGNATURES: &[(usize, &[u8], &str)] = &[
+    (0, b"\x89PNG\r\n\x1a\n", "image/png"),
+    (0, b"\xff\xd8\xff", "image/jpeg"),
+    (0, b"GIF87a", "image/gif"),
+    (0, b"GIF89a", "image/gif"),
+    (0, b"\x00\x00\x01\x00", "image/x-icon"),
+    (0, b"II*\x00", "image/tiff"),
+    (0, b"MM\x00*", "image/tiff"),
+    (0, b"\x1a\x45\xdf\xa3", "video/webm"),
+    (0, b"OggS", "audio/ogg"),
+    (0, b"fLaC", "audio/flac"),
+    (0, b"ID3", "audio/mpeg"),
+    (0, b"\xff\xfb", "audio/mpeg"),
+    (0, b"%PDF-", "application/pdf"),
+    (0, b"PK\x03\x04", ZIP),
+    (0, b"PK\x05\x06", ZIP),
+    (0, b"\x1f\x8b", "application/gzip"),
+    (0, b"BZh", "application/x-bzip2"),
+    (0, b"\xfd7zXZ\x00", "application/x-xz"),
+    (0, b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    (0, b"Rar!\x1a\x07", "application/vnd.rar"),
+    (257, b"ustar", "application/x-tar"),
+    (0, b"\x00asm", "application/wasm"),
+    (0, b"SQLite format 3\x00", "application/vnd.sqlite3"),
+    (0, b"wOFF", "font/woff"),
+    (0, b"wOF2", "font/woff2"),
+    (0, b"\x00\x01\x00\x00\x00", "font/ttf"),
+    (0, b"OTTO", "font/otf"),
+];
+
+/// The content types of the RIFF containers, by the form type at offset 8.
+const RIFF_FORMS: &[(&[u8], &str)] = &[
+    (b"WEBP", "image/webp"),
+    (b"WAVE", "audio/wav"),
+    (b"AVI ", "video/x-msvideo"),
+];
+
+/// The content types of the ISO base media files, by the major brand of the `ftyp` box.
+const FTYP_BRANDS: &[(&[u8], &str)] = &[
+    (b"avif", "image/avif"),
+    (b"avis", "image/avif"),
+    (b"heic", "image/heic"),
+    (b"heix", "image/heic"),
+    (b"mif1", "image/heif"),
+    (b"msf1", "image/heif"),
+    (b"qt  ", "video/quicktime"),
+    (b"M4A ", "audio/mp4"),
+    (b"M4B ", "audio/mp4"),
+    (b"M4V ", "video/mp4"),
+    (b"isom", "video/mp4"),
+    (b"iso2", "video/mp4"),
+    (b"mp41", "video/mp4"),
+    (b"mp42", "video/mp4"),
+    (b"avc1", "video/mp4"),
+    (b"dash", "video/mp4"),
+    (b"3gp4", "video/3gpp"),
+    (b"3gp5", "video/3gpp"),
+];
+
+/// The content types of the ZIP based formats, which the extension tells apart.
+const ZIP_CONTAINERS: &[(&str, &str)] = &[
+    (
+        "docx",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    (
+        "xlsx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+    (
+        "pptx",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    ),
+    ("odt", "application/vnd.oasis.opendocument.text"),
+    ("ods", "application/vnd.oasis.opendocument.spreadsheet"),
+    ("odp", "application/vnd.oasis.opendocument.presentation"),
+    ("epub", "application/epub+zip"),
+    ("jar", "application/java-archive"),
+    ("apk", "application/vnd.android.package-archive"),
+];
+
+/// The content types by lowercase file extension.
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("txt", "text/plain"),
+    ("md", "text/markdown"),
+    ("csv", "text/csv"),
+    ("html", "text/html"),
+    ("htm", "text/html"),
+    ("css", "text/css"),
+    ("js", "text/javascript"),
+    ("mjs", "text/javascript"),
+    ("json", "application/json"),
+    ("xml", "application/xml"),
+    ("svg", "image/svg+xml"),
+    ("yaml", "application/yaml"),
+    ("yml", "application/yaml"),
+    ("toml", "application/toml"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("avif", "image/avif"),
+    ("heic", "image/heic"),
+    ("heif", "image/heif"),
+    ("bmp", "image/bmp"),
+    ("ico", "image/x-icon"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    ("mp3", "audio/mpeg"),
+    ("wav", "audio/wav"),
+    ("ogg", "audio/ogg"),
+    ("flac", "audio/flac"),
+    ("m4a", "audio/mp4"),
+    ("mp4", "video/mp4"),
+    ("mov", "video/quicktime"),
+    ("webm", "video/webm"),
+    ("avi", "video/x-msvideo"),
+    ("pdf", "application/pdf"),
+    ("zip", "application/zip"),
+    ("gz", "application/gzip"),
+    ("tar", "application/x-tar"),
+    ("7z", "application/x-7z-compressed"),
+    ("wasm", "application/wasm"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("ttf", "font/ttf"),
+    ("otf", "font/otf"),
+];
+
+/// Guesses the content type of the file from its first bytes, then from its extension,
+/// falling back to [`OCTET_STREAM`]. The ZIP based formats, e.g. `docx` or `jar`, are told
+/// apart by their extension.
+///
+/// Only the first [`SNIFF_LEN`] bytes of the file are read.
+pub fn mime_type(path: &Path) -> io::Result<&'static str> {
+    let mut header = Vec::new();
+    File::open(path)?.take(SNIFF_LEN).read_to_end(&mut header)?;
+    Ok(match sniff(&header) {
+        Some(ZIP) => lookup(ZIP_CONTAINERS, path).unwrap_or(ZIP),
+        Some(mime) => mime,
+        None => from_extension(path).unwrap_or(OCTET_STREAM),
+    })
+}
+
+fn sniff(header: &[u8]) -> Option<&'static str> {
+    if header.starts_with(b"RIFF") {
+        let form = header.get(8..12)?;
+        return RIFF_FORMS
+            .iter()
+            .find(|(magic, _)| *magic == form)
+            .map(|(_, mime)| *mime);
+    }
+    if header.get(4..8) == Some(&b"ftyp"[..]) {
+        let brand = header.get(8..12)?;
+        return FTYP_BRANDS
+            .iter()
+            .find(|(magic, _)| *magic == brand)
+            .map(|(_, mime)| *mime);
+    }
+    SIGNATURES
+        .iter()
+        .find(|(offset, magic, _)| {
+            header
+                .get(*offset..)
+                .map_or(false, |bytes| bytes.starts_with(magic))
+        })
+        .map(|(_, _, mime)| *mime)
+}
+
+fn from_extension(path: &Path) -> Option<&'static str> {
+    lookup(EXTENSIONS, path).or_else(|| lookup(ZIP_CONTAINERS, path))
+}
+
+fn lookup(table: &[(&str, &'static str)], path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    table
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, mime)| *mime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ftyp(brand: &[u8]) -> Vec<u8> {
+        let mut header = b"\x00\x00\x00\x18ftyp".to_vec();
+        header.extend(brand);
+        header.extend(b"\x00\x00\x00\x00");
+        header
+    }
+
+    #[test]
+    fn sniffs_the_ftyp_brand() {
+        assert_eq!(sniff(&ftyp(b"isom")), Some("video/mp4"));
+        assert_eq!(sniff(&ftyp(b"M4A ")), Some("audio/mp4"));
+        assert_eq!(sniff(&ftyp(b"heic")), Some("image/heic"));
+        assert_eq!(sniff(&ftyp(b"qt  ")), Some("video/quicktime"));
+        assert_eq!(sniff(&ftyp(b"crx ")), None);
+    }
+
+    #[test]
+    fn sniffs_signatures() {
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n\x00"), Some("image/png"));
+        assert_eq!(sniff(b"RIFF\x00\x00\x00\x00WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff(b"RIFF\x00\x00\x00\x00CDXA"), None);
+        assert_eq!(sniff(b"plain text"), None);
+    }
+
+    #[test]
+    fn extension_is_case_insensitive() {
+        assert_eq!(from_extension(Path::new("notes.TXT")), Some("text/plain"));
+        assert_eq!(from_extension(Path::new("notes")), None);
+    }
+
+    #[test]
+    fn zip_containers_keep_their_extension() {
+        let dir = std::env::temp_dir().join(format!("tauri-fs-mime-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let zip = b"PK\x03\x04\x14\x00\x00\x00";
+        for (name, mime) in [
+            ("archive.zip", ZIP),
+            ("archive.bin", ZIP),
+            ("library.jar", "application/java-archive"),
+            (
+                "report.docx",
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            ),
+        ] {
+            let path = dir.join(name);
+            std::fs::write(&path, zip).unwrap();
+            assert_eq!(mime_type(&path).unwrap(), mime, "{name}");
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use filetime::FileTime;
+use serde::Serialize;
+
+use std::{
+    fs::{self, File},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Progress of a file moved by copying it to another filesystem.
+#[derive(Clone, Serialize)]
+pub struct ProgressPayload {
+    pub progress: u64,
+    pub total: u64,
+}
+
+fn is_cross_device(error: &io::Error) -> bool {
+    // EXDEV on Linux and macOS
+    #[cfg(not(windows))]
+    let code = 18;
+    // ERROR_NOT_SAME_DEVICE
+    #[cfg(windows)]
+    let code = 17;
+    error.raw_os_error() == Some(code)
+}
+
+/// Moves a file, copying it and removing the source when `from` and `to` are on different filesystems.
+///
+/// The copy is written next to the destination and only renamed to it once its size matches
+/// the source, keeping the permissions and timestamps. The source is removed last,
+/// so a failure at any step leaves it untouched.
+pub fn move_file<F: FnMut(ProgressPayload)>(
+    from: &Path,
+    to: &Path,
+    on_progress: F,
+) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if is_cross_device(&e) && from.is_file() => {}
+        result => return result,
+    }
+
+    let partial = partial_path(to);
+    if let Err(e) = copy(from, &partial, on_progress).and_then(|_| fs::rename(&partial, to)) {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+
+    fs::remove_file(from)
+}
+
+fn partial_path(to: &Path) -> PathBuf {
+    let mut name = to.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.part", uuid::Uuid::new_v4()));
+    to.with_file_name(name)
+}
+
+fn copy<F: FnMut(ProgressPayload)>(from: &Path, to: &Path, mut on_progress: F) -> io::Result<()> {
+    let metadata = fs::metadata(from)?;
+    let total = metadata.len();
+
+    let mut source = File::open(from)?;
+    let mut dest = File::create(to)?;
+    let mut buffer = vec![0; CHUNK_SIZE];
+    let mut progress = 0;
+    loop {
+        let read = source.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        dest.write_all(&buffer[..read])?;
+        progress += read as u64;
+        on_progress(ProgressPayload { progress, total });
+    }
+    dest.sync_all()?;
+
+    // the source is removed afterwards, so make sure nothing was lost on the way
+    if progress != total || dest.metadata()?.len() != total {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!(
+                "copied {progress} of {total} bytes from {} to {}",
+                from.display(),
+                to.display()
+            ),
+        ));
+    }
+    drop(dest);
+
+    filetime::set_file_times(
+        to,
+        FileTime::from_last_access_time(&metadata),
+        FileTime::from_last_modification_time(&metadata),
+    )?;
+    fs::set_permissions(to, metadata.permissions())?;
+
+    Ok(())
+}
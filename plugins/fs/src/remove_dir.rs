@@ -0,0 +1,254 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Serialize;
+
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The entries removed from a directory tree, or that would be removed by a dry run.
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveDirReport {
+    /// The removed entries, each directory listed after its contents and the root last.
+    pub entries: Vec<PathBuf>,
+    /// The entries that could not be read or removed, which are kept with their parents.
+    pub errors: Vec<RemoveDirError>,
+}
+
+/// An entry that could not be read or removed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveDirError {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+enum Step {
+    /// Lists the entries of the directory.
+    Enter(PathBuf),
+    /// Removes the directory once its entries are gone.
+    Leave(PathBuf),
+}
+
+/// Removes the directory tree of `root`, going on with the other entries when one fails.
+///
+/// Symbolic links are removed, not followed. Entries rejected by `is_allowed` are kept.
+/// With `dry_run`, the entries are only listed.
+pub fn remove_dir<A: Fn(&Path) -> bool>(
+    root: &Path,
+    dry_run: bool,
+    is_allowed: A,
+) -> io::Result<RemoveDirReport> {
+    let mut report = RemoveDirReport::default();
+    // the directories with an entry that is kept
+    let mut kept = HashSet::new();
+
+    if !fs::symlink_metadata(root)?.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("{} is not a directory", root.display()),
+        ));
+    }
+
+    let mut pending = vec![Step::Enter(root.to_path_buf())];
+    while let Some(step) = pending.pop() {
+        match step {
+            Step::Enter(dir) => {
+                let entries = match fs::read_dir(&dir) {
+                    Ok(entries) => entries,
+                    Err(e) if dir == root => return Err(e),
+                    Err(e) => {
+                        report.fail(&mut kept, dir, e.to_string());
+                        continue;
+                    }
+                };
+                pending.push(Step::Leave(dir.clone()));
+
+                for entry in entries {
+                    let path = match entry {
+                        Ok(entry) => entry.path(),
+                        Err(e) => {
+                            kept.insert(dir.clone());
+                            report.fail(&mut kept, dir.clone(), e.to_string());
+                            continue;
+                        }
+                    };
+                    if !is_allowed(&path) {
+                        report.fail(&mut kept, path, "forbidden path".into());
+                        continue;
+                    }
+                    match fs::symlink_metadata(&path) {
+                        Ok(metadata) if metadata.is_dir() => pending.push(Step::Enter(path)),
+                        Ok(_) => report.remove(&mut kept, path, dry_run, remove_file),
+                        Err(e) => report.fail(&mut kept, path, e.to_string()),
+                    }
+                }
+            }
+            Step::Leave(dir) => {
+                if kept.contains(&dir) {
+                    if let Some(parent) = dir.parent() {
+                        kept.insert(parent.to_path_buf());
+                    }
+                } else {
+                    report.remove(&mut kept, dir, dry_run, |dir| fs::remove_dir(dir));
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+impl RemoveDirReport {
+    fn remove<F: FnOnce(&Path) -> io::Result<()>>(
+        &mut self,
+        kept: &mut HashSet<PathBuf>,
+        path: PathBuf,
+        dry_run: bool,
+        remove: F,
+    ) {
+        if dry_run {
+            self.entries.push(path);
+            return;
+        }
+        match remove(&path) {
+            Ok(()) => self.entries.push(path),
+            Err(e) => self.fail(kept, path, e.to_string()),
+        }
+    }
+
+    /// Records the error of an entry, which keeps its parent.
+    fn fail(&mut self, kept: &mut HashSet<PathBuf>, path: PathBuf, error: String) {
+        if let Some(parent) = path.parent() {
+            kept.insert(parent.to_path_buf());
+        }
+        self.errors.push(RemoveDirError { path, error });
+    }
+}
+
+fn remove_file(path: &Path) -> io::Result<()> {
+    let result = fs::remove_file(path);
+    // a link to a directory is removed as a directory
+    #[cfg(windows)]
+    if result.is_err() && path.is_dir() {
+        return fs::remove_dir(path);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates `root/a/b/file.txt`, `root/a/other.txt` and `root/top.txt`.
+    fn tree(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("tauri-fs-remove-dir-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("a").join("b")).unwrap();
+        fs::write(root.join("a").join("b").join("file.txt"), "").unwrap();
+        fs::write(root.join("a").join("other.txt"), "").unwrap();
+        fs::write(root.join("top.txt"), "").unwrap();
+        root
+    }
+
+    fn position(report: &RemoveDirReport, path: &Path) -> usize {
+        report
+            .entries
+            .iter()
+            .position(|entry| entry == path)
+            .unwrap_or_else(|| panic!("{} was not removed", path.display()))
+    }
+
+    #[test]
+    fn removes_contents_before_their_directory() {
+        let root = tree("all");
+        let report = remove_dir(&root, false, |_| true).unwrap();
+
+        assert!(!root.exists());
+        assert!(report.errors.is_empty());
+        assert_eq!(report.entries.len(), 6);
+        assert_eq!(report.entries.last(), Some(&root));
+        let (a, b) = (root.join("a"), root.join("a").join("b"));
+        assert!(position(&report, &b.join("file.txt")) < position(&report, &b));
+        assert!(position(&report, &b) < position(&report, &a));
+        assert!(position(&report, &a.join("other.txt")) < position(&report, &a));
+    }
+
+    #[test]
+    fn dry_run_only_lists_the_entries() {
+        let root = tree("dry-run");
+        let report = remove_dir(&root, true, |_| true).unwrap();
+
+        assert!(report.errors.is_empty());
+        assert_eq!(report.entries.len(), 6);
+        assert_eq!(report.entries.last(), Some(&root));
+        for entry in &report.entries {
+            assert!(entry.exists(), "{}", entry.display());
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn keeps_the_parents_of_forbidden_entries() {
+        let root = tree("forbidden");
+        let forbidden = root.join("a").join("b").join("file.txt");
+        let report = remove_dir(&root, false, |path| path != forbidden).unwrap();
+
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].path, forbidden);
+        assert_eq!(report.errors[0].error, "forbidden path");
+        // the other entries are removed
+        assert!(forbidden.exists());
+        assert!(!root.join("a").join("other.txt").exists());
+        assert!(!root.join("top.txt").exists());
+        // the root files are removed before its directories are entered
+        assert_eq!(
+            report.entries,
+            vec![root.join("top.txt"), root.join("a").join("other.txt")]
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn dry_run_reports_the_errors_too() {
+        let root = tree("dry-run-forbidden");
+        let forbidden = root.join("a");
+        let report = remove_dir(&root, true, |path| path != forbidden).unwrap();
+
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].path, forbidden);
+        // the root keeps the forbidden directory
+        assert_eq!(report.entries, vec![root.join("top.txt")]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn fails_on_a_file() {
+        let root = tree("file");
+        assert!(remove_dir(&root.join("top.txt"), false, |_| true).is_err());
+        assert!(remove_dir(&root.join("missing"), false, |_| true).is_err());
+        assert!(root.join("top.txt").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn removes_links_without_following_them() {
+        let root = tree("link");
+        let target = tree("link-target");
+        std::os::unix::fs::symlink(&target, root.join("link")).unwrap();
+        let report = remove_dir(&root, false, |_| true).unwrap();
+
+        assert!(report.errors.is_empty());
+        assert!(report.entries.contains(&root.join("link")));
+        assert!(!root.exists());
+        assert!(target.join("a").join("b").join("file.txt").exists());
+        fs::remove_dir_all(&target).unwrap();
+    }
+}
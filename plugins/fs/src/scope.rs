@@ -211,15 +211,7 @@ impl Scope {
 
         if let Ok(path) = path {
             let path: PathBuf = path.components().collect();
-            let options = glob::MatchOptions {
-                // this is needed so `/dir/*` doesn't match files within subdirectories such as `/dir/subdir/file.txt`
-                // see: https://github.com/tauri-apps/tauri/security/advisories/GHSA-6mv3-wm7j-h4w5
-                require_literal_separator: true,
-                // dotfiles are not supposed to be exposed by default
-                #[cfg(unix)]
-                require_literal_leading_dot: true,
-                ..Default::default()
-            };
+            let options = match_options();
 
             let forbidden = self
                 .forbidden_patterns
@@ -243,6 +235,97 @@ impl Scope {
             false
         }
     }
+
+    /// Determines if the given directory, or all the entries in it, are allowed on this scope.
+    ///
+    /// The entries are allowed by a pattern ending with `*` or `**` under the directory, such as
+    /// `$APPDATA/**`, while a pattern only allowing some names such as `$APPDATA/*.json` or
+    /// `$APPDATA/e*` does not allow the directory.
+    pub fn is_directory_allowed<P: AsRef<Path>>(&self, path: P) -> bool {
+        let path = path.as_ref();
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let path: PathBuf = path.components().collect();
+        let covers_entries = |patterns: &Mutex<HashSet<Pattern>>| {
+            patterns
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|p| matches_entries(p, &path))
+        };
+        self.is_allowed(&path)
+            || (!covers_entries(&self.forbidden_patterns) && covers_entries(&self.allowed_patterns))
+    }
+
+    /// Determines if the given directory is the root of an allowed pattern, such as `$APPDATA` for
+    /// `$APPDATA/**`, or a directory allowed with [`Self::allow_directory`].
+    pub fn is_root<P: AsRef<Path>>(&self, path: P) -> bool {
+        let path = path.as_ref();
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let path: PathBuf = path.components().collect();
+        self.allowed_patterns.lock().unwrap().iter().any(|p| {
+            match entries_root(p) {
+                Some((root, _)) => root.matches_path(&path),
+                // only a literal path, which does not match its entries
+                None => literal_path(p.as_str()).map_or(false, |literal| literal == path),
+            }
+        })
+    }
+}
+
+fn match_options() -> glob::MatchOptions {
+    glob::MatchOptions {
+        // this is needed so `/dir/*` doesn't match files within subdirectories such as `/dir/subdir/file.txt`
+        // see: https://github.com/tauri-apps/tauri/security/advisories/GHSA-6mv3-wm7j-h4w5
+        require_literal_separator: true,
+        // dotfiles are not supposed to be exposed by default
+        #[cfg(unix)]
+        require_literal_leading_dot: true,
+        ..Default::default()
+    }
+}
+
+/// The directory whose entries the pattern matches, `dir` for `dir/*` or `dir/**`, and whether
+/// it matches the entries of its subdirectories too.
+fn entries_root(pattern: &Pattern) -> Option<(Pattern, bool)> {
+    let pattern = pattern.as_str();
+    let (root, recursive) = match pattern.strip_suffix("**") {
+        Some(root) => (root, true),
+        None => (pattern.strip_suffix('*')?, false),
+    };
+    let root = root.strip_suffix(['/', MAIN_SEPARATOR])?;
+    Some((Pattern::new(root).ok()?, recursive))
+}
+
+/// Whether the pattern matches all the entries of the directory.
+fn matches_entries(pattern: &Pattern, dir: &Path) -> bool {
+    let options = match_options();
+    entries_root(pattern).map_or(false, |(root, recursive)| {
+        // a recursive pattern matching the directory matches its entries too
+        root.matches_path_with(dir, options)
+            || (recursive && pattern.matches_path_with(dir, options))
+    })
+}
+
+/// The path matched by a pattern without wildcards, unescaping the characters escaped with
+/// [`Pattern::escape`].
+fn literal_path(pattern: &str) -> Option<PathBuf> {
+    let mut path = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' | '?' => return None,
+            // an escaped character is the only one in its brackets
+            '[' => {
+                let escaped = chars.next()?;
+                if chars.next()? != ']' {
+                    return None;
+                }
+                path.push(escaped);
+            }
+            c => path.push(c),
+        }
+    }
+    Some(path.into())
 }
 
 fn escaped_pattern(p: &str) -> Result<Pattern, glob::PatternError> {
@@ -365,4 +448,80 @@ mod tests {
             assert!(scope.is_allowed("C:\\home\\tauri\\anyfile"));
         }
     }
+
+    #[test]
+    fn directory_is_allowed() {
+        let scope = new_scope();
+        #[cfg(unix)]
+        {
+            scope.allow_file("/home/tauri").unwrap();
+            scope
+                .allowed_patterns
+                .lock()
+                .unwrap()
+                .insert(glob::Pattern::new("/home/app/**").unwrap());
+            scope
+                .allowed_patterns
+                .lock()
+                .unwrap()
+                .insert(glob::Pattern::new("/home/other/*.json").unwrap());
+            scope
+                .allowed_patterns
+                .lock()
+                .unwrap()
+                .insert(glob::Pattern::new("/home/named/entry").unwrap());
+            scope
+                .allowed_patterns
+                .lock()
+                .unwrap()
+                .insert(glob::Pattern::new("/home/prefixed/e*").unwrap());
+            assert!(scope.is_directory_allowed("/home/tauri"));
+            assert!(scope.is_directory_allowed("/home/app"));
+            assert!(scope.is_directory_allowed("/home/app/nested"));
+            assert!(!scope.is_directory_allowed("/home/other"));
+            assert!(!scope.is_directory_allowed("/home/named"));
+            assert!(!scope.is_directory_allowed("/home/prefixed"));
+            assert!(!scope.is_directory_allowed("/home"));
+
+            scope.forbid_directory("/home/app", true).unwrap();
+            assert!(!scope.is_directory_allowed("/home/app"));
+        }
+    }
+
+    #[test]
+    fn root_is_detected() {
+        let scope = new_scope();
+        #[cfg(unix)]
+        {
+            scope.allow_directory("/home/tauri", true).unwrap();
+            scope
+                .allowed_patterns
+                .lock()
+                .unwrap()
+                .insert(glob::Pattern::new("/home/app/*.json").unwrap());
+            scope
+                .allowed_patterns
+                .lock()
+                .unwrap()
+                .insert(glob::Pattern::new("**").unwrap());
+            scope
+                .allowed_patterns
+                .lock()
+                .unwrap()
+                .insert(glob::Pattern::new("/home/prefixed/e*").unwrap());
+            scope
+                .allowed_patterns
+                .lock()
+                .unwrap()
+                .insert(glob::Pattern::new("/home/ent*").unwrap());
+            scope.allow_file("/home/[file]").unwrap();
+            assert!(scope.is_root("/home/tauri"));
+            assert!(!scope.is_root("/home/tauri/dir"));
+            assert!(!scope.is_root("/home/app"));
+            assert!(!scope.is_root("/home/prefixed"));
+            assert!(!scope.is_root("/home/entry"));
+            assert!(scope.is_root("/home/[file]"));
+            assert!(!scope.is_root("/home"));
+        }
+    }
 }
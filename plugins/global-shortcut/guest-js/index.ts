@@ -8,6 +8,8 @@
  * @module
  */
 
+import { Channel } from "@tauri-apps/api/tauri";
+
 declare global {
   interface Window {
     __TAURI_INVOKE__: <T>(cmd: string, args?: unknown) => Promise<T>;
@@ -19,6 +21,45 @@ declare global {
 
 export type ShortcutHandler = (shortcut: string) => void;
 
+/**
+ * Triggers the handler repeatedly while the shortcut is held.
+ * Holding a shortcut registered without it only triggers the handler once.
+ *
+ * @since 2.0.0
+ */
+export interface Repeat {
+  /** The delay between the press and the first repetition, in milliseconds. */
+  initialDelayMs: number;
+  /** The delay between two repetitions, in milliseconds. */
+  intervalMs: number;
+}
+
+/**
+ * @since 2.0.0
+ */
+export interface ShortcutOptions {
+  repeat?: Repeat;
+}
+
+/**
+ * @since 2.0.0
+ */
+export type ShortcutState = "pressed" | "released";
+
+/**
+ * The event sent to the channel given to {@link registerWithChannel}.
+ * A repetition of a shortcut held with a {@link Repeat} is sent as another press.
+ *
+ * @since 2.0.0
+ */
+export interface ShortcutEvent {
+  /** The shortcut as it was registered, e.g. `CommandOrControl+Shift+C`. */
+  accelerator: string;
+  state: ShortcutState;
+  /** The time of the event, in milliseconds since the Unix epoch. */
+  timestamp: number;
+}
+
 /**
  * Register a global shortcut.
  * @example
@@ -27,20 +68,27 @@ export type ShortcutHandler = (shortcut: string) => void;
  * await register('CommandOrControl+Shift+C', () => {
  *   console.log('Shortcut triggered');
  * });
+ * // Zoom in continuously while the shortcut is held
+ * await register('CommandOrControl+Shift+=', zoomIn, {
+ *   repeat: { initialDelayMs: 300, intervalMs: 50 },
+ * });
  * ```
  *
  * @param shortcut Shortcut definition, modifiers and key separated by "+" e.g. CmdOrControl+Q
  * @param handler Shortcut handler callback - takes the triggered shortcut as argument
+ * @param options Repeat the handler while the shortcut is held
  *
  * @since 2.0.0
  */
 async function register(
   shortcut: string,
-  handler: ShortcutHandler
+  handler: ShortcutHandler,
+  options: ShortcutOptions = {}
 ): Promise<void> {
   return await window.__TAURI_INVOKE__("plugin:globalShortcut|register", {
     shortcut,
     handler: window.__TAURI__.transformCallback(handler),
+    repeat: options.repeat,
   });
 }
 
@@ -56,19 +104,58 @@ async function register(
  *
  * @param shortcuts Array of shortcut definitions, modifiers and key separated by "+" e.g. CmdOrControl+Q
  * @param handler Shortcut handler callback - takes the triggered shortcut as argument
+ * @param options Repeat the handler while a shortcut is held
  *
  * @since 2.0.0
  */
 async function registerAll(
   shortcuts: string[],
-  handler: ShortcutHandler
+  handler: ShortcutHandler,
+  options: ShortcutOptions = {}
 ): Promise<void> {
   return await window.__TAURI_INVOKE__("plugin:globalShortcut|register_all", {
     shortcuts,
     handler: window.__TAURI__.transformCallback(handler),
+    repeat: options.repeat,
   });
 }
 
+/**
+ * Register global shortcuts that send a {@link ShortcutEvent} to the channel when pressed and released.
+ *
+ * The events carry their accelerator, so a single channel can route the events of many shortcuts.
+ * @example
+ * ```typescript
+ * import { Channel } from '@tauri-apps/api/tauri';
+ * import { registerWithChannel, ShortcutEvent } from '@tauri-apps/plugin-global-shortcut';
+ * const channel = new Channel<ShortcutEvent>();
+ * channel.onmessage = ({ accelerator, state }) => {
+ *   console.log(`Shortcut ${accelerator} ${state}`);
+ * };
+ * await registerWithChannel(['CommandOrControl+Shift+C', 'Ctrl+Alt+F12'], channel);
+ * ```
+ *
+ * @param shortcuts Array of shortcut definitions, modifiers and key separated by "+" e.g. CmdOrControl+Q
+ * @param channel The channel receiving the events of all the shortcuts
+ * @param options Repeat the press events while a shortcut is held
+ *
+ * @since 2.0.0
+ */
+async function registerWithChannel(
+  shortcuts: string[],
+  channel: Channel<ShortcutEvent>,
+  options: ShortcutOptions = {}
+): Promise<void> {
+  return await window.__TAURI_INVOKE__(
+    "plugin:globalShortcut|register_channel",
+    {
+      shortcuts,
+      channel,
+      repeat: options.repeat,
+    }
+  );
+}
+
 /**
  * Determines whether the given shortcut is registered by this application or not.
  *
@@ -122,4 +209,11 @@ async function unregisterAll(): Promise<void> {
   return await window.__TAURI_INVOKE__("plugin:globalShortcut|unregister_all");
 }
 
-export { register, registerAll, isRegistered, unregister, unregisterAll };
+export {
+  register,
+  registerAll,
+  registerWithChannel,
+  isRegistered,
+  unregister,
+  unregisterAll,
+};
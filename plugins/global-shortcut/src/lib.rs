@@ -7,13 +7,19 @@
 use std::{
     collections::HashMap,
     str::FromStr,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 pub use global_hotkey::hotkey::{Code, HotKey as Shortcut, Modifiers};
-use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager};
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+use serde::{Deserialize, Serialize};
 use tauri::{
-    api::ipc::CallbackFn,
+    api::ipc::{CallbackFn, Channel},
     plugin::{Builder as PluginBuilder, TauriPlugin},
     AppHandle, Manager, Runtime, State, Window,
 };
@@ -24,12 +30,15 @@ pub use error::Error;
 type Result<T> = std::result::Result<T, Error>;
 type HotKeyId = u32;
 type HandlerFn = Box<dyn Fn(&Shortcut) + Send + Sync + 'static>;
+type Shortcuts<R> = Arc<Mutex<HashMap<HotKeyId, RegisteredShortcut<R>>>>;
 
 enum ShortcutSource<R: Runtime> {
     Ipc {
         window: Window<R>,
         handler: CallbackFn,
     },
+    /// Receives the [`ShortcutEvent`]s of all the shortcuts registered with it.
+    Channel(Arc<Channel<R>>),
     Rust,
 }
 
@@ -40,6 +49,7 @@ impl<R: Runtime> Clone for ShortcutSource<R> {
                 window: window.clone(),
                 handler: *handler,
             },
+            Self::Channel(channel) => Self::Channel(channel.clone()),
             Self::Rust => Self::Rust,
         }
     }
@@ -60,16 +70,180 @@ impl TryFrom<&str> for ShortcutWrapper {
     }
 }
 
+/// Repeats the shortcut handler while the shortcut is held.
+///
+/// Without it, holding the shortcut only triggers the handler once.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Repeat {
+    /// The delay between the press and the first repetition, in milliseconds.
+    pub initial_delay_ms: u64,
+    /// The delay between two repetitions, in milliseconds.
+    pub interval_ms: u64,
+}
+
+/// Whether a [`ShortcutEvent`] reports a press or a release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutState {
+    Pressed,
+    Released,
+}
+
+/// The event sent to the channels given to [`GlobalShortcut::register_with_channel`].
+///
+/// A repetition of a shortcut held with a [`Repeat`] is sent as another press.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutEvent {
+    /// The shortcut as it was registered, e.g. `CommandOrControl+Shift+C`.
+    pub accelerator: String,
+    pub state: ShortcutState,
+    /// The time of the event, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
 struct RegisteredShortcut<R: Runtime> {
     source: ShortcutSource<R>,
     shortcut: (Shortcut, Option<String>),
+    repeat: Option<Repeat>,
+    /// Set while the shortcut is held, cleared on release to stop the repetitions.
+    held: Option<Arc<AtomicBool>>,
+}
+
+impl<R: Runtime> RegisteredShortcut<R> {
+    fn new(
+        source: ShortcutSource<R>,
+        shortcut: (Shortcut, Option<String>),
+        repeat: Option<Repeat>,
+    ) -> Self {
+        Self {
+            source,
+            shortcut,
+            repeat,
+            held: None,
+        }
+    }
+
+    /// What triggering the shortcut needs, so it is triggered without holding the store lock.
+    fn target(&self) -> ShortcutTarget<R> {
+        ShortcutTarget {
+            source: self.source.clone(),
+            shortcut: self.shortcut.clone(),
+        }
+    }
+
+    fn release(&mut self) {
+        if let Some(held) = self.held.take() {
+            held.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+struct ShortcutTarget<R: Runtime> {
+    source: ShortcutSource<R>,
+    shortcut: (Shortcut, Option<String>),
+}
+
+impl<R: Runtime> ShortcutTarget<R> {
+    fn trigger(&self, handler: &Option<Arc<HandlerFn>>, state: ShortcutState) {
+        // the handlers are only called on press, the channels also receive the releases
+        match &self.source {
+            ShortcutSource::Channel(channel) => {
+                let timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_millis() as u64);
+                let _ = channel.send(&ShortcutEvent {
+                    accelerator: self.shortcut.1.clone().unwrap_or_default(),
+                    state,
+                    timestamp,
+                });
+            }
+            _ if state == ShortcutState::Released => {}
+            ShortcutSource::Ipc { window, handler } => {
+                let callback_string = tauri::api::ipc::format_callback(*handler, &self.shortcut.1)
+                    .expect("unable to serialize shortcut string to json");
+                let _ = window.eval(callback_string.as_str());
+            }
+            ShortcutSource::Rust => {
+                if let Some(handler) = handler {
+                    handler(&self.shortcut.0);
+                }
+            }
+        }
+    }
+}
+
+fn handle_event<R: Runtime>(
+    shortcuts: &Shortcuts<R>,
+    handler: &Option<Arc<HandlerFn>>,
+    event: GlobalHotKeyEvent,
+) {
+    let (target, state) = {
+        let mut registered = shortcuts.lock().unwrap();
+        let Some(shortcut) = registered.get_mut(&event.id) else {
+            return;
+        };
+        match event.state {
+            HotKeyState::Pressed => {
+                // the keyboard auto-repeat of a held shortcut must not trigger it again
+                if shortcut.held.is_some() {
+                    return;
+                }
+                let held = Arc::new(AtomicBool::new(true));
+                shortcut.held.replace(held.clone());
+                if let Some(repeat) = shortcut.repeat {
+                    spawn_repeat(shortcuts.clone(), handler.clone(), event.id, repeat, held);
+                }
+                (shortcut.target(), ShortcutState::Pressed)
+            }
+            HotKeyState::Released => {
+                // the auto-repeat may release a shortcut whose press was ignored
+                if shortcut.held.is_none() {
+                    return;
+                }
+                shortcut.release();
+                (shortcut.target(), ShortcutState::Released)
+            }
+        }
+    };
+    // the handler may unregister shortcuts, which locks the store again
+    target.trigger(handler, state);
+}
+
+fn spawn_repeat<R: Runtime>(
+    shortcuts: Shortcuts<R>,
+    handler: Option<Arc<HandlerFn>>,
+    id: HotKeyId,
+    repeat: Repeat,
+    held: Arc<AtomicBool>,
+) {
+    thread::spawn(move || {
+        let mut delay = Duration::from_millis(repeat.initial_delay_ms);
+        loop {
+            thread::sleep(delay);
+            if !held.load(Ordering::Relaxed) {
+                break;
+            }
+            let target = shortcuts
+                .lock()
+                .unwrap()
+                .get(&id)
+                .map(RegisteredShortcut::target);
+            match target {
+                Some(target) => target.trigger(&handler, ShortcutState::Pressed),
+                None => break,
+            }
+            delay = Duration::from_millis(repeat.interval_ms.max(1));
+        }
+    });
 }
 
 pub struct GlobalShortcut<R: Runtime> {
     #[allow(dead_code)]
     app: AppHandle<R>,
     manager: std::result::Result<GlobalHotKeyManager, global_hotkey::Error>,
-    shortcuts: Arc<Mutex<HashMap<HotKeyId, RegisteredShortcut<R>>>>,
+    shortcuts: Shortcuts<R>,
 }
 
 impl<R: Runtime> GlobalShortcut<R> {
@@ -77,13 +251,14 @@ impl<R: Runtime> GlobalShortcut<R> {
         &self,
         shortcut: (Shortcut, Option<String>),
         source: ShortcutSource<R>,
+        repeat: Option<Repeat>,
     ) -> Result<()> {
         let id = shortcut.0.id();
         acquire_manager(&self.manager)?.register(shortcut.0)?;
         self.shortcuts
             .lock()
             .unwrap()
-            .insert(id, RegisteredShortcut { source, shortcut });
+            .insert(id, RegisteredShortcut::new(source, shortcut, repeat));
         Ok(())
     }
 
@@ -91,6 +266,7 @@ impl<R: Runtime> GlobalShortcut<R> {
         &self,
         shortcuts: S,
         source: ShortcutSource<R>,
+        repeat: Option<Repeat>,
     ) -> Result<()> {
         let hotkeys = shortcuts
             .into_iter()
@@ -103,10 +279,7 @@ impl<R: Runtime> GlobalShortcut<R> {
 
             shortcuts.insert(
                 hotkey.0.id(),
-                RegisteredShortcut {
-                    source: source.clone(),
-                    shortcut: hotkey,
-                },
+                RegisteredShortcut::new(source.clone(), hotkey, repeat),
             );
         }
 
@@ -117,7 +290,27 @@ impl<R: Runtime> GlobalShortcut<R> {
     where
         S::Error: std::error::Error,
     {
-        self.register_internal((try_into_shortcut(shortcut)?, None), ShortcutSource::Rust)
+        self.register_internal(
+            (try_into_shortcut(shortcut)?, None),
+            ShortcutSource::Rust,
+            None,
+        )
+    }
+
+    /// Registers a shortcut that triggers the handler repeatedly while it is held.
+    pub fn register_with_repeat<S: TryInto<ShortcutWrapper>>(
+        &self,
+        shortcut: S,
+        repeat: Repeat,
+    ) -> Result<()>
+    where
+        S::Error: std::error::Error,
+    {
+        self.register_internal(
+            (try_into_shortcut(shortcut)?, None),
+            ShortcutSource::Rust,
+            Some(repeat),
+        )
     }
 
     pub fn register_all<T: TryInto<ShortcutWrapper>, S: IntoIterator<Item = T>>(
@@ -131,16 +324,36 @@ impl<R: Runtime> GlobalShortcut<R> {
         for shortcut in shortcuts {
             s.push((try_into_shortcut(shortcut)?, None));
         }
-        self.register_all_internal(s, ShortcutSource::Rust)
+        self.register_all_internal(s, ShortcutSource::Rust, None)
+    }
+
+    /// Registers shortcuts that send a [`ShortcutEvent`] to `channel` on press and release,
+    /// instead of triggering the handler.
+    ///
+    /// The events carry the accelerator they were registered with, so a single channel can
+    /// route the events of many shortcuts.
+    pub fn register_with_channel<S: AsRef<str>, I: IntoIterator<Item = S>>(
+        &self,
+        shortcuts: I,
+        channel: Channel<R>,
+        repeat: Option<Repeat>,
+    ) -> Result<()> {
+        let mut hotkeys = Vec::new();
+        for shortcut in shortcuts {
+            let shortcut = shortcut.as_ref();
+            hotkeys.push((parse_shortcut(shortcut)?, Some(shortcut.to_string())));
+        }
+        self.register_all_internal(hotkeys, ShortcutSource::Channel(Arc::new(channel)), repeat)
     }
 
     pub fn unregister<S: TryInto<ShortcutWrapper>>(&self, shortcut: S) -> Result<()>
     where
         S::Error: std::error::Error,
     {
-        acquire_manager(&self.manager)?
-            .unregister(try_into_shortcut(shortcut)?)
-            .map_err(Into::into)
+        let shortcut = try_into_shortcut(shortcut)?;
+        acquire_manager(&self.manager)?.unregister(shortcut)?;
+        self.forget(&[shortcut]);
+        Ok(())
     }
 
     pub fn unregister_all<T: TryInto<ShortcutWrapper>, S: IntoIterator<Item = T>>(
@@ -154,9 +367,19 @@ impl<R: Runtime> GlobalShortcut<R> {
         for shortcut in shortcuts {
             s.push(try_into_shortcut(shortcut)?);
         }
-        acquire_manager(&self.manager)?
-            .unregister_all(&s)
-            .map_err(Into::into)
+        acquire_manager(&self.manager)?.unregister_all(&s)?;
+        self.forget(&s);
+        Ok(())
+    }
+
+    /// Removes the unregistered shortcuts, stopping their repetitions.
+    fn forget(&self, shortcuts: &[Shortcut]) {
+        let mut registered = self.shortcuts.lock().unwrap();
+        for shortcut in shortcuts {
+            if let Some(mut shortcut) = registered.remove(&shortcut.id()) {
+                shortcut.release();
+            }
+        }
     }
 
     /// Determines whether the given shortcut is registered by this application or not.
@@ -212,10 +435,12 @@ fn register<R: Runtime>(
     global_shortcut: State<'_, GlobalShortcut<R>>,
     shortcut: String,
     handler: CallbackFn,
+    repeat: Option<Repeat>,
 ) -> Result<()> {
     global_shortcut.register_internal(
         (parse_shortcut(&shortcut)?, Some(shortcut)),
         ShortcutSource::Ipc { window, handler },
+        repeat,
     )
 }
 
@@ -225,12 +450,23 @@ fn register_all<R: Runtime>(
     global_shortcut: State<'_, GlobalShortcut<R>>,
     shortcuts: Vec<String>,
     handler: CallbackFn,
+    repeat: Option<Repeat>,
 ) -> Result<()> {
     let mut hotkeys = Vec::new();
     for shortcut in shortcuts {
         hotkeys.push((parse_shortcut(&shortcut)?, Some(shortcut)));
     }
-    global_shortcut.register_all_internal(hotkeys, ShortcutSource::Ipc { window, handler })
+    global_shortcut.register_all_internal(hotkeys, ShortcutSource::Ipc { window, handler }, repeat)
+}
+
+#[tauri::command]
+fn register_channel<R: Runtime>(
+    global_shortcut: State<'_, GlobalShortcut<R>>,
+    shortcuts: Vec<String>,
+    channel: Channel<R>,
+    repeat: Option<Repeat>,
+) -> Result<()> {
+    global_shortcut.register_with_channel(shortcuts, channel, repeat)
 }
 
 #[tauri::command]
@@ -281,39 +517,23 @@ impl Builder {
     }
 
     pub fn build<R: Runtime>(self) -> TauriPlugin<R> {
-        let handler = self.handler;
+        let handler = self.handler.map(Arc::new);
         PluginBuilder::new("globalShortcut")
             .js_init_script(include_str!("api-iife.js").to_string())
             .invoke_handler(tauri::generate_handler![
                 register,
                 register_all,
+                register_channel,
                 unregister,
                 unregister_all,
                 is_registered
             ])
             .setup(move |app, _api| {
-                let shortcuts =
-                    Arc::new(Mutex::new(HashMap::<HotKeyId, RegisteredShortcut<R>>::new()));
+                let shortcuts = Shortcuts::<R>::default();
                 let shortcuts_ = shortcuts.clone();
 
                 GlobalHotKeyEvent::set_event_handler(Some(move |e: GlobalHotKeyEvent| {
-                    if let Some(shortcut) = shortcuts_.lock().unwrap().get(&e.id) {
-                        match &shortcut.source {
-                            ShortcutSource::Ipc { window, handler } => {
-                                let callback_string = tauri::api::ipc::format_callback(
-                                    *handler,
-                                    &shortcut.shortcut.1,
-                                )
-                                .expect("unable to serialize shortcut string to json");
-                                let _ = window.eval(callback_string.as_str());
-                            }
-                            ShortcutSource::Rust => {
-                                if let Some(handler) = &handler {
-                                    handler(&shortcut.shortcut.0);
-                                }
-                            }
-                        }
-                    }
+                    handle_event(&shortcuts_, &handler, e)
                 }));
 
                 app.manage(GlobalShortcut {
@@ -24,6 +24,8 @@
  * @module
  */
 
+import { Channel } from "@tauri-apps/api/tauri";
+
 declare global {
   interface Window {
     __TAURI_INVOKE__: <T>(cmd: string, args?: unknown) => Promise<T>;
@@ -38,6 +40,16 @@ interface Duration {
   nanos: number;
 }
 
+/**
+ * The HTTP version of the requests:
+ * - `auto` negotiates it with the server, using HTTP/2 when offered over TLS.
+ * - `http1Only` only uses HTTP/1.1.
+ * - `http2PriorKnowledge` uses HTTP/2 without negotiation, also without TLS.
+ *
+ * @since 2.0.0
+ */
+type HttpVersion = "auto" | "http1Only" | "http2PriorKnowledge";
+
 /**
  * @since 2.0.0
  */
@@ -48,6 +60,8 @@ interface ClientOptions {
    */
   maxRedirections?: number;
   connectTimeout?: number | Duration;
+  /** The HTTP version of the requests. Defaults to `auto`. */
+  httpVersion?: HttpVersion;
 }
 
 /**
@@ -230,6 +244,17 @@ type HttpVerb =
   | "CONNECT"
   | "TRACE";
 
+/**
+ * The bytes of a request body sent so far.
+ *
+ * @since 2.0.0
+ */
+interface UploadProgress {
+  progress: number;
+  /** The length of the body, 0 if unknown. */
+  total: number;
+}
+
 /**
  * Options object sent to the backend.
  *
@@ -243,6 +268,43 @@ interface HttpOptions {
   body?: Body;
   timeout?: number | Duration;
   responseType?: ResponseType;
+  /**
+   * The HTTP version of this request, overriding the one of the client.
+   * A request pinning another version than the client does not reuse its connections.
+   */
+  httpVersion?: HttpVersion;
+  /** Whether to measure the request phases and return them in {@link Response.timing}. Defaults to `false`. */
+  timing?: boolean;
+  /**
+   * Aborts the request when signaled, rejecting the returned promise.
+   * The partially read response is discarded.
+   *
+   * @since 2.0.0
+   */
+  signal?: AbortSignal;
+  /**
+   * Called as the request body is sent.
+   * For multipart forms, only the bytes of the files are counted.
+   *
+   * @since 2.0.0
+   */
+  onUploadProgress?: (progress: UploadProgress) => void;
+}
+
+/**
+ * Timing breakdown of a request, in milliseconds.
+ *
+ * @since 2.0.0
+ */
+interface Timing {
+  /** Time spent resolving the host name, `null` if the connection was reused or the host is an IP address. */
+  dns: number | null;
+  /** Time until the response headers were received. */
+  ttfb: number;
+  /** Time until the response body was read. */
+  total: number;
+  /** Whether the request used a pooled connection, `null` if it can not be inferred. */
+  connectionReused: boolean | null;
 }
 
 /** Request options. */
@@ -254,9 +316,11 @@ type FetchOptions = Omit<HttpOptions, "url">;
 interface IResponse<T> {
   url: string;
   status: number;
+  version: string;
   headers: Record<string, string>;
   rawHeaders: Record<string, string[]>;
   data: T;
+  timing?: Timing;
 }
 
 /**
@@ -271,21 +335,27 @@ class Response<T> {
   status: number;
   /** A boolean indicating whether the response was successful (status in the range 200–299) or not. */
   ok: boolean;
+  /** The HTTP version negotiated with the server, e.g. `HTTP/1.1` or `HTTP/2.0`. */
+  version: string;
   /** The response headers. */
   headers: Record<string, string>;
   /** The response raw headers. */
   rawHeaders: Record<string, string[]>;
   /** The response data. */
   data: T;
+  /** The timing breakdown of the request, if the `timing` option was set. */
+  timing?: Timing;
 
   /** @ignore */
   constructor(response: IResponse<T>) {
     this.url = response.url;
     this.status = response.status;
     this.ok = this.status >= 200 && this.status < 300;
+    this.version = response.version;
     this.headers = response.headers;
     this.rawHeaders = response.rawHeaders;
     this.data = response.data;
+    this.timing = response.timing;
   }
 }
 
@@ -326,16 +396,48 @@ class Client {
    * });
    * ```
    */
-  async request<T>(options: HttpOptions): Promise<Response<T>> {
+  async request<T>({
+    signal,
+    onUploadProgress,
+    ...options
+  }: HttpOptions): Promise<Response<T>> {
     const jsonResponse =
       !options.responseType || options.responseType === ResponseType.JSON;
     if (jsonResponse) {
       options.responseType = ResponseType.Text;
     }
+    if (signal?.aborted) {
+      throw Error("request aborted");
+    }
+
+    let requestId: number | undefined;
+    let onAbort: (() => void) | undefined;
+    if (signal) {
+      const id = Math.floor(Math.random() * 0xffffffff);
+      requestId = id;
+      onAbort = () => {
+        void window.__TAURI_INVOKE__("plugin:http|abort", { requestId: id });
+      };
+      signal.addEventListener("abort", onAbort);
+    }
+
+    let uploadProgress: Channel<UploadProgress> | undefined;
+    if (onUploadProgress) {
+      uploadProgress = new Channel<UploadProgress>();
+      uploadProgress.onmessage = onUploadProgress;
+    }
+
     return window
       .__TAURI_INVOKE__<IResponse<T>>("plugin:http|request", {
         clientId: this.id,
         options,
+        requestId,
+        onUploadProgress: uploadProgress,
+      })
+      .finally(() => {
+        if (onAbort) {
+          signal?.removeEventListener("abort", onAbort);
+        }
       })
       .then((res) => {
         const response = new Response(res);
@@ -511,6 +613,11 @@ let defaultClient: Client | null = null;
  *   method: 'GET',
  *   timeout: 30,
  * });
+ *
+ * // abort the request when leaving the page
+ * const controller = new AbortController();
+ * window.addEventListener('beforeunload', () => controller.abort());
+ * await fetch('http://localhost:3003/users', { signal: controller.signal });
  * ```
  */
 async function fetch<T>(
@@ -527,19 +634,231 @@ async function fetch<T>(
   });
 }
 
+/**
+ * An event received from a server-sent events stream.
+ *
+ * @since 2.0.0
+ */
+interface ServerSentEvent {
+  /** The event type, `message` unless set by the server. */
+  event: string;
+  /** The event data. */
+  data: string;
+  /** The last event ID, sent back with the `Last-Event-ID` header when reconnecting. */
+  id: string;
+  /** The reconnection delay in milliseconds, if updated by this event. */
+  retry: number | null;
+}
+
+/**
+ * A message of a server-sent events stream.
+ *
+ * @since 2.0.0
+ */
+type EventSourceMessage =
+  | { type: "open" }
+  | { type: "event"; payload: ServerSentEvent }
+  | { type: "error"; payload: string }
+  | { type: "closed" };
+
+/**
+ * @since 2.0.0
+ */
+interface EventSourceOptions {
+  /** Additional request headers. */
+  headers?: Record<string, string>;
+  /** Called for every message of the stream, including connection state changes. */
+  onMessage: (message: EventSourceMessage) => void;
+}
+
+/**
+ * A server-sent events connection.
+ *
+ * @since 2.0.0
+ */
+class EventSource {
+  id: number;
+  /** @ignore */
+  constructor(id: number) {
+    this.id = id;
+  }
+
+  /**
+   * Closes the connection and stops reconnecting.
+   */
+  async close(): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:http|sse_close", {
+      id: this.id,
+    });
+  }
+}
+
+/**
+ * Connects to a `text/event-stream` endpoint.
+ *
+ * The stream is parsed in Rust and reconnects automatically when the connection drops,
+ * waiting for the delay set by the server's `retry` field and sending the `Last-Event-ID` header.
+ * @example
+ * ```typescript
+ * import { sseConnect } from '@tauri-apps/plugin-http';
+ * const source = await sseConnect('https://example.com/events', {
+ *   onMessage: (message) => {
+ *     if (message.type === 'event') console.log(message.payload.data);
+ *   },
+ * });
+ * await source.close();
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function sseConnect(
+  url: string,
+  options: EventSourceOptions
+): Promise<EventSource> {
+  const onEvent = new Channel<EventSourceMessage>();
+  onEvent.onmessage = options.onMessage;
+  return window
+    .__TAURI_INVOKE__<number>("plugin:http|sse_connect", {
+      url,
+      headers: options.headers,
+      onEvent,
+    })
+    .then((id) => new EventSource(id));
+}
+
+/**
+ * A line of a newline-delimited JSON stream that is not valid JSON.
+ *
+ * @since 2.0.0
+ */
+interface NdjsonParseError {
+  /** The offending line, without its line ending. */
+  line: string;
+  error: string;
+}
+
+/**
+ * A message of a newline-delimited JSON stream.
+ *
+ * An invalid line is reported with a `parseError` message,
+ * the stream goes on with the next line.
+ *
+ * @since 2.0.0
+ */
+type NdjsonMessage<T = unknown> =
+  | { type: "open" }
+  | { type: "value"; payload: T }
+  | { type: "parseError"; payload: NdjsonParseError }
+  | { type: "error"; payload: string }
+  | { type: "closed" };
+
+/**
+ * @since 2.0.0
+ */
+interface NdjsonStreamOptions<T = unknown> {
+  /** Additional request headers. */
+  headers?: Record<string, string>;
+  /** Called for every message of the stream, including its opening and end. */
+  onMessage: (message: NdjsonMessage<T>) => void;
+}
+
+/**
+ * A newline-delimited JSON stream.
+ *
+ * @since 2.0.0
+ */
+class NdjsonStream {
+  id: number;
+  /** @ignore */
+  constructor(id: number) {
+    this.id = id;
+  }
+
+  /**
+   * Closes the stream.
+   */
+  async close(): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:http|ndjson_close", {
+      id: this.id,
+    });
+  }
+}
+
+/**
+ * Reads a newline-delimited JSON response, e.g. a log tail or a live feed.
+ *
+ * Every line is parsed as JSON in Rust, even when split across chunks of the response.
+ * The stream is not reopened once it ended.
+ * @example
+ * ```typescript
+ * import { ndjsonStream } from '@tauri-apps/plugin-http';
+ * const stream = await ndjsonStream<{ level: string }>('https://example.com/logs', {
+ *   onMessage: (message) => {
+ *     if (message.type === 'value') console.log(message.payload.level);
+ *   },
+ * });
+ * await stream.close();
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function ndjsonStream<T = unknown>(
+  url: string,
+  options: NdjsonStreamOptions<T>
+): Promise<NdjsonStream> {
+  const onEvent = new Channel<NdjsonMessage<T>>();
+  onEvent.onmessage = options.onMessage;
+  return window
+    .__TAURI_INVOKE__<number>("plugin:http|ndjson_stream", {
+      url,
+      headers: options.headers,
+      onEvent,
+    })
+    .then((id) => new NdjsonStream(id));
+}
+
+/**
+ * Removes the responses stored by the HTTP cache,
+ * enabled with `tauri_plugin_http::Builder::cache` on the Rust side.
+ * @example
+ * ```typescript
+ * import { clearCache } from '@tauri-apps/plugin-http';
+ * await clearCache();
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function clearCache(): Promise<void> {
+  return await window.__TAURI_INVOKE__("plugin:http|clear_cache");
+}
+
 export type {
   Duration,
   ClientOptions,
+  HttpVersion,
   Part,
   HttpVerb,
   HttpOptions,
   RequestOptions,
   FetchOptions,
+  Timing,
+  UploadProgress,
+  ServerSentEvent,
+  EventSourceMessage,
+  EventSourceOptions,
+  NdjsonParseError,
+  NdjsonMessage,
+  NdjsonStreamOptions,
 };
 
 export {
   getClient,
   fetch,
+  sseConnect,
+  ndjsonStream,
+  clearCache,
+  EventSource,
+  NdjsonStream,
   Body,
   Client,
   Response,
@@ -0,0 +1,430 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! A private HTTP cache for the `GET` requests, enabled with [`crate::Builder::cache`].
+//!
+//! The responses are stored in the app data directory. A fresh response is returned without
+//! a request, and a stale one is revalidated with its `ETag` and `Last-Modified` validators.
+//!
+//! The responses are keyed by the request URL and the [`KEY_HEADERS`], and only answer the
+//! requests with the same values for the headers listed in their `Vary` header.
+
+use reqwest::{header, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::client::{
+    decode_body, header_maps, version_name, Client, HeaderMap, HttpRequestBuilder, ResponseData,
+    ResponseType,
+};
+use super::timing::PendingTiming;
+
+/// The directory of the cache, in the app data directory.
+pub(crate) const CACHE_DIR: &str = "http-cache";
+
+/// The request headers that always change the response, so they are part of the cache key.
+const KEY_HEADERS: [header::HeaderName; 2] = [header::AUTHORIZATION, header::ACCEPT];
+
+pub struct Cache {
+    dir: PathBuf,
+}
+
+/// The stored metadata of a response.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// The request URL with its query, which a response with the same key must match.
+    request_url: Url,
+    /// The request headers named by the `Vary` header of the response, with their values.
+    vary: Vec<(String, Option<String>)>,
+    url: Url,
+    status: u16,
+    /// The HTTP version of the last response, empty for the entries stored before it was recorded.
+    #[serde(default)]
+    version: String,
+    headers: Vec<(String, String)>,
+    /// When the response was stored or last revalidated, in seconds since the Unix epoch.
+    stored_at: u64,
+}
+
+impl Entry {
+    fn header(&self, name: header::HeaderName) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name.as_str()))
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn is_fresh(&self) -> bool {
+        let directives = CacheControl::parse(self.header(header::CACHE_CONTROL));
+        match directives.max_age {
+            Some(max_age) if !directives.no_cache => now() < self.stored_at + max_age,
+            _ => false,
+        }
+    }
+
+    fn header_map(&self) -> header::HeaderMap {
+        let mut headers = header::HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                header::HeaderName::from_bytes(name.as_bytes()),
+                header::HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+        headers
+    }
+}
+
+/// The `Cache-Control` directives used by the cache.
+#[derive(Default)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    private: bool,
+    max_age: Option<u64>,
+}
+
+impl CacheControl {
+    fn parse(value: Option<&str>) -> Self {
+        let mut directives = Self::default();
+        for directive in value.unwrap_or_default().split(',') {
+            let (name, value) = directive
+                .split_once('=')
+                .map_or((directive, None), |(n, v)| (n, Some(v)));
+            match name.trim().to_ascii_lowercase().as_str() {
+                "no-store" => directives.no_store = true,
+                "no-cache" => directives.no_cache = true,
+                "private" => directives.private = true,
+                "max-age" => {
+                    directives.max_age = value.and_then(|v| v.trim().trim_matches('"').parse().ok())
+                }
+                _ => {}
+            }
+        }
+        directives
+    }
+}
+
+impl Cache {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Whether the request may be answered from the cache.
+    pub(crate) fn accepts(request: &HttpRequestBuilder) -> bool {
+        request.method.eq_ignore_ascii_case("GET")
+            && request.body.is_none()
+            && !CacheControl::parse(request_header(&request.headers, header::CACHE_CONTROL))
+                .no_store
+    }
+
+    /// Sends the request, answering it from the cache when possible.
+    pub(crate) async fn send(
+        &self,
+        client: &Client,
+        mut request: HttpRequestBuilder,
+    ) -> crate::Result<ResponseData> {
+        let response_type = request.response_type.clone().unwrap_or(ResponseType::Json);
+        let request_url = request_url(&request);
+        let key = key(&request_url, &request);
+        let entry = self.load(&key, &request_url, &request);
+        // the values of the headers named by `Vary`, before the validators are added
+        let request_headers = request.headers.as_ref().map(|h| HeaderMap(h.0.clone()));
+
+        if let Some((entry, body)) = &entry {
+            let request_directives =
+                CacheControl::parse(request_header(&request.headers, header::CACHE_CONTROL));
+            if entry.is_fresh() && !request_directives.no_cache {
+                return response_data(entry, body, &response_type, None);
+            }
+
+            let headers = &mut request.headers.get_or_insert_with(Default::default).0;
+            if let Some(etag) = entry.header(header::ETAG) {
+                if let Ok(value) = header::HeaderValue::from_str(etag) {
+                    headers.insert(header::IF_NONE_MATCH, value);
+                }
+            }
+            if let Some(last_modified) = entry.header(header::LAST_MODIFIED) {
+                if let Ok(value) = header::HeaderValue::from_str(last_modified) {
+                    headers.insert(header::IF_MODIFIED_SINCE, value);
+                }
+            }
+        }
+
+        let response = client.send(request).await?;
+        let (_, response, timing) = response.into_parts();
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some((mut entry, body)) = entry {
+                // the 304 response carries the updated freshness and validators
+                for (name, value) in response.headers() {
+                    if let Ok(value) = value.to_str() {
+                        entry
+                            .headers
+                            .retain(|(n, _)| !n.eq_ignore_ascii_case(name.as_str()));
+                        entry.headers.push((name.as_str().into(), value.into()));
+                    }
+                }
+                entry.version = version_name(response.version());
+                entry.stored_at = now();
+                self.store(&key, &entry, None);
+                return response_data(&entry, &body, &response_type, timing);
+            }
+        }
+
+        let url = response.url().clone();
+        let status = response.status();
+        let version = version_name(response.version());
+        let (headers, raw_headers) = header_maps(response.headers())?;
+        let directives = CacheControl::parse(
+            response
+                .headers()
+                .get(header::CACHE_CONTROL)
+                .and_then(|v| v.to_str().ok()),
+        );
+        let storable = status == StatusCode::OK
+            && !directives.no_store
+            && !directives.private
+            && response.headers().get(header::VARY).map(|v| v.as_bytes()) != Some(b"*")
+            && (directives.max_age.is_some()
+                || response.headers().contains_key(header::ETAG)
+                || response.headers().contains_key(header::LAST_MODIFIED));
+        let entry = storable.then(|| Entry {
+            request_url,
+            vary: response
+                .headers()
+                .get_all(header::VARY)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(','))
+                .map(|name| {
+                    let name = name.trim().to_ascii_lowercase();
+                    let value = request_header(&request_headers, &name).map(Into::into);
+                    (name, value)
+                })
+                .collect(),
+            url: url.clone(),
+            status: status.as_u16(),
+            version: version.clone(),
+            headers: response
+                .headers()
+                .iter()
+                .filter_map(|(n, v)| Some((n.as_str().into(), v.to_str().ok()?.into())))
+                .collect(),
+            stored_at: now(),
+        });
+
+        let body = response.bytes().await?;
+        if let Some(entry) = entry {
+            self.store(&key, &entry, Some(&body));
+        }
+
+        Ok(ResponseData {
+            url,
+            status: status.as_u16(),
+            version,
+            headers,
+            raw_headers,
+            data: decode_body(&response_type, &body)?,
+            timing: timing.map(PendingTiming::finish),
+        })
+    }
+
+    /// Removes all the cached responses.
+    pub(crate) fn clear(&self) -> io::Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Loads the response stored with `key`, if it answers the request.
+    fn load(
+        &self,
+        key: &str,
+        request_url: &Url,
+        request: &HttpRequestBuilder,
+    ) -> Option<(Entry, Vec<u8>)> {
+        let entry = fs::read(self.dir.join(format!("{key}.json"))).ok()?;
+        let entry: Entry = serde_json::from_slice(&entry).ok()?;
+        let matches = &entry.request_url == request_url
+            && entry
+                .vary
+                .iter()
+                .all(|(name, value)| request_header(&request.headers, name) == value.as_deref());
+        if !matches {
+            return None;
+        }
+        let body = fs::read(self.dir.join(format!("{key}.body"))).ok()?;
+        Some((entry, body))
+    }
+
+    /// Stores the entry, and the body unless it is unchanged. Failures only cost a cache miss.
+    fn store(&self, key: &str, entry: &Entry, body: Option<&[u8]>) {
+        let _ = fs::create_dir_all(&self.dir).and_then(|_| {
+            if let Some(body) = body {
+                fs::write(self.dir.join(format!("{key}.body")), body)?;
+            }
+            fs::write(
+                self.dir.join(format!("{key}.json")),
+                serde_json::to_vec(entry)?,
+            )
+        });
+    }
+}
+
+/// The request URL with its query, sorted so the order of the parameters does not matter.
+fn request_url(request: &HttpRequestBuilder) -> Url {
+    let mut url = request.url.clone();
+    if let Some(query) = &request.query {
+        let mut query = query.iter().collect::<Vec<_>>();
+        query.sort();
+        url.query_pairs_mut().extend_pairs(query);
+    }
+    url
+}
+
+/// The file name of the cached response, derived from the request URL and the [`KEY_HEADERS`].
+fn key(request_url: &Url, request: &HttpRequestBuilder) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(request_url.as_str());
+    for name in &KEY_HEADERS {
+        // the separators keep the values of different headers apart
+        hasher.update([0]);
+        if let Some(value) = request_header(&request.headers, name) {
+            hasher.update(value);
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn request_header<K: header::AsHeaderName>(headers: &Option<HeaderMap>, name: K) -> Option<&str> {
+    headers
+        .as_ref()
+        .and_then(|h| h.0.get(name))
+        .and_then(|v| v.to_str().ok())
+}
+
+fn response_data(
+    entry: &Entry,
+    body: &[u8],
+    response_type: &ResponseType,
+    timing: Option<PendingTiming>,
+) -> crate::Result<ResponseData> {
+    let (headers, raw_headers) = header_maps(&entry.header_map())?;
+    Ok(ResponseData {
+        url: entry.url.clone(),
+        status: entry.status,
+        version: entry.version.clone(),
+        headers,
+        raw_headers,
+        data: decode_body(response_type, body)?,
+        timing: timing.map(PendingTiming::finish),
+    })
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(cache_control: &str, stored_at: u64) -> Entry {
+        let url = Url::parse("https://tauri.app/").unwrap();
+        Entry {
+            request_url: url.clone(),
+            vary: Vec::new(),
+            url,
+            status: 200,
+            version: "HTTP/1.1".into(),
+            headers: vec![("cache-control".into(), cache_control.into())],
+            stored_at,
+        }
+    }
+
+    fn request(url: &str, headers: &[(header::HeaderName, &str)]) -> HttpRequestBuilder {
+        let mut request = HttpRequestBuilder::new("GET", Url::parse(url).unwrap());
+        let mut map = header::HeaderMap::new();
+        for (name, value) in headers {
+            map.insert(name.clone(), header::HeaderValue::from_str(value).unwrap());
+        }
+        request.headers = Some(HeaderMap(map));
+        request
+    }
+
+    #[test]
+    fn parses_cache_control() {
+        let directives = CacheControl::parse(Some("Private, max-age=\"60\", no-cache"));
+        assert!(directives.private);
+        assert!(directives.no_cache);
+        assert!(!directives.no_store);
+        assert_eq!(directives.max_age, Some(60));
+
+        let directives = CacheControl::parse(Some("no-store, max-age=invalid"));
+        assert!(directives.no_store);
+        assert_eq!(directives.max_age, None);
+
+        assert!(CacheControl::parse(None).max_age.is_none());
+    }
+
+    #[test]
+    fn freshness() {
+        assert!(entry("max-age=60", now()).is_fresh());
+        assert!(!entry("max-age=60", now() - 61).is_fresh());
+        assert!(!entry("max-age=60, no-cache", now()).is_fresh());
+        assert!(!entry("public", now()).is_fresh());
+    }
+
+    #[test]
+    fn key_includes_the_request_headers() {
+        let anonymous = request("https://tauri.app/", &[]);
+        let authorized = request("https://tauri.app/", &[(header::AUTHORIZATION, "Bearer a")]);
+        let other_user = request("https://tauri.app/", &[(header::AUTHORIZATION, "Bearer b")]);
+        let json = request(
+            "https://tauri.app/",
+            &[(header::ACCEPT, "application/json")],
+        );
+
+        let keys = [&anonymous, &authorized, &other_user, &json]
+            .map(|request| key(&request_url(request), request));
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(key.len(), 64);
+            assert!(!keys[i + 1..].contains(key));
+        }
+        assert_eq!(keys[1], key(&request_url(&authorized), &authorized));
+    }
+
+    #[test]
+    fn load_matches_the_request() {
+        let dir = std::env::temp_dir().join(format!("tauri-http-cache-{}", std::process::id()));
+        let cache = Cache::new(dir.clone());
+        let request = request("https://tauri.app/", &[(header::ACCEPT_LANGUAGE, "fr")]);
+        let request_url = request_url(&request);
+
+        let mut stored = entry("max-age=60", now());
+        stored.vary = vec![("accept-language".into(), Some("fr".into()))];
+        cache.store("key", &stored, Some(b"body"));
+        assert!(cache.load("key", &request_url, &request).is_some());
+
+        let other_url = Url::parse("https://tauri.app/other").unwrap();
+        assert!(cache.load("key", &other_url, &request).is_none());
+
+        let english = self::request("https://tauri.app/", &[(header::ACCEPT_LANGUAGE, "en")]);
+        assert!(cache.load("key", &request_url, &english).is_none());
+
+        cache.clear().unwrap();
+    }
+}
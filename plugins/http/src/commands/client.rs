@@ -2,13 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::{collections::HashMap, path::PathBuf, time::Duration};
-
-use reqwest::{header, Method, Url};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use reqwest::{header, Method, Url, Version};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
+use super::{
+    progress::UploadProgressHandler,
+    timing::{PendingTiming, Timing, TimingResolver},
+};
+
 #[derive(Deserialize)]
 #[serde(untagged)]
 enum SerdeDuration {
@@ -29,6 +39,19 @@ fn deserialize_duration<'de, D: Deserializer<'de>>(
     }
 }
 
+/// The HTTP version used by a [`Client`] or a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HttpVersion {
+    /// Negotiate the version with the server, using HTTP/2 when it is offered over TLS.
+    #[default]
+    Auto,
+    /// Only use HTTP/1.1.
+    Http1Only,
+    /// Use HTTP/2 without negotiation, also for plain text connections.
+    Http2PriorKnowledge,
+}
+
 /// The builder of [`Client`].
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -38,12 +61,26 @@ pub struct ClientBuilder {
     /// Connect timeout for the request.
     #[serde(deserialize_with = "deserialize_duration", default)]
     pub connect_timeout: Option<Duration>,
+    /// The HTTP version of the requests, negotiated by default.
+    pub http_version: Option<HttpVersion>,
+    /// The headers added to the requests that do not set them, from [`crate::Builder::default_header`].
+    #[serde(skip)]
+    pub(crate) default_headers: header::HeaderMap,
+    /// Whether to install the resolver measuring the DNS time of the timed requests.
+    #[serde(skip)]
+    pub(crate) timing: bool,
 }
 
 impl ClientBuilder {
     /// Builds the Client.
     pub fn build(self) -> crate::Result<Client> {
-        let mut client_builder = reqwest::Client::builder();
+        // the headers of a request replace the defaults of the same name
+        let mut client_builder =
+            reqwest::Client::builder().default_headers(self.default_headers.clone());
+
+        if self.timing {
+            client_builder = client_builder.dns_resolver(Arc::new(TimingResolver));
+        }
 
         if let Some(max_redirections) = self.max_redirections {
             client_builder = client_builder.redirect(if max_redirections == 0 {
@@ -57,23 +94,63 @@ impl ClientBuilder {
             client_builder = client_builder.connect_timeout(connect_timeout);
         }
 
+        match self.http_version.unwrap_or_default() {
+            HttpVersion::Auto => {}
+            HttpVersion::Http1Only => client_builder = client_builder.http1_only(),
+            HttpVersion::Http2PriorKnowledge => {
+                client_builder = client_builder.http2_prior_knowledge()
+            }
+        }
+
         let client = client_builder.build()?;
-        Ok(Client(client))
+        Ok(Client(client, self, Default::default()))
     }
 }
 
+/// The clients built for the requests overriding the version or the timing of a [`Client`].
+type Variants = Arc<Mutex<HashMap<(Option<HttpVersion>, bool), reqwest::Client>>>;
+
 /// The HTTP client based on [`reqwest`].
 #[derive(Debug, Clone)]
-pub struct Client(reqwest::Client);
+pub struct Client(reqwest::Client, ClientBuilder, Variants);
 
 impl Client {
+    /// The underlying client, for the requests not sent with [`Client::send`].
+    pub(crate) fn reqwest(&self) -> &reqwest::Client {
+        &self.0
+    }
+
     /// Executes an HTTP request
     ///
     /// # Examples
     pub async fn send(&self, mut request: HttpRequestBuilder) -> crate::Result<Response> {
         let method = Method::from_bytes(request.method.to_uppercase().as_bytes())?;
 
-        let mut request_builder = self.0.request(method, request.url.as_str());
+        // the version and the resolver are connection settings, so they need their own client,
+        // kept to reuse its connections
+        let http_version = request.http_version.or(self.1.http_version);
+        let timing = request.timing || self.1.timing;
+        let client = if http_version != self.1.http_version || timing != self.1.timing {
+            let mut variants = self.2.lock().unwrap();
+            match variants.get(&(http_version, timing)) {
+                Some(client) => client.clone(),
+                None => {
+                    let client = ClientBuilder {
+                        http_version,
+                        timing,
+                        ..self.1.clone()
+                    }
+                    .build()?
+                    .0;
+                    variants.insert((http_version, timing), client.clone());
+                    client
+                }
+            }
+        } else {
+            self.0.clone()
+        };
+
+        let mut request_builder = client.request(method, request.url.as_str());
 
         if let Some(query) = request.query {
             request_builder = request_builder.query(&query);
@@ -94,6 +171,7 @@ impl Client {
                         request_builder: reqwest::RequestBuilder,
                         headers: &mut Option<HeaderMap>,
                         form_body: FormBody,
+                        upload_progress: Option<&UploadProgressHandler>,
                     ) -> crate::Result<reqwest::RequestBuilder> {
                         #[cfg(feature = "multipart")]
                         if matches!(
@@ -107,6 +185,9 @@ impl Client {
                             headers.as_mut().map(|h| h.0.remove("content-type"));
                             let mut multipart = reqwest::multipart::Form::new();
 
+                            // the files are read first, so the total of the upload progress is known
+                            let mut parts = Vec::new();
+                            let mut total = 0;
                             for (name, part) in form_body.0 {
                                 let part = match part {
                                     FormPart::File {
@@ -115,7 +196,42 @@ impl Client {
                                         file_name,
                                     } => {
                                         let bytes: Vec<u8> = file.try_into()?;
-                                        let mut part = reqwest::multipart::Part::bytes(bytes);
+                                        total += bytes.len() as u64;
+                                        FormPart::File {
+                                            file: FilePart::Contents(bytes),
+                                            mime,
+                                            file_name,
+                                        }
+                                    }
+                                    part => part,
+                                };
+                                parts.push((name, part));
+                            }
+                            // the text parts are not counted
+                            let sent = Arc::default();
+
+                            for (name, part) in parts {
+                                let part = match part {
+                                    FormPart::File {
+                                        file,
+                                        mime,
+                                        file_name,
+                                    } => {
+                                        let bytes: Vec<u8> = file.try_into()?;
+                                        let mut part = match upload_progress {
+                                            Some(handler) => {
+                                                let length = bytes.len() as u64;
+                                                reqwest::multipart::Part::stream_with_length(
+                                                    handler.body(
+                                                        bytes.into(),
+                                                        Arc::clone(&sent),
+                                                        total,
+                                                    ),
+                                                    length,
+                                                )
+                                            }
+                                            None => reqwest::multipart::Part::bytes(bytes),
+                                        };
                                         if let Some(mime) = mime {
                                             part = part.mime_str(&mime)?;
                                         }
@@ -145,7 +261,12 @@ impl Client {
                         }
                         Ok(request_builder.form(&form))
                     }
-                    send_form(request_builder, &mut request.headers, form_body)?
+                    send_form(
+                        request_builder,
+                        &mut request.headers,
+                        form_body,
+                        request.on_upload_progress.as_ref(),
+                    )?
                 }
             };
         }
@@ -154,13 +275,24 @@ impl Client {
             request_builder = request_builder.headers(headers.0);
         }
 
-        let http_request = request_builder.build()?;
+        let mut http_request = request_builder.build()?;
+        if let Some(handler) = &request.on_upload_progress {
+            handler.wrap(&mut http_request);
+        }
 
-        let response = self.0.execute(http_request).await?;
+        let (response, timing) = if request.timing {
+            let host_is_domain = http_request.url().domain().is_some();
+            let (response, timing) =
+                PendingTiming::measure(host_is_domain, client.execute(http_request)).await;
+            (response?, Some(timing))
+        } else {
+            (client.execute(http_request).await?, None)
+        };
 
         Ok(Response(
             request.response_type.unwrap_or(ResponseType::Json),
             response,
+            timing,
         ))
     }
 }
@@ -179,7 +311,7 @@ pub enum ResponseType {
 }
 
 #[derive(Debug)]
-pub struct Response(ResponseType, reqwest::Response);
+pub struct Response(ResponseType, reqwest::Response, Option<PendingTiming>);
 
 impl Response {
     /// Reads the response.
@@ -188,24 +320,9 @@ impl Response {
     pub async fn read(self) -> crate::Result<ResponseData> {
         let url = self.1.url().clone();
 
-        let mut headers = HashMap::new();
-        let mut raw_headers = HashMap::new();
-        for (name, value) in self.1.headers() {
-            headers.insert(
-                name.as_str().to_string(),
-                String::from_utf8(value.as_bytes().to_vec())?,
-            );
-            raw_headers.insert(
-                name.as_str().to_string(),
-                self.1
-                    .headers()
-                    .get_all(name)
-                    .into_iter()
-                    .map(|v| String::from_utf8(v.as_bytes().to_vec()).map_err(Into::into))
-                    .collect::<crate::Result<Vec<String>>>()?,
-            );
-        }
+        let (headers, raw_headers) = header_maps(self.1.headers())?;
         let status = self.1.status().as_u16();
+        let version = version_name(self.1.version());
 
         let data = match self.0 {
             ResponseType::Json => self.1.json().await?,
@@ -216,11 +333,55 @@ impl Response {
         Ok(ResponseData {
             url,
             status,
+            version,
             headers,
             raw_headers,
             data,
+            timing: self.2.map(PendingTiming::finish),
         })
     }
+
+    pub(crate) fn into_parts(self) -> (ResponseType, reqwest::Response, Option<PendingTiming>) {
+        (self.0, self.1, self.2)
+    }
+}
+
+/// The name of the HTTP version, e.g. `HTTP/1.1`.
+pub(crate) fn version_name(version: Version) -> String {
+    format!("{version:?}")
+}
+
+/// The last value of each header, and all the values of each header.
+#[allow(clippy::type_complexity)]
+pub(crate) fn header_maps(
+    response_headers: &header::HeaderMap,
+) -> crate::Result<(HashMap<String, String>, HashMap<String, Vec<String>>)> {
+    let mut headers = HashMap::new();
+    let mut raw_headers = HashMap::new();
+    for (name, value) in response_headers {
+        headers.insert(
+            name.as_str().to_string(),
+            String::from_utf8(value.as_bytes().to_vec())?,
+        );
+        raw_headers.insert(
+            name.as_str().to_string(),
+            response_headers
+                .get_all(name)
+                .into_iter()
+                .map(|v| String::from_utf8(v.as_bytes().to_vec()).map_err(Into::into))
+                .collect::<crate::Result<Vec<String>>>()?,
+        );
+    }
+    Ok((headers, raw_headers))
+}
+
+/// Decodes a body that was read as bytes, e.g. from the cache.
+pub(crate) fn decode_body(response_type: &ResponseType, body: &[u8]) -> crate::Result<Value> {
+    Ok(match response_type {
+        ResponseType::Json => serde_json::from_slice(body)?,
+        ResponseType::Text => Value::String(String::from_utf8_lossy(body).into_owned()),
+        ResponseType::Binary => serde_json::to_value(body)?,
+    })
 }
 
 #[derive(Debug, Serialize)]
@@ -231,12 +392,17 @@ pub struct ResponseData {
     pub url: Url,
     /// Response status code.
     pub status: u16,
+    /// The HTTP version negotiated with the server, e.g. `HTTP/1.1` or `HTTP/2.0`.
+    pub version: String,
     /// Response headers.
     pub headers: HashMap<String, String>,
     /// Response raw headers.
     pub raw_headers: HashMap<String, Vec<String>>,
     /// Response data.
     pub data: Value,
+    /// Timing breakdown of the request, if requested with [`HttpRequestBuilder::timing`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
 }
 
 /// A file path or contents.
@@ -295,7 +461,13 @@ pub enum Body {
 }
 
 #[derive(Debug, Default)]
-pub struct HeaderMap(header::HeaderMap);
+pub struct HeaderMap(pub(crate) header::HeaderMap);
+
+impl From<header::HeaderMap> for HeaderMap {
+    fn from(headers: header::HeaderMap) -> Self {
+        Self(headers)
+    }
+}
 
 impl<'de> Deserialize<'de> for HeaderMap {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -338,4 +510,30 @@ pub struct HttpRequestBuilder {
     pub timeout: Option<Duration>,
     /// The response type (defaults to Json)
     pub response_type: Option<ResponseType>,
+    /// The HTTP version of the request, overriding the one of the client
+    pub http_version: Option<HttpVersion>,
+    /// Whether to measure the request phases and return them in [`ResponseData::timing`]
+    #[serde(default)]
+    pub timing: bool,
+    /// The handler of the upload progress of the body
+    #[serde(skip)]
+    pub on_upload_progress: Option<UploadProgressHandler>,
+}
+
+impl HttpRequestBuilder {
+    /// Creates a request with the given method, reading the response as JSON.
+    pub fn new(method: impl Into<String>, url: Url) -> Self {
+        Self {
+            method: method.into(),
+            url,
+            query: None,
+            headers: None,
+            body: None,
+            timeout: None,
+            response_type: None,
+            http_version: None,
+            timing: false,
+            on_upload_progress: None,
+        }
+    }
 }
@@ -2,15 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use tauri::{path::SafePathBuf, AppHandle, Runtime, State};
+use reqwest::Url;
+use tauri::{api::ipc::Channel, path::SafePathBuf, AppHandle, Manager, Runtime, State};
 use tauri_plugin_fs::FsExt;
+use tokio::sync::Notify;
 
-use crate::{ClientId, Http};
+use std::sync::Arc;
 
+use crate::{ClientId, EventSourceId, Http, RequestId};
+
+mod cache;
 mod client;
-use client::{Body, ClientBuilder, FilePart, FormPart, HttpRequestBuilder, ResponseData};
+mod progress;
+mod timing;
+pub use client::{
+    Body, ClientBuilder, FilePart, FormBody, FormPart, HeaderMap, HttpRequestBuilder, HttpVersion,
+    ResponseData, ResponseType,
+};
 
+pub(crate) use cache::{Cache, CACHE_DIR};
 pub use client::Client;
+pub use progress::{UploadProgress, UploadProgressHandler};
 
 #[tauri::command]
 pub async fn create_client<R: Runtime>(
@@ -18,7 +30,11 @@ pub async fn create_client<R: Runtime>(
     http: State<'_, Http<R>>,
     options: Option<ClientBuilder>,
 ) -> super::Result<ClientId> {
-    let client = options.unwrap_or_default().build()?;
+    let client = ClientBuilder {
+        default_headers: http.default_headers.clone(),
+        ..options.unwrap_or_default()
+    }
+    .build()?;
     let mut store = http.clients.lock().unwrap();
     let id = rand::random::<ClientId>();
     store.insert(id, client);
@@ -38,21 +54,58 @@ pub async fn drop_client<R: Runtime>(
 
 #[tauri::command]
 pub async fn request<R: Runtime>(
-    app: AppHandle<R>,
     http: State<'_, Http<R>>,
     client_id: ClientId,
     options: Box<HttpRequestBuilder>,
+    request_id: Option<RequestId>,
+    on_upload_progress: Option<Channel<R>>,
 ) -> super::Result<ResponseData> {
-    if http.scope.is_allowed(&options.url) {
-        let client = http
-            .clients
-            .lock()
-            .unwrap()
-            .get(&client_id)
-            .ok_or_else(|| crate::Error::HttpClientNotInitialized)?
-            .clone();
-        let options = *options;
-        if let Some(Body::Form(form)) = &options.body {
+    let client = http
+        .clients
+        .lock()
+        .unwrap()
+        .get(&client_id)
+        .ok_or_else(|| crate::Error::HttpClientNotInitialized)?
+        .clone();
+    let mut options = *options;
+    if let Some(on_upload_progress) = on_upload_progress {
+        options.on_upload_progress = Some(UploadProgressHandler::new(move |progress| {
+            let _ = on_upload_progress.send(&progress);
+        }));
+    }
+    let response = http.send(&client, options);
+    match request_id {
+        Some(id) => {
+            let abort = Arc::new(Notify::new());
+            http.aborts.lock().unwrap().insert(id, abort.clone());
+            // dropping the response future closes the connection and discards the partial body
+            let result = tokio::select! {
+                result = response => result,
+                _ = abort.notified() => Err(crate::Error::Aborted),
+            };
+            http.aborts.lock().unwrap().remove(&id);
+            result
+        }
+        None => response.await,
+    }
+}
+
+impl<R: Runtime> Http<R> {
+    /// Sends a request with the client of the plugin, built from the default headers, checking it
+    /// against the scopes and going through the cache like the requests of the frontend.
+    pub async fn request(&self, request: HttpRequestBuilder) -> crate::Result<ResponseData> {
+        self.send(&self.client, request).await
+    }
+
+    pub(crate) async fn send(
+        &self,
+        client: &Client,
+        request: HttpRequestBuilder,
+    ) -> crate::Result<ResponseData> {
+        if !self.scope.is_allowed(&request.url) {
+            return Err(crate::Error::UrlNotAllowed(request.url));
+        }
+        if let Some(Body::Form(form)) = &request.body {
             for value in form.0.values() {
                 if let FormPart::File {
                     file: FilePart::Path(path),
@@ -60,7 +113,8 @@ pub async fn request<R: Runtime>(
                 } = value
                 {
                     if SafePathBuf::new(path.clone()).is_err()
-                        || !app
+                        || !self
+                            .app
                             .try_fs_scope()
                             .map(|s| s.is_allowed(path))
                             .unwrap_or_default()
@@ -70,9 +124,117 @@ pub async fn request<R: Runtime>(
                 }
             }
         }
-        let response = client.send(options).await?;
-        Ok(response.read().await?)
-    } else {
-        Err(crate::Error::UrlNotAllowed(options.url))
+        match self.cache.as_ref().filter(|_| Cache::accepts(&request)) {
+            Some(cache) => cache.send(client, request).await,
+            None => client.send(request).await?.read().await,
+        }
+    }
+}
+
+/// Aborts the request started with the given ID, failing it with [`crate::Error::Aborted`].
+#[tauri::command]
+pub async fn abort<R: Runtime>(
+    _app: AppHandle<R>,
+    http: State<'_, Http<R>>,
+    request_id: RequestId,
+) -> super::Result<()> {
+    // a finished or unknown request has no signal
+    if let Some(abort) = http.aborts.lock().unwrap().get(&request_id) {
+        // the permit is kept if the request has not started waiting yet
+        abort.notify_one();
+    }
+    Ok(())
+}
+
+/// Removes the responses stored by the cache.
+#[tauri::command]
+pub async fn clear_cache<R: Runtime>(
+    _app: AppHandle<R>,
+    http: State<'_, Http<R>>,
+) -> super::Result<()> {
+    http.clear_cache()
+}
+
+#[tauri::command]
+pub async fn sse_connect<R: Runtime>(
+    app: AppHandle<R>,
+    http: State<'_, Http<R>>,
+    url: Url,
+    headers: Option<HeaderMap>,
+    on_event: Channel<R>,
+) -> super::Result<EventSourceId> {
+    if !http.scope.is_allowed(&url) {
+        return Err(crate::Error::UrlNotAllowed(url));
+    }
+
+    let id = rand::random::<EventSourceId>();
+    // the headers of the connection replace the defaults of the same name
+    let mut request_headers = http.default_headers.clone();
+    request_headers.extend(headers.unwrap_or_default().0);
+    // hold the lock so the task can not remove itself before it is inserted
+    let mut event_sources = http.event_sources.lock().unwrap();
+    let task = tauri::async_runtime::spawn(async move {
+        crate::sse::connect(url, request_headers, on_event).await;
+        app.state::<Http<R>>()
+            .event_sources
+            .lock()
+            .unwrap()
+            .remove(&id);
+    });
+    event_sources.insert(id, task);
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn sse_close<R: Runtime>(
+    _app: AppHandle<R>,
+    http: State<'_, Http<R>>,
+    id: EventSourceId,
+) -> super::Result<()> {
+    if let Some(task) = http.event_sources.lock().unwrap().remove(&id) {
+        task.abort();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn ndjson_stream<R: Runtime>(
+    app: AppHandle<R>,
+    http: State<'_, Http<R>>,
+    url: Url,
+    headers: Option<HeaderMap>,
+    on_event: Channel<R>,
+) -> super::Result<EventSourceId> {
+    if !http.scope.is_allowed(&url) {
+        return Err(crate::Error::UrlNotAllowed(url));
+    }
+
+    let id = rand::random::<EventSourceId>();
+    let client = http.client.reqwest().clone();
+    let mut request_headers = http.default_headers.clone();
+    request_headers.extend(headers.unwrap_or_default().0);
+    // hold the lock so the task can not remove itself before it is inserted
+    let mut event_sources = http.event_sources.lock().unwrap();
+    let task = tauri::async_runtime::spawn(async move {
+        crate::ndjson::stream(client, url, request_headers, on_event).await;
+        app.state::<Http<R>>()
+            .event_sources
+            .lock()
+            .unwrap()
+            .remove(&id);
+    });
+    event_sources.insert(id, task);
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn ndjson_close<R: Runtime>(
+    _app: AppHandle<R>,
+    http: State<'_, Http<R>>,
+    id: EventSourceId,
+) -> super::Result<()> {
+    if let Some(task) = http.event_sources.lock().unwrap().remove(&id) {
+        task.abort();
     }
+    Ok(())
 }
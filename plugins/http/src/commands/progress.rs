@@ -0,0 +1,75 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use bytes::Bytes;
+use futures_util::stream;
+use reqwest::header::{HeaderValue, CONTENT_LENGTH};
+use serde::Serialize;
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+/// The size of the chunks the request bodies are streamed in.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The bytes of a request body sent so far.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct UploadProgress {
+    pub progress: u64,
+    /// The length of the body, 0 if unknown.
+    pub total: u64,
+}
+
+/// Reports the [`UploadProgress`] of a request body, streamed in chunks.
+#[derive(Clone)]
+pub struct UploadProgressHandler(Arc<dyn Fn(UploadProgress) + Send + Sync>);
+
+impl fmt::Debug for UploadProgressHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("UploadProgressHandler").finish()
+    }
+}
+
+impl UploadProgressHandler {
+    pub fn new(handler: impl Fn(UploadProgress) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(handler))
+    }
+
+    /// A body streaming the bytes, counted in `sent` when the connection reads them.
+    pub(crate) fn body(&self, bytes: Bytes, sent: Arc<AtomicU64>, total: u64) -> reqwest::Body {
+        let handler = self.0.clone();
+        let chunks = (0..bytes.len())
+            .step_by(CHUNK_SIZE)
+            .map(move |start| bytes.slice(start..bytes.len().min(start + CHUNK_SIZE)));
+        reqwest::Body::wrap_stream(stream::iter(chunks.map(move |chunk| {
+            let progress =
+                sent.fetch_add(chunk.len() as u64, Ordering::Relaxed) + chunk.len() as u64;
+            handler(UploadProgress { progress, total });
+            Ok::<_, std::io::Error>(chunk)
+        })))
+    }
+
+    /// Streams the body of the request, unless it is already a stream, e.g. a multipart form.
+    pub(crate) fn wrap(&self, request: &mut reqwest::Request) {
+        let Some(bytes) = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .map(Bytes::copy_from_slice)
+        else {
+            return;
+        };
+        let total = bytes.len() as u64;
+        // the length of a stream is unknown, so it would be sent chunked without the header
+        request
+            .headers_mut()
+            .entry(CONTENT_LENGTH)
+            .or_insert_with(|| HeaderValue::from(total));
+        *request.body_mut() = Some(self.body(bytes, Default::default(), total));
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde::Serialize;
+
+use std::{
+    cell::Cell,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+tokio::task_local! {
+    /// The time spent resolving host names for the request running on the current task.
+    static DNS: Cell<Option<Duration>>;
+}
+
+/// A DNS resolver recording its resolution time on the request being timed.
+///
+/// It is only installed on the clients of the timed requests.
+/// The connections opened in the background of another request are not attributed to any request.
+pub(crate) struct TimingResolver;
+
+impl Resolve for TimingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let start = Instant::now();
+            let addrs = tokio::net::lookup_host((name.as_str(), 0)).await?;
+            let elapsed = start.elapsed();
+            // redirects may resolve several hosts
+            let _ = DNS.try_with(|dns| dns.set(Some(dns.get().unwrap_or_default() + elapsed)));
+            Ok(Box::new(addrs) as Addrs)
+        })
+    }
+}
+
+/// The timing breakdown of a request, in milliseconds.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Timing {
+    /// Time spent resolving the host name.
+    ///
+    /// `None` when no resolution happened, because the connection was reused or the host is an IP address.
+    pub dns: Option<f64>,
+    /// Time until the response headers were received.
+    pub ttfb: f64,
+    /// Time until the response body was read.
+    pub total: f64,
+    /// Whether the request used a pooled connection.
+    ///
+    /// `None` when it can not be inferred, i.e. when the host is an IP address.
+    pub connection_reused: Option<bool>,
+}
+
+/// The timings of a request whose body is not read yet.
+#[derive(Debug)]
+pub(crate) struct PendingTiming {
+    start: Instant,
+    dns: Option<Duration>,
+    ttfb: Duration,
+    host_is_domain: bool,
+}
+
+impl PendingTiming {
+    /// Runs the request future, recording the time until the response headers and the DNS resolutions.
+    pub(crate) async fn measure<T, F: Future<Output = T>>(
+        host_is_domain: bool,
+        request: F,
+    ) -> (T, Self) {
+        let start = Instant::now();
+        let (result, dns) = DNS
+            .scope(Cell::new(None), async move {
+                let result = request.await;
+                (result, DNS.with(Cell::get))
+            })
+            .await;
+        (
+            result,
+            Self {
+                start,
+                dns,
+                ttfb: start.elapsed(),
+                host_is_domain,
+            },
+        )
+    }
+
+    /// Completes the timings once the response body is read.
+    pub(crate) fn finish(self) -> Timing {
+        Timing {
+            dns: self.dns.map(millis),
+            ttfb: millis(self.ttfb),
+            total: millis(self.start.elapsed()),
+            connection_reused: self.host_is_domain.then_some(self.dns.is_none()),
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -24,6 +24,12 @@ pub enum Error {
     /// Client with specified ID not found.
     #[error("http client dropped or not initialized")]
     HttpClientNotInitialized,
+    /// The request was aborted with the `abort` command.
+    #[error("request aborted")]
+    Aborted,
+    /// Invalid header name or value.
+    #[error(transparent)]
+    Http(#[from] http::Error),
     /// HTTP method error.
     #[error(transparent)]
     HttpMethod(#[from] http::method::InvalidMethod),
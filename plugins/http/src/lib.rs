@@ -3,31 +3,63 @@
 // SPDX-License-Identifier: MIT
 
 use config::{Config, HttpAllowlistScope};
+use http::header::{self, HeaderName, HeaderValue};
 pub use reqwest as client;
 use tauri::{
-    plugin::{Builder, TauriPlugin},
+    async_runtime::JoinHandle,
+    plugin::{Builder as PluginBuilder, TauriPlugin},
     AppHandle, Manager, Runtime,
 };
 
-use std::{collections::HashMap, sync::Mutex};
+use tokio::sync::Notify;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 mod commands;
 mod config;
 mod error;
+mod ndjson;
 mod scope;
+mod sse;
 
+pub use commands::{
+    Body, Client, ClientBuilder, FilePart, FormBody, FormPart, HeaderMap, HttpRequestBuilder,
+    HttpVersion, ResponseData, ResponseType, UploadProgress, UploadProgressHandler,
+};
 pub use error::Error;
 type Result<T> = std::result::Result<T, Error>;
 type ClientId = u32;
+type EventSourceId = u32;
+type RequestId = u32;
 
 pub struct Http<R: Runtime> {
-    #[allow(dead_code)]
     app: AppHandle<R>,
     pub(crate) clients: Mutex<HashMap<ClientId, commands::Client>>,
+    /// The client of [`Http::request`] and the NDJSON streams, built from the default headers.
+    pub(crate) client: commands::Client,
+    /// The tasks of the event sources and NDJSON streams.
+    pub(crate) event_sources: Mutex<HashMap<EventSourceId, JoinHandle<()>>>,
+    /// The abort signal of the requests started with an ID.
+    pub(crate) aborts: Mutex<HashMap<RequestId, Arc<Notify>>>,
     pub(crate) scope: scope::Scope,
+    /// The response cache, enabled with [`Builder::cache`].
+    pub(crate) cache: Option<Arc<commands::Cache>>,
+    /// The headers of all the requests, set with [`Builder::default_header`].
+    pub(crate) default_headers: header::HeaderMap,
 }
 
-impl<R: Runtime> Http<R> {}
+impl<R: Runtime> Http<R> {
+    /// Removes the responses stored by the cache. A no-op when the cache is disabled.
+    pub fn clear_cache(&self) -> Result<()> {
+        match &self.cache {
+            Some(cache) => cache.clear().map_err(Into::into),
+            None => Ok(()),
+        }
+    }
+}
 
 pub trait HttpExt<R: Runtime> {
     fn http(&self) -> &Http<R>;
@@ -40,26 +72,114 @@ impl<R: Runtime, T: Manager<R>> HttpExt<R> for T {
 }
 
 pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
-    Builder::<R, Option<Config>>::new("http")
-        .js_init_script(include_str!("api-iife.js").to_string())
-        .invoke_handler(tauri::generate_handler![
-            commands::create_client,
-            commands::drop_client,
-            commands::request
-        ])
-        .setup(|app, api| {
-            let default_scope = HttpAllowlistScope::default();
-            app.manage(Http {
-                app: app.clone(),
-                clients: Default::default(),
-                scope: scope::Scope::new(
-                    api.config()
-                        .as_ref()
-                        .map(|c| &c.scope)
-                        .unwrap_or(&default_scope),
-                ),
-            });
-            Ok(())
-        })
-        .build()
+    Builder::new().build()
+}
+
+/// The http plugin builder.
+#[derive(Default)]
+pub struct Builder {
+    cache: bool,
+    default_headers: header::HeaderMap,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables the cache of the `GET` responses, stored in the app data directory.
+    ///
+    /// The cache follows the `Cache-Control` header of the responses, skipping the
+    /// `no-store` and `private` ones, and revalidates the stale responses with their
+    /// `ETag` and `Last-Modified` headers.
+    pub fn cache(mut self, enabled: bool) -> Self {
+        self.cache = enabled;
+        self
+    }
+
+    /// Adds a header to all the requests and event sources, unless they set a header of the
+    /// same name themselves.
+    pub fn default_header<K, V>(mut self, key: K, value: V) -> Result<Self>
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        let key: std::result::Result<HeaderName, http::Error> = key.try_into().map_err(Into::into);
+        let value: std::result::Result<HeaderValue, http::Error> =
+            value.try_into().map_err(Into::into);
+        self.default_headers.insert(key?, value?);
+        Ok(self)
+    }
+
+    /// Sets the `User-Agent` header of all the requests and event sources, unless they set
+    /// their own.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), tauri_plugin_http::Error> {
+    /// tauri::Builder::default().plugin(
+    ///     tauri_plugin_http::Builder::new()
+    ///         .user_agent(concat!("my-app/", env!("CARGO_PKG_VERSION")))?
+    ///         .build(),
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn user_agent<V>(self, value: V) -> Result<Self>
+    where
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        self.default_header(header::USER_AGENT, value)
+    }
+
+    pub fn build<R: Runtime>(self) -> TauriPlugin<R, Option<Config>> {
+        PluginBuilder::<R, Option<Config>>::new("http")
+            .js_init_script(include_str!("api-iife.js").to_string())
+            .invoke_handler(tauri::generate_handler![
+                commands::create_client,
+                commands::drop_client,
+                commands::request,
+                commands::abort,
+                commands::clear_cache,
+                commands::sse_connect,
+                commands::sse_close,
+                commands::ndjson_stream,
+                commands::ndjson_close
+            ])
+            .setup(move |app, api| {
+                let default_scope = HttpAllowlistScope::default();
+                let cache = if self.cache {
+                    let dir = app.path().app_data_dir()?.join(commands::CACHE_DIR);
+                    Some(Arc::new(commands::Cache::new(dir)))
+                } else {
+                    None
+                };
+                let client = ClientBuilder {
+                    default_headers: self.default_headers.clone(),
+                    ..Default::default()
+                }
+                .build()?;
+                app.manage(Http {
+                    app: app.clone(),
+                    clients: Default::default(),
+                    client,
+                    event_sources: Default::default(),
+                    aborts: Default::default(),
+                    scope: scope::Scope::new(
+                        api.config()
+                            .as_ref()
+                            .map(|c| &c.scope)
+                            .unwrap_or(&default_scope),
+                    ),
+                    cache,
+                    default_headers: self.default_headers.clone(),
+                });
+                Ok(())
+            })
+            .build()
+    }
 }
@@ -0,0 +1,228 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! [Newline-delimited JSON](https://github.com/ndjson/ndjson-spec) streams support.
+
+use reqwest::{header, Url};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{api::ipc::Channel, Runtime};
+
+/// A line of the stream that is not valid JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseError {
+    /// The offending line, without its line ending.
+    pub line: String,
+    pub error: String,
+}
+
+/// A message sent to the stream channel.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "payload", rename_all = "camelCase")]
+enum Message {
+    Open,
+    Value(Value),
+    ParseError(ParseError),
+    Error(String),
+    Closed,
+}
+
+/// The default maximum length of a line, in bytes.
+pub const DEFAULT_MAX_LINE_LEN: usize = 1024 * 1024;
+
+/// The number of bytes of a line too long kept for its [`ParseError`].
+const PREVIEW_LEN: usize = 256;
+
+/// An incremental newline-delimited JSON parser.
+///
+/// Blank lines are skipped and a `\r\n` line ending is accepted. A line longer than the maximum
+/// length is reported as a [`ParseError`] with its start, without buffering the rest of it.
+#[derive(Debug)]
+pub struct NdjsonParser {
+    line: Vec<u8>,
+    max_line_len: usize,
+    /// Whether the current line is too long, so its bytes are discarded.
+    overflowed: bool,
+}
+
+impl Default for NdjsonParser {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_LINE_LEN)
+    }
+}
+
+impl NdjsonParser {
+    pub fn new(max_line_len: usize) -> Self {
+        Self {
+            line: Vec::new(),
+            max_line_len,
+            overflowed: false,
+        }
+    }
+
+    /// Feeds a chunk of the stream, returning the values of the lines it completed.
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<Result<Value, ParseError>> {
+        let mut values = Vec::new();
+        let mut lines = chunk.split(|&byte| byte == b'\n');
+        // the last part is not terminated yet
+        let rest = lines.next_back().unwrap_or_default();
+        for line in lines {
+            self.append(line);
+            values.extend(self.take_line());
+        }
+        self.append(rest);
+        values
+    }
+
+    /// Parses the last line of the stream if it has no line ending.
+    pub fn finish(&mut self) -> Option<Result<Value, ParseError>> {
+        self.take_line()
+    }
+
+    fn append(&mut self, bytes: &[u8]) {
+        if self.overflowed {
+            return;
+        }
+        if self.line.len() + bytes.len() > self.max_line_len {
+            let preview_len = PREVIEW_LEN.min(self.max_line_len);
+            let kept = preview_len.saturating_sub(self.line.len()).min(bytes.len());
+            self.line.extend_from_slice(&bytes[..kept]);
+            self.line.truncate(preview_len);
+            self.overflowed = true;
+        } else {
+            self.line.extend_from_slice(bytes);
+        }
+    }
+
+    fn take_line(&mut self) -> Option<Result<Value, ParseError>> {
+        let line = std::mem::take(&mut self.line);
+        if std::mem::take(&mut self.overflowed) {
+            return Some(Err(ParseError {
+                line: String::from_utf8_lossy(&line).into_owned(),
+                error: format!("the line is longer than {} bytes", self.max_line_len),
+            }));
+        }
+        parse_line(&line)
+    }
+}
+
+fn parse_line(line: &[u8]) -> Option<Result<Value, ParseError>> {
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    if line.iter().all(u8::is_ascii_whitespace) {
+        return None;
+    }
+    Some(serde_json::from_slice(line).map_err(|e| ParseError {
+        line: String::from_utf8_lossy(line).into_owned(),
+        error: e.to_string(),
+    }))
+}
+
+/// Reads the stream with `client` and forwards its values to `channel`, until it ends or fails.
+pub async fn stream<R: Runtime>(
+    client: reqwest::Client,
+    url: Url,
+    mut headers: header::HeaderMap,
+    channel: Channel<R>,
+) {
+    headers
+        .entry(header::ACCEPT)
+        .or_insert(header::HeaderValue::from_static("application/x-ndjson"));
+    let request = client.get(url).headers(headers);
+    let mut response = match request.send().await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            let _ = channel.send(&Message::Error(format!(
+                "unexpected stream response with status {}",
+                response.status()
+            )));
+            let _ = channel.send(&Message::Closed);
+            return;
+        }
+        Err(e) => {
+            let _ = channel.send(&Message::Error(e.to_string()));
+            let _ = channel.send(&Message::Closed);
+            return;
+        }
+    };
+
+    if channel.send(&Message::Open).is_err() {
+        return;
+    }
+
+    let mut parser = NdjsonParser::default();
+    loop {
+        let values = match response.chunk().await {
+            Ok(Some(chunk)) => parser.feed(&chunk),
+            Ok(None) => {
+                if let Some(value) = parser.finish() {
+                    let _ = channel.send(&message(value));
+                }
+                break;
+            }
+            Err(e) => {
+                let _ = channel.send(&Message::Error(e.to_string()));
+                break;
+            }
+        };
+        for value in values {
+            // the webview is gone
+            if channel.send(&message(value)).is_err() {
+                return;
+            }
+        }
+    }
+    let _ = channel.send(&Message::Closed);
+}
+
+fn message(value: Result<Value, ParseError>) -> Message {
+    match value {
+        Ok(value) => Message::Value(value),
+        Err(e) => Message::ParseError(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn handles_split_chunks_and_line_endings() {
+        let mut parser = NdjsonParser::default();
+        assert!(parser.feed(br#"{"a":"#).is_empty());
+        assert_eq!(
+            parser.feed(b"1}\r\n\n[2]\n\"th"),
+            vec![Ok(json!({ "a": 1 })), Ok(json!([2]))]
+        );
+        assert!(parser.feed("ré\"".as_bytes()).is_empty());
+        assert_eq!(parser.finish(), Some(Ok(json!("thré"))));
+        assert_eq!(parser.finish(), None);
+    }
+
+    #[test]
+    fn reports_invalid_lines() {
+        let mut parser = NdjsonParser::default();
+        let values = parser.feed(b"1\n{oops\n3\n");
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0], Ok(json!(1)));
+        assert!(matches!(&values[1], Err(e) if e.line == "{oops"));
+        assert_eq!(values[2], Ok(json!(3)));
+    }
+
+    #[test]
+    fn reports_lines_too_long() {
+        let mut parser = NdjsonParser::new(8);
+        assert!(parser.feed(b"[1,2,3,").is_empty());
+        assert!(parser.feed(b"4,5,6,7]").is_empty());
+        let values = parser.feed(b"8]\n[9]\n");
+        assert_eq!(values.len(), 2);
+        assert!(matches!(&values[0], Err(e) if e.line == "[1,2,3,4"));
+        assert_eq!(values[1], Ok(json!([9])));
+
+        assert!(parser.feed(b"123456789").is_empty());
+        assert!(matches!(parser.finish(), Some(Err(e)) if e.line == "12345678"));
+        assert_eq!(parser.finish(), None);
+    }
+}
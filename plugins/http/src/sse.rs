@@ -0,0 +1,283 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! [Server-sent events](https://html.spec.whatwg.org/multipage/server-sent-events.html) support.
+
+use reqwest::{header, StatusCode, Url};
+use serde::Serialize;
+use tauri::{api::ipc::Channel, Runtime};
+
+use std::time::Duration;
+
+/// The reconnection delay used until the server sends a `retry` field.
+const DEFAULT_RETRY: Duration = Duration::from_secs(3);
+
+/// An event dispatched by the event stream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    /// The event type, `message` unless set by the `event` field.
+    pub event: String,
+    /// The event data, the `data` fields joined by new lines.
+    pub data: String,
+    /// The last event ID, sent back on reconnection with the `Last-Event-ID` header.
+    pub id: String,
+    /// The reconnection delay in milliseconds, if updated by this event.
+    pub retry: Option<u64>,
+}
+
+/// A message sent to the event stream channel.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "payload", rename_all = "camelCase")]
+enum Message {
+    Open,
+    Event(Event),
+    Error(String),
+    Closed,
+}
+
+/// An incremental `text/event-stream` parser.
+#[derive(Debug, Default)]
+pub struct EventStreamParser {
+    line: Vec<u8>,
+    after_cr: bool,
+    started: bool,
+    event: String,
+    data: String,
+    last_event_id: String,
+    retry: Option<u64>,
+    retry_updated: bool,
+}
+
+impl EventStreamParser {
+    /// The last event ID set by the stream.
+    pub fn last_event_id(&self) -> &str {
+        &self.last_event_id
+    }
+
+    /// The reconnection delay set by the stream.
+    pub fn retry(&self) -> Option<Duration> {
+        self.retry.map(Duration::from_millis)
+    }
+
+    /// Discards the pending event, keeping the last event ID and reconnection delay.
+    pub fn reset(&mut self) {
+        self.line.clear();
+        self.after_cr = false;
+        self.started = false;
+        self.event.clear();
+        self.data.clear();
+        self.retry_updated = false;
+    }
+
+    /// Feeds a chunk of the stream, returning the events it completed.
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<Event> {
+        let mut events = Vec::new();
+        for &byte in chunk {
+            // a `\r\n` pair is a single line break
+            if std::mem::take(&mut self.after_cr) && byte == b'\n' {
+                continue;
+            }
+            match byte {
+                b'\r' | b'\n' => {
+                    self.after_cr = byte == b'\r';
+                    let line = std::mem::take(&mut self.line);
+                    if let Some(event) = self.process_line(&line) {
+                        events.push(event);
+                    }
+                }
+                _ => self.line.push(byte),
+            }
+        }
+        events
+    }
+
+    fn process_line(&mut self, mut line: &[u8]) -> Option<Event> {
+        if !std::mem::replace(&mut self.started, true) {
+            line = line.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(line);
+        }
+
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(b":") {
+            return None;
+        }
+
+        let line = String::from_utf8_lossy(line);
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line.as_ref(), ""),
+        };
+
+        match field {
+            "event" => self.event = value.into(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.last_event_id = value.into(),
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(retry) = value.parse() {
+                    self.retry.replace(retry);
+                    self.retry_updated = true;
+                }
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<Event> {
+        let event = std::mem::take(&mut self.event);
+        let mut data = std::mem::take(&mut self.data);
+        if data.is_empty() {
+            return None;
+        }
+        data.pop();
+
+        Some(Event {
+            event: if event.is_empty() {
+                "message".into()
+            } else {
+                event
+            },
+            data,
+            id: self.last_event_id.clone(),
+            retry: std::mem::take(&mut self.retry_updated)
+                .then_some(self.retry)
+                .flatten(),
+        })
+    }
+}
+
+/// Connects to the event stream and forwards its events to `channel`,
+/// reconnecting whenever the connection drops until the stream is closed or fails.
+pub async fn connect<R: Runtime>(url: Url, headers: header::HeaderMap, channel: Channel<R>) {
+    let client = reqwest::Client::new();
+    let mut parser = EventStreamParser::default();
+
+    loop {
+        let mut request = client
+            .get(url.clone())
+            .headers(headers.clone())
+            .header(header::ACCEPT, "text/event-stream")
+            .header(header::CACHE_CONTROL, "no-cache");
+        if !parser.last_event_id().is_empty() {
+            request = request.header("Last-Event-ID", parser.last_event_id());
+        }
+
+        match request.send().await {
+            Ok(mut response) => {
+                let is_event_stream = response
+                    .headers()
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .map(|value| value.starts_with("text/event-stream"))
+                    .unwrap_or_default();
+
+                // anything but a `200 OK` event stream fails the connection for good
+                if response.status() != StatusCode::OK || !is_event_stream {
+                    let _ = channel.send(&Message::Error(format!(
+                        "unexpected event stream response with status {}",
+                        response.status()
+                    )));
+                    let _ = channel.send(&Message::Closed);
+                    return;
+                }
+
+                if channel.send(&Message::Open).is_err() {
+                    return;
+                }
+
+                parser.reset();
+                loop {
+                    match response.chunk().await {
+                        Ok(Some(chunk)) => {
+                            for event in parser.feed(&chunk) {
+                                // the webview is gone
+                                if channel.send(&Message::Event(event)).is_err() {
+                                    return;
+                                }
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            let _ = channel.send(&Message::Error(e.to_string()));
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                if channel.send(&Message::Error(e.to_string())).is_err() {
+                    return;
+                }
+            }
+        }
+
+        tokio::time::sleep(parser.retry().unwrap_or(DEFAULT_RETRY)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(data: &str) -> Event {
+        Event {
+            event: "message".into(),
+            data: data.into(),
+            id: String::new(),
+            retry: None,
+        }
+    }
+
+    #[test]
+    fn parses_fields() {
+        let mut parser = EventStreamParser::default();
+        let events = parser.feed(b"event: update\nid: 1\nretry: 5000\ndata: a\ndata:b\n\n");
+        assert_eq!(
+            events,
+            vec![Event {
+                event: "update".into(),
+                data: "a\nb".into(),
+                id: "1".into(),
+                retry: Some(5000),
+            }]
+        );
+        assert_eq!(parser.last_event_id(), "1");
+        assert_eq!(parser.retry(), Some(Duration::from_millis(5000)));
+    }
+
+    #[test]
+    fn handles_split_chunks_and_line_endings() {
+        let mut parser = EventStreamParser::default();
+        assert!(parser.feed(b"\xEF\xBB\xBFdata: fi").is_empty());
+        assert!(parser.feed(b"rst\r").is_empty());
+        assert_eq!(
+            parser.feed(b"\n\r\ndata: second\r\r"),
+            vec![event("first"), event("second")]
+        );
+        assert_eq!(parser.feed(b"data\n\n"), vec![event("")]);
+    }
+
+    #[test]
+    fn ignores_comments_and_invalid_fields() {
+        let mut parser = EventStreamParser::default();
+        let events = parser.feed(b": keep-alive\nretry: 1s\nid: a\0b\nunknown: x\n\n");
+        assert!(events.is_empty());
+        assert_eq!(parser.retry(), None);
+        assert_eq!(parser.last_event_id(), "");
+    }
+
+    #[test]
+    fn reset_keeps_last_event_id() {
+        let mut parser = EventStreamParser::default();
+        parser.feed(b"id: 42\ndata: lost");
+        parser.reset();
+        assert_eq!(parser.feed(b"data: kept\n\n")[0].id, "42");
+    }
+}
@@ -16,7 +16,7 @@ export type LogOptions = {
   keyValues?: Record<string, string | undefined>;
 };
 
-enum LogLevel {
+export enum LogLevel {
   /**
    * The "trace" level.
    *
@@ -188,6 +188,48 @@ export async function trace(
   await log(LogLevel.Trace, message, options);
 }
 
+/**
+ * A log record kept in memory by the `Memory` target.
+ */
+export interface LogRecord {
+  /** Milliseconds since the Unix epoch. */
+  timestamp: number;
+  level: LogLevel;
+  target: string;
+  /** The message, formatted by the logger format. */
+  message: string;
+}
+
+/**
+ * Which records {@link recentLogs} returns.
+ */
+export interface LogFilter {
+  /** The least severe level returned. */
+  level?: LogLevel;
+  /** The target, including its submodules, e.g. `app` matches `app::commands`. */
+  target?: string;
+  /** A substring of the message. */
+  contains?: string;
+  /** The maximum number of records returned, the most recent ones. */
+  limit?: number;
+}
+
+/**
+ * Gets the records kept in memory by the `Memory` target, oldest first.
+ * Empty if no memory target is configured.
+ *
+ * # Examples
+ *
+ * ```js
+ * import { recentLogs, LogLevel } from '@tauri-apps/plugin-log';
+ *
+ * const errors = await recentLogs({ level: LogLevel.Warn, limit: 50 });
+ * ```
+ */
+export async function recentLogs(filter?: LogFilter): Promise<LogRecord[]> {
+  return await window.__TAURI_INVOKE__("plugin:log|recent_logs", { filter });
+}
+
 interface RecordPayload {
   level: LogLevel;
   message: string;
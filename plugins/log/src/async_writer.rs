@@ -0,0 +1,113 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The background writes of the file targets, enabled with
+//! [`Builder::async_file_writes`](crate::Builder::async_file_writes).
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc,
+    },
+    thread,
+};
+
+/// What logging does when the queue of a file target is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Wait for the queue to make room, so no record is lost.
+    Block,
+    /// Drop the record. The number of dropped records is written once the queue has room again.
+    Drop,
+}
+
+enum Message {
+    Write(String),
+    Flush(mpsc::Sender<()>),
+}
+
+/// A file written by a dedicated thread, fed through a bounded queue.
+pub(crate) struct AsyncWriter {
+    sender: SyncSender<Message>,
+    overflow: OverflowPolicy,
+    dropped: AtomicU64,
+}
+
+impl AsyncWriter {
+    pub(crate) fn spawn(file: File, capacity: usize, overflow: OverflowPolicy) -> Arc<Self> {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        thread::Builder::new()
+            .name("tauri-plugin-log-writer".into())
+            .spawn(move || write_loop(BufWriter::new(file), receiver))
+            .expect("failed to spawn the log writer thread");
+        Arc::new(Self {
+            sender,
+            overflow,
+            dropped: AtomicU64::new(0),
+        })
+    }
+
+    pub(crate) fn write(&self, line: String) {
+        match self.overflow {
+            OverflowPolicy::Block => {
+                let _ = self.sender.send(Message::Write(line));
+            }
+            OverflowPolicy::Drop => {
+                let dropped = self.dropped.swap(0, Ordering::Relaxed);
+                let line = if dropped > 0 {
+                    format!("[{dropped} log records dropped, the log queue was full]\n{line}")
+                } else {
+                    line
+                };
+                if let Err(TrySendError::Full(_)) = self.sender.try_send(Message::Write(line)) {
+                    self.dropped.fetch_add(dropped + 1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Blocks until the records queued so far are written to the file.
+    pub(crate) fn flush(&self) {
+        let (ack, done) = mpsc::channel();
+        if self.sender.send(Message::Flush(ack)).is_ok() {
+            let _ = done.recv();
+        }
+    }
+}
+
+fn write_loop(mut file: BufWriter<File>, receiver: Receiver<Message>) {
+    while let Ok(message) = receiver.recv() {
+        let mut next = Some(message);
+        // write what is queued before flushing, so bursts are batched
+        while let Some(message) = next.take() {
+            match message {
+                Message::Write(line) => {
+                    let _ = file.write_all(line.as_bytes());
+                }
+                Message::Flush(ack) => {
+                    let _ = file.flush();
+                    let _ = ack.send(());
+                }
+            }
+            next = receiver.try_recv().ok();
+        }
+        let _ = file.flush();
+    }
+    let _ = file.flush();
+}
+
+/// The async writers of the file targets, flushed when the app exits.
+#[derive(Default)]
+pub(crate) struct AsyncWriters(pub(crate) Vec<Arc<AsyncWriter>>);
+
+impl AsyncWriters {
+    pub(crate) fn flush(&self) {
+        for writer in &self.0 {
+            writer.flush();
+        }
+    }
+}
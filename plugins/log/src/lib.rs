@@ -16,15 +16,21 @@ use std::{
     fs::{self, File},
     iter::FromIterator,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 use tauri::{
     plugin::{self, TauriPlugin},
-    Manager, Runtime,
+    Manager, RunEvent, Runtime,
 };
 
+pub use async_writer::OverflowPolicy;
 pub use fern;
+pub use ring_buffer::{LogFilter, LogRecord};
 use time::OffsetDateTime;
 
+mod async_writer;
+mod ring_buffer;
+
 pub const WEBVIEW_TARGET: &str = "Webview";
 
 #[cfg(target_os = "ios")]
@@ -174,6 +180,10 @@ pub enum TargetKind {
     ///
     /// This requires the webview to subscribe to log events, via this plugins `attachConsole` function.
     Webview,
+    /// Keep the last `capacity` records in memory, returned by the `recentLogs` function.
+    ///
+    /// Only one memory target can be configured, the plugin setup fails otherwise.
+    Memory { capacity: usize },
 }
 
 /// A log target.
@@ -243,6 +253,7 @@ pub struct Builder {
     timezone_strategy: TimezoneStrategy,
     max_file_size: u128,
     targets: Vec<Target>,
+    async_file_writes: Option<(usize, OverflowPolicy)>,
 }
 
 impl Default for Builder {
@@ -271,6 +282,7 @@ impl Default for Builder {
             timezone_strategy: DEFAULT_TIMEZONE_STRATEGY,
             max_file_size: DEFAULT_MAX_FILE_SIZE,
             targets: DEFAULT_LOG_TARGETS.into(),
+            async_file_writes: None,
         }
     }
 }
@@ -308,6 +320,21 @@ impl Builder {
         self
     }
 
+    /// Writes the file targets from a background thread, so logging does not wait for the disk.
+    ///
+    /// Each file target queues up to `capacity` records, and `overflow` decides what happens
+    /// when its queue is full. The queued records are written before the app exits.
+    ///
+    /// ```rust
+    /// use tauri_plugin_log::OverflowPolicy;
+    /// tauri_plugin_log::Builder::new()
+    ///     .async_file_writes(4096, OverflowPolicy::Block);
+    /// ```
+    pub fn async_file_writes(mut self, capacity: usize, overflow: OverflowPolicy) -> Self {
+        self.async_file_writes = Some((capacity, overflow));
+        self
+    }
+
     pub fn format<F>(mut self, formatter: F) -> Self
     where
         F: Fn(FormatCallback, &Arguments, &Record) + Sync + Send + 'static,
@@ -390,9 +417,10 @@ impl Builder {
     pub fn build<R: Runtime>(mut self) -> TauriPlugin<R> {
         plugin::Builder::new("log")
             .js_init_script(include_str!("api-iife.js").to_string())
-            .invoke_handler(tauri::generate_handler![log])
+            .invoke_handler(tauri::generate_handler![log, ring_buffer::recent_logs])
             .setup(move |app_handle, _api| {
                 let app_name = &app_handle.package_info().name;
+                let mut async_writers = async_writer::AsyncWriters::default();
 
                 // setup targets
                 for target in self.targets {
@@ -431,14 +459,17 @@ impl Builder {
                                 fs::create_dir_all(&path).unwrap();
                             }
 
-                            fern::log_file(get_log_file_path(
-                                &path,
-                                file_name.as_deref().unwrap_or(app_name),
-                                &self.rotation_strategy,
-                                &self.timezone_strategy,
-                                self.max_file_size,
-                            )?)?
-                            .into()
+                            file_output(
+                                get_log_file_path(
+                                    &path,
+                                    file_name.as_deref().unwrap_or(app_name),
+                                    &self.rotation_strategy,
+                                    &self.timezone_strategy,
+                                    self.max_file_size,
+                                )?,
+                                self.async_file_writes,
+                                &mut async_writers,
+                            )?
                         }
                         #[cfg(mobile)]
                         TargetKind::LogDir { .. } => continue,
@@ -449,14 +480,17 @@ impl Builder {
                                 fs::create_dir_all(&path).unwrap();
                             }
 
-                            fern::log_file(get_log_file_path(
-                                &path,
-                                file_name.as_deref().unwrap_or(app_name),
-                                &self.rotation_strategy,
-                                &self.timezone_strategy,
-                                self.max_file_size,
-                            )?)?
-                            .into()
+                            file_output(
+                                get_log_file_path(
+                                    &path,
+                                    file_name.as_deref().unwrap_or(app_name),
+                                    &self.rotation_strategy,
+                                    &self.timezone_strategy,
+                                    self.max_file_size,
+                                )?,
+                                self.async_file_writes,
+                                &mut async_writers,
+                            )?
                         }
                         TargetKind::Webview => {
                             let app_handle = app_handle.clone();
@@ -472,6 +506,18 @@ impl Builder {
                                 });
                             })
                         }
+                        TargetKind::Memory { capacity } => {
+                            if !app_handle.manage(Arc::new(ring_buffer::RingBuffer::new(capacity)))
+                            {
+                                return Err("only one memory target can be configured".into());
+                            }
+                            let buffer = app_handle
+                                .state::<Arc<ring_buffer::RingBuffer>>()
+                                .inner()
+                                .clone();
+
+                            fern::Output::call(move |record| buffer.push(record))
+                        }
                     };
                     target_dispatch = target_dispatch.chain(logger);
 
@@ -479,13 +525,40 @@ impl Builder {
                 }
 
                 self.dispatch.apply()?;
+                app_handle.manage(async_writers);
 
                 Ok(())
             })
+            .on_event(|app_handle, event| {
+                if let (RunEvent::Exit, Some(writers)) =
+                    (event, app_handle.try_state::<async_writer::AsyncWriters>())
+                {
+                    writers.flush();
+                }
+            })
             .build()
     }
 }
 
+fn file_output(
+    path: PathBuf,
+    async_file_writes: Option<(usize, OverflowPolicy)>,
+    async_writers: &mut async_writer::AsyncWriters,
+) -> plugin::Result<fern::Output> {
+    let Some((capacity, overflow)) = async_file_writes else {
+        return Ok(fern::log_file(path)?.into());
+    };
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let writer = async_writer::AsyncWriter::spawn(file, capacity, overflow);
+    async_writers.0.push(writer.clone());
+    Ok(fern::Output::call(move |record| {
+        writer.write(format!("{}\n", record.args()))
+    }))
+}
+
 fn get_log_file_path(
     dir: &impl AsRef<Path>,
     file_name: &str,
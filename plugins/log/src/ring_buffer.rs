@@ -0,0 +1,131 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The records kept in memory by the [`TargetKind::Memory`](crate::TargetKind::Memory) target.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, Runtime, Window};
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::LogLevel;
+
+/// A log record kept in memory.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRecord {
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub level: LogLevel,
+    pub target: String,
+    /// The message, formatted by the builder's format.
+    pub message: String,
+}
+
+/// Which records [`recent_logs`] returns.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFilter {
+    /// The least severe level returned.
+    level: Option<LogLevel>,
+    /// The target, including its submodules, e.g. `app` matches `app::commands`.
+    target: Option<String>,
+    /// A substring of the message.
+    contains: Option<String>,
+    /// The maximum number of records returned, the most recent ones.
+    limit: Option<usize>,
+}
+
+impl LogFilter {
+    fn matches(&self, record: &LogRecord) -> bool {
+        if let Some(level) = &self.level {
+            if log::Level::from(record.level.clone()) > log::Level::from(level.clone()) {
+                return false;
+            }
+        }
+        if let Some(target) = &self.target {
+            let submodule = record
+                .target
+                .strip_prefix(target.as_str())
+                .map(|rest| rest.is_empty() || rest.starts_with("::"));
+            if submodule != Some(true) {
+                return false;
+            }
+        }
+        if let Some(contains) = &self.contains {
+            if !record.message.contains(contains.as_str()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// The last `capacity` records.
+pub(crate) struct RingBuffer {
+    capacity: usize,
+    records: Mutex<VecDeque<LogRecord>>,
+}
+
+impl RingBuffer {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub(crate) fn push(&self, record: &log::Record) {
+        if self.capacity == 0 {
+            return;
+        }
+        // build the record before locking, so the lock is only held to move it in
+        let record = LogRecord {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+            level: record.level().into(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+        let mut records = self.records.lock().unwrap();
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    fn query(&self, filter: &LogFilter) -> Vec<LogRecord> {
+        let records = self.records.lock().unwrap();
+        let mut matches: Vec<LogRecord> = records
+            .iter()
+            .rev()
+            .filter(|record| filter.matches(record))
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
+        drop(records);
+        matches.reverse();
+        matches
+    }
+}
+
+/// The records kept by the memory target, oldest first.
+///
+/// Empty if no memory target is configured.
+#[tauri::command]
+pub(crate) fn recent_logs<R: Runtime>(
+    window: Window<R>,
+    filter: Option<LogFilter>,
+) -> Vec<LogRecord> {
+    window
+        .try_state::<Arc<RingBuffer>>()
+        .map(|buffer| buffer.query(&filter.unwrap_or_default()))
+        .unwrap_or_default()
+}
@@ -14,6 +14,7 @@ import {
   PluginListener,
   addPluginListener,
 } from "@tauri-apps/api/tauri";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
 
 /**
  * Options to send a notification.
@@ -431,6 +432,46 @@ async function cancelAll(): Promise<void> {
   return invoke("plugin:notification|cancel");
 }
 
+/**
+ * Shows the notification with the given identifier again after a delay, e.g. from a snooze action.
+ * A repeating notification keeps its schedule.
+ *
+ * On desktop only the notifications shown since the app started can be snoozed.
+ * On mobile the notification must still be active and the snoozed notification gets a new identifier.
+ *
+ * @example
+ * ```typescript
+ * import { onAction, snooze } from '@tauri-apps/api/notification';
+ * await onAction((notification) => snooze(notification.id, 10 * 60 * 1000));
+ * ```
+ *
+ * @param id The notification identifier.
+ * @param duration The delay in milliseconds.
+ * @returns A promise resolving to the identifier of the snoozed notification.
+ *
+ * @since 2.0.0
+ */
+async function snooze(id: number, duration: number): Promise<number> {
+  return invoke("plugin:notification|snooze", { id, duration });
+}
+
+/**
+ * Cancels the pending and repeating occurrences of the notification with the given identifier.
+ *
+ * @example
+ * ```typescript
+ * import { cancelRepeat } from '@tauri-apps/api/notification';
+ * await cancelRepeat(42);
+ * ```
+ *
+ * @returns A promise indicating the success or failure of the operation.
+ *
+ * @since 2.0.0
+ */
+async function cancelRepeat(id: number): Promise<void> {
+  return invoke("plugin:notification|cancel_repeat", { id });
+}
+
 /**
  * Retrieves the list of active notifications.
  *
@@ -483,7 +524,10 @@ async function removeAllActive(): Promise<void> {
 }
 
 /**
- * Removes all active notifications.
+ * Creates a notification channel.
+ *
+ * Channels are only used on Android, this is a no-op on other platforms.
+ * Channels can also be registered ahead of time with the Rust plugin `Builder::channel` API.
  *
  * @example
  * ```typescript
@@ -503,7 +547,7 @@ async function removeAllActive(): Promise<void> {
  * @since 2.0.0
  */
 async function createChannel(channel: Channel): Promise<void> {
-  return invoke("plugin:notification|create_channel", { ...channel });
+  return invoke("plugin:notification|create_channel", { channel });
 }
 
 /**
@@ -537,7 +581,7 @@ async function removeChannel(id: string): Promise<void> {
  * @since 2.0.0
  */
 async function channels(): Promise<Channel[]> {
-  return invoke("plugin:notification|getActive");
+  return invoke("plugin:notification|list_channels");
 }
 
 async function onNotificationReceived(
@@ -552,6 +596,81 @@ async function onAction(
   return addPluginListener("notification", "actionPerformed", cb);
 }
 
+/**
+ * A click on a notification action, with the text typed in its input if any.
+ *
+ * @since 2.0.0
+ */
+interface Interaction {
+  /** The notification identifier. */
+  id: number;
+  /** The identifier of the clicked action. */
+  actionId: string;
+  /** The reply typed in the action input. */
+  input?: string;
+  /** When the user interacted with the notification, in milliseconds since the Unix epoch. */
+  timestamp: number;
+}
+
+/**
+ * Removes and returns the recorded notification interactions, including the ones
+ * that launched the app. Only available on desktop.
+ *
+ * The interactions are kept, even across restarts, until they are taken.
+ *
+ * @example
+ * ```typescript
+ * import { takeInteractions } from '@tauri-apps/api/notification';
+ * // ignore the interactions older than an hour
+ * for (const interaction of await takeInteractions(60 * 60 * 1000)) {
+ *   console.log(interaction.actionId, interaction.input);
+ * }
+ * ```
+ *
+ * @param maxAge Drops the interactions older than this delay, in milliseconds.
+ *
+ * @since 2.0.0
+ */
+async function takeInteractions(maxAge?: number): Promise<Interaction[]> {
+  return invoke("plugin:notification|take_interactions", { maxAge });
+}
+
+/**
+ * Listens to the notification interactions recorded while the app is running.
+ * Only available on desktop.
+ *
+ * @since 2.0.0
+ */
+async function onInteraction(
+  cb: (interaction: Interaction) => void
+): Promise<UnlistenFn> {
+  return listen<Interaction>("notification://interaction", (event) =>
+    cb(event.payload)
+  );
+}
+
+/**
+ * Sets the count shown on the app icon, e.g. of the unread messages.
+ * `null` or `0` clears it. Only available on desktop.
+ *
+ * #### Platform-specific
+ *
+ * - **macOS:** Shown on the dock icon, in full.
+ * - **Linux:** Only shown by the docks supporting the Unity launcher API, e.g. Dash to Dock, Plank or KDE Plasma, for the desktop file named after the app binary. Some docks only show up to 4 digits.
+ * - **Windows:** Drawn as the overlay icon of the taskbar buttons of the open windows. Counts above 99 are shown as `99+`.
+ *
+ * @example
+ * ```typescript
+ * import { setBadgeCount } from '@tauri-apps/api/notification';
+ * await setBadgeCount(3);
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function setBadgeCount(count: number | null): Promise<void> {
+  return invoke("plugin:notification|set_badge_count", { count });
+}
+
 export type {
   Attachment,
   Options,
@@ -561,6 +680,7 @@ export type {
   PendingNotification,
   ActiveNotification,
   Channel,
+  Interaction,
 };
 
 export {
@@ -573,6 +693,8 @@ export {
   pending,
   cancel,
   cancelAll,
+  snooze,
+  cancelRepeat,
   active,
   removeActive,
   removeAllActive,
@@ -581,4 +703,7 @@ export {
   channels,
   onNotificationReceived,
   onAction,
+  takeInteractions,
+  onInteraction,
+  setBadgeCount,
 };
@@ -0,0 +1,244 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The count on the app icon, e.g. of the unread messages.
+//!
+//! - **macOS:** The label of the dock tile.
+//! - **Linux:** The count of the launcher entry, sent with the `com.canonical.Unity.LauncherEntry`
+//!   D-Bus signal that the Unity, Dash to Dock, Plank and KDE Plasma docks listen to.
+//! - **Windows:** A taskbar overlay icon with the number, drawn on the buttons of the open windows.
+
+use tauri::{AppHandle, Runtime};
+
+/// The state of the badge.
+#[derive(Default)]
+pub(crate) struct Badge {
+    /// The docks clear the count of the launcher entry once its sender leaves the bus,
+    /// so the connection is kept open.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    ))]
+    connection: std::sync::Mutex<Option<zbus::blocking::Connection>>,
+}
+
+impl Badge {
+    #[cfg(target_os = "macos")]
+    pub(crate) fn set<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        count: Option<u32>,
+    ) -> crate::Result<()> {
+        use cocoa::{
+            appkit::NSApp,
+            base::{id, nil},
+            foundation::NSString,
+        };
+        use objc::*;
+
+        let count = count.filter(|count| *count > 0);
+        app.run_on_main_thread(move || {
+            // Safety: objc runtime calls are unsafe
+            unsafe {
+                let label = match count {
+                    Some(count) => NSString::alloc(nil).init_str(&count.to_string()),
+                    None => nil,
+                };
+                let dock_tile: id = msg_send![NSApp(), dockTile];
+                let _: () = msg_send![dock_tile, setBadgeLabel: label];
+                if label != nil {
+                    let _: () = msg_send![label, release];
+                }
+            }
+        })?;
+        Ok(())
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    ))]
+    pub(crate) fn set<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        count: Option<u32>,
+    ) -> crate::Result<()> {
+        use std::collections::HashMap;
+        use tauri::Manager;
+        use zbus::{blocking::Connection, zvariant::Value};
+
+        // the launcher entry is the desktop file the bundler names after the binary
+        let exe = tauri::utils::platform::current_exe()?;
+        let desktop_id = exe
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let app_uri = format!("application://{desktop_id}.desktop");
+        let path = format!(
+            "/com/canonical/unity/launcherentry/{}",
+            app.config()
+                .tauri
+                .bundle
+                .identifier
+                .replace(['.', '-'], "_")
+        );
+
+        let mut properties = HashMap::<&str, Value>::new();
+        properties.insert("count", Value::from(i64::from(count.unwrap_or(0))));
+        properties.insert("count-visible", Value::from(count.unwrap_or(0) > 0));
+
+        let mut connection = self.connection.lock().unwrap();
+        if connection.is_none() {
+            connection.replace(Connection::session()?);
+        }
+        connection.as_ref().unwrap().emit_signal(
+            None::<&str>,
+            path.as_str(),
+            "com.canonical.Unity.LauncherEntry",
+            "Update",
+            &(app_uri, properties),
+        )?;
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    pub(crate) fn set<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        count: Option<u32>,
+    ) -> crate::Result<()> {
+        use tauri::Manager;
+
+        let windows = app
+            .windows()
+            .values()
+            .filter_map(|window| window.hwnd().ok())
+            .map(|hwnd| hwnd.0)
+            .collect::<Vec<_>>();
+        let count = count.filter(|count| *count > 0);
+        app.run_on_main_thread(move || {
+            if let Err(e) = overlay::set(&windows, count) {
+                log::error!("failed to set the taskbar overlay icon: {e}");
+            }
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod overlay {
+    use windows::{
+        core::{Result, HSTRING, PCWSTR},
+        Win32::{
+            Foundation::{COLORREF, HWND, RECT},
+            Graphics::Gdi::{
+                CreateBitmap, CreateCompatibleBitmap, CreateCompatibleDC, CreateSolidBrush,
+                DeleteDC, DeleteObject, DrawTextW, Ellipse, FillRect, GetDC, GetStockObject,
+                ReleaseDC, SelectObject, SetBkMode, SetTextColor, BLACK_BRUSH, DEFAULT_GUI_FONT,
+                DT_CENTER, DT_SINGLELINE, DT_VCENTER, HBRUSH, NULL_PEN, TRANSPARENT, WHITE_BRUSH,
+            },
+            System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER},
+            UI::{
+                Shell::{ITaskbarList3, TaskbarList},
+                WindowsAndMessaging::{
+                    CreateIconIndirect, DestroyIcon, GetSystemMetrics, HICON, ICONINFO, SM_CXSMICON,
+                },
+            },
+        },
+    };
+
+    /// Sets the overlay icon with the count on the taskbar buttons of the windows,
+    /// or removes it without a count.
+    pub(super) fn set(windows: &[isize], count: Option<u32>) -> Result<()> {
+        let taskbar: ITaskbarList3 =
+            unsafe { CoCreateInstance(&TaskbarList, None, CLSCTX_INPROC_SERVER)? };
+        unsafe { taskbar.HrInit()? };
+
+        let (icon, description) = match count {
+            Some(count) => (icon(&label(count))?, HSTRING::from(count.to_string())),
+            None => (HICON(0), HSTRING::new()),
+        };
+        let mut result = Ok(());
+        for hwnd in windows {
+            result = result.and(unsafe {
+                taskbar.SetOverlayIcon(HWND(*hwnd), icon, PCWSTR(description.as_ptr()))
+            });
+        }
+        // the taskbar keeps a copy of the icon
+        if icon.0 != 0 {
+            unsafe { DestroyIcon(icon)? };
+        }
+        result
+    }
+
+    fn label(count: u32) -> String {
+        if count > 99 {
+            "99+".into()
+        } else {
+            count.to_string()
+        }
+    }
+
+    /// Draws the label in white on a red circle.
+    fn icon(label: &str) -> Result<HICON> {
+        unsafe {
+            let size = GetSystemMetrics(SM_CXSMICON).max(16);
+            let mut rect = RECT {
+                left: 0,
+                top: 0,
+                right: size,
+                bottom: size,
+            };
+
+            let screen = GetDC(HWND(0));
+            let dc = CreateCompatibleDC(screen);
+            let color = CreateCompatibleBitmap(screen, size, size);
+            let mask = CreateBitmap(size, size, 1, 1, None);
+            ReleaseDC(HWND(0), screen);
+
+            // the icon is opaque where the mask is black
+            let previous = SelectObject(dc, mask);
+            FillRect(dc, &rect, HBRUSH(GetStockObject(WHITE_BRUSH).0));
+            SelectObject(dc, GetStockObject(BLACK_BRUSH));
+            SelectObject(dc, GetStockObject(NULL_PEN));
+            // the null pen draws the ellipse one pixel smaller
+            Ellipse(dc, 0, 0, size + 1, size + 1);
+
+            SelectObject(dc, color);
+            let red = CreateSolidBrush(COLORREF(0x0030_30e0));
+            FillRect(dc, &rect, red);
+            SetBkMode(dc, TRANSPARENT);
+            SetTextColor(dc, COLORREF(0x00ff_ffff));
+            SelectObject(dc, GetStockObject(DEFAULT_GUI_FONT));
+            let mut text = label.encode_utf16().collect::<Vec<u16>>();
+            DrawTextW(
+                dc,
+                &mut text,
+                &mut rect,
+                DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+            );
+            SelectObject(dc, previous);
+
+            let icon = CreateIconIndirect(&ICONINFO {
+                fIcon: true.into(),
+                xHotspot: 0,
+                yHotspot: 0,
+                hbmMask: mask,
+                hbmColor: color,
+            });
+
+            DeleteObject(red);
+            DeleteObject(mask);
+            DeleteObject(color);
+            DeleteDC(dc);
+            icon
+        }
+    }
+}
@@ -4,7 +4,11 @@
 
 use tauri::{command, AppHandle, Runtime, State};
 
-use crate::{Notification, NotificationData, PermissionState, Result};
+use std::time::Duration;
+
+#[cfg(desktop)]
+use crate::Interaction;
+use crate::{Channel, Notification, NotificationData, PermissionState, Result};
 
 #[command]
 pub(crate) async fn is_permission_granted<R: Runtime>(
@@ -37,3 +41,61 @@ pub(crate) async fn notify<R: Runtime>(
     builder.data = options;
     builder.show()
 }
+
+#[command]
+pub(crate) async fn create_channel<R: Runtime>(
+    notification: State<'_, Notification<R>>,
+    channel: Channel,
+) -> Result<()> {
+    notification.create_channel(channel)
+}
+
+#[command]
+pub(crate) async fn delete_channel<R: Runtime>(
+    notification: State<'_, Notification<R>>,
+    id: String,
+) -> Result<()> {
+    notification.delete_channel(id)
+}
+
+#[command]
+pub(crate) async fn list_channels<R: Runtime>(
+    notification: State<'_, Notification<R>>,
+) -> Result<Vec<Channel>> {
+    notification.list_channels()
+}
+
+#[command]
+pub(crate) async fn snooze<R: Runtime>(
+    notification: State<'_, Notification<R>>,
+    id: i32,
+    duration: u64,
+) -> Result<i32> {
+    notification.snooze(id, Duration::from_millis(duration))
+}
+
+#[command]
+pub(crate) async fn cancel_repeat<R: Runtime>(
+    notification: State<'_, Notification<R>>,
+    id: i32,
+) -> Result<()> {
+    notification.cancel_repeat(id)
+}
+
+#[cfg(desktop)]
+#[command]
+pub(crate) async fn take_interactions<R: Runtime>(
+    notification: State<'_, Notification<R>>,
+    max_age: Option<u64>,
+) -> Result<Vec<Interaction>> {
+    Ok(notification.take_interactions(max_age.map(Duration::from_millis)))
+}
+
+#[cfg(desktop)]
+#[command]
+pub(crate) async fn set_badge_count<R: Runtime>(
+    notification: State<'_, Notification<R>>,
+    count: Option<u32>,
+) -> Result<()> {
+    notification.set_badge_count(count)
+}
@@ -3,22 +3,46 @@
 // SPDX-License-Identifier: MIT
 
 use serde::de::DeserializeOwned;
-use tauri::{plugin::PluginApi, AppHandle, Runtime};
+use tauri::{plugin::PluginApi, AppHandle, Manager, Runtime};
+use time::UtcOffset;
 
-use crate::{models::*, NotificationBuilder};
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    badge::Badge, interactions::Interactions, models::*, scheduler::Scheduler, NotificationBuilder,
+};
 
 pub fn init<R: Runtime, C: DeserializeOwned>(
     app: &AppHandle<R>,
     _api: PluginApi<R, C>,
+    local_offset: Option<UtcOffset>,
 ) -> crate::Result<Notification<R>> {
-    Ok(Notification(app.clone()))
+    Ok(Notification {
+        app: app.clone(),
+        scheduler: Scheduler::start(app, local_offset),
+        interactions: Interactions::load(app),
+        badge: Badge::default(),
+    })
 }
 
 /// Access to the {{ plugin_name }} APIs.
-pub struct Notification<R: Runtime>(AppHandle<R>);
+pub struct Notification<R: Runtime> {
+    app: AppHandle<R>,
+    scheduler: Arc<Scheduler<R>>,
+    interactions: Interactions,
+    badge: Badge,
+}
 
 impl<R: Runtime> crate::NotificationBuilder<R> {
+    /// Shows the notification, or schedules it if it has a [`Schedule`].
     pub fn show(self) -> crate::Result<()> {
+        if let Some(notification) = self.app.try_state::<Notification<R>>() {
+            if self.data.schedule.is_some() {
+                return notification.scheduler.schedule(self.data);
+            }
+            notification.scheduler.record(&self.data);
+        }
+
         let mut notification =
             imp::Notification::new(self.app.config().tauri.bundle.identifier.clone());
 
@@ -35,6 +59,13 @@ impl<R: Runtime> crate::NotificationBuilder<R> {
         if let Some(icon) = self.data.icon {
             notification = notification.icon(icon);
         }
+        let app = self.app.clone();
+        let id = self.data.id;
+        notification = notification.on_action(move |action| {
+            if let Some(notification) = app.try_state::<Notification<R>>() {
+                notification.record_interaction(Interaction::new(id, action, None));
+            }
+        });
         #[cfg(feature = "windows7-compat")]
         {
             notification.notify(&self.app)?;
@@ -48,7 +79,7 @@ impl<R: Runtime> crate::NotificationBuilder<R> {
 
 impl<R: Runtime> Notification<R> {
     pub fn builder(&self) -> NotificationBuilder<R> {
-        NotificationBuilder::new(self.0.clone())
+        NotificationBuilder::new(self.app.clone())
     }
 
     pub fn request_permission(&self) -> crate::Result<PermissionState> {
@@ -58,6 +89,70 @@ impl<R: Runtime> Notification<R> {
     pub fn permission_state(&self) -> crate::Result<PermissionState> {
         Ok(PermissionState::Granted)
     }
+
+    /// Notification channels are an Android concept, this is a no-op on desktop.
+    pub fn create_channel(&self, _channel: Channel) -> crate::Result<()> {
+        Ok(())
+    }
+
+    /// Notification channels are an Android concept, this is a no-op on desktop.
+    pub fn delete_channel(&self, _id: impl Into<String>) -> crate::Result<()> {
+        Ok(())
+    }
+
+    /// Notification channels are an Android concept, this always returns an empty list on desktop.
+    pub fn list_channels(&self) -> crate::Result<Vec<Channel>> {
+        Ok(Vec::new())
+    }
+
+    /// Shows the notification with the given identifier again after `duration`, returning its identifier.
+    ///
+    /// Only the notifications shown since the app started can be snoozed.
+    pub fn snooze(&self, id: i32, duration: Duration) -> crate::Result<i32> {
+        self.scheduler.snooze(id, duration)?;
+        Ok(id)
+    }
+
+    /// Cancels the pending and repeating occurrences of the notification with the given identifier.
+    pub fn cancel_repeat(&self, id: i32) -> crate::Result<()> {
+        self.scheduler.cancel(id);
+        Ok(())
+    }
+
+    /// Records a click on a notification action, e.g. from the activation handler of a relaunched app.
+    ///
+    /// The interaction is emitted to the running windows and kept, even across restarts,
+    /// until [`Self::take_interactions`] is called.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Linux:** The clicks on the notifications shown by the app are recorded automatically,
+    ///   with the `default` action identifier.
+    /// - **macOS / Windows:** The clicks are not reported, so they must be recorded by the app.
+    pub fn record_interaction(&self, interaction: Interaction) {
+        self.interactions.record(&self.app, interaction);
+    }
+
+    /// Removes and returns the recorded interactions, ignoring the ones older than `max_age`.
+    pub fn take_interactions(&self, max_age: Option<Duration>) -> Vec<Interaction> {
+        self.interactions
+            .take(max_age.map(|max_age| max_age.as_millis() as u64))
+    }
+
+    /// Sets the count shown on the app icon, e.g. of the unread messages. `None` or `0` clears it.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **macOS:** Shown on the dock icon, in full.
+    /// - **Linux:** Only shown by the docks supporting the Unity launcher API, e.g. Dash to Dock,
+    ///   Plank or KDE Plasma, for the desktop file named after the app binary. Some docks only
+    ///   show up to 4 digits.
+    /// - **Windows:** Drawn as the overlay icon of the taskbar buttons of the open windows, so
+    ///   windows created afterwards do not show it. Counts above 99 are shown as `99+`, and the
+    ///   overlay is hidden when the taskbar uses small buttons.
+    pub fn set_badge_count(&self, count: Option<u32>) -> crate::Result<()> {
+        self.badge.set(&self.app, count)
+    }
 }
 
 mod imp {
@@ -90,7 +185,7 @@ mod imp {
     /// app.run(|_app_handle, _event| {});
     /// ```
     #[allow(dead_code)]
-    #[derive(Debug, Default)]
+    #[derive(Default)]
     pub struct Notification {
         /// The notification body.
         body: Option<String>,
@@ -100,6 +195,8 @@ mod imp {
         icon: Option<String>,
         /// The notification identifier
         identifier: String,
+        /// Called with the identifier of the clicked action.
+        on_action: Option<Box<dyn FnOnce(&str) + Send>>,
     }
 
     impl Notification {
@@ -132,6 +229,13 @@ mod imp {
             self
         }
 
+        /// Sets the handler of a click on the notification, which is only called on Linux.
+        #[must_use]
+        pub fn on_action(mut self, handler: impl FnOnce(&str) + Send + 'static) -> Self {
+            self.on_action = Some(Box::new(handler));
+            self
+        }
+
         /// Shows the notification.
         ///
         /// # Examples
@@ -197,6 +301,23 @@ mod imp {
                 });
             }
 
+            #[cfg(all(unix, not(target_os = "macos")))]
+            {
+                // the server calls the `default` action when the notification body is clicked
+                notification.action("default", "");
+                let on_action = self.on_action;
+                // waiting for the action blocks until the notification is closed
+                tauri::async_runtime::spawn_blocking(move || {
+                    if let (Ok(handle), Some(on_action)) = (notification.show(), on_action) {
+                        handle.wait_for_action(|action| {
+                            if action != "__closed" {
+                                on_action(action);
+                            }
+                        });
+                    }
+                });
+            }
+            #[cfg(not(all(unix, not(target_os = "macos"))))]
             tauri::async_runtime::spawn(async move {
                 let _ = notification.show();
             });
@@ -10,6 +10,29 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    /// The notification to snooze was not shown by the plugin or is no longer active.
+    #[error("notification {0} not found")]
+    NotificationNotFound(i32),
+    /// The calendar rule never matches.
+    #[error("the notification schedule never fires")]
+    InvalidSchedule,
+    /// The local offset, which the calendar schedules follow, could not be determined.
+    #[cfg(desktop)]
+    #[error("the local UTC offset could not be determined")]
+    UnknownLocalOffset,
+    #[cfg(desktop)]
+    #[error(transparent)]
+    Tauri(#[from] tauri::Error),
+    /// The launcher entry count could not be sent on the session bus.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    ))]
+    #[error(transparent)]
+    DBus(#[from] zbus::Error),
     #[cfg(mobile)]
     #[error(transparent)]
     PluginInvoke(#[from] tauri::plugin::mobile::PluginInvokeError),
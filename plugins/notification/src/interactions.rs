@@ -0,0 +1,93 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Notification interactions delivered to the next launch of the app.
+//!
+//! Clicking a notification action of a closed app relaunches it, before any listener exists.
+//! The interactions are persisted in the app data directory until the app takes them
+//! with the `take_interactions` command once its listeners are ready,
+//! so they also survive the app exiting again before handling them.
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use std::{fs, path::PathBuf, sync::Mutex};
+
+use crate::{models::now_millis, Interaction};
+
+const STORE_FILE: &str = ".notification-interactions.json";
+
+/// Event emitted to all windows when an interaction is recorded.
+pub const INTERACTION_EVENT: &str = "notification://interaction";
+
+pub struct Interactions {
+    path: Option<PathBuf>,
+    pending: Mutex<Vec<Interaction>>,
+}
+
+impl Interactions {
+    /// Loads the interactions persisted by the previous runs of the app.
+    pub fn load<R: Runtime>(app: &AppHandle<R>) -> Self {
+        let path = app
+            .path()
+            .app_data_dir()
+            .ok()
+            .map(|dir| dir.join(STORE_FILE));
+        let pending = path
+            .as_ref()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            pending: Mutex::new(pending),
+        }
+    }
+
+    /// Keeps the interaction until it is taken and notifies the running listeners.
+    pub fn record<R: Runtime>(&self, app: &AppHandle<R>, interaction: Interaction) {
+        let mut pending = self.pending.lock().unwrap();
+        pending.push(interaction.clone());
+        self.save(&pending);
+        drop(pending);
+        let _ = app.emit_all(INTERACTION_EVENT, interaction);
+    }
+
+    /// Removes and returns the pending interactions, dropping the ones older than `max_age_ms`.
+    pub fn take(&self, max_age_ms: Option<u64>) -> Vec<Interaction> {
+        let mut pending = self.pending.lock().unwrap();
+        let interactions = std::mem::take(&mut *pending);
+        self.save(&pending);
+        drop(pending);
+
+        match max_age_ms {
+            Some(max_age) => {
+                let now = now_millis();
+                interactions
+                    .into_iter()
+                    .filter(|i| now.saturating_sub(i.timestamp) <= max_age)
+                    .collect()
+            }
+            None => interactions,
+        }
+    }
+
+    fn save(&self, pending: &[Interaction]) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = if pending.is_empty() {
+            match fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            }
+        } else {
+            path.parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::write(path, serde_json::to_vec(pending).unwrap_or_default()))
+        };
+        if let Err(e) = result {
+            log::warn!("failed to persist notification interactions: {e}");
+        }
+    }
+}
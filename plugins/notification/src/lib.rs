@@ -8,7 +8,7 @@ use tauri::plugin::PluginHandle;
 #[cfg(desktop)]
 use tauri::AppHandle;
 use tauri::{
-    plugin::{Builder, TauriPlugin},
+    plugin::{Builder as PluginBuilder, TauriPlugin},
     Manager, Runtime,
 };
 
@@ -19,11 +19,19 @@ mod desktop;
 #[cfg(mobile)]
 mod mobile;
 
+#[cfg(desktop)]
+mod badge;
 mod commands;
 mod error;
+#[cfg(desktop)]
+mod interactions;
 mod models;
+#[cfg(desktop)]
+mod scheduler;
 
 pub use error::{Error, Result};
+#[cfg(desktop)]
+pub use interactions::INTERACTION_EVENT;
 
 #[cfg(desktop)]
 use desktop::Notification;
@@ -212,22 +220,62 @@ impl<R: Runtime, T: Manager<R>> crate::NotificationExt<R> for T {
 
 /// Initializes the plugin.
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    let mut init_script = include_str!("init.js").to_string();
-    init_script.push_str(include_str!("api-iife.js"));
-    Builder::new("notification")
-        .invoke_handler(tauri::generate_handler![
-            commands::notify,
-            commands::request_permission,
-            commands::is_permission_granted
-        ])
-        .js_init_script(init_script)
-        .setup(|app, api| {
-            #[cfg(mobile)]
-            let notification = mobile::init(app, api)?;
-            #[cfg(desktop)]
-            let notification = desktop::init(app, api)?;
-            app.manage(notification);
-            Ok(())
-        })
-        .build()
+    Builder::new().build()
+}
+
+/// The notification plugin builder.
+#[derive(Default)]
+pub struct Builder {
+    channels: Vec<Channel>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a notification channel that is created when the plugin is initialized.
+    ///
+    /// Notifications target a channel with [`NotificationBuilder::channel_id`].
+    /// Channels are only used on Android, other platforms ignore them.
+    pub fn channel(mut self, channel: Channel) -> Self {
+        self.channels.push(channel);
+        self
+    }
+
+    pub fn build<R: Runtime>(self) -> TauriPlugin<R> {
+        let mut init_script = include_str!("init.js").to_string();
+        init_script.push_str(include_str!("api-iife.js"));
+        // on Unix the offset can only be read while the app is single threaded
+        #[cfg(desktop)]
+        let local_offset = time::UtcOffset::current_local_offset().ok();
+        PluginBuilder::new("notification")
+            .invoke_handler(tauri::generate_handler![
+                commands::notify,
+                commands::request_permission,
+                commands::is_permission_granted,
+                commands::create_channel,
+                commands::delete_channel,
+                commands::list_channels,
+                commands::snooze,
+                commands::cancel_repeat,
+                #[cfg(desktop)]
+                commands::take_interactions,
+                #[cfg(desktop)]
+                commands::set_badge_count
+            ])
+            .js_init_script(init_script)
+            .setup(move |app, api| {
+                #[cfg(mobile)]
+                let notification = mobile::init(app, api)?;
+                #[cfg(desktop)]
+                let notification = desktop::init(app, api, local_offset)?;
+                for channel in self.channels {
+                    notification.create_channel(channel)?;
+                }
+                app.manage(notification);
+                Ok(())
+            })
+            .build()
+    }
 }
@@ -10,7 +10,7 @@ use tauri::{
 
 use crate::models::*;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 #[cfg(target_os = "android")]
 const PLUGIN_IDENTIFIER: &str = "app.tauri.notification";
@@ -119,28 +119,97 @@ impl<R: Runtime> Notification<R> {
         self.0.run_mobile_plugin("cancel", ()).map_err(Into::into)
     }
 
-    #[cfg(target_os = "android")]
+    /// Shows the active notification with the given identifier again after `duration`,
+    /// returning the identifier of the snoozed notification.
+    ///
+    /// The snoozed notification gets a new identifier so it does not replace
+    /// the pending occurrences of a repeating notification.
+    pub fn snooze(&self, id: i32, duration: Duration) -> crate::Result<i32> {
+        let active = self
+            .active()?
+            .into_iter()
+            .find(|n| n.id() == id)
+            .ok_or(crate::Error::NotificationNotFound(id))?;
+
+        let mut builder = self.builder().schedule(Schedule::At {
+            date: time::OffsetDateTime::now_utc() + duration,
+            repeating: false,
+        });
+        let snoozed_id = builder.data.id;
+        if let Some(title) = active.title() {
+            builder = builder.title(title);
+        }
+        if let Some(body) = active.body() {
+            builder = builder.body(body);
+        }
+        if let Some(group) = active.group() {
+            builder = builder.group(group);
+        }
+        if let Some(action_type_id) = active.action_type_id() {
+            builder = builder.action_type_id(action_type_id);
+        }
+        if let Some(sound) = active.sound() {
+            builder = builder.sound(sound);
+        }
+        for attachment in active.attachments() {
+            builder = builder.attachment(attachment.clone());
+        }
+        for (key, value) in active.extra() {
+            builder = builder.extra(key, value);
+        }
+        builder.show()?;
+
+        Ok(snoozed_id)
+    }
+
+    /// Cancels the pending and repeating occurrences of the notification with the given identifier.
+    pub fn cancel_repeat(&self, id: i32) -> crate::Result<()> {
+        self.cancel(vec![id])
+    }
+
+    /// Creates a notification channel. No-op on iOS.
     pub fn create_channel(&self, channel: Channel) -> crate::Result<()> {
-        self.0
-            .run_mobile_plugin("createChannel", channel)
-            .map_err(Into::into)
+        #[cfg(target_os = "android")]
+        {
+            self.0
+                .run_mobile_plugin("createChannel", channel)
+                .map_err(Into::into)
+        }
+        #[cfg(target_os = "ios")]
+        {
+            let _ = channel;
+            Ok(())
+        }
     }
 
-    #[cfg(target_os = "android")]
+    /// Deletes the notification channel with the given identifier. No-op on iOS.
     pub fn delete_channel(&self, id: impl Into<String>) -> crate::Result<()> {
-        let mut args = HashMap::new();
-        args.insert("id", id.into());
-        self.0
-            .run_mobile_plugin("deleteChannel", args)
-            .map_err(Into::into)
+        #[cfg(target_os = "android")]
+        {
+            let mut args = HashMap::new();
+            args.insert("id", id.into());
+            self.0
+                .run_mobile_plugin("deleteChannel", args)
+                .map_err(Into::into)
+        }
+        #[cfg(target_os = "ios")]
+        {
+            let _ = id;
+            Ok(())
+        }
     }
 
-    #[cfg(target_os = "android")]
+    /// Lists the notification channels. Always empty on iOS.
     pub fn list_channels(&self) -> crate::Result<Vec<Channel>> {
-        self.0
-            .run_mobile_plugin::<ListChannelsResult>("listChannels", ())
-            .map(|r| r.channels)
-            .map_err(Into::into)
+        #[cfg(target_os = "android")]
+        {
+            self.0
+                .run_mobile_plugin::<ListChannelsResult>("listChannels", ())
+                .map(|r| r.channels)
+                .map_err(Into::into)
+        }
+        #[cfg(target_os = "ios")]
+        Ok(Vec::new())
     }
 }
 
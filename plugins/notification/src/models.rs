@@ -2,13 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::{collections::HashMap, fmt::Display};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
 
 use url::Url;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Attachment {
     id: String,
@@ -21,7 +25,7 @@ impl Attachment {
     }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ScheduleInterval {
     pub year: Option<u8>,
@@ -33,7 +37,7 @@ pub struct ScheduleInterval {
     pub second: Option<u8>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum ScheduleEvery {
     Year,
     Month,
@@ -93,7 +97,7 @@ impl<'de> Deserialize<'de> for ScheduleEvery {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "kind", content = "data")]
 pub enum Schedule {
     At {
@@ -133,7 +137,7 @@ mod iso8601 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NotificationData {
     #[serde(default = "default_id")]
@@ -365,10 +369,44 @@ pub struct Action {
     input_placeholder: Option<String>,
 }
 
-#[cfg(target_os = "android")]
+/// A click on a notification action, with the text typed in its input if any.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Interaction {
+    /// The notification identifier.
+    pub id: i32,
+    /// The identifier of the clicked [`Action`].
+    pub action_id: String,
+    /// The reply typed in the action input.
+    pub input: Option<String>,
+    /// When the user interacted with the notification, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl Interaction {
+    /// An interaction happening now.
+    pub fn new(id: i32, action_id: impl Into<String>, input: Option<String>) -> Self {
+        Self {
+            id,
+            action_id: action_id.into(),
+            input,
+            timestamp: now_millis(),
+        }
+    }
+}
+
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
 pub use android::*;
 
-#[cfg(target_os = "android")]
+/// Android notification channels.
+///
+/// Channels are only honored on Android, other platforms accept but ignore them.
 mod android {
     use serde::{Deserialize, Serialize};
     use serde_repr::{Deserialize_repr, Serialize_repr};
@@ -397,16 +435,19 @@ mod android {
         Public = 1,
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Channel {
         id: String,
         name: String,
         description: Option<String>,
         sound: Option<String>,
+        #[serde(default)]
         lights: bool,
         light_color: Option<String>,
+        #[serde(default)]
         vibration: bool,
+        #[serde(default)]
         importance: Importance,
         visibility: Option<Visibility>,
     }
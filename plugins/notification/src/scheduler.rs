@@ -0,0 +1,498 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Scheduled and repeating notifications on desktop.
+//!
+//! The desktop notification servers have no scheduling support, so the pending notifications
+//! are kept and fired by the plugin. They are persisted in the app data directory so the
+//! repeating notifications survive restarts.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+use time::{Date, Duration, OffsetDateTime, Time, UtcOffset};
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread::{sleep, spawn},
+};
+
+use crate::{
+    Error, NotificationBuilder, NotificationData, Result, Schedule, ScheduleEvery, ScheduleInterval,
+};
+
+const STORE_FILE: &str = ".notification-schedule.json";
+
+/// How often the pending notifications are checked.
+const TICK: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// The number of days searched for the next occurrence of a calendar rule,
+/// enough to find the next February 29 on a given week day.
+const MAX_SEARCH_DAYS: i64 = 366 * 28;
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    data: NotificationData,
+    #[serde(with = "time::serde::rfc3339")]
+    next: OffsetDateTime,
+    /// The period, in seconds, of a repeating `At` schedule.
+    ///
+    /// Like on iOS, it repeats with the delay between scheduling it and its first occurrence.
+    period: Option<i64>,
+    /// The day of the month a monthly or yearly schedule repeats on,
+    /// kept since the occurrences in the shorter months are on their last day.
+    #[serde(default)]
+    day: Option<u8>,
+}
+
+impl Entry {
+    /// The first occurrence after `now`, if the notification repeats.
+    ///
+    /// The occurrences missed while the app was not running are skipped. The calendar rules
+    /// follow the offset of the entry, the local offset it was scheduled with.
+    fn following(&self, now: OffsetDateTime) -> Option<OffsetDateTime> {
+        let now = now.to_offset(self.next.offset());
+        let period = match (&self.data.schedule, self.period) {
+            (_, Some(period)) => period.max(1),
+            (Some(Schedule::Every { interval }), _) => match every_months(*interval) {
+                Some(months) => {
+                    let day = self.day.unwrap_or_else(|| self.next.day());
+                    return (1..)
+                        .map(|n| add_months(self.next, n * months, day))
+                        .find(|next| next.map_or(true, |next| next > now))
+                        .flatten();
+                }
+                None => every_period(*interval).whole_seconds(),
+            },
+            (Some(Schedule::Interval(rule)), _) => return next_matching(rule, now),
+            _ => return None,
+        };
+        let missed = (now - self.next).whole_seconds().max(0) / period;
+        Some(self.next + Duration::seconds((missed + 1) * period))
+    }
+}
+
+pub struct Scheduler<R: Runtime> {
+    app: AppHandle<R>,
+    path: Option<PathBuf>,
+    local_offset: Option<UtcOffset>,
+    entries: Mutex<Vec<Entry>>,
+    /// The notifications last shown with each identifier, so they can be snoozed.
+    shown: Mutex<HashMap<i32, NotificationData>>,
+}
+
+impl<R: Runtime> Scheduler<R> {
+    /// Loads the persisted notifications and starts firing them.
+    ///
+    /// The calendar schedules follow `local_offset`, and are rejected without it.
+    pub fn start(app: &AppHandle<R>, local_offset: Option<UtcOffset>) -> Arc<Self> {
+        let path = app
+            .path()
+            .app_data_dir()
+            .ok()
+            .map(|dir| dir.join(STORE_FILE));
+        let entries = path
+            .as_ref()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+
+        let scheduler = Arc::new(Self {
+            app: app.clone(),
+            path,
+            local_offset,
+            entries: Mutex::new(entries),
+            shown: Default::default(),
+        });
+
+        let scheduler_ = scheduler.clone();
+        spawn(move || loop {
+            sleep(TICK);
+            scheduler_.tick();
+        });
+
+        scheduler
+    }
+
+    /// Schedules a notification according to its [`Schedule`].
+    pub fn schedule(&self, data: NotificationData) -> Result<()> {
+        let now = OffsetDateTime::now_utc();
+        let (next, period, day) = match &data.schedule {
+            Some(Schedule::At { date, repeating }) => {
+                let period = repeating.then(|| (*date - now).whole_seconds());
+                // a date in the past has no delay to repeat with
+                if period.map_or(false, |period| period < 1) {
+                    return Err(Error::InvalidSchedule);
+                }
+                (*date, period, None)
+            }
+            Some(Schedule::Every { interval }) => match every_months(*interval) {
+                Some(months) => {
+                    let now = self.now_local()?;
+                    let next = add_months(now, months, now.day()).ok_or(Error::InvalidSchedule)?;
+                    (next, None, Some(now.day()))
+                }
+                None => (now + every_period(*interval), None, None),
+            },
+            Some(Schedule::Interval(rule)) => (
+                next_matching(rule, self.now_local()?).ok_or(Error::InvalidSchedule)?,
+                None,
+                None,
+            ),
+            None => (now, None, None),
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+        // a notification replaces the pending one with the same identifier, like on mobile
+        entries.retain(|e| e.data.id != data.id);
+        entries.push(Entry {
+            data,
+            next,
+            period,
+            day,
+        });
+        self.save(&entries);
+        Ok(())
+    }
+
+    /// Shows the notification with the given identifier again after `duration`.
+    ///
+    /// Its repeating schedule, if any, is kept.
+    pub fn snooze(&self, id: i32, duration: std::time::Duration) -> Result<()> {
+        let mut data = self
+            .shown
+            .lock()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .ok_or(Error::NotificationNotFound(id))?;
+        data.schedule = None;
+
+        let mut entries = self.entries.lock().unwrap();
+        entries.push(Entry {
+            data,
+            next: OffsetDateTime::now_utc() + duration,
+            period: None,
+            day: None,
+        });
+        self.save(&entries);
+        Ok(())
+    }
+
+    /// Cancels the pending and repeating occurrences of the notification with the given identifier.
+    pub fn cancel(&self, id: i32) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|e| e.data.id != id);
+        self.save(&entries);
+    }
+
+    pub(crate) fn record(&self, data: &NotificationData) {
+        self.shown.lock().unwrap().insert(data.id, data.clone());
+    }
+
+    /// The current time in the local offset, which the calendar schedules follow.
+    fn now_local(&self) -> Result<OffsetDateTime> {
+        self.local_offset
+            .map(|offset| OffsetDateTime::now_utc().to_offset(offset))
+            .ok_or(Error::UnknownLocalOffset)
+    }
+
+    fn tick(&self) {
+        let now = OffsetDateTime::now_utc();
+        let mut due = Vec::new();
+        {
+            let mut entries = self.entries.lock().unwrap();
+            if !entries.iter().any(|e| e.next <= now) {
+                return;
+            }
+
+            let mut pending = Vec::new();
+            for mut entry in std::mem::take(&mut *entries) {
+                if entry.next > now {
+                    pending.push(entry);
+                    continue;
+                }
+
+                let mut data = entry.data.clone();
+                data.schedule = None;
+                due.push(data);
+
+                // occurrences missed while the app was not running are only shown once
+                if let Some(next) = entry.following(now) {
+                    entry.next = next;
+                    pending.push(entry);
+                }
+            }
+            *entries = pending;
+            self.save(&entries);
+        }
+
+        for data in due {
+            let mut builder = NotificationBuilder::new(self.app.clone());
+            builder.data = data;
+            if let Err(e) = builder.show() {
+                log::error!("failed to show scheduled notification: {e}");
+            }
+        }
+    }
+
+    fn save(&self, entries: &[Entry]) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(path, serde_json::to_vec(entries).unwrap_or_default()));
+        if let Err(e) = result {
+            log::warn!("failed to persist scheduled notifications: {e}");
+        }
+    }
+}
+
+/// Adds `months` to `date`, on `day` or the last day of the month if it is shorter.
+fn add_months(date: OffsetDateTime, months: i32, day: u8) -> Option<OffsetDateTime> {
+    let month_index = date.year() * 12 + date.month() as i32 - 1 + months;
+    let (year, month) = (
+        month_index.div_euclid(12),
+        month_index.rem_euclid(12) as u8 + 1,
+    );
+    let month = time::Month::try_from(month).expect("month is in range");
+    let day = day.min(time::util::days_in_year_month(year, month));
+    Date::from_calendar_date(year, month, day)
+        .ok()
+        .map(|d| date.replace_date(d))
+}
+
+/// The number of months of a monthly or yearly interval.
+fn every_months(every: ScheduleEvery) -> Option<i32> {
+    match every {
+        ScheduleEvery::Year => Some(12),
+        ScheduleEvery::Month => Some(1),
+        _ => None,
+    }
+}
+
+/// The period of the intervals with a fixed length.
+fn every_period(every: ScheduleEvery) -> Duration {
+    match every {
+        ScheduleEvery::Year | ScheduleEvery::Month => unreachable!("months have no fixed length"),
+        ScheduleEvery::TwoWeeks => Duration::weeks(2),
+        ScheduleEvery::Week => Duration::weeks(1),
+        ScheduleEvery::Day => Duration::days(1),
+        ScheduleEvery::Hour => Duration::hours(1),
+        ScheduleEvery::Minute => Duration::minutes(1),
+        ScheduleEvery::Second => Duration::seconds(1),
+    }
+}
+
+/// The values a time component of a calendar rule matches.
+///
+/// Like the iOS calendar triggers, an unset component matches any value only if
+/// no larger component is set, and is zero otherwise (e.g. `hour: 8` fires at 08:00:00).
+fn component_values(value: Option<u8>, larger_set: bool, max: u8) -> Vec<u8> {
+    match value {
+        Some(value) => vec![value],
+        None if larger_set => vec![0],
+        None => (0..max).collect(),
+    }
+}
+
+/// The first time strictly after `after` matching the calendar rule.
+///
+/// The year is ignored: the rule stores it as a `u8`, which can not hold a calendar year.
+fn next_matching(rule: &ScheduleInterval, after: OffsetDateTime) -> Option<OffsetDateTime> {
+    let start = after.replace_nanosecond(0).ok()? + Duration::seconds(1);
+
+    let date_set = rule.month.is_some() || rule.day.is_some() || rule.weekday.is_some();
+    let hours = component_values(rule.hour, date_set, 24);
+    let minutes = component_values(rule.minute, date_set || rule.hour.is_some(), 60);
+    let seconds = component_values(
+        rule.second,
+        date_set || rule.hour.is_some() || rule.minute.is_some(),
+        60,
+    );
+
+    for offset in 0..MAX_SEARCH_DAYS {
+        let date = start.date().checked_add(Duration::days(offset))?;
+        if rule.month.map_or(false, |m| m != date.month() as u8)
+            || rule.day.map_or(false, |d| d != date.day())
+            || rule
+                .weekday
+                .map_or(false, |w| w != date.weekday().number_from_sunday())
+        {
+            continue;
+        }
+
+        for &hour in &hours {
+            for &minute in &minutes {
+                for &second in &seconds {
+                    let Ok(time) = Time::from_hms(hour, minute, second) else {
+                        continue;
+                    };
+                    let candidate = start.replace_date(date).replace_time(time);
+                    if candidate >= start {
+                        return Some(candidate);
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn calendar_rule_defaults_smaller_components_to_zero() {
+        let rule = ScheduleInterval {
+            hour: Some(8),
+            ..Default::default()
+        };
+        assert_eq!(
+            next_matching(&rule, datetime!(2023-05-10 09:30 UTC)),
+            Some(datetime!(2023-05-11 08:00 UTC))
+        );
+        assert_eq!(
+            next_matching(&rule, datetime!(2023-05-10 07:59:59 UTC)),
+            Some(datetime!(2023-05-10 08:00 UTC))
+        );
+    }
+
+    #[test]
+    fn calendar_rule_matches_weekday_and_month() {
+        // Mondays of June
+        let rule = ScheduleInterval {
+            month: Some(6),
+            weekday: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            next_matching(&rule, datetime!(2023-05-10 12:00 UTC)),
+            Some(datetime!(2023-06-05 00:00 UTC))
+        );
+    }
+
+    fn entry(next: OffsetDateTime, schedule: Schedule, period: Option<i64>) -> Entry {
+        Entry {
+            data: NotificationData {
+                schedule: Some(schedule),
+                ..Default::default()
+            },
+            next,
+            period,
+            day: Some(next.day()),
+        }
+    }
+
+    #[test]
+    fn every_month_clamps_the_day() {
+        assert_eq!(
+            add_months(datetime!(2023-01-31 10:00 UTC), 1, 31),
+            Some(datetime!(2023-02-28 10:00 UTC))
+        );
+    }
+
+    #[test]
+    fn every_month_keeps_the_original_day() {
+        let mut entry = entry(
+            datetime!(2023-01-31 10:00 UTC),
+            Schedule::Every {
+                interval: ScheduleEvery::Month,
+            },
+            None,
+        );
+        let mut occurrences = Vec::new();
+        for _ in 0..3 {
+            entry.next = entry.following(entry.next).unwrap();
+            occurrences.push(entry.next);
+        }
+        assert_eq!(
+            occurrences,
+            [
+                datetime!(2023-02-28 10:00 UTC),
+                datetime!(2023-03-31 10:00 UTC),
+                datetime!(2023-04-30 10:00 UTC),
+            ]
+        );
+    }
+
+    #[test]
+    fn repeating_date_skips_the_missed_periods() {
+        let at = Schedule::At {
+            date: datetime!(2023-05-10 10:00 UTC),
+            repeating: true,
+        };
+        let entry = entry(datetime!(2023-05-10 10:00 UTC), at, Some(3600));
+        assert_eq!(
+            entry.following(datetime!(2023-05-12 15:30 UTC)),
+            Some(datetime!(2023-05-12 16:00 UTC))
+        );
+        assert_eq!(
+            entry.following(datetime!(2023-05-12 16:00 UTC)),
+            Some(datetime!(2023-05-12 17:00 UTC))
+        );
+    }
+
+    #[test]
+    fn calendar_rule_follows_the_entry_offset() {
+        let rule = ScheduleInterval {
+            hour: Some(1),
+            ..Default::default()
+        };
+        let entry = entry(
+            datetime!(2023-05-11 01:00 +2),
+            Schedule::Interval(rule),
+            None,
+        );
+        // still May 10 in UTC, where the hour would match again two hours later
+        let next = entry.following(datetime!(2023-05-10 23:00 UTC)).unwrap();
+        assert_eq!(next, datetime!(2023-05-12 01:00 +2));
+        assert_eq!(next.offset(), entry.next.offset());
+    }
+
+    #[test]
+    fn interval_keeps_the_entry_offset() {
+        let daily = entry(
+            datetime!(2023-05-10 10:00 -5),
+            Schedule::Every {
+                interval: ScheduleEvery::Day,
+            },
+            None,
+        );
+        let next = daily.following(datetime!(2023-05-20 14:00 UTC)).unwrap();
+        assert_eq!(next, datetime!(2023-05-20 10:00 -5));
+        assert_eq!(next.offset(), daily.next.offset());
+
+        let monthly = entry(
+            datetime!(2023-01-31 23:00 -5),
+            Schedule::Every {
+                interval: ScheduleEvery::Month,
+            },
+            None,
+        );
+        // already February 1 in UTC
+        let next = monthly.following(datetime!(2023-02-01 04:30 UTC)).unwrap();
+        assert_eq!(next, datetime!(2023-02-28 23:00 -5));
+        assert_eq!(next.offset(), monthly.next.offset());
+    }
+
+    #[test]
+    fn every_day_skips_the_missed_days() {
+        let every = Schedule::Every {
+            interval: ScheduleEvery::Day,
+        };
+        let entry = entry(datetime!(2023-05-10 10:00 UTC), every, None);
+        assert_eq!(
+            entry.following(datetime!(2023-05-20 09:00 UTC)),
+            Some(datetime!(2023-05-20 10:00 UTC))
+        );
+    }
+}
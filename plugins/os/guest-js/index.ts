@@ -8,6 +8,8 @@
  * @module
  */
 
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+
 declare global {
   interface Window {
     __TAURI_INVOKE__: <T>(cmd: string, args?: unknown) => Promise<T>;
@@ -173,6 +175,105 @@ async function hostname(): Promise<string | null> {
   return window.__TAURI_INVOKE__("plugin:os|hostname");
 }
 
+/**
+ * Returns the value of an environment variable allowed on the Rust side with `Builder::allow_env`.
+ *
+ * The variables that are not allowed are reported as unset, like the ones that are not valid unicode.
+ * @example
+ * ```typescript
+ * import { env } from '@tauri-apps/plugin-os';
+ * const lang = await env('LANG');
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function env(name: string): Promise<string | null> {
+  return window.__TAURI_INVOKE__("plugin:os|env", { name });
+}
+
+/**
+ * Returns the environment variables allowed on the Rust side with `Builder::allow_env` that are set.
+ * @example
+ * ```typescript
+ * import { envAllowlist } from '@tauri-apps/plugin-os';
+ * const vars = await envAllowlist();
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function envAllowlist(): Promise<Record<string, string>> {
+  return window.__TAURI_INVOKE__("plugin:os|env_allowlist");
+}
+
+/**
+ * A rectangle in physical pixels on the virtual desktop.
+ *
+ * @since 2.0.0
+ */
+interface Rect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+/**
+ * A display connected to the system.
+ *
+ * @since 2.0.0
+ */
+interface Display {
+  /** Platform identifier of the display. */
+  id: number;
+  bounds: Rect;
+  /** The bounds without the taskbar, dock and menu bar. Only available on Windows. */
+  workArea: Rect | null;
+  scaleFactor: number;
+  /** The refresh rate in hertz. */
+  refreshRate: number;
+  /** The clockwise rotation in degrees. */
+  rotation: number;
+  isPrimary: boolean;
+  /** The number of bits per pixel. Only available on Windows. */
+  colorDepth: number | null;
+}
+
+/**
+ * Returns the displays connected to the system.
+ * @example
+ * ```typescript
+ * import { displays } from '@tauri-apps/plugin-os';
+ * const primary = (await displays()).find((d) => d.isPrimary);
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function displays(): Promise<Display[]> {
+  return window.__TAURI_INVOKE__("plugin:os|displays");
+}
+
+/**
+ * Listens to displays being added, removed or reconfigured.
+ *
+ * The handler receives the new list of displays.
+ * @example
+ * ```typescript
+ * import { onDisplaysChanged } from '@tauri-apps/plugin-os';
+ * const unlisten = await onDisplaysChanged((displays) => console.log(displays));
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function onDisplaysChanged(
+  handler: (displays: Display[]) => void
+): Promise<UnlistenFn> {
+  const unlisten = await listen<Display[]>("os://displays-changed", (event) =>
+    handler(event.payload)
+  );
+  await window.__TAURI_INVOKE__("plugin:os|watch_displays");
+  return unlisten;
+}
+
 export {
   EOL,
   platform,
@@ -183,5 +284,9 @@ export {
   locale,
   exeExtension,
   hostname,
+  env,
+  envAllowlist,
+  displays,
+  onDisplaysChanged,
 };
-export type { Platform, OsType, Arch, Family };
+export type { Platform, OsType, Arch, Family, Rect, Display };
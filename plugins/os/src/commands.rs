@@ -41,3 +41,30 @@ pub fn locale() -> Option<String> {
 pub fn hostname() -> String {
     crate::hostname()
 }
+
+#[tauri::command]
+pub(crate) fn env(allowlist: tauri::State<'_, crate::EnvAllowlist>, name: String) -> Option<String> {
+    allowlist.get(&name)
+}
+
+#[tauri::command]
+pub(crate) fn env_allowlist(
+    allowlist: tauri::State<'_, crate::EnvAllowlist>,
+) -> std::collections::HashMap<String, String> {
+    allowlist
+        .vars()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+}
+
+#[cfg(desktop)]
+#[tauri::command]
+pub fn displays() -> crate::Result<Vec<crate::Display>> {
+    crate::displays()
+}
+
+#[cfg(desktop)]
+#[tauri::command]
+pub fn watch_displays<R: tauri::Runtime>(app: tauri::AppHandle<R>) {
+    crate::displays::watch(app)
+}
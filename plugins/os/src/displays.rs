@@ -0,0 +1,161 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use display_info::DisplayInfo;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread::{sleep, spawn},
+    time::Duration,
+};
+
+use crate::{Error, Result};
+
+/// Event emitted to all windows when a display is added, removed or reconfigured.
+pub const DISPLAYS_CHANGED_EVENT: &str = "os://displays-changed";
+
+/// How often the display configuration is compared once watched.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+static WATCHING: AtomicBool = AtomicBool::new(false);
+
+/// A rectangle in physical pixels on the virtual desktop.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A display connected to the system.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Display {
+    /// Platform identifier of the display.
+    pub id: u32,
+    /// The display bounds.
+    pub bounds: Rect,
+    /// The display bounds without the taskbar, dock and menu bar.
+    ///
+    /// Only available on Windows.
+    pub work_area: Option<Rect>,
+    pub scale_factor: f32,
+    /// The refresh rate in hertz.
+    pub refresh_rate: f32,
+    /// The clockwise rotation in degrees.
+    pub rotation: f32,
+    pub is_primary: bool,
+    /// The number of bits per pixel.
+    ///
+    /// Only available on Windows.
+    pub color_depth: Option<u32>,
+}
+
+/// Returns the displays connected to the system.
+pub fn displays() -> Result<Vec<Display>> {
+    let displays = DisplayInfo::all().map_err(|e| Error::Displays(e.to_string()))?;
+    Ok(displays
+        .into_iter()
+        .map(|d| {
+            let (work_area, color_depth) = platform::details(d.x, d.y);
+            Display {
+                id: d.id,
+                bounds: Rect {
+                    x: d.x,
+                    y: d.y,
+                    width: d.width,
+                    height: d.height,
+                },
+                work_area,
+                scale_factor: d.scale_factor,
+                refresh_rate: d.frequency,
+                rotation: d.rotation,
+                is_primary: d.is_primary,
+                color_depth,
+            }
+        })
+        .collect())
+}
+
+/// Starts emitting [`DISPLAYS_CHANGED_EVENT`] with the new displays when the configuration changes.
+///
+/// Calling this more than once has no effect.
+pub fn watch<R: Runtime>(app: AppHandle<R>) {
+    if WATCHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    spawn(move || {
+        let mut previous = displays().unwrap_or_default();
+        loop {
+            sleep(POLL_INTERVAL);
+            if let Ok(current) = displays() {
+                if current != previous {
+                    let _ = app.emit_all(DISPLAYS_CHANGED_EVENT, &current);
+                    previous = current;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::Rect;
+    use windows_sys::Win32::{
+        Foundation::POINT,
+        Graphics::Gdi::{
+            EnumDisplaySettingsW, GetMonitorInfoW, MonitorFromPoint, DEVMODEW,
+            ENUM_CURRENT_SETTINGS, MONITORINFOEXW, MONITOR_DEFAULTTONULL,
+        },
+    };
+
+    /// Reads the work area and color depth of the monitor at the display origin.
+    pub fn details(x: i32, y: i32) -> (Option<Rect>, Option<u32>) {
+        unsafe {
+            let monitor = MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONULL);
+            if monitor == 0 {
+                return (None, None);
+            }
+
+            let mut info: MONITORINFOEXW = std::mem::zeroed();
+            info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+            if GetMonitorInfoW(monitor, &mut info as *mut _ as *mut _) == 0 {
+                return (None, None);
+            }
+            let work = info.monitorInfo.rcWork;
+            let work_area = Rect {
+                x: work.left,
+                y: work.top,
+                width: (work.right - work.left) as u32,
+                height: (work.bottom - work.top) as u32,
+            };
+
+            let mut mode: DEVMODEW = std::mem::zeroed();
+            mode.dmSize = std::mem::size_of::<DEVMODEW>() as u16;
+            let color_depth =
+                if EnumDisplaySettingsW(info.szDevice.as_ptr(), ENUM_CURRENT_SETTINGS, &mut mode)
+                    != 0
+                {
+                    Some(mode.dmBitsPerPel)
+                } else {
+                    None
+                };
+
+            (Some(work_area), color_depth)
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use super::Rect;
+
+    pub fn details(_x: i32, _y: i32) -> (Option<Rect>, Option<u32>) {
+        (None, None)
+    }
+}
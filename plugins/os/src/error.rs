@@ -5,7 +5,11 @@
 use serde::{Serialize, Serializer};
 
 #[derive(Debug, thiserror::Error)]
-pub enum Error {}
+pub enum Error {
+    /// The displays could not be enumerated.
+    #[error("failed to enumerate displays: {0}")]
+    Displays(String),
+}
 
 impl Serialize for Error {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
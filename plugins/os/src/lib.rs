@@ -2,18 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::fmt::Display;
+use std::{collections::HashSet, fmt::Display};
 
 pub use os_info::Version;
 use tauri::{
-    plugin::{Builder, TauriPlugin},
-    Runtime,
+    plugin::{Builder as PluginBuilder, TauriPlugin},
+    Manager, Runtime,
 };
 
 mod commands;
+#[cfg(desktop)]
+mod displays;
 mod error;
 
+#[cfg(desktop)]
+pub use displays::{displays, Display, Rect, DISPLAYS_CHANGED_EVENT};
 pub use error::Error;
+type Result<T> = std::result::Result<T, Error>;
 
 pub enum OsType {
     Linux,
@@ -90,18 +95,74 @@ pub fn hostname() -> String {
     gethostname::gethostname().to_string_lossy().to_string()
 }
 
+/// The environment variables the webview may read, nothing by default.
+pub(crate) struct EnvAllowlist(HashSet<String>);
+
+impl EnvAllowlist {
+    /// The value of the variable, `None` if it is unset, not valid unicode or not allowed.
+    pub(crate) fn get(&self, name: &str) -> Option<String> {
+        if self.0.contains(name) {
+            std::env::var(name).ok()
+        } else {
+            None
+        }
+    }
+
+    /// The allowed variables that are set.
+    pub(crate) fn vars(&self) -> impl Iterator<Item = (&str, String)> {
+        self.0
+            .iter()
+            .filter_map(|name| std::env::var(name).ok().map(|value| (name.as_str(), value)))
+    }
+}
+
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    Builder::new("os")
-        .js_init_script(include_str!("api-iife.js").to_string())
-        .invoke_handler(tauri::generate_handler![
-            commands::platform,
-            commands::version,
-            commands::os_type,
-            commands::family,
-            commands::arch,
-            commands::exe_extension,
-            commands::locale,
-            commands::hostname
-        ])
-        .build()
+    Builder::new().build()
+}
+
+#[derive(Default)]
+pub struct Builder {
+    env_allowlist: HashSet<String>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows the webview to read the environment variable with this exact name.
+    ///
+    /// The other variables are reported as unset, so the webview can not read secrets from
+    /// the environment or tell which variables exist.
+    pub fn allow_env(mut self, name: impl Into<String>) -> Self {
+        self.env_allowlist.insert(name.into());
+        self
+    }
+
+    pub fn build<R: Runtime>(self) -> TauriPlugin<R> {
+        let env_allowlist = EnvAllowlist(self.env_allowlist);
+        PluginBuilder::new("os")
+            .js_init_script(include_str!("api-iife.js").to_string())
+            .invoke_handler(tauri::generate_handler![
+                commands::platform,
+                commands::version,
+                commands::os_type,
+                commands::family,
+                commands::arch,
+                commands::exe_extension,
+                commands::locale,
+                commands::hostname,
+                commands::env,
+                commands::env_allowlist,
+                #[cfg(desktop)]
+                commands::displays,
+                #[cfg(desktop)]
+                commands::watch_displays
+            ])
+            .setup(move |app, _api| {
+                app.manage(env_allowlist);
+                Ok(())
+            })
+            .build()
+    }
 }
@@ -85,6 +85,68 @@ interface SpawnOptions {
    * @since 2.0.0
    *  */
   encoding?: string;
+  /**
+   * Character encoding for stdout, overriding {@link SpawnOptions.encoding}.
+   *
+   * With `base64`, the raw bytes are read in chunks instead of lines and emitted as base64 strings,
+   * so binary output, e.g. an image written by `ffmpeg`, is kept intact.
+   * {@link Command.execute} then returns the whole output as a single base64 string.
+   *
+   * @since 2.0.0
+   */
+  stdoutEncoding?: string;
+  /**
+   * Character encoding for stderr, overriding {@link SpawnOptions.encoding}.
+   * Accepts `base64` like {@link SpawnOptions.stdoutEncoding}.
+   *
+   * @since 2.0.0
+   */
+  stderrEncoding?: string;
+  /**
+   * Limits the events sent to the webview until they are handled,
+   * so a process writing a lot of output can not overwhelm it.
+   *
+   * @since 2.0.0
+   */
+  backpressure?: Backpressure;
+  /**
+   * File read as the `stdin` of the process, which must be allowed by the `fs` plugin scope.
+   * {@link Child.write} can not be used when set.
+   *
+   * @since 2.0.0
+   */
+  stdinFile?: string;
+  /**
+   * Data written to the `stdin` of the process, which is closed afterwards so the process reads EOF.
+   * {@link Child.write} can not be used when set.
+   *
+   * @since 2.0.0
+   */
+  stdinData?: string | Uint8Array | number[];
+  /**
+   * Spawns the process in a new process group, so {@link Child.killTree}
+   * reliably kills all of its descendants.
+   * On Windows, the descendants are also killed once the process exits.
+   *
+   * @since 2.0.0
+   */
+  processGroup?: boolean;
+}
+
+/**
+ * Flow control of the events sent to the webview.
+ *
+ * @since 2.0.0
+ */
+interface Backpressure {
+  /** Maximum number of events sent to the webview and not handled yet. */
+  bufferSize: number;
+  /**
+   * Keep reading the process output when the webview falls behind, dropping the oldest pending lines
+   * and emitting the `dropped` event, instead of pausing the process until the webview catches up.
+   * Defaults to `false`.
+   */
+  dropOldest?: boolean;
 }
 
 /** @ignore */
@@ -128,12 +190,57 @@ async function execute<O extends IOPayload>(
     Object.freeze(args);
   }
 
-  return window.__TAURI_INVOKE__<number>("plugin:shell|execute", {
-    program,
-    args,
-    options,
-    onEventFn: window.__TAURI__.transformCallback(onEvent),
-  });
+  if (options?.stdinData instanceof Uint8Array) {
+    // correctly serialize Uint8Arrays
+    options = { ...options, stdinData: Array.from(options.stdinData) };
+  }
+
+  if (!options?.backpressure) {
+    return window.__TAURI_INVOKE__<number>("plugin:shell|execute", {
+      program,
+      args,
+      options,
+      onEventFn: window.__TAURI__.transformCallback(onEvent),
+    });
+  }
+
+  // acknowledge the handled events once the webview is idle, in batches
+  let pid: number | undefined;
+  let handled = 0;
+  let flushScheduled = false;
+  const flush = (): void => {
+    flushScheduled = false;
+    if (pid === undefined || handled === 0) {
+      return;
+    }
+    const count = handled;
+    handled = 0;
+    void window.__TAURI_INVOKE__("plugin:shell|ack", { pid, count });
+  };
+
+  return window
+    .__TAURI_INVOKE__<number>("plugin:shell|execute", {
+      program,
+      args,
+      options,
+      onEventFn: window.__TAURI__.transformCallback(
+        (event: CommandEvent<O>) => {
+          onEvent(event);
+          if (event.event !== "Dropped") {
+            handled += 1;
+            if (!flushScheduled) {
+              flushScheduled = true;
+              setTimeout(flush, 0);
+            }
+          }
+        }
+      ),
+    })
+    .then((id) => {
+      pid = id;
+      flush();
+      return id;
+    });
 }
 
 /**
@@ -378,11 +485,38 @@ class Child {
       pid: this.pid,
     });
   }
+
+  /**
+   * Kills the child process and all of its descendants,
+   * e.g. the `node` processes started by `npm run`.
+   *
+   * The descendants are reliably reached when spawned with the
+   * {@link SpawnOptions.processGroup} option, otherwise they are looked up
+   * in the process tree.
+   * @example
+   * ```typescript
+   * import { Command } from '@tauri-apps/plugin-shell';
+   * const command = Command.create('npm', ['run', 'dev'], { processGroup: true });
+   * const child = await command.spawn();
+   * await child.killTree();
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async killTree(): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:shell|kill_tree", {
+      pid: this.pid,
+    });
+  }
 }
 
 interface CommandEvents {
   close: TerminatedPayload;
   error: string;
+  /** Number of output lines dropped with the {@link Backpressure.dropOldest} policy. */
+  dropped: number;
 }
 
 interface OutputEvents<O extends IOPayload> {
@@ -531,6 +665,9 @@ class Command<O extends IOPayload> extends EventEmitter<CommandEvents> {
           case "Stderr":
             this.stderr.emit("data", event.payload);
             break;
+          case "Dropped":
+            this.emit("dropped", event.payload);
+            break;
         }
       },
       this.program,
@@ -569,11 +706,18 @@ class Command<O extends IOPayload> extends EventEmitter<CommandEvents> {
       });
 
       this.on("close", (payload: TerminatedPayload) => {
+        const options = this.options;
         resolve({
           code: payload.code,
           signal: payload.signal,
-          stdout: this.collectOutput(stdout) as O,
-          stderr: this.collectOutput(stderr) as O,
+          stdout: this.collectOutput(
+            stdout,
+            options.stdoutEncoding ?? options.encoding
+          ) as O,
+          stderr: this.collectOutput(
+            stderr,
+            options.stderrEncoding ?? options.encoding
+          ) as O,
         });
       });
 
@@ -582,11 +726,17 @@ class Command<O extends IOPayload> extends EventEmitter<CommandEvents> {
   }
 
   /** @ignore */
-  private collectOutput(events: O[]): string | Uint8Array {
-    if (this.options.encoding === "raw") {
+  private collectOutput(
+    events: O[],
+    encoding: string | undefined
+  ): string | Uint8Array {
+    if (encoding === "raw") {
       return events.reduce<Uint8Array>((p, c) => {
         return new Uint8Array([...p, ...(c as Uint8Array), 10]);
       }, new Uint8Array());
+    } else if (encoding === "base64") {
+      // each chunk is encoded on its own, with its own padding
+      return window.btoa(events.map((c) => window.atob(c as string)).join(""));
     } else {
       return events.join("\n");
     }
@@ -619,7 +769,8 @@ type CommandEvent<O extends IOPayload> =
   | Event<"Stdout", O>
   | Event<"Stderr", O>
   | Event<"Terminated", TerminatedPayload>
-  | Event<"Error", string>;
+  | Event<"Error", string>
+  | Event<"Dropped", number>;
 
 /**
  * Opens a path or URL with the system's default app,
@@ -654,7 +805,27 @@ async function open(path: string, openWith?: string): Promise<void> {
   });
 }
 
-export { Command, Child, EventEmitter, open };
+/**
+ * Opens the system file manager (Finder, Explorer or the freedesktop file manager) with the given file or directory selected.
+ *
+ * @example
+ * ```typescript
+ * import { reveal } from '@tauri-apps/plugin-shell';
+ * await reveal('/path/to/file.txt');
+ * ```
+ *
+ * @param path The path to reveal. The promise is rejected if it does not exist
+ * or is not allowed on the fs scope.
+ *
+ * @since 2.0.0
+ */
+async function reveal(path: string): Promise<void> {
+  return window.__TAURI_INVOKE__("plugin:shell|reveal", {
+    path,
+  });
+}
+
+export { Command, Child, EventEmitter, open, reveal };
 export type {
   IOPayload,
   CommandEvents,
@@ -662,4 +833,5 @@ export type {
   OutputEvents,
   ChildProcess,
   SpawnOptions,
+  Backpressure,
 };
@@ -2,20 +2,31 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::{collections::HashMap, path::PathBuf, string::FromUtf8Error};
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+    string::FromUtf8Error,
+};
 
+use base64::{engine::general_purpose::STANDARD, Engine};
 use encoding_rs::Encoding;
 use serde::{Deserialize, Serialize};
-use tauri::{api::ipc::CallbackFn, Manager, Runtime, State, Window};
+use tauri::{
+    api::ipc::CallbackFn,
+    async_runtime::{channel, Receiver},
+    path::SafePathBuf,
+    Manager, Runtime, State, Window,
+};
+use tauri_plugin_fs::FsExt;
 
 use crate::{
     open::Program,
     process::{CommandEvent, TerminatedPayload},
     scope::ExecuteArgs,
-    Shell,
+    ChildStore, Shell,
 };
 
-type ChildId = u32;
+pub(crate) type ChildId = u32;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "event", content = "payload")]
@@ -29,6 +40,14 @@ enum JSCommandEvent {
     Error(String),
     /// Command process terminated.
     Terminated(TerminatedPayload),
+    /// Stdout and stderr lines dropped because the webview fell behind, with the drop oldest policy.
+    Dropped(usize),
+}
+
+impl JSCommandEvent {
+    fn is_output(&self) -> bool {
+        matches!(self, Self::Stdout(_) | Self::Stderr(_))
+    }
 }
 
 fn get_event_buffer(line: Vec<u8>, encoding: EncodingWrapper) -> Result<Buffer, FromUtf8Error> {
@@ -40,18 +59,19 @@ fn get_event_buffer(line: Vec<u8>, encoding: EncodingWrapper) -> Result<Buffer,
             None => String::from_utf8(line).map(Buffer::Text),
         },
         EncodingWrapper::Raw => Ok(Buffer::Raw(line)),
+        EncodingWrapper::Base64 => Ok(Buffer::Text(STANDARD.encode(line))),
     }
 }
 
 impl JSCommandEvent {
-    pub fn new(event: CommandEvent, encoding: EncodingWrapper) -> Self {
+    pub fn new(event: CommandEvent, encodings: Encodings) -> Self {
         match event {
             CommandEvent::Terminated(payload) => JSCommandEvent::Terminated(payload),
             CommandEvent::Error(error) => JSCommandEvent::Error(error),
-            CommandEvent::Stderr(line) => get_event_buffer(line, encoding)
+            CommandEvent::Stderr(line) => get_event_buffer(line, encodings.stderr)
                 .map(JSCommandEvent::Stderr)
                 .unwrap_or_else(|e| JSCommandEvent::Error(e.to_string())),
-            CommandEvent::Stdout(line) => get_event_buffer(line, encoding)
+            CommandEvent::Stdout(line) => get_event_buffer(line, encodings.stdout)
                 .map(JSCommandEvent::Stdout)
                 .unwrap_or_else(|e| JSCommandEvent::Error(e.to_string())),
         }
@@ -69,9 +89,36 @@ pub enum Buffer {
 #[derive(Debug, Copy, Clone)]
 pub enum EncodingWrapper {
     Raw,
+    /// Chunks of raw bytes sent as base64 strings, for binary output.
+    Base64,
     Text(Option<&'static Encoding>),
 }
 
+impl EncodingWrapper {
+    fn from_label(label: Option<String>) -> crate::Result<Self> {
+        match label.as_deref() {
+            Option::None => Ok(Self::Text(None)),
+            Some("raw") => Ok(Self::Raw),
+            Some("base64") => Ok(Self::Base64),
+            Some(label) => Encoding::for_label(label.as_bytes())
+                .map(|encoding| Self::Text(Some(encoding)))
+                .ok_or_else(|| crate::Error::UnknownEncoding(label.into())),
+        }
+    }
+
+    /// Whether the stream is read in chunks instead of lines.
+    fn is_chunked(self) -> bool {
+        matches!(self, Self::Base64)
+    }
+}
+
+/// The encoding of each output stream.
+#[derive(Debug, Copy, Clone)]
+pub struct Encodings {
+    stdout: EncodingWrapper,
+    stderr: EncodingWrapper,
+}
+
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CommandOptions {
@@ -84,6 +131,30 @@ pub struct CommandOptions {
     env: Option<HashMap<String, String>>,
     // Character encoding for stdout/stderr
     encoding: Option<String>,
+    // Character encoding for stdout, overriding `encoding`
+    stdout_encoding: Option<String>,
+    // Character encoding for stderr, overriding `encoding`
+    stderr_encoding: Option<String>,
+    // Limits the events forwarded to the webview until it acknowledges them
+    backpressure: Option<Backpressure>,
+    // File read as the process stdin, must be allowed by the fs scope
+    stdin_file: Option<PathBuf>,
+    // Data written to the process stdin before closing it
+    stdin_data: Option<Buffer>,
+    // Spawns the process in a new process group, so `kill_tree` reaches all its descendants
+    #[serde(default)]
+    process_group: bool,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Backpressure {
+    /// Maximum number of events sent to the webview and not acknowledged yet.
+    buffer_size: usize,
+    /// Keep reading the child output when the webview falls behind, dropping the oldest
+    /// pending lines, instead of pausing the child.
+    #[serde(default)]
+    drop_oldest: bool,
 }
 
 #[allow(clippy::unnecessary_wraps)]
@@ -132,6 +203,7 @@ pub fn execute<R: Runtime>(
             }
         }
     };
+    command = shell.with_env(command);
     if let Some(cwd) = options.cwd {
         command = command.current_dir(cwd);
     }
@@ -140,19 +212,38 @@ pub fn execute<R: Runtime>(
     } else {
         command = command.env_clear();
     }
-    let encoding = match options.encoding {
-        Option::None => EncodingWrapper::Text(None),
-        Some(encoding) => match encoding.as_str() {
-            "raw" => EncodingWrapper::Raw,
-            _ => {
-                if let Some(text_encoding) = Encoding::for_label(encoding.as_bytes()) {
-                    EncodingWrapper::Text(Some(text_encoding))
-                } else {
-                    return Err(crate::Error::UnknownEncoding(encoding));
-                }
+    match (options.stdin_file, options.stdin_data) {
+        (Some(_), Some(_)) => return Err(crate::Error::StdinConflict),
+        (Some(path), None) => {
+            if SafePathBuf::new(path.clone()).is_err()
+                || !window
+                    .try_fs_scope()
+                    .map(|s| s.is_allowed(&path))
+                    .unwrap_or_default()
+            {
+                return Err(crate::Error::PathNotAllowed(path));
             }
-        },
+            command = command.stdin_file(path);
+        }
+        (None, Some(Buffer::Text(text))) => command = command.stdin_data(text),
+        (None, Some(Buffer::Raw(data))) => command = command.stdin_data(data),
+        (None, None) => {}
+    }
+    if options.process_group {
+        command = command.process_group();
+    }
+    let stdout_encoding = options.stdout_encoding.or_else(|| options.encoding.clone());
+    let stderr_encoding = options.stderr_encoding.or(options.encoding);
+    let encodings = Encodings {
+        stdout: EncodingWrapper::from_label(stdout_encoding)?,
+        stderr: EncodingWrapper::from_label(stderr_encoding)?,
     };
+    if encodings.stdout.is_chunked() {
+        command = command.stdout_chunks();
+    }
+    if encodings.stderr.is_chunked() {
+        command = command.stderr_chunks();
+    }
 
     let (mut rx, child) = command.spawn()?;
 
@@ -160,22 +251,123 @@ pub fn execute<R: Runtime>(
     shell.children.lock().unwrap().insert(pid, child);
     let children = shell.children.clone();
 
+    if let Some(backpressure) = options.backpressure {
+        let (ack_tx, ack_rx) = channel(16);
+        shell.acks.lock().unwrap().insert(pid, ack_tx);
+        let acks = shell.acks.clone();
+        tauri::async_runtime::spawn(async move {
+            forward_with_backpressure(
+                &window,
+                on_event_fn,
+                rx,
+                ack_rx,
+                &children,
+                pid,
+                encodings,
+                backpressure,
+            )
+            .await;
+            acks.lock().unwrap().remove(&pid);
+        });
+        return Ok(pid);
+    }
+
     tauri::async_runtime::spawn(async move {
         while let Some(event) = rx.recv().await {
             if matches!(event, crate::process::CommandEvent::Terminated(_)) {
                 children.lock().unwrap().remove(&pid);
             };
-            let js_event = JSCommandEvent::new(event, encoding);
-            let js = tauri::api::ipc::format_callback(on_event_fn, &js_event)
-                .expect("unable to serialize CommandEvent");
-
-            let _ = window.eval(js.as_str());
+            let js_event = JSCommandEvent::new(event, encodings);
+            send_event(&window, on_event_fn, &js_event);
         }
     });
 
     Ok(pid)
 }
 
+fn send_event<R: Runtime>(window: &Window<R>, on_event_fn: CallbackFn, event: &JSCommandEvent) {
+    let js = tauri::api::ipc::format_callback(on_event_fn, event)
+        .expect("unable to serialize CommandEvent");
+    let _ = window.eval(js.as_str());
+}
+
+/// Forwards the command events to the webview, keeping at most `buffer_size` of them
+/// unacknowledged.
+///
+/// Once the webview falls behind, the child output is either not read anymore, so the child
+/// blocks writing to its full pipes, or read and queued, dropping the oldest lines.
+#[allow(clippy::too_many_arguments)]
+async fn forward_with_backpressure<R: Runtime>(
+    window: &Window<R>,
+    on_event_fn: CallbackFn,
+    mut rx: Receiver<CommandEvent>,
+    mut ack_rx: Receiver<usize>,
+    children: &ChildStore,
+    pid: ChildId,
+    encodings: Encodings,
+    backpressure: Backpressure,
+) {
+    let buffer_size = backpressure.buffer_size.max(1);
+    let mut queue = VecDeque::new();
+    let mut in_flight = 0;
+    let mut dropped = 0;
+    let mut finished = false;
+
+    loop {
+        while in_flight < buffer_size {
+            let Some(event) = queue.pop_front() else {
+                break;
+            };
+            send_event(window, on_event_fn, &event);
+            in_flight += 1;
+        }
+        if dropped > 0 {
+            send_event(window, on_event_fn, &JSCommandEvent::Dropped(dropped));
+            dropped = 0;
+        }
+        if finished && queue.is_empty() {
+            break;
+        }
+
+        let read = !finished && (backpressure.drop_oldest || queue.is_empty());
+        tokio::select! {
+            Some(count) = ack_rx.recv() => in_flight = in_flight.saturating_sub(count),
+            event = rx.recv(), if read => match event {
+                Some(event) => {
+                    if matches!(event, CommandEvent::Terminated(_)) {
+                        children.lock().unwrap().remove(&pid);
+                    }
+                    if queue.len() >= buffer_size {
+                        if let Some(oldest) = queue.iter().position(JSCommandEvent::is_output) {
+                            queue.remove(oldest);
+                            dropped += 1;
+                        }
+                    }
+                    queue.push_back(JSCommandEvent::new(event, encodings));
+                }
+                None => finished = true,
+            },
+            // the plugin dropped the acknowledgements sender
+            else => break,
+        }
+    }
+}
+
+/// Acknowledges `count` events received by the webview for a command spawned with backpressure.
+#[tauri::command]
+pub async fn ack<R: Runtime>(
+    _window: Window<R>,
+    shell: State<'_, Shell<R>>,
+    pid: ChildId,
+    count: usize,
+) -> crate::Result<()> {
+    let ack_tx = shell.acks.lock().unwrap().get(&pid).cloned();
+    if let Some(ack_tx) = ack_tx {
+        let _ = ack_tx.send(count).await;
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub fn stdin_write<R: Runtime>(
     _window: Window<R>,
@@ -204,6 +396,18 @@ pub fn kill<R: Runtime>(
     Ok(())
 }
 
+#[tauri::command]
+pub fn kill_tree<R: Runtime>(
+    _window: Window<R>,
+    shell: State<'_, Shell<R>>,
+    pid: ChildId,
+) -> crate::Result<()> {
+    if let Some(child) = shell.children.lock().unwrap().remove(&pid) {
+        child.kill_tree()?;
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub fn open<R: Runtime>(
     _window: Window<R>,
@@ -213,3 +417,24 @@ pub fn open<R: Runtime>(
 ) -> crate::Result<()> {
     shell.open(path, with)
 }
+
+#[tauri::command]
+pub async fn reveal<R: Runtime>(
+    window: Window<R>,
+    shell: State<'_, Shell<R>>,
+    path: PathBuf,
+) -> crate::Result<()> {
+    // the scope is checked against the resolved path so links can't escape it
+    let canonical = path
+        .canonicalize()
+        .map_err(|_| crate::Error::PathNotFound(path.clone()))?;
+    if SafePathBuf::new(path.clone()).is_err()
+        || !window
+            .try_fs_scope()
+            .map(|s| s.is_allowed(&canonical))
+            .unwrap_or_default()
+    {
+        return Err(crate::Error::PathNotAllowed(path));
+    }
+    shell.reveal(canonical)
+}
@@ -17,6 +17,10 @@ pub struct Config {
     /// Open URL with the user's default application.
     #[serde(default)]
     pub open: ShellAllowlistOpen,
+    /// Spawn commands with the environment of the user's login shell.
+    /// See [`crate::process::Command::login_shell_env`].
+    #[serde(default)]
+    pub login_shell_env: bool,
 }
 
 /// A command allowed to be executed by the webview API.
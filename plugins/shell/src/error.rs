@@ -14,6 +14,15 @@ pub enum Error {
     CurrentExeHasNoParent,
     #[error("unknown program {0}")]
     UnknownProgramName(String),
+    /// The program to spawn does not exist.
+    #[error("program not found: {0}")]
+    NotFound(PathBuf),
+    /// The program to spawn can not be executed by the user.
+    #[error("permission denied to execute the program: {0}")]
+    PermissionDenied(PathBuf),
+    /// The program to spawn is not an executable of the platform.
+    #[error("program is not an executable: {0}")]
+    NotExecutable(PathBuf),
     #[error(transparent)]
     Scope(#[from] crate::scope::Error),
     /// Sidecar not allowed by the configuration.
@@ -24,6 +33,15 @@ pub enum Error {
     ProgramNotAllowed(PathBuf),
     #[error("unknown encoding {0}")]
     UnknownEncoding(String),
+    /// Both `stdinFile` and `stdinData` were set.
+    #[error("the stdin of a process can not be both a file and data")]
+    StdinConflict,
+    /// Path not allowed by the fs scope.
+    #[error("path not allowed on the configured fs scope: {0}")]
+    PathNotAllowed(PathBuf),
+    /// The path to reveal does not exist.
+    #[error("path not found: {0}")]
+    PathNotFound(PathBuf),
 }
 
 impl Serialize for Error {
@@ -26,18 +26,21 @@ use config::{Config, ShellAllowedArg, ShellAllowedArgs, ShellAllowlistOpen, Shel
 pub use error::Error;
 type Result<T> = std::result::Result<T, Error>;
 type ChildStore = Arc<Mutex<HashMap<u32, CommandChild>>>;
+type AckStore = Arc<Mutex<HashMap<u32, tauri::async_runtime::Sender<usize>>>>;
 
 pub struct Shell<R: Runtime> {
     #[allow(dead_code)]
     app: AppHandle<R>,
     scope: Scope,
     children: ChildStore,
+    acks: AckStore,
+    login_shell_env: bool,
 }
 
 impl<R: Runtime> Shell<R> {
     /// Creates a new Command for launching the given program.
     pub fn command(&self, program: impl Into<String>) -> Command {
-        Command::new(program)
+        self.with_env(Command::new(program))
     }
 
     /// Creates a new Command for launching the given sidecar program.
@@ -45,7 +48,16 @@ impl<R: Runtime> Shell<R> {
     /// A sidecar program is a embedded external binary in order to make your application work
     /// or to prevent users having to install additional dependencies (e.g. Node.js, Python, etc).
     pub fn sidecar(&self, program: impl Into<String>) -> Result<Command> {
-        Command::new_sidecar(program)
+        Command::new_sidecar(program).map(|command| self.with_env(command))
+    }
+
+    /// Applies the configured environment options to the command.
+    pub(crate) fn with_env(&self, command: Command) -> Command {
+        if self.login_shell_env {
+            command.login_shell_env()
+        } else {
+            command
+        }
     }
 
     /// Open a (url) path with a default or specific browser opening program.
@@ -54,6 +66,13 @@ impl<R: Runtime> Shell<R> {
     pub fn open(&self, path: impl Into<String>, with: Option<open::Program>) -> Result<()> {
         open::open(&self.scope, path.into(), with).map_err(Into::into)
     }
+
+    /// Opens the system file manager with the given path selected.
+    ///
+    /// See [`open::reveal`] for the platform-specific behavior.
+    pub fn reveal(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        open::reveal(path)
+    }
 }
 
 pub trait ShellExt<R: Runtime> {
@@ -76,7 +95,10 @@ pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
             commands::execute,
             commands::stdin_write,
             commands::kill,
-            commands::open
+            commands::kill_tree,
+            commands::ack,
+            commands::open,
+            commands::reveal
         ])
         .setup(|app, api| {
             let default_config = Config::default();
@@ -84,7 +106,9 @@ pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
             app.manage(Shell {
                 app: app.clone(),
                 children: Default::default(),
+                acks: Default::default(),
                 scope: Scope::new(app, shell_scope(config.scope.clone(), &config.open)),
+                login_shell_env: config.login_shell_env,
             });
             Ok(())
         })
@@ -96,7 +120,11 @@ pub fn init<R: Runtime>() -> TauriPlugin<R, Option<Config>> {
                     std::mem::take(&mut *lock)
                 };
                 for child in children.into_values() {
-                    let _ = child.kill();
+                    let _ = if child.process_group() {
+                        child.kill_tree()
+                    } else {
+                        child.kill()
+                    };
                 }
             }
         })
@@ -7,7 +7,11 @@
 use serde::{Deserialize, Deserializer};
 
 use crate::scope::Scope;
-use std::str::FromStr;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+};
 
 /// Program to use on the [`open()`] call.
 pub enum Program {
@@ -120,3 +124,96 @@ impl Program {
 pub fn open<P: AsRef<str>>(scope: &Scope, path: P, with: Option<Program>) -> crate::Result<()> {
     scope.open(path.as_ref(), with).map_err(Into::into)
 }
+
+/// Opens the system file manager with the given file or directory selected.
+///
+/// ## Platform-specific
+///
+/// - **macOS:** Uses `open -R` to reveal the item in Finder.
+/// - **Windows:** Uses `explorer /select,`.
+/// - **Linux:** Calls the freedesktop `org.freedesktop.FileManager1.ShowItems` D-Bus method,
+///   falling back to opening the parent directory when no file manager implements it.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri_plugin_shell::ShellExt;
+/// tauri::Builder::default()
+///   .setup(|app| {
+///     app.shell().reveal("/path/to/file.txt")?;
+///     Ok(())
+///   });
+/// ```
+pub fn reveal<P: AsRef<Path>>(path: P) -> crate::Result<()> {
+    let path = path.as_ref();
+    let path = path
+        .canonicalize()
+        .map_err(|_| crate::Error::PathNotFound(path.to_path_buf()))?;
+    reveal_canonical(path)
+}
+
+#[cfg(target_os = "macos")]
+fn reveal_canonical(path: PathBuf) -> crate::Result<()> {
+    Command::new("open").arg("-R").arg(path).spawn()?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn reveal_canonical(path: PathBuf) -> crate::Result<()> {
+    use std::os::windows::process::CommandExt;
+
+    // `canonicalize` returns a verbatim `\\?\` path which explorer doesn't understand
+    let path = path.display().to_string();
+    let path = path.strip_prefix(r"\\?\").unwrap_or(&path);
+    // explorer does its own argument parsing, so the path must be quoted by hand
+    Command::new("explorer")
+        .raw_arg(format!("/select,\"{path}\""))
+        .spawn()?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn reveal_canonical(path: PathBuf) -> crate::Result<()> {
+    let shown = Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{}", file_uri(&path)))
+        .arg("string:")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+
+    if !shown {
+        let dir = if path.is_dir() {
+            path.as_path()
+        } else {
+            path.parent().unwrap_or(&path)
+        };
+        open::that(dir)?;
+    }
+
+    Ok(())
+}
+
+/// Converts an absolute path to a `file://` URI, percent-encoding everything but unreserved characters.
+#[cfg(not(any(target_os = "macos", windows)))]
+fn file_uri(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut uri = String::from("file://");
+    for byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(*byte as char)
+            }
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
@@ -0,0 +1,91 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Resolution of the user's login shell environment.
+//!
+//! Applications launched from a desktop environment (Finder, the dock, a launcher) do not
+//! inherit the environment configured in the user's shell profile, so tools installed on the
+//! `PATH` set up there are not found.
+
+use std::collections::HashMap;
+
+use once_cell::sync::OnceCell;
+
+static LOGIN_ENV: OnceCell<Option<HashMap<String, String>>> = OnceCell::new();
+
+/// Returns the environment of the user's login shell, resolving it on first use.
+///
+/// Returns `None` if the environment could not be resolved or on platforms without login shells.
+pub(crate) fn login_shell_env() -> Option<&'static HashMap<String, String>> {
+    LOGIN_ENV.get_or_init(imp::resolve).as_ref()
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::{
+        collections::HashMap,
+        io::Read,
+        process::{Command, Stdio},
+        sync::mpsc::channel,
+        time::Duration,
+    };
+
+    /// Surrounds the `env` output so anything the profile scripts print is ignored.
+    const MARKER: &str = "__TAURI_SHELL_ENV__";
+    /// How long to wait for the profile scripts before giving up.
+    const TIMEOUT: Duration = Duration::from_secs(5);
+    /// Variables describing the resolving shell itself rather than the user environment.
+    const IGNORED: &[&str] = &["_", "PWD", "OLDPWD", "SHLVL"];
+
+    pub fn resolve() -> Option<HashMap<String, String>> {
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".into());
+        let mut child = Command::new(shell)
+            .arg("-ilc")
+            .arg(format!("printf {MARKER}; env -0; printf {MARKER}"))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+
+        let mut stdout = child.stdout.take()?;
+        let (tx, rx) = channel();
+        std::thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = tx.send(stdout.read_to_end(&mut output).map(|_| output));
+        });
+
+        let output = match rx.recv_timeout(TIMEOUT) {
+            Ok(output) => output.ok(),
+            Err(_) => {
+                let _ = child.kill();
+                None
+            }
+        };
+        let _ = child.wait();
+
+        parse(&String::from_utf8_lossy(&output?))
+    }
+
+    fn parse(output: &str) -> Option<HashMap<String, String>> {
+        let mut sections = output.split(MARKER);
+        let env = sections.nth(1)?;
+        Some(
+            env.split('\0')
+                .filter_map(|entry| entry.split_once('='))
+                .filter(|(key, _)| !key.is_empty() && !IGNORED.contains(key))
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use std::collections::HashMap;
+
+    pub fn resolve() -> Option<HashMap<String, String>> {
+        None
+    }
+}
@@ -4,7 +4,8 @@
 
 use std::{
     collections::HashMap,
-    io::{BufReader, Write},
+    fs::File,
+    io::{self, BufReader, Read, Write},
     path::PathBuf,
     process::{Command as StdCommand, Stdio},
     sync::{Arc, RwLock},
@@ -12,13 +13,16 @@ use std::{
 };
 
 #[cfg(unix)]
-use std::os::unix::process::ExitStatusExt;
+use std::os::unix::process::{CommandExt as _, ExitStatusExt};
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
 #[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x0800_0000;
 const NEWLINE_BYTE: u8 = b'\n';
+/// The size of the chunks read from an output streamed with [`Command::stdout_chunks`] or
+/// [`Command::stderr_chunks`].
+const CHUNK_SIZE: usize = 64 * 1024;
 
 use tauri::async_runtime::{block_on as block_on_task, channel, Receiver, Sender};
 
@@ -28,6 +32,11 @@ use serde::Serialize;
 use shared_child::SharedChild;
 use tauri::utils::platform;
 
+mod login_env;
+mod tree;
+
+pub use tree::kill_tree;
+
 /// Payload for the [`CommandEvent::Terminated`] command event.
 #[derive(Debug, Clone, Serialize)]
 pub struct TerminatedPayload {
@@ -41,9 +50,11 @@ pub struct TerminatedPayload {
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub enum CommandEvent {
-    /// Stderr bytes until a newline (\n) or carriage return (\r) is found.
+    /// Stderr bytes until a newline (\n) or carriage return (\r) is found,
+    /// or a chunk of the raw bytes with [`Command::stderr_chunks`].
     Stderr(Vec<u8>),
-    /// Stdout bytes until a newline (\n) or carriage return (\r) is found.
+    /// Stdout bytes until a newline (\n) or carriage return (\r) is found,
+    /// or a chunk of the raw bytes with [`Command::stdout_chunks`].
     Stdout(Vec<u8>),
     /// An error happened waiting for the command to finish or converting the stdout/stderr bytes to an UTF-8 string.
     Error(String),
@@ -51,6 +62,16 @@ pub enum CommandEvent {
     Terminated(TerminatedPayload),
 }
 
+/// The input of a child process.
+#[derive(Debug, Default)]
+enum Stdin {
+    /// Written with [`CommandChild::write`].
+    #[default]
+    Piped,
+    File(PathBuf),
+    Data(Vec<u8>),
+}
+
 /// The type to spawn commands.
 #[derive(Debug)]
 pub struct Command {
@@ -59,19 +80,36 @@ pub struct Command {
     env_clear: bool,
     env: HashMap<String, String>,
     current_dir: Option<PathBuf>,
+    login_shell_env: bool,
+    stdin: Stdin,
+    process_group: bool,
+    stdout_chunks: bool,
+    stderr_chunks: bool,
 }
 
 /// Spawned child process.
 #[derive(Debug)]
 pub struct CommandChild {
     inner: Arc<SharedChild>,
-    stdin_writer: PipeWriter,
+    /// `None` if the input was set at spawn time.
+    stdin_writer: Option<PipeWriter>,
+    process_group: bool,
+    #[cfg(windows)]
+    job: Option<tree::Job>,
 }
 
 impl CommandChild {
     /// Writes to process stdin.
+    ///
+    /// Fails if the input of the command was set with [`Command::stdin_file`] or [`Command::stdin_data`].
     pub fn write(&mut self, buf: &[u8]) -> crate::Result<()> {
-        self.stdin_writer.write_all(buf)?;
+        let stdin_writer = self.stdin_writer.as_mut().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "the stdin of the process was set when spawning it",
+            )
+        })?;
+        stdin_writer.write_all(buf)?;
         Ok(())
     }
 
@@ -81,6 +119,35 @@ impl CommandChild {
         Ok(())
     }
 
+    /// Kills the child and all of its descendants.
+    ///
+    /// The descendants are reliably reached when the command was spawned with
+    /// [`Command::process_group`], otherwise they are looked up in the process tree, which
+    /// misses the ones whose parent already exited.
+    pub fn kill_tree(self) -> crate::Result<()> {
+        #[cfg(windows)]
+        if let Some(job) = &self.job {
+            job.terminate()?;
+            return Ok(());
+        }
+        // the group outlives its leader until all of its members exit
+        #[cfg(unix)]
+        if self.process_group {
+            tree::kill_group(self.pid())?;
+            return Ok(());
+        }
+        // the pid is only signaled while the child was not reaped, so it can not be reused
+        if self.inner.try_wait()?.is_none() {
+            kill_tree(self.pid())?;
+        }
+        Ok(())
+    }
+
+    /// Whether the child was spawned with [`Command::process_group`].
+    pub fn process_group(&self) -> bool {
+        self.process_group
+    }
+
     /// Returns the process pid.
     pub fn pid(&self) -> u32 {
         self.inner.id()
@@ -135,11 +202,19 @@ impl From<Command> for StdCommand {
         command.stderr(Stdio::piped());
         if cmd.env_clear {
             command.env_clear();
+        } else if cmd.login_shell_env {
+            if let Some(env) = login_env::login_shell_env() {
+                command.envs(env);
+            }
         }
         command.envs(cmd.env);
         if let Some(current_dir) = cmd.current_dir {
             command.current_dir(current_dir);
         }
+        #[cfg(unix)]
+        if cmd.process_group {
+            command.process_group(0);
+        }
         #[cfg(windows)]
         command.creation_flags(CREATE_NO_WINDOW);
         command
@@ -154,6 +229,11 @@ impl Command {
             env_clear: false,
             env: Default::default(),
             current_dir: None,
+            login_shell_env: false,
+            stdin: Stdin::Piped,
+            process_group: false,
+            stdout_chunks: false,
+            stderr_chunks: false,
         }
     }
 
@@ -188,6 +268,23 @@ impl Command {
         self
     }
 
+    /// Runs the child process with the environment of the user's login shell.
+    ///
+    /// The environment is resolved once by running `$SHELL -ilc env` and cached for the
+    /// lifetime of the application, so tools added to the `PATH` by the user's shell profile
+    /// are found even if the app was not launched from a terminal.
+    /// Variables set with [`Self::envs`] take precedence. Has no effect if the environment
+    /// is cleared with [`Self::env_clear`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Windows:** Unsupported, the process environment is used.
+    #[must_use]
+    pub fn login_shell_env(mut self) -> Self {
+        self.login_shell_env = true;
+        self
+    }
+
     /// Sets the working directory for the child process.
     #[must_use]
     pub fn current_dir(mut self, current_dir: PathBuf) -> Self {
@@ -195,6 +292,51 @@ impl Command {
         self
     }
 
+    /// Reads the input of the child process from a file.
+    #[must_use]
+    pub fn stdin_file(mut self, path: PathBuf) -> Self {
+        self.stdin = Stdin::File(path);
+        self
+    }
+
+    /// Writes `data` to the input of the child process, then closes it so the process reads EOF.
+    #[must_use]
+    pub fn stdin_data(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.stdin = Stdin::Data(data.into());
+        self
+    }
+
+    /// Spawns the process in a new process group, so [`CommandChild::kill_tree`] reliably
+    /// kills all of its descendants.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Unix:** The process leads a new process group, which its descendants join unless
+    ///   they create their own.
+    /// - **Windows:** The process is assigned to a new Job Object. Its descendants are also
+    ///   killed when the [`CommandChild`] is dropped, e.g. once the process exits when spawned
+    ///   from the webview.
+    #[must_use]
+    pub fn process_group(mut self) -> Self {
+        self.process_group = true;
+        self
+    }
+
+    /// Reads the stdout in chunks of raw bytes instead of lines, so binary output, e.g. an
+    /// image written by `ffmpeg`, is kept intact.
+    #[must_use]
+    pub fn stdout_chunks(mut self) -> Self {
+        self.stdout_chunks = true;
+        self
+    }
+
+    /// Reads the stderr in chunks of raw bytes instead of lines, so binary output is kept intact.
+    #[must_use]
+    pub fn stderr_chunks(mut self) -> Self {
+        self.stderr_chunks = true;
+        self
+    }
+
     /// Spawns the command.
     ///
     /// # Examples
@@ -225,16 +367,55 @@ impl Command {
     ///     Ok(())
     /// });
     /// ```
-    pub fn spawn(self) -> crate::Result<(Receiver<CommandEvent>, CommandChild)> {
+    pub fn spawn(mut self) -> crate::Result<(Receiver<CommandEvent>, CommandChild)> {
+        let stdin = std::mem::take(&mut self.stdin);
+        let process_group = self.process_group;
+        let (stdout_chunks, stderr_chunks) = (self.stdout_chunks, self.stderr_chunks);
         let mut command: StdCommand = self.into();
         let (stdout_reader, stdout_writer) = pipe()?;
         let (stderr_reader, stderr_writer) = pipe()?;
-        let (stdin_reader, stdin_writer) = pipe()?;
         command.stdout(stdout_writer);
         command.stderr(stderr_writer);
-        command.stdin(stdin_reader);
+        let (stdin_writer, stdin_data) = match stdin {
+            Stdin::Piped => {
+                let (stdin_reader, stdin_writer) = pipe()?;
+                command.stdin(stdin_reader);
+                (Some(stdin_writer), None)
+            }
+            Stdin::File(path) => {
+                command.stdin(File::open(path)?);
+                (None, None)
+            }
+            Stdin::Data(data) => {
+                let (stdin_reader, stdin_writer) = pipe()?;
+                command.stdin(stdin_reader);
+                (None, Some((stdin_writer, data)))
+            }
+        };
+
+        let shared_child =
+            SharedChild::spawn(&mut command).map_err(|e| spawn_error(&command, e))?;
 
-        let shared_child = SharedChild::spawn(&mut command)?;
+        #[cfg(windows)]
+        let job = if process_group {
+            match tree::Job::assign(shared_child.id()) {
+                Ok(job) => Some(job),
+                Err(e) => {
+                    let _ = shared_child.kill();
+                    return Err(e.into());
+                }
+            }
+        } else {
+            None
+        };
+
+        if let Some((mut stdin_writer, data)) = stdin_data {
+            // the child may not read all of its input, so write from a thread;
+            // dropping the writer closes the pipe and the child reads EOF
+            spawn(move || {
+                let _ = stdin_writer.write_all(&data);
+            });
+        }
         let child = Arc::new(shared_child);
         let child_ = child.clone();
         let guard = Arc::new(RwLock::new(()));
@@ -247,12 +428,14 @@ impl Command {
             tx.clone(),
             guard.clone(),
             stdout_reader,
+            stdout_chunks,
             CommandEvent::Stdout,
         );
         spawn_pipe_reader(
             tx.clone(),
             guard.clone(),
             stderr_reader,
+            stderr_chunks,
             CommandEvent::Stderr,
         );
 
@@ -284,6 +467,9 @@ impl Command {
             CommandChild {
                 inner: child,
                 stdin_writer,
+                process_group,
+                #[cfg(windows)]
+                job,
             },
         ))
     }
@@ -329,6 +515,8 @@ impl Command {
     ///   });
     /// ```
     pub async fn output(self) -> crate::Result<Output> {
+        // the chunks are not split on newlines, so none is added back between them
+        let (stdout_chunks, stderr_chunks) = (self.stdout_chunks, self.stderr_chunks);
         let (mut rx, _child) = self.spawn()?;
 
         let mut code = None;
@@ -342,11 +530,15 @@ impl Command {
                 }
                 CommandEvent::Stdout(line) => {
                     stdout.extend(line);
-                    stdout.push(NEWLINE_BYTE);
+                    if !stdout_chunks {
+                        stdout.push(NEWLINE_BYTE);
+                    }
                 }
                 CommandEvent::Stderr(line) => {
                     stderr.extend(line);
-                    stderr.push(NEWLINE_BYTE);
+                    if !stderr_chunks {
+                        stderr.push(NEWLINE_BYTE);
+                    }
                 }
                 CommandEvent::Error(_) => {}
             }
@@ -359,10 +551,31 @@ impl Command {
     }
 }
 
+/// Reads a line of the pipe, or a chunk of at most [`CHUNK_SIZE`] raw bytes, `None` at its end.
+fn read_event(reader: &mut BufReader<PipeReader>, chunks: bool) -> io::Result<Option<Vec<u8>>> {
+    let mut buf = Vec::new();
+    let n = if chunks {
+        buf.resize(CHUNK_SIZE, 0);
+        let n = loop {
+            match reader.read(&mut buf) {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                result => break result?,
+            }
+        };
+        buf.truncate(n);
+        n
+    } else {
+        // the line ending is not kept, so an empty line still reads bytes
+        tauri::utils::io::read_line(reader, &mut buf)?
+    };
+    Ok((n > 0).then_some(buf))
+}
+
 fn spawn_pipe_reader<F: Fn(Vec<u8>) -> CommandEvent + Send + Copy + 'static>(
     tx: Sender<CommandEvent>,
     guard: Arc<RwLock<()>>,
     pipe_reader: PipeReader,
+    chunks: bool,
     wrapper: F,
 ) {
     spawn(move || {
@@ -370,15 +583,12 @@ fn spawn_pipe_reader<F: Fn(Vec<u8>) -> CommandEvent + Send + Copy + 'static>(
         let mut reader = BufReader::new(pipe_reader);
 
         loop {
-            let mut buf = Vec::new();
-            match tauri::utils::io::read_line(&mut reader, &mut buf) {
-                Ok(n) => {
-                    if n == 0 {
-                        break;
-                    }
+            match read_event(&mut reader, chunks) {
+                Ok(Some(buf)) => {
                     let tx_ = tx.clone();
                     let _ = block_on_task(async move { tx_.send(wrapper(buf)).await });
                 }
+                Ok(None) => break,
                 Err(e) => {
                     let tx_ = tx.clone();
                     let _ =
@@ -391,6 +601,50 @@ fn spawn_pipe_reader<F: Fn(Vec<u8>) -> CommandEvent + Send + Copy + 'static>(
     });
 }
 
+/// Tells a missing, forbidden or invalid program apart from the other spawn failures.
+fn spawn_error(command: &StdCommand, error: io::Error) -> crate::Error {
+    #[cfg(unix)]
+    const CODES: [(i32, SpawnFailure); 4] = [
+        (libc::ENOENT, SpawnFailure::NotFound),
+        (libc::EACCES, SpawnFailure::PermissionDenied),
+        (libc::EPERM, SpawnFailure::PermissionDenied),
+        (libc::ENOEXEC, SpawnFailure::NotExecutable),
+    ];
+    #[cfg(windows)]
+    const CODES: [(i32, SpawnFailure); 4] = {
+        use windows_sys::Win32::Foundation::{
+            ERROR_ACCESS_DENIED, ERROR_BAD_EXE_FORMAT, ERROR_FILE_NOT_FOUND, ERROR_PATH_NOT_FOUND,
+        };
+        [
+            (ERROR_FILE_NOT_FOUND as i32, SpawnFailure::NotFound),
+            (ERROR_PATH_NOT_FOUND as i32, SpawnFailure::NotFound),
+            (ERROR_ACCESS_DENIED as i32, SpawnFailure::PermissionDenied),
+            (ERROR_BAD_EXE_FORMAT as i32, SpawnFailure::NotExecutable),
+        ]
+    };
+
+    let failure = error
+        .raw_os_error()
+        .and_then(|code| CODES.iter().find(|(c, _)| *c == code))
+        .map(|(_, failure)| *failure);
+    // a missing working directory fails with the same code as a missing program
+    let missing_dir = command.get_current_dir().map_or(false, |dir| !dir.exists());
+    let program = PathBuf::from(command.get_program());
+    match failure {
+        Some(SpawnFailure::NotFound) if !missing_dir => crate::Error::NotFound(program),
+        Some(SpawnFailure::PermissionDenied) => crate::Error::PermissionDenied(program),
+        Some(SpawnFailure::NotExecutable) => crate::Error::NotExecutable(program),
+        _ => error.into(),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum SpawnFailure {
+    NotFound,
+    PermissionDenied,
+    NotExecutable,
+}
+
 // tests for the commands functions.
 #[cfg(test)]
 mod tests {
@@ -502,6 +756,17 @@ mod tests {
         );
     }
 
+    #[cfg(not(windows))]
+    #[test]
+    fn test_cmd_output_chunks() {
+        let cmd = Command::new("printf")
+            .args(["a\\r\\n\\000b"])
+            .stdout_chunks();
+        let output = tauri::async_runtime::block_on(cmd.output()).unwrap();
+
+        assert_eq!(output.stdout, b"a\r\n\0b");
+    }
+
     #[cfg(not(windows))]
     #[test]
     fn test_cmd_output_output_fail() {
@@ -514,4 +779,51 @@ mod tests {
             "cat: test/: Is a directory\n"
         );
     }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_cmd_spawn_errors() {
+        let error = Command::new("tauri-plugin-shell-missing-program")
+            .spawn()
+            .unwrap_err();
+        assert!(matches!(error, crate::Error::NotFound(_)));
+
+        // the test document is not executable
+        let error = Command::new("test/test.txt").spawn().unwrap_err();
+        assert!(matches!(error, crate::Error::PermissionDenied(_)));
+
+        let error = Command::new("cat")
+            .current_dir("test/missing")
+            .spawn()
+            .unwrap_err();
+        assert!(matches!(error, crate::Error::Io(_)));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_spawn_error_codes() {
+        use super::*;
+        use windows_sys::Win32::Foundation::{
+            ERROR_ACCESS_DENIED, ERROR_BAD_EXE_FORMAT, ERROR_FILE_NOT_FOUND, ERROR_PATH_NOT_FOUND,
+        };
+
+        let command = StdCommand::new("app.exe");
+        let error = |code: u32| spawn_error(&command, io::Error::from_raw_os_error(code as i32));
+        assert!(matches!(
+            error(ERROR_FILE_NOT_FOUND),
+            crate::Error::NotFound(_)
+        ));
+        assert!(matches!(
+            error(ERROR_PATH_NOT_FOUND),
+            crate::Error::NotFound(_)
+        ));
+        assert!(matches!(
+            error(ERROR_ACCESS_DENIED),
+            crate::Error::PermissionDenied(_)
+        ));
+        assert!(matches!(
+            error(ERROR_BAD_EXE_FORMAT),
+            crate::Error::NotExecutable(_)
+        ));
+    }
 }
@@ -0,0 +1,176 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Termination of a process with all of its descendants.
+//!
+//! On Unix, a process spawned with [`Command::process_group`](super::Command::process_group)
+//! leads its own group, which is signaled at once. On Windows, it is assigned to a Job Object
+//! that is terminated instead, and that also kills the remaining descendants once the
+//! [`CommandChild`](super::CommandChild) is dropped.
+//! Other processes fall back to walking the process tree.
+
+#[cfg(unix)]
+pub(crate) use imp::kill_group;
+#[cfg(windows)]
+pub(crate) use imp::Job;
+
+/// Kills the process and all of its descendants.
+///
+/// Prefer [`CommandChild::kill_tree`](super::CommandChild::kill_tree) for the processes
+/// spawned by this plugin, which also reaches the descendants that detached from the tree
+/// when the process was spawned with [`Command::process_group`](super::Command::process_group).
+pub fn kill_tree(pid: u32) -> crate::Result<()> {
+    imp::kill_tree(pid).map_err(Into::into)
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::{io, process::Command};
+
+    pub(super) fn kill_tree(pid: u32) -> io::Result<()> {
+        let pid = pid as libc::pid_t;
+        // list the descendants first, they are reparented once their parent dies
+        let descendants = descendants(pid);
+        if unsafe { libc::getpgid(pid) } == pid {
+            check(unsafe { libc::killpg(pid, libc::SIGKILL) })?;
+        } else {
+            check(unsafe { libc::kill(pid, libc::SIGKILL) })?;
+        }
+        for descendant in descendants {
+            // the descendant may have exited or been killed with the group already
+            unsafe { libc::kill(descendant, libc::SIGKILL) };
+        }
+        Ok(())
+    }
+
+    /// Kills the members of the process group led by the process.
+    pub(crate) fn kill_group(pid: u32) -> io::Result<()> {
+        check(unsafe { libc::killpg(pid as libc::pid_t, libc::SIGKILL) })
+    }
+
+    fn check(result: libc::c_int) -> io::Result<()> {
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The descendants of the process, from the output of `ps`.
+    fn descendants(pid: libc::pid_t) -> Vec<libc::pid_t> {
+        let Ok(output) = Command::new("ps")
+            .args(["-A", "-o", "pid=", "-o", "ppid="])
+            .output()
+        else {
+            return Vec::new();
+        };
+        let processes = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut ids = line.split_whitespace().map(str::parse::<libc::pid_t>);
+                Some((ids.next()?.ok()?, ids.next()?.ok()?))
+            })
+            .collect::<Vec<_>>();
+
+        let mut descendants = Vec::new();
+        let mut parents = vec![pid];
+        while let Some(parent) = parents.pop() {
+            for (child, _) in processes.iter().filter(|(_, ppid)| *ppid == parent) {
+                descendants.push(*child);
+                parents.push(*child);
+            }
+        }
+        descendants
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::{io, os::windows::process::CommandExt, process::Command};
+
+    use windows_sys::Win32::{
+        Foundation::{CloseHandle, HANDLE},
+        System::{
+            JobObjects::{
+                AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+                SetInformationJobObject, TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+                JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+            },
+            Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE},
+        },
+    };
+
+    use super::super::CREATE_NO_WINDOW;
+
+    pub(super) fn kill_tree(pid: u32) -> io::Result<()> {
+        let status = Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &pid.to_string()])
+            .creation_flags(CREATE_NO_WINDOW)
+            .status()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("taskkill failed to kill the process tree of {pid}"),
+            ))
+        }
+    }
+
+    /// A Job Object killing its processes when it is closed.
+    #[derive(Debug)]
+    pub(crate) struct Job(HANDLE);
+
+    impl Job {
+        /// Creates a job and assigns the process to it.
+        pub(crate) fn assign(pid: u32) -> io::Result<Self> {
+            let job = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+            if job == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let job = Self(job);
+
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            let set = unsafe {
+                SetInformationJobObject(
+                    job.0,
+                    JobObjectExtendedLimitInformation,
+                    &info as *const _ as *const std::ffi::c_void,
+                    std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                )
+            };
+            if set == 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let process = unsafe { OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid) };
+            if process == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let assigned = unsafe { AssignProcessToJobObject(job.0, process) };
+            let error = io::Error::last_os_error();
+            unsafe { CloseHandle(process) };
+            if assigned == 0 {
+                return Err(error);
+            }
+            Ok(job)
+        }
+
+        /// Kills all the processes of the job.
+        pub(crate) fn terminate(&self) -> io::Result<()> {
+            if unsafe { TerminateJobObject(self.0, 1) } == 0 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+}
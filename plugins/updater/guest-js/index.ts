@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: MIT
 
 import { invoke, Channel } from "@tauri-apps/api/tauri";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
 
 interface CheckOptions {
   /**
@@ -28,10 +29,69 @@ interface UpdateResponse {
 }
 
 type DownloadEvent =
-  | { event: "Started"; data: { contentLength?: number } }
+  | {
+      event: "Started";
+      /**
+       * `contentLength` includes the `resumedFrom` bytes downloaded
+       * before the app restarted, `0` for a new download.
+       */
+      data: { contentLength?: number; resumedFrom: number };
+    }
   | { event: "Progress"; data: { chunkLength: number } }
   | { event: "Finished" };
 
+type Stage = "check" | "download" | "verify" | "install";
+
+type LifecycleEvent =
+  | { event: "CheckStarted" }
+  | { event: "UpdateAvailable"; data: { version: string } }
+  | { event: "NoUpdate"; data: { latestVersion?: string } }
+  | {
+      event: "DownloadStarted";
+      data: { contentLength?: number; resumedFrom: number };
+    }
+  | {
+      event: "DownloadProgress";
+      data: {
+        chunkLength: number;
+        downloaded: number;
+        contentLength?: number;
+      };
+    }
+  | { event: "DownloadFinished" }
+  | { event: "VerifyStarted" }
+  | { event: "VerifyFinished" }
+  | { event: "InstallStarted" }
+  | {
+      event: "CodeSignatureChecked";
+      data: { valid: boolean; message?: string };
+    }
+  | { event: "InstallFinished" }
+  | { event: "PostInstallFailed"; data: { message: string } }
+  | { event: "Error"; data: { stage: Stage; message: string } };
+
+type Slot = "a" | "b";
+
+/**
+ * The backup of the bundle replaced by the last update.
+ *
+ * The installed bundle and its backup act as A/B slots: `currentSlot` is
+ * switched by each update and by each rollback. `previousVersion` is the
+ * version restored if the running version fails its health check.
+ */
+interface BackupInfo {
+  currentSlot: Slot;
+  previousVersion?: string;
+  healthCheckPending: boolean;
+  lastRollback?: {
+    fromVersion: string;
+    toVersion: string;
+    reason: "timeout" | "exited";
+    /** Milliseconds since the Unix epoch. */
+    timestamp: number;
+  };
+}
+
 class Update {
   response: UpdateResponse;
 
@@ -58,5 +118,62 @@ async function check(options?: CheckOptions): Promise<Update> {
   );
 }
 
-export type { CheckOptions, UpdateResponse, DownloadEvent };
-export { check, Update };
+/**
+ * Reports the running version healthy, keeping the update.
+ *
+ * With the `health-check` configuration, an update must call this before the
+ * timeout of its first launch, otherwise the previous version is restored and
+ * restarted. Does nothing unless the running version is on trial.
+ *
+ * @example
+ * ```typescript
+ * import { markHealthy } from "@tauri-apps/plugin-updater";
+ * // once the app finished loading
+ * await markHealthy();
+ * ```
+ */
+async function markHealthy(): Promise<void> {
+  return invoke("plugin:updater|mark_healthy");
+}
+
+/**
+ * Gets the current slot, the version kept in the backup and the last rolled
+ * back update, for diagnostics.
+ */
+async function backupInfo(): Promise<BackupInfo> {
+  return invoke("plugin:updater|backup_info");
+}
+
+/**
+ * Listens to the lifecycle events of every update check, download and installation.
+ *
+ * `InstallFinished` is not emitted on Windows, where the application exits to run the installer.
+ *
+ * @example
+ * ```typescript
+ * import { onLifecycle } from "@tauri-apps/plugin-updater";
+ * const unlisten = await onLifecycle((event) => {
+ *   if (event.event === "Error") {
+ *     console.error(`update ${event.data.stage} failed: ${event.data.message}`);
+ *   }
+ * });
+ * ```
+ */
+async function onLifecycle(
+  handler: (event: LifecycleEvent) => void
+): Promise<UnlistenFn> {
+  return listen<LifecycleEvent>("updater://lifecycle", (event) =>
+    handler(event.payload)
+  );
+}
+
+export type {
+  CheckOptions,
+  UpdateResponse,
+  DownloadEvent,
+  LifecycleEvent,
+  Stage,
+  BackupInfo,
+  Slot,
+};
+export { check, onLifecycle, markHealthy, backupInfo, Update };
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::{PendingUpdate, Result, UpdaterExt};
+use crate::{Backup, BackupInfo, PendingUpdate, Result, UpdaterExt};
 
 use http::header;
 use serde::{Deserialize, Deserializer, Serialize};
@@ -104,3 +104,13 @@ pub(crate) async fn download_and_install<R: Runtime>(
     }
     Ok(())
 }
+
+#[tauri::command]
+pub(crate) fn mark_healthy(backup: State<'_, Backup>) -> Result<()> {
+    backup.mark_healthy()
+}
+
+#[tauri::command]
+pub(crate) fn backup_info(backup: State<'_, Backup>) -> BackupInfo {
+    backup.info()
+}
@@ -13,6 +13,66 @@ pub struct Config {
     /// Additional arguments given to the NSIS or WiX installer.
     #[serde(default, alias = "installer-args")]
     pub installer_args: Vec<String>,
+    /// Whether the installed update must pass the operating system code signing checks.
+    #[serde(default, alias = "code-signing")]
+    pub code_signing: CodeSigning,
+    /// Rolls back an update that does not report itself healthy on its first launch.
+    #[serde(default, alias = "health-check")]
+    pub health_check: Option<HealthCheck>,
+    /// TUF-style metadata used to pin the update bundles.
+    #[cfg(feature = "tuf")]
+    #[serde(default)]
+    pub tuf: Option<TufConfig>,
+}
+
+/// Operating system code signature verification of the installed update.
+///
+/// The new macOS bundle must pass `codesign` and the Gatekeeper assessment, otherwise the
+/// previous bundle is restored. The Windows installer must pass `WinVerifyTrust` before it is started.
+/// Has no effect on Linux.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CodeSigning {
+    /// Verify the update only if the running application passes the checks itself,
+    /// so unsigned development builds can still be updated.
+    #[default]
+    Auto,
+    /// Always verify the update.
+    Always,
+    /// Never verify the update.
+    Never,
+}
+
+/// The health check of an update on its first launch.
+///
+/// The new version must call `markHealthy` before the timeout, otherwise the previous
+/// version is restored and restarted. Has no effect on Windows.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheck {
+    /// The seconds the new version has to report itself healthy after it is launched.
+    #[serde(default = "default_health_timeout")]
+    pub timeout: u64,
+}
+
+fn default_health_timeout() -> u64 {
+    60
+}
+
+/// TUF-style metadata configuration.
+///
+/// The `targetsUrl` and `rootUrl` support the same variables as the endpoints.
+#[cfg(feature = "tuf")]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TufConfig {
+    /// The trusted root metadata envelope, shipped with the application.
+    pub root: serde_json::Value,
+    /// URL used to fetch newer root metadata versions.
+    /// `{{version}}` is replaced with the version being looked up.
+    pub root_url: Option<UpdaterEndpoint>,
+    /// URL of the signed targets metadata.
+    pub targets_url: UpdaterEndpoint,
 }
 
 /// A URL to an updater server.
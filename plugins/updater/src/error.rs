@@ -84,6 +84,47 @@ pub enum Error {
     /// Ignore error.
     #[error("failed to walkdir: {0}")]
     Ignore(#[from] ignore::Error),
+    /// Tauri error.
+    #[error(transparent)]
+    Tauri(#[from] tauri::Error),
+    /// A TUF role did not meet its signature threshold.
+    #[cfg(feature = "tuf")]
+    #[error("the `{role}` metadata has {valid} valid signatures but {threshold} are required")]
+    TufThreshold {
+        role: &'static str,
+        valid: usize,
+        threshold: usize,
+    },
+    /// The TUF metadata has expired.
+    #[cfg(feature = "tuf")]
+    #[error("the `{0}` metadata has expired")]
+    TufExpired(&'static str),
+    /// The TUF metadata is older than the last trusted version.
+    #[cfg(feature = "tuf")]
+    #[error("the `{role}` metadata version {version} is older than the trusted version {trusted}")]
+    TufRollback {
+        role: &'static str,
+        version: u64,
+        trusted: u64,
+    },
+    /// The TUF metadata could not be parsed.
+    #[cfg(feature = "tuf")]
+    #[error("invalid `{0}` metadata: {1}")]
+    TufMetadata(&'static str, String),
+    /// The release returned by the endpoint does not match the pinned target.
+    #[cfg(feature = "tuf")]
+    #[error("the release does not match the pinned `{0}` target")]
+    TufTargetMismatch(String),
+    /// The downloaded bundle does not match the pinned length and hash.
+    #[cfg(feature = "tuf")]
+    #[error("the downloaded bundle does not match the pinned length and hash")]
+    TufHashMismatch,
+    /// The installed update does not pass the operating system code signing checks.
+    #[error("the update does not pass the code signing checks: {0}")]
+    InvalidCodeSignature(String),
+    /// The pre-install hook aborted the installation.
+    #[error("the installation was aborted: {0}")]
+    InstallAborted(String),
     /// Zip error.
     #[cfg(windows)]
     #[error(transparent)]
@@ -9,19 +9,26 @@ use tauri::{
 
 use tokio::sync::Mutex;
 
+use std::{fmt::Display, future::Future};
+
 mod commands;
 mod config;
 mod error;
 mod updater;
 
-pub use config::Config;
+#[cfg(feature = "tuf")]
+pub use config::TufConfig;
+pub use config::{CodeSigning, Config, HealthCheck};
 pub use error::Error;
 pub use updater::*;
 pub type Result<T> = std::result::Result<T, Error>;
 
+use updater::InstallHooks;
+
 struct UpdaterState {
     target: Option<String>,
     config: Config,
+    hooks: InstallHooks,
 }
 
 struct PendingUpdate<R: Runtime>(Mutex<Option<UpdateResponse<R>>>);
@@ -30,6 +37,7 @@ struct PendingUpdate<R: Runtime>(Mutex<Option<UpdateResponse<R>>>);
 pub struct Builder {
     target: Option<String>,
     installer_args: Option<Vec<String>>,
+    hooks: InstallHooks,
 }
 
 /// Extension trait to use the updater on [`tauri::App`], [`tauri::AppHandle`] and [`tauri::Window`].
@@ -50,12 +58,31 @@ pub trait UpdaterExt<R: Runtime> {
     ///   });
     /// ```
     fn updater(&self) -> updater::UpdateBuilder<R>;
+
+    /// Gets the backup of the bundle replaced by the last update, e.g. to report the running
+    /// version healthy once it started.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tauri_plugin_updater::UpdaterExt;
+    /// tauri::Builder::default()
+    ///   .setup(|app| {
+    ///     app.updater_backup().mark_healthy()?;
+    ///     Ok(())
+    ///   });
+    /// ```
+    fn updater_backup(&self) -> &Backup;
 }
 
 impl<R: Runtime, T: Manager<R>> UpdaterExt<R> for T {
     fn updater(&self) -> updater::UpdateBuilder<R> {
         updater::builder(self.app_handle())
     }
+
+    fn updater_backup(&self) -> &Backup {
+        self.state::<Backup>().inner()
+    }
 }
 
 impl Builder {
@@ -78,9 +105,54 @@ impl Builder {
         self
     }
 
+    /// Sets a hook awaited right before the update replaces the application,
+    /// e.g. to stop a bundled background service.
+    ///
+    /// Returning an error aborts the installation with [`Error::InstallAborted`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tauri::Builder::default()
+    ///   .plugin(tauri_plugin_updater::Builder::new().pre_install(|update| async move {
+    ///     if update.version.starts_with("3.") {
+    ///       return Err("this release requires a manual migration");
+    ///     }
+    ///     Ok(())
+    ///   }).build());
+    /// ```
+    pub fn pre_install<F, Fut, E>(mut self, hook: F) -> Self
+    where
+        F: Fn(InstallContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = std::result::Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        self.hooks.pre.replace(updater::boxed_hook(hook));
+        self
+    }
+
+    /// Sets a hook awaited once the update is installed, e.g. to run a migration.
+    ///
+    /// The update is kept when the hook fails, and the failure is reported with
+    /// [`LifecycleEvent::PostInstallFailed`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Windows:** Never called, the application exits to run the installer.
+    pub fn post_install<F, Fut, E>(mut self, hook: F) -> Self
+    where
+        F: Fn(InstallContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = std::result::Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        self.hooks.post.replace(updater::boxed_hook(hook));
+        self
+    }
+
     pub fn build<R: Runtime>(self) -> TauriPlugin<R, Config> {
         let target = self.target;
         let installer_args = self.installer_args;
+        let hooks = self.hooks;
         PluginBuilder::<R, Config>::new("updater")
             .js_init_script(include_str!("api-iife.js").to_string())
             .setup(move |app, api| {
@@ -88,13 +160,23 @@ impl Builder {
                 if let Some(installer_args) = installer_args {
                     config.installer_args = installer_args;
                 }
-                app.manage(UpdaterState { target, config });
+                let backup = Backup::load(app, config.health_check.as_ref())?;
+                app.manage(UpdaterState {
+                    target,
+                    config,
+                    hooks,
+                });
                 app.manage(PendingUpdate::<R>(Default::default()));
+                app.manage(backup);
+                // a failed rollback keeps the update rather than preventing the launch
+                let _ = app.updater_backup().launched(app);
                 Ok(())
             })
             .invoke_handler(tauri::generate_handler![
                 commands::check,
-                commands::download_and_install
+                commands::download_and_install,
+                commands::mark_healthy,
+                commands::backup_info
             ])
             .build()
     }
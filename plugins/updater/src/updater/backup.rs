@@ -0,0 +1,481 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The backup of the bundle replaced by an update, restored when the update fails its first launch.
+//!
+//! The update is installed in place, so before it replaces the installed bundle, the bundle is
+//! copied into the app data directory and the update becomes a trial. Once launched, the new
+//! version must report itself healthy with [`Backup::mark_healthy`] before the [`HealthCheck`]
+//! timeout. A trial that times out, or whose first launch ended before reporting, e.g. because
+//! it crashed, is rolled back: the backup is restored and the application restarted.
+//!
+//! The installed bundle and its backup act as A/B slots: each update switches the running
+//! [`Slot`], and a rollback switches it back. The slots are not separate install locations, the
+//! bundle is always launched from its installed path.
+//!
+//! A launch crashing before the plugin is set up can not be detected until the next one.
+//! Windows updates are run by an installer that can not be undone, so no backup is kept there.
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use tauri::{
+    utils::{platform::current_exe, Env},
+    AppHandle, Manager, Runtime,
+};
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::core::extract_path_from_executable;
+use crate::{config::HealthCheck, Result};
+
+const DIR: &str = "updater";
+const STATE_FILENAME: &str = "backup.json";
+const BACKUP_DIR: &str = "backup";
+
+/// The slot of the installed bundle, switched by each update and rollback.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Slot {
+    #[default]
+    A,
+    B,
+}
+
+impl Slot {
+    fn other(self) -> Self {
+        match self {
+            Self::A => Self::B,
+            Self::B => Self::A,
+        }
+    }
+}
+
+/// Why an update was rolled back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RollbackReason {
+    /// The new version did not report itself healthy before the timeout.
+    Timeout,
+    /// The first launch of the new version ended before it reported itself healthy.
+    Exited,
+}
+
+/// A rolled back update.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rollback {
+    /// The version that failed its first launch.
+    pub from_version: String,
+    /// The version that was restored.
+    pub to_version: String,
+    pub reason: RollbackReason,
+    /// When the update was rolled back, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// The state of the backup, returned by [`Backup::info`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    /// The slot of the running bundle.
+    pub current_slot: Slot,
+    /// The version kept in the backup, restored if the running version fails its health check.
+    pub previous_version: Option<String>,
+    /// Whether the running version still has to report itself healthy.
+    pub health_check_pending: bool,
+    /// The last update that was rolled back, if any.
+    pub last_rollback: Option<Rollback>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Trial {
+    version: String,
+    previous_version: String,
+    /// Whether the new version was launched already.
+    launched: bool,
+}
+
+/// What a launch of the app means for the trial.
+#[derive(Debug, PartialEq, Eq)]
+enum Launch {
+    /// No version is on trial.
+    Untested,
+    /// The trial was for another version, e.g. replaced by a manual installation.
+    Replaced,
+    /// The first launch of the version on trial, which starts its health check.
+    Trial,
+    /// The version on trial was launched before without reporting itself healthy.
+    Failed,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct State {
+    #[serde(default)]
+    current_slot: Slot,
+    previous_version: Option<String>,
+    trial: Option<Trial>,
+    last_rollback: Option<Rollback>,
+}
+
+impl State {
+    fn begin_trial(&mut self, version: &str, previous_version: &Version) {
+        self.current_slot = self.current_slot.other();
+        self.previous_version.replace(previous_version.to_string());
+        self.trial.replace(Trial {
+            version: version.into(),
+            previous_version: previous_version.to_string(),
+            launched: false,
+        });
+    }
+
+    fn launch(&mut self, version: &str) -> Launch {
+        let Some(trial) = self.trial.as_mut() else {
+            return Launch::Untested;
+        };
+        if trial.version != version {
+            self.trial = None;
+            Launch::Replaced
+        } else if trial.launched {
+            Launch::Failed
+        } else {
+            trial.launched = true;
+            Launch::Trial
+        }
+    }
+
+    /// Ends the trial, returning whether a version was on trial.
+    fn mark_healthy(&mut self) -> bool {
+        self.trial.take().is_some()
+    }
+
+    /// Records the rollback of the trial, if it is not over.
+    fn roll_back(&mut self, reason: RollbackReason, timestamp: u64) -> Option<&Rollback> {
+        let trial = self.trial.take()?;
+        self.current_slot = self.current_slot.other();
+        self.previous_version = None;
+        self.last_rollback.replace(Rollback {
+            from_version: trial.version,
+            to_version: trial.previous_version,
+            reason,
+            timestamp,
+        });
+        self.last_rollback.as_ref()
+    }
+}
+
+/// The paths and timeout of an enabled health check.
+struct Enabled {
+    dir: PathBuf,
+    /// The installed bundle, e.g. the `.app` directory or the AppImage.
+    bundle: PathBuf,
+    health_timeout: Duration,
+}
+
+/// The backup of the bundle replaced by the last update.
+pub struct Backup {
+    /// `None` when the health check is not configured or not supported.
+    enabled: Option<Enabled>,
+    state: Mutex<State>,
+}
+
+impl Backup {
+    /// Loads the backup state, unless the health check is disabled or not supported.
+    pub(crate) fn load<R: Runtime>(
+        app: &AppHandle<R>,
+        health_check: Option<&HealthCheck>,
+    ) -> Result<Self> {
+        // the macOS bundle and the AppImage are replaced in place, other packages can not be restored
+        let env = app.state::<Env>();
+        #[cfg(target_os = "linux")]
+        let supported = env.appimage.is_some();
+        #[cfg(not(target_os = "linux"))]
+        let supported = cfg!(target_os = "macos");
+        let Some(health_check) = health_check.filter(|_| supported) else {
+            return Ok(Self {
+                enabled: None,
+                state: Default::default(),
+            });
+        };
+
+        let dir = app.path().app_data_dir()?.join(DIR);
+        let bundle = extract_path_from_executable(&env, &current_exe()?);
+        let state = fs::read(dir.join(STATE_FILENAME))
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default();
+        Ok(Self {
+            enabled: Some(Enabled {
+                dir,
+                bundle,
+                health_timeout: Duration::from_secs(health_check.timeout),
+            }),
+            state: Mutex::new(state),
+        })
+    }
+
+    /// The version kept in the backup, and the last rolled back update.
+    pub fn info(&self) -> BackupInfo {
+        let state = self.state.lock().unwrap();
+        BackupInfo {
+            current_slot: state.current_slot,
+            previous_version: state.previous_version.clone(),
+            health_check_pending: state.trial.is_some(),
+            last_rollback: state.last_rollback.clone(),
+        }
+    }
+
+    /// Reports the running version healthy, keeping the update.
+    ///
+    /// Does nothing unless the running version is on trial.
+    pub fn mark_healthy(&self) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.mark_healthy() {
+            self.save(&state)?;
+        }
+        Ok(())
+    }
+
+    /// Copies the installed bundle into the backup, before an update replaces it.
+    pub(crate) fn preserve(&self) -> Result<()> {
+        let Some(enabled) = &self.enabled else {
+            return Ok(());
+        };
+        // only the bundle being replaced is kept
+        remove(&enabled.dir.join(BACKUP_DIR))?;
+        copy_bundle(&enabled.bundle, &enabled.backup_path())
+    }
+
+    /// Records the installed update as a trial.
+    pub(crate) fn begin_trial(&self, version: &str, previous_version: &Version) -> Result<()> {
+        if self.enabled.is_none() {
+            return Ok(());
+        }
+        let mut state = self.state.lock().unwrap();
+        state.begin_trial(version, previous_version);
+        self.save(&state)
+    }
+
+    /// Starts the health check of a version on trial, or rolls back the trial whose first
+    /// launch ended before reporting.
+    pub(crate) fn launched<R: Runtime>(&self, app: &AppHandle<R>) -> Result<()> {
+        let Some(enabled) = &self.enabled else {
+            return Ok(());
+        };
+        let version = app.package_info().version.to_string();
+        let mut state = self.state.lock().unwrap();
+        match state.launch(&version) {
+            Launch::Untested => Ok(()),
+            Launch::Replaced => self.save(&state),
+            Launch::Failed => {
+                drop(state);
+                self.rollback(app, RollbackReason::Exited)
+            }
+            Launch::Trial => {
+                self.save(&state)?;
+                let timeout = enabled.health_timeout;
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(timeout).await;
+                    let backup = app.state::<Backup>();
+                    let _ = backup.rollback(&app, RollbackReason::Timeout);
+                });
+                Ok(())
+            }
+        }
+    }
+
+    /// Restores the backup and restarts it, unless the trial ended.
+    fn rollback<R: Runtime>(&self, app: &AppHandle<R>, reason: RollbackReason) -> Result<()> {
+        let Some(enabled) = &self.enabled else {
+            return Ok(());
+        };
+        let mut state = self.state.lock().unwrap();
+        if state.trial.is_none() {
+            return Ok(());
+        }
+        restore(&enabled.backup_path(), &enabled.bundle)?;
+        remove(&enabled.dir.join(BACKUP_DIR))?;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        state.roll_back(reason, timestamp);
+        self.save(&state)?;
+        drop(state);
+
+        app.restart();
+        Ok(())
+    }
+
+    fn save(&self, state: &State) -> Result<()> {
+        let Some(enabled) = &self.enabled else {
+            return Ok(());
+        };
+        fs::create_dir_all(&enabled.dir)?;
+        fs::write(enabled.dir.join(STATE_FILENAME), serde_json::to_vec(state)?)?;
+        Ok(())
+    }
+}
+
+impl Enabled {
+    fn backup_path(&self) -> PathBuf {
+        let name = self.bundle.file_name().unwrap_or_default();
+        self.dir.join(BACKUP_DIR).join(name)
+    }
+}
+
+/// Copies the bundle, which the backup may keep on another filesystem.
+fn copy_bundle(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // `ditto` keeps the symbolic links and extended attributes the code signature relies on
+    #[cfg(target_os = "macos")]
+    {
+        let status = std::process::Command::new("ditto")
+            .arg(from)
+            .arg(to)
+            .status()?;
+        if !status.success() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("failed to copy {} to {}", from.display(), to.display()),
+            )
+            .into());
+        }
+    }
+    // the AppImage is a single file, copied with its permissions
+    #[cfg(not(target_os = "macos"))]
+    fs::copy(from, to)?;
+
+    Ok(())
+}
+
+/// Replaces the installed bundle with the backup.
+///
+/// The backup is first copied next to the installed bundle, so the bundle is only missing
+/// between two renames.
+fn restore(backup: &Path, bundle: &Path) -> Result<()> {
+    let sibling = |suffix: &str| {
+        let mut name = bundle.file_name().unwrap_or_default().to_os_string();
+        name.push(suffix);
+        bundle.with_file_name(name)
+    };
+    let staged = sibling(".rollback");
+    let failed = sibling(".failed");
+
+    remove(&staged)?;
+    copy_bundle(backup, &staged)?;
+    remove(&failed)?;
+    fs::rename(bundle, &failed)?;
+    if let Err(e) = fs::rename(&staged, bundle) {
+        fs::rename(&failed, bundle)?;
+        return Err(e.into());
+    }
+    remove(&failed)
+}
+
+fn remove(path: &Path) -> Result<()> {
+    let removed = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    match removed {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on_trial() -> State {
+        let mut state = State::default();
+        state.begin_trial("1.1.0", &Version::new(1, 0, 0));
+        state
+    }
+
+    #[test]
+    fn healthy_trial_keeps_the_update() {
+        let mut state = on_trial();
+        assert_eq!(state.launch("1.1.0"), Launch::Trial);
+        assert!(state.mark_healthy());
+        assert!(!state.mark_healthy());
+        assert_eq!(state.launch("1.1.0"), Launch::Untested);
+        assert_eq!(state.previous_version.as_deref(), Some("1.0.0"));
+        assert_eq!(state.current_slot, Slot::B);
+        assert!(state.last_rollback.is_none());
+    }
+
+    #[test]
+    fn relaunched_trial_fails() {
+        let mut state = on_trial();
+        assert_eq!(state.launch("1.1.0"), Launch::Trial);
+        assert_eq!(state.launch("1.1.0"), Launch::Failed);
+
+        let rollback = state.roll_back(RollbackReason::Exited, 42).unwrap();
+        assert_eq!(rollback.from_version, "1.1.0");
+        assert_eq!(rollback.to_version, "1.0.0");
+        assert_eq!(rollback.reason, RollbackReason::Exited);
+        assert_eq!(rollback.timestamp, 42);
+        assert!(state.trial.is_none());
+        assert!(state.previous_version.is_none());
+        assert_eq!(state.current_slot, Slot::A);
+    }
+
+    #[test]
+    fn rollback_after_the_trial_ended_does_nothing() {
+        let mut state = on_trial();
+        state.launch("1.1.0");
+        state.mark_healthy();
+        assert!(state.roll_back(RollbackReason::Timeout, 0).is_none());
+        assert!(state.last_rollback.is_none());
+    }
+
+    #[test]
+    fn replaced_trial_is_cleared() {
+        let mut state = on_trial();
+        assert_eq!(state.launch("2.0.0"), Launch::Replaced);
+        assert!(state.trial.is_none());
+        assert_eq!(state.launch("2.0.0"), Launch::Untested);
+    }
+
+    #[test]
+    fn state_survives_a_restart() {
+        let mut state = on_trial();
+        state.launch("1.1.0");
+        let mut state: State =
+            serde_json::from_slice(&serde_json::to_vec(&state).unwrap()).unwrap();
+        assert_eq!(state.launch("1.1.0"), Launch::Failed);
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn restore_replaces_the_bundle() {
+        let dir = std::env::temp_dir().join(format!("tauri-updater-backup-{}", std::process::id()));
+        let bundle = dir.join("app.AppImage");
+        let backup = dir.join(BACKUP_DIR).join("app.AppImage");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&bundle, "old").unwrap();
+        copy_bundle(&bundle, &backup).unwrap();
+        fs::write(&bundle, "new").unwrap();
+
+        restore(&backup, &bundle).unwrap();
+        assert_eq!(fs::read_to_string(&bundle).unwrap(), "old");
+        assert!(!dir.join("app.AppImage.rollback").exists());
+        assert!(!dir.join("app.AppImage.failed").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Operating system code signature checks of the installed update.
+//!
+//! On macOS the new `.app` bundle must pass `codesign` and the Gatekeeper assessment,
+//! which also covers notarization. On Windows the installer must pass `WinVerifyTrust`
+//! before it is started.
+
+use std::path::Path;
+
+use tauri::{AppHandle, Runtime};
+
+use super::lifecycle::{self, LifecycleEvent};
+use crate::{config::CodeSigning, Error, Result};
+
+/// Checks the code signature of the installed bundle, according to [`CodeSigning`].
+pub(crate) struct CodeSignCheck<'a, R: Runtime> {
+    app: &'a AppHandle<R>,
+    enforce: bool,
+}
+
+impl<'a, R: Runtime> CodeSignCheck<'a, R> {
+    /// `current` is the bundle being replaced, checked with [`CodeSigning::Auto`].
+    pub(crate) fn new(app: &'a AppHandle<R>, mode: CodeSigning, current: &Path) -> Self {
+        let enforce = match mode {
+            CodeSigning::Always => true,
+            CodeSigning::Never => false,
+            CodeSigning::Auto => verify(current).is_ok(),
+        };
+        Self { app, enforce }
+    }
+
+    /// Verifies the code signature of `path`, emitting [`LifecycleEvent::CodeSignatureChecked`].
+    pub(crate) fn check(&self, path: &Path) -> Result<()> {
+        if !self.enforce {
+            return Ok(());
+        }
+
+        let result = verify(path);
+        lifecycle::emit(
+            self.app,
+            LifecycleEvent::CodeSignatureChecked {
+                valid: result.is_ok(),
+                message: result.as_ref().err().cloned(),
+            },
+        );
+        result.map_err(Error::InvalidCodeSignature)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn verify(path: &Path) -> std::result::Result<(), String> {
+    use std::process::Command;
+
+    let run = |command: &mut Command| {
+        let output = command.output().map_err(|e| e.to_string())?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    };
+
+    run(Command::new("codesign")
+        .args(["--verify", "--deep", "--strict"])
+        .arg(path))?;
+    run(Command::new("spctl")
+        .args(["--assess", "--type", "execute"])
+        .arg(path))
+}
+
+#[cfg(windows)]
+fn verify(path: &Path) -> std::result::Result<(), String> {
+    use std::{ffi::c_void, mem, os::windows::ffi::OsStrExt, ptr};
+    use windows_sys::Win32::Security::WinTrust::{
+        WinVerifyTrust, WINTRUST_ACTION_GENERIC_VERIFY_V2, WINTRUST_DATA, WINTRUST_FILE_INFO,
+        WTD_CHOICE_FILE, WTD_REVOKE_NONE, WTD_STATEACTION_CLOSE, WTD_STATEACTION_VERIFY,
+        WTD_UI_NONE,
+    };
+
+    let file_path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut file = WINTRUST_FILE_INFO {
+        cbStruct: mem::size_of::<WINTRUST_FILE_INFO>() as u32,
+        pcwszFilePath: file_path.as_ptr(),
+        hFile: 0,
+        pgKnownSubject: ptr::null_mut(),
+    };
+
+    // SAFETY: all the fields of WINTRUST_DATA are integers, pointers or a union of pointers
+    let mut data: WINTRUST_DATA = unsafe { mem::zeroed() };
+    data.cbStruct = mem::size_of::<WINTRUST_DATA>() as u32;
+    data.dwUIChoice = WTD_UI_NONE;
+    data.fdwRevocationChecks = WTD_REVOKE_NONE;
+    data.dwUnionChoice = WTD_CHOICE_FILE;
+    data.Anonymous.pFile = &mut file;
+    data.dwStateAction = WTD_STATEACTION_VERIFY;
+
+    let mut action = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+    let status = unsafe {
+        WinVerifyTrust(
+            0,
+            &mut action,
+            &mut data as *mut WINTRUST_DATA as *mut c_void,
+        )
+    };
+
+    // release the state allocated by the verification
+    data.dwStateAction = WTD_STATEACTION_CLOSE;
+    unsafe {
+        WinVerifyTrust(
+            0,
+            &mut action,
+            &mut data as *mut WINTRUST_DATA as *mut c_void,
+        )
+    };
+
+    if status == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "WinVerifyTrust rejected {} with status {:#010x}",
+            path.display(),
+            status as u32
+        ))
+    }
+}
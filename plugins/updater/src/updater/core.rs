@@ -7,6 +7,17 @@ use super::{
     extract::{ArchiveFormat, Extract},
     move_file::Move,
 };
+
+use super::backup::Backup;
+#[cfg(any(target_os = "macos", windows))]
+use super::codesign::CodeSignCheck;
+use super::hooks::{InstallContext, InstallHooks};
+use super::lifecycle::{self, LifecycleEvent, Stage};
+use super::partial::{self, Partial};
+#[cfg(feature = "tuf")]
+use super::tuf::{self, TargetMeta};
+#[cfg(feature = "tuf")]
+use crate::config::TufConfig;
 use crate::{Error, Result};
 use base64::Engine;
 use futures_util::StreamExt;
@@ -215,6 +226,8 @@ pub struct UpdateBuilder<R: Runtime> {
     should_install: Option<Box<ShouldInstall>>,
     timeout: Option<Duration>,
     headers: HeaderMap,
+    #[cfg(feature = "tuf")]
+    tuf: Option<TufConfig>,
 }
 
 impl<R: Runtime> fmt::Debug for UpdateBuilder<R> {
@@ -244,6 +257,8 @@ impl<R: Runtime> UpdateBuilder<R> {
             should_install: None,
             timeout: None,
             headers: Default::default(),
+            #[cfg(feature = "tuf")]
+            tuf: None,
         }
     }
 
@@ -319,7 +334,46 @@ impl<R: Runtime> UpdateBuilder<R> {
         Ok(self)
     }
 
-    pub async fn build(mut self) -> Result<Update<R>> {
+    /// Pin the release to the bundle listed on the TUF-style targets metadata.
+    #[cfg(feature = "tuf")]
+    pub fn tuf(mut self, config: TufConfig) -> Self {
+        self.tuf.replace(config);
+        self
+    }
+
+    pub async fn build(self) -> Result<Update<R>> {
+        let app = self.app.clone();
+        lifecycle::emit(&app, LifecycleEvent::CheckStarted);
+        match self.fetch().await {
+            Ok(update) => {
+                lifecycle::emit(
+                    &app,
+                    if update.should_update {
+                        LifecycleEvent::UpdateAvailable {
+                            version: update.version.clone(),
+                        }
+                    } else {
+                        LifecycleEvent::NoUpdate {
+                            latest_version: Some(update.version.clone()),
+                        }
+                    },
+                );
+                Ok(update)
+            }
+            Err(Error::UpToDate) => {
+                lifecycle::emit(
+                    &app,
+                    LifecycleEvent::NoUpdate {
+                        latest_version: None,
+                    },
+                );
+                Err(Error::UpToDate)
+            }
+            Err(e) => Err(lifecycle::failed(&app, Stage::Check, e)),
+        }
+    }
+
+    async fn fetch(mut self) -> Result<Update<R>> {
         let mut remote_release: Option<RemoteRelease> = None;
 
         // make sure we have at least one url
@@ -432,6 +486,33 @@ impl<R: Runtime> UpdateBuilder<R> {
 
         headers.remove("Accept");
 
+        let download_url = final_release.download_url(&json_target)?.to_owned();
+
+        // the release must match the bundle pinned by the signed targets metadata
+        #[cfg(feature = "tuf")]
+        let pinned_target = if let Some(config) = &self.tuf {
+            let current_version = self.current_version.to_string();
+            let pinned = tuf::verify_target(
+                &self.app,
+                config,
+                &json_target,
+                &headers,
+                self.timeout,
+                |url| {
+                    url.replace("{{current_version}}", &current_version)
+                        .replace("{{target}}", &target)
+                        .replace("{{arch}}", arch)
+                },
+            )
+            .await?;
+            if pinned.url != download_url || &pinned.version != final_release.version() {
+                return Err(Error::TufTargetMismatch(json_target));
+            }
+            Some(pinned)
+        } else {
+            None
+        };
+
         // create our new updater
         Ok(Update {
             app: self.app,
@@ -441,13 +522,15 @@ impl<R: Runtime> UpdateBuilder<R> {
             version: final_release.version().to_string(),
             date: final_release.pub_date().cloned(),
             current_version: self.current_version,
-            download_url: final_release.download_url(&json_target)?.to_owned(),
+            download_url,
             body: final_release.notes().cloned(),
             signature: final_release.signature(&json_target)?.to_owned(),
             #[cfg(target_os = "windows")]
             with_elevated_task: final_release.with_elevated_task(&json_target)?,
             timeout: self.timeout,
             headers,
+            #[cfg(feature = "tuf")]
+            pinned_target,
         })
     }
 }
@@ -487,6 +570,9 @@ pub struct Update<R: Runtime> {
     timeout: Option<Duration>,
     /// Request headers
     headers: HeaderMap,
+    /// Bundle pinned by the TUF-style targets metadata
+    #[cfg(feature = "tuf")]
+    pinned_target: Option<TargetMeta>,
 }
 
 impl<R: Runtime> Clone for Update<R> {
@@ -506,6 +592,8 @@ impl<R: Runtime> Clone for Update<R> {
             with_elevated_task: self.with_elevated_task,
             timeout: self.timeout,
             headers: self.headers.clone(),
+            #[cfg(feature = "tuf")]
+            pinned_target: self.pinned_target.clone(),
         }
     }
 }
@@ -515,7 +603,10 @@ impl<R: Runtime> Clone for Update<R> {
 pub enum DownloadEvent {
     #[serde(rename_all = "camelCase")]
     Started {
+        /// The length of the whole bundle, including the bytes downloaded before resuming.
         content_length: Option<u64>,
+        /// The number of bytes downloaded before resuming, `0` for a new download.
+        resumed_from: u64,
     },
     #[serde(rename_all = "camelCase")]
     Progress {
@@ -539,9 +630,129 @@ impl<R: Runtime> Update<R> {
         // anything with it yet
         #[cfg(target_os = "linux")]
         if self.app.state::<Env>().appimage.is_none() {
-            return Err(Error::UnsupportedLinuxPackage);
+            return Err(lifecycle::failed(
+                &self.app,
+                Stage::Install,
+                Error::UnsupportedLinuxPackage,
+            ));
+        }
+
+        let buffer = self
+            .download(on_event)
+            .await
+            .map_err(|e| lifecycle::failed(&self.app, Stage::Download, e))?;
+
+        lifecycle::emit(&self.app, LifecycleEvent::VerifyStarted);
+        let verified = self.verify(buffer, &pub_key);
+        // a complete bundle is not resumed, whether it is valid or not
+        partial::remove(&self.app);
+        let archive_buffer =
+            verified.map_err(|e| lifecycle::failed(&self.app, Stage::Verify, e))?;
+        lifecycle::emit(&self.app, LifecycleEvent::VerifyFinished);
+
+        let context = InstallContext {
+            current_version: self.current_version.clone(),
+            version: self.version.clone(),
+        };
+
+        lifecycle::emit(&self.app, LifecycleEvent::InstallStarted);
+        let pre_install = self.hooks().pre.as_ref().map(|hook| hook(context.clone()));
+        if let Some(pre_install) = pre_install {
+            pre_install.await.map_err(|message| {
+                lifecycle::failed(&self.app, Stage::Install, Error::InstallAborted(message))
+            })?;
+        }
+        let backup = self.app.state::<Backup>();
+        backup
+            .preserve()
+            .and_then(|_| self.install(archive_buffer))
+            .and_then(|_| backup.begin_trial(&self.version, &self.current_version))
+            .map_err(|e| lifecycle::failed(&self.app, Stage::Install, e))?;
+        lifecycle::emit(&self.app, LifecycleEvent::InstallFinished);
+
+        // the update is already in place, so a failing hook must not fail the installation
+        let post_install = self.hooks().post.as_ref().map(|hook| hook(context));
+        if let Some(post_install) = post_install {
+            if let Err(message) = post_install.await {
+                lifecycle::emit(&self.app, LifecycleEvent::PostInstallFailed { message });
+            }
+        }
+
+        // We are done!
+        Ok(())
+    }
+
+    fn hooks(&self) -> &InstallHooks {
+        &self.app.state::<crate::UpdaterState>().inner().hooks
+    }
+
+    /// Downloads the bundle, resuming the partial download of this release if any.
+    async fn download<F: Fn(DownloadEvent)>(&self, on_event: F) -> Result<Vec<u8>> {
+        let mut partial = Partial::open(
+            &self.app,
+            &self.version,
+            &self.download_url,
+            &self.signature,
+        )?;
+
+        let client = ClientBuilder::new().build()?;
+        let mut response = None;
+        if !partial.is_complete() {
+            let mut res = self.request(&client, partial.range_headers()).await?;
+            if res.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+                // the stored bytes do not match the bundle anymore
+                partial.reset()?;
+                res = self.request(&client, HeaderMap::new()).await?;
+            }
+
+            // make sure it's success
+            if !res.status().is_success() {
+                return Err(Error::DownloadFailed(res.status()));
+            }
+
+            partial.start(res.status(), res.headers())?;
+            response.replace(res);
         }
 
+        let resumed_from = partial.offset();
+        let content_length = partial.content_length();
+
+        on_event(DownloadEvent::Started {
+            content_length,
+            resumed_from,
+        });
+        lifecycle::emit(
+            &self.app,
+            LifecycleEvent::DownloadStarted {
+                content_length,
+                resumed_from,
+            },
+        );
+
+        if let Some(response) = response {
+            let mut progress = lifecycle::Progress::new(content_length);
+            let mut stream = response.bytes_stream();
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk?;
+                let chunk_length = chunk.len();
+                partial.write(&chunk)?;
+                on_event(DownloadEvent::Progress { chunk_length });
+                progress.chunk(&self.app, chunk_length, partial.offset());
+            }
+            progress.flush(&self.app, partial.offset());
+        }
+
+        on_event(DownloadEvent::Finished);
+        lifecycle::emit(&self.app, LifecycleEvent::DownloadFinished);
+
+        partial.into_bytes()
+    }
+
+    async fn request(
+        &self,
+        client: &reqwest::Client,
+        range_headers: HeaderMap,
+    ) -> Result<reqwest::Response> {
         // set our headers
         let mut headers = self.headers.clone();
         headers.insert(
@@ -552,8 +763,8 @@ impl<R: Runtime> Update<R> {
             "User-Agent",
             HeaderValue::from_str("tauri/updater").unwrap(),
         );
+        headers.extend(range_headers);
 
-        let client = ClientBuilder::new().build()?;
         // Create our request
         let mut req = client
             .request(Method::GET, self.download_url.clone())
@@ -562,48 +773,43 @@ impl<R: Runtime> Update<R> {
             req = req.timeout(timeout);
         }
 
-        let response = req.send().await?;
-
-        // make sure it's success
-        if !response.status().is_success() {
-            return Err(Error::DownloadFailed(response.status()));
-        }
-
-        let content_length: Option<u64> = response
-            .headers()
-            .get("Content-Length")
-            .and_then(|value| value.to_str().ok())
-            .and_then(|value| value.parse().ok());
-
-        on_event(DownloadEvent::Started { content_length });
-
-        let mut buffer = Vec::new();
+        req.send().await.map_err(Into::into)
+    }
 
-        let mut stream = response.bytes_stream();
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-            let bytes = chunk.as_ref().to_vec();
-            on_event(DownloadEvent::Progress {
-                chunk_length: bytes.len(),
-            });
-            buffer.extend(bytes);
+    fn verify(&self, buffer: Vec<u8>, pub_key: &str) -> Result<Cursor<Vec<u8>>> {
+        #[cfg(feature = "tuf")]
+        if let Some(pinned_target) = &self.pinned_target {
+            pinned_target.verify(&buffer)?;
         }
 
-        on_event(DownloadEvent::Finished);
-
         // create memory buffer from our archive (Seek + Read)
         let mut archive_buffer = Cursor::new(buffer);
 
         // We need an announced signature by the server
         // if there is no signature, bail out.
-        verify_signature(&mut archive_buffer, &self.signature, &pub_key)?;
+        verify_signature(&mut archive_buffer, &self.signature, pub_key)?;
 
+        Ok(archive_buffer)
+    }
+
+    #[cfg_attr(not(desktop), allow(unused_variables, clippy::unnecessary_wraps))]
+    fn install(&self, archive_buffer: Cursor<Vec<u8>>) -> Result<()> {
         // TODO: implement updater in mobile
         #[cfg(desktop)]
         {
             // we copy the files depending of the operating system
             // we run the setup, appimage re-install or overwrite the
             // macos .app
+            #[cfg(any(target_os = "macos", windows))]
+            let code_sign = {
+                let mode = self.app.state::<crate::UpdaterState>().config.code_signing;
+                #[cfg(windows)]
+                let current = current_exe()?;
+                #[cfg(target_os = "macos")]
+                let current = self.extract_path.clone();
+                CodeSignCheck::new(&self.app, mode, &current)
+            };
+
             #[cfg(target_os = "windows")]
             copy_files_and_run(
                 archive_buffer,
@@ -611,12 +817,14 @@ impl<R: Runtime> Update<R> {
                 self.with_elevated_task,
                 &self.app.config(),
                 &self.app.state::<crate::UpdaterState>().config,
+                &code_sign,
             )?;
-            #[cfg(not(target_os = "windows"))]
+            #[cfg(target_os = "macos")]
+            copy_files_and_run(archive_buffer, &self.extract_path, &code_sign)?;
+            #[cfg(target_os = "linux")]
             copy_files_and_run(archive_buffer, &self.extract_path)?;
         }
 
-        // We are done!
         Ok(())
     }
 }
@@ -703,14 +911,17 @@ fn copy_files_and_run<R: Read + Seek>(archive_buffer: R, extract_path: &Path) ->
 //
 // ## EXE
 // Update server can provide a custom EXE (installer) who can run any task.
+//
+// The installer must pass the code signing checks before it is started.
 #[cfg(target_os = "windows")]
 #[allow(clippy::unnecessary_wraps)]
-fn copy_files_and_run<R: Read + Seek>(
+fn copy_files_and_run<R: Read + Seek, T: Runtime>(
     archive_buffer: R,
     _extract_path: &Path,
     with_elevated_task: bool,
     config: &tauri::Config,
     updater_config: &crate::Config,
+    code_sign: &CodeSignCheck<'_, T>,
 ) -> Result<()> {
     // FIXME: We need to create a memory buffer with the MSI and then run it.
     //        (instead of extracting the MSI to a temp path)
@@ -732,6 +943,11 @@ fn copy_files_and_run<R: Read + Seek>(
 
     for path in paths {
         let found_path = path?.path();
+        let is_installer =
+            [Some(OsStr::new("exe")), Some(OsStr::new("msi"))].contains(&found_path.extension());
+        if is_installer {
+            code_sign.check(&found_path)?;
+        }
         // we support 2 type of files exe & msi for now
         // If it's an `exe` we expect an installer not a runtime.
         if found_path.extension() == Some(OsStr::new("exe")) {
@@ -861,8 +1077,14 @@ fn copy_files_and_run<R: Read + Seek>(
 // │      └── Contents                          # Application contents...
 // │          └── ...
 // └── ...
+//
+// The new bundle must pass the code signing checks, otherwise the previous one is restored.
 #[cfg(target_os = "macos")]
-fn copy_files_and_run<R: Read + Seek>(archive_buffer: R, extract_path: &Path) -> Result<()> {
+fn copy_files_and_run<R: Read + Seek, T: Runtime>(
+    archive_buffer: R,
+    extract_path: &Path,
+    code_sign: &CodeSignCheck<'_, T>,
+) -> Result<()> {
     let mut extracted_files: Vec<PathBuf> = Vec::new();
 
     // extract the buffer to the tmp_dir
@@ -904,6 +1126,12 @@ fn copy_files_and_run<R: Read + Seek>(archive_buffer: R, extract_path: &Path) ->
         Ok(false)
     })?;
 
+    if let Err(err) = code_sign.check(extract_path) {
+        std::fs::remove_dir_all(extract_path)?;
+        Move::from_source(tmp_dir.path()).to_dest(extract_path)?;
+        return Err(err);
+    }
+
     let _ = std::process::Command::new("touch")
         .arg(extract_path)
         .status();
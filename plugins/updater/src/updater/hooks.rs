@@ -0,0 +1,43 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Application hooks awaited around the installation of an update.
+
+use std::{fmt::Display, future::Future};
+
+use futures_util::future::{BoxFuture, FutureExt};
+use semver::Version;
+
+/// The update being installed, passed to the install hooks.
+#[derive(Debug, Clone)]
+pub struct InstallContext {
+    /// The version of the running application.
+    pub current_version: Version,
+    /// The version being installed.
+    pub version: String,
+}
+
+pub(crate) type InstallHook =
+    Box<dyn Fn(InstallContext) -> BoxFuture<'static, Result<(), String>> + Send + Sync>;
+
+/// The hooks registered with [`Builder::pre_install`](crate::Builder::pre_install)
+/// and [`Builder::post_install`](crate::Builder::post_install).
+#[derive(Default)]
+pub(crate) struct InstallHooks {
+    pub(crate) pre: Option<InstallHook>,
+    pub(crate) post: Option<InstallHook>,
+}
+
+pub(crate) fn boxed<F, Fut, E>(hook: F) -> InstallHook
+where
+    F: Fn(InstallContext) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), E>> + Send + 'static,
+    E: Display,
+{
+    Box::new(move |context| {
+        hook(context)
+            .map(|result| result.map_err(|e| e.to_string()))
+            .boxed()
+    })
+}
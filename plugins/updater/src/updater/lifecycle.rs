@@ -0,0 +1,143 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::Error;
+
+use std::time::{Duration, Instant};
+
+/// Event emitted to all windows on each [`LifecycleEvent`].
+pub const LIFECYCLE_EVENT: &str = "updater://lifecycle";
+
+/// The minimum delay between two [`LifecycleEvent::DownloadProgress`] events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A phase of the update process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Stage {
+    /// Fetching and validating the release metadata.
+    Check,
+    /// Downloading the update bundle.
+    Download,
+    /// Verifying the bundle signature.
+    Verify,
+    /// Installing the update bundle.
+    Install,
+}
+
+/// An update lifecycle event, emitted with [`LIFECYCLE_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data")]
+pub enum LifecycleEvent {
+    CheckStarted,
+    #[serde(rename_all = "camelCase")]
+    UpdateAvailable {
+        version: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    NoUpdate {
+        /// The latest version of the remote release, if the server returned one.
+        latest_version: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    DownloadStarted {
+        content_length: Option<u64>,
+        /// The number of bytes downloaded before resuming, `0` for a new download.
+        resumed_from: u64,
+    },
+    /// Emitted at most every 100 milliseconds, `chunk_length` being the bytes received since
+    /// the previous progress event.
+    #[serde(rename_all = "camelCase")]
+    DownloadProgress {
+        chunk_length: usize,
+        downloaded: u64,
+        content_length: Option<u64>,
+    },
+    DownloadFinished,
+    VerifyStarted,
+    VerifyFinished,
+    InstallStarted,
+    /// The operating system code signature of the installed update was checked.
+    #[serde(rename_all = "camelCase")]
+    CodeSignatureChecked {
+        valid: bool,
+        message: Option<String>,
+    },
+    /// Not emitted on Windows, where the application exits to run the installer.
+    InstallFinished,
+    /// The post-install hook failed. The update is still installed.
+    #[serde(rename_all = "camelCase")]
+    PostInstallFailed {
+        message: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Error {
+        stage: Stage,
+        message: String,
+    },
+}
+
+pub(crate) fn emit<R: Runtime>(app: &AppHandle<R>, event: LifecycleEvent) {
+    let _ = app.emit_all(LIFECYCLE_EVENT, event);
+}
+
+/// Emits [`LifecycleEvent::Error`] for the failed stage and returns the error back.
+pub(crate) fn failed<R: Runtime>(app: &AppHandle<R>, stage: Stage, error: Error) -> Error {
+    emit(
+        app,
+        LifecycleEvent::Error {
+            stage,
+            message: error.to_string(),
+        },
+    );
+    error
+}
+
+/// Batches the downloaded chunks into [`LifecycleEvent::DownloadProgress`] events, so a fast
+/// download does not flood every window with events.
+pub(crate) struct Progress {
+    content_length: Option<u64>,
+    pending: usize,
+    last_emit: Option<Instant>,
+}
+
+impl Progress {
+    pub fn new(content_length: Option<u64>) -> Self {
+        Self {
+            content_length,
+            pending: 0,
+            last_emit: None,
+        }
+    }
+
+    /// Records a chunk, emitting the progress if the last event is old enough.
+    pub fn chunk<R: Runtime>(&mut self, app: &AppHandle<R>, chunk_length: usize, downloaded: u64) {
+        self.pending += chunk_length;
+        if self
+            .last_emit
+            .map_or(true, |last| last.elapsed() >= PROGRESS_INTERVAL)
+        {
+            self.flush(app, downloaded);
+        }
+    }
+
+    /// Emits the progress of the chunks received since the last event, if any.
+    pub fn flush<R: Runtime>(&mut self, app: &AppHandle<R>, downloaded: u64) {
+        if self.pending == 0 {
+            return;
+        }
+        emit(
+            app,
+            LifecycleEvent::DownloadProgress {
+                chunk_length: std::mem::take(&mut self.pending),
+                downloaded,
+                content_length: self.content_length,
+            },
+        );
+        self.last_emit.replace(Instant::now());
+    }
+}
@@ -11,9 +11,17 @@
 //! Check [`UpdateBuilder`] to see how to trigger and customize the updater at runtime.
 //! ```
 
+mod backup;
+#[cfg(any(target_os = "macos", windows))]
+mod codesign;
 mod core;
 mod extract;
+mod hooks;
+mod lifecycle;
 mod move_file;
+mod partial;
+#[cfg(feature = "tuf")]
+mod tuf;
 
 use std::time::Duration;
 
@@ -21,7 +29,11 @@ use http::header::{HeaderName, HeaderValue};
 use semver::Version;
 use time::OffsetDateTime;
 
+pub use self::backup::{Backup, BackupInfo, Rollback, RollbackReason, Slot};
 pub use self::core::{DownloadEvent, RemoteRelease};
+pub use self::hooks::InstallContext;
+pub(crate) use self::hooks::{boxed as boxed_hook, InstallHooks};
+pub use self::lifecycle::{LifecycleEvent, Stage, LIFECYCLE_EVENT};
 
 use tauri::{AppHandle, Manager, Runtime};
 
@@ -208,7 +220,7 @@ impl<R: Runtime> UpdateBuilder<R> {
     ///           if update.is_update_available() {
     ///             update.download_and_install(|event| {
     ///                 match event {
-    ///                     DownloadEvent::Started { content_length } => println!("started! size: {:?}", content_length),
+    ///                     DownloadEvent::Started { content_length, .. } => println!("started! size: {:?}", content_length),
     ///                     DownloadEvent::Progress { chunk_length } => println!("Downloaded {chunk_length} bytes"),
     ///                     DownloadEvent::Finished => println!("download finished"),
     ///                 }
@@ -301,8 +313,13 @@ pub fn builder<R: Runtime>(handle: AppHandle<R>) -> UpdateBuilder<R> {
     let mut builder = self::core::builder(handle.clone())
         .urls(&endpoints[..])
         .current_version(package_info.version);
-    if let Some(target) = &handle.state::<crate::UpdaterState>().target {
+    let state = handle.state::<UpdaterState>();
+    if let Some(target) = &state.target {
         builder = builder.target(target);
     }
+    #[cfg(feature = "tuf")]
+    if let Some(tuf) = &state.config.tuf {
+        builder = builder.tuf(tuf.clone());
+    }
     UpdateBuilder { inner: builder }
 }
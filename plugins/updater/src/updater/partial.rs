@@ -0,0 +1,198 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Partial update downloads, resumed across restarts with HTTP range requests.
+//!
+//! The bundle is written to the app cache directory as it is downloaded, next to a metadata
+//! file with the release it belongs to and its expected length. A download of the same
+//! release resumes from the stored bytes, while a different version, URL or signature
+//! discards them. Once complete, the bundle is verified and removed, valid or not.
+
+use http::{header, HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+use url::Url;
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use crate::Result;
+
+const DIR: &str = "updater";
+const BUNDLE_FILENAME: &str = "update.part";
+const METADATA_FILENAME: &str = "update.part.json";
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Release {
+    version: String,
+    url: Url,
+    signature: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Metadata {
+    release: Release,
+    /// The length of the whole bundle, if the server sent it.
+    content_length: Option<u64>,
+    /// The `ETag` or `Last-Modified` of the bundle, sent in `If-Range` so a changed
+    /// bundle is sent again from the start.
+    validator: Option<String>,
+}
+
+/// The bundle being downloaded.
+pub(crate) struct Partial {
+    dir: PathBuf,
+    metadata: Metadata,
+    file: File,
+    /// The number of bytes stored.
+    offset: u64,
+}
+
+impl Partial {
+    /// Opens the partial download of the release, discarding the one of another release.
+    pub(crate) fn open<R: Runtime>(
+        app: &AppHandle<R>,
+        version: &str,
+        url: &Url,
+        signature: &str,
+    ) -> Result<Self> {
+        let dir = dir(app)?;
+        fs::create_dir_all(&dir)?;
+
+        let release = Release {
+            version: version.into(),
+            url: url.clone(),
+            signature: signature.into(),
+        };
+        let stored = fs::read(dir.join(METADATA_FILENAME))
+            .ok()
+            .and_then(|data| serde_json::from_slice::<Metadata>(&data).ok())
+            .filter(|metadata| metadata.release == release);
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(BUNDLE_FILENAME))?;
+        let mut partial = Self {
+            dir,
+            metadata: Metadata {
+                release,
+                content_length: None,
+                validator: None,
+            },
+            offset: file.metadata()?.len(),
+            file,
+        };
+        match stored {
+            Some(metadata) => partial.metadata = metadata,
+            None => partial.reset()?,
+        }
+        Ok(partial)
+    }
+
+    /// The number of bytes already downloaded.
+    pub(crate) fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The length of the whole bundle, if known.
+    pub(crate) fn content_length(&self) -> Option<u64> {
+        self.metadata.content_length
+    }
+
+    /// Whether the whole bundle was downloaded.
+    pub(crate) fn is_complete(&self) -> bool {
+        self.offset > 0 && self.metadata.content_length == Some(self.offset)
+    }
+
+    /// The headers requesting the missing bytes of the bundle.
+    pub(crate) fn range_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if self.offset > 0 {
+            if let Ok(range) = format!("bytes={}-", self.offset).parse() {
+                headers.insert(header::RANGE, range);
+            }
+            if let Some(validator) = self
+                .metadata
+                .validator
+                .as_ref()
+                .and_then(|v| v.parse().ok())
+            {
+                headers.insert(header::IF_RANGE, validator);
+            }
+        }
+        headers
+    }
+
+    /// Discards the downloaded bytes.
+    pub(crate) fn reset(&mut self) -> Result<()> {
+        self.file.set_len(0)?;
+        self.offset = 0;
+        self.metadata.content_length = None;
+        self.metadata.validator = None;
+        Ok(())
+    }
+
+    /// Records the bundle length and validator from the response headers, starting over
+    /// unless the server sent the requested range.
+    pub(crate) fn start(&mut self, status: StatusCode, headers: &HeaderMap) -> Result<()> {
+        let resumed = self.offset > 0 && status == StatusCode::PARTIAL_CONTENT;
+        if !resumed {
+            self.reset()?;
+        }
+
+        let value = |name| headers.get(name).and_then(|v| v.to_str().ok());
+        let content_length = value(header::CONTENT_LENGTH).and_then(|v| v.parse::<u64>().ok());
+        self.metadata.content_length = if resumed {
+            // `Content-Range: bytes <start>-<end>/<length>`
+            value(header::CONTENT_RANGE)
+                .and_then(|v| v.rsplit_once('/'))
+                .and_then(|(_, length)| length.parse().ok())
+                .or_else(|| content_length.map(|length| self.offset + length))
+        } else {
+            content_length
+        };
+        // weak validators can not be used in `If-Range`
+        self.metadata.validator = value(header::ETAG)
+            .filter(|etag| !etag.starts_with("W/"))
+            .or_else(|| value(header::LAST_MODIFIED))
+            .map(Into::into);
+
+        fs::write(
+            self.dir.join(METADATA_FILENAME),
+            serde_json::to_vec(&self.metadata)?,
+        )?;
+        Ok(())
+    }
+
+    /// Appends a chunk of the bundle.
+    pub(crate) fn write(&mut self, chunk: &[u8]) -> Result<()> {
+        self.file.write_all(chunk)?;
+        self.offset += chunk.len() as u64;
+        Ok(())
+    }
+
+    /// Reads the downloaded bundle.
+    pub(crate) fn into_bytes(self) -> Result<Vec<u8>> {
+        self.file.sync_all()?;
+        fs::read(self.dir.join(BUNDLE_FILENAME)).map_err(Into::into)
+    }
+}
+
+/// Removes the partial download, if any.
+pub(crate) fn remove<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(dir) = dir(app) {
+        let _ = fs::remove_file(dir.join(METADATA_FILENAME));
+        let _ = fs::remove_file(dir.join(BUNDLE_FILENAME));
+    }
+}
+
+fn dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf> {
+    Ok(app.path().app_cache_dir()?.join(DIR))
+}
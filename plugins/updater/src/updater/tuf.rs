@@ -0,0 +1,342 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! TUF-style metadata pinning.
+//!
+//! A root role shipped with the application lists the keys and signature thresholds
+//! for the `root` and `targets` roles. The signed targets metadata pins the URL, length,
+//! SHA-256 hash and version of each platform bundle, so a compromised update server can
+//! not serve a different bundle, a stale manifest or roll the application back.
+//!
+//! Every metadata file is an envelope with a base64 encoded JSON payload
+//! and a list of minisign signatures over the decoded payload:
+//!
+//! ```json
+//! {
+//!   "signed": "eyJ2ZXJzaW9uIjoxLC4uLn0=",
+//!   "signatures": [{ "keyid": "release-1", "sig": "dW50cnVzdGVkIGNvbW1lbnQ6..." }]
+//! }
+//! ```
+
+use base64::Engine;
+use http::{HeaderMap, Method, StatusCode};
+use reqwest::ClientBuilder;
+use semver::Version;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager, Runtime};
+use time::OffsetDateTime;
+use url::Url;
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{create_dir_all, read, write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use super::core::verify_signature;
+use crate::{
+    config::{TufConfig, UpdaterEndpoint},
+    Error, Result,
+};
+
+const STATE_FILENAME: &str = ".updater-tuf.json";
+
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    signed: String,
+    signatures: Vec<KeySignature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeySignature {
+    keyid: String,
+    sig: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Role {
+    keyids: Vec<String>,
+    threshold: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct Roles {
+    root: Role,
+    targets: Role,
+}
+
+#[derive(Debug, Deserialize)]
+struct Root {
+    version: u64,
+    #[serde(deserialize_with = "parse_expires")]
+    expires: OffsetDateTime,
+    /// Minisign public keys, indexed by key id.
+    keys: HashMap<String, String>,
+    roles: Roles,
+}
+
+#[derive(Debug, Deserialize)]
+struct Targets {
+    version: u64,
+    #[serde(deserialize_with = "parse_expires")]
+    expires: OffsetDateTime,
+    targets: HashMap<String, TargetMeta>,
+}
+
+/// A bundle pinned by the targets metadata.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TargetMeta {
+    pub url: Url,
+    pub length: u64,
+    pub sha256: String,
+    #[serde(deserialize_with = "parse_version")]
+    pub version: Version,
+}
+
+impl TargetMeta {
+    /// Checks the downloaded bundle against the pinned length and hash.
+    pub fn verify(&self, data: &[u8]) -> Result<()> {
+        let hash = format!("{:x}", Sha256::digest(data));
+        if data.len() as u64 != self.length || !hash.eq_ignore_ascii_case(&self.sha256) {
+            return Err(Error::TufHashMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// The highest metadata versions seen so far, persisted to prevent rollbacks.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct TrustedState {
+    root: u64,
+    targets: u64,
+}
+
+fn parse_expires<'de, D>(deserializer: D) -> std::result::Result<OffsetDateTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let date = String::deserialize(deserializer)?;
+    OffsetDateTime::parse(&date, &time::format_description::well_known::Rfc3339)
+        .map_err(|e| serde::de::Error::custom(format!("invalid value for `expires`: {e}")))
+}
+
+fn parse_version<'de, D>(deserializer: D) -> std::result::Result<Version, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let str = String::deserialize(deserializer)?;
+    Version::from_str(str.trim_start_matches('v')).map_err(serde::de::Error::custom)
+}
+
+/// Verifies that `envelope` is signed by at least `role.threshold` distinct keys of the role,
+/// and returns its decoded payload.
+fn verify_role<T: DeserializeOwned>(
+    name: &'static str,
+    envelope: &Envelope,
+    role: &Role,
+    keys: &HashMap<String, String>,
+) -> Result<T> {
+    let payload = base64::engine::general_purpose::STANDARD.decode(&envelope.signed)?;
+
+    let mut valid = HashSet::new();
+    for signature in &envelope.signatures {
+        if !role.keyids.contains(&signature.keyid) || valid.contains(&signature.keyid) {
+            continue;
+        }
+        if let Some(key) = keys.get(&signature.keyid) {
+            if verify_signature(&mut payload.as_slice(), &signature.sig, key).is_ok() {
+                valid.insert(signature.keyid.clone());
+            }
+        }
+    }
+
+    // a zero threshold would trust unsigned metadata
+    if role.threshold == 0 || valid.len() < role.threshold {
+        return Err(Error::TufThreshold {
+            role: name,
+            valid: valid.len(),
+            threshold: role.threshold,
+        });
+    }
+
+    serde_json::from_slice(&payload).map_err(|e| Error::TufMetadata(name, e.to_string()))
+}
+
+fn check_expiration(name: &'static str, expires: &OffsetDateTime) -> Result<()> {
+    if OffsetDateTime::now_utc() > *expires {
+        Err(Error::TufExpired(name))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_rollback(name: &'static str, version: u64, trusted: u64) -> Result<()> {
+    if version < trusted {
+        Err(Error::TufRollback {
+            role: name,
+            version,
+            trusted,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Fetches a metadata envelope, returning `None` if the server responds with `404`.
+async fn fetch(
+    url: &str,
+    headers: &HeaderMap,
+    timeout: Option<Duration>,
+) -> Result<Option<Envelope>> {
+    let client = ClientBuilder::new().build()?;
+    let mut request = client.request(Method::GET, url).headers(headers.clone());
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+
+    let response = request.send().await?;
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(Error::DownloadFailed(status));
+    }
+
+    let data = response.bytes().await?;
+    Ok(Some(serde_json::from_slice(&data)?))
+}
+
+fn state_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf> {
+    Ok(app.path().app_data_dir()?.join(STATE_FILENAME))
+}
+
+fn load_state(path: &Path) -> TrustedState {
+    read(path)
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+/// The URL of a metadata file with its variables replaced by `resolve_url`.
+///
+/// The `Url` percent-encodes the braces of the variables, so they are decoded first.
+fn metadata_url<F: Fn(&str) -> String>(endpoint: &UpdaterEndpoint, resolve_url: &F) -> String {
+    let url = percent_encoding::percent_decode(endpoint.0.as_str().as_bytes()).decode_utf8_lossy();
+    resolve_url(&url)
+}
+
+/// Walks the root chain from the pinned root, verifies the targets metadata
+/// and returns the bundle pinned for `json_target`.
+///
+/// `resolve_url` replaces the endpoint variables in the configured metadata URLs.
+pub async fn verify_target<R: Runtime, F: Fn(&str) -> String>(
+    app: &AppHandle<R>,
+    config: &TufConfig,
+    json_target: &str,
+    headers: &HeaderMap,
+    timeout: Option<Duration>,
+    resolve_url: F,
+) -> Result<TargetMeta> {
+    let path = state_path(app)?;
+    let mut state = load_state(&path);
+
+    // the pinned root must be signed by its own root role
+    let pinned: Envelope = serde_json::from_value(config.root.clone())
+        .map_err(|e| Error::TufMetadata("root", e.to_string()))?;
+    let mut root: Root = {
+        let unverified: Root = serde_json::from_slice(
+            &base64::engine::general_purpose::STANDARD.decode(&pinned.signed)?,
+        )
+        .map_err(|e| Error::TufMetadata("root", e.to_string()))?;
+        verify_role("root", &pinned, &unverified.roles.root, &unverified.keys)?
+    };
+
+    // a new root must be signed by both the previous and its own root role
+    if let Some(root_url) = &config.root_url {
+        let root_url = metadata_url(root_url, &resolve_url);
+        loop {
+            let next_version = root.version + 1;
+            let url = root_url.replace("{{version}}", &next_version.to_string());
+            let Some(envelope) = fetch(&url, headers, timeout).await? else {
+                break;
+            };
+            let _: Root = verify_role("root", &envelope, &root.roles.root, &root.keys)?;
+            let unverified: Root = serde_json::from_slice(
+                &base64::engine::general_purpose::STANDARD.decode(&envelope.signed)?,
+            )
+            .map_err(|e| Error::TufMetadata("root", e.to_string()))?;
+            let next: Root =
+                verify_role("root", &envelope, &unverified.roles.root, &unverified.keys)?;
+            if next.version != next_version {
+                return Err(Error::TufMetadata(
+                    "root",
+                    format!("expected version {next_version} but found {}", next.version),
+                ));
+            }
+            root = next;
+        }
+    }
+
+    check_rollback("root", root.version, state.root)?;
+    check_expiration("root", &root.expires)?;
+
+    let envelope = fetch(
+        &metadata_url(&config.targets_url, &resolve_url),
+        headers,
+        timeout,
+    )
+    .await?
+    .ok_or(Error::DownloadFailed(StatusCode::NOT_FOUND))?;
+    let mut targets: Targets = verify_role("targets", &envelope, &root.roles.targets, &root.keys)?;
+
+    check_rollback("targets", targets.version, state.targets)?;
+    check_expiration("targets", &targets.expires)?;
+
+    let target = targets
+        .targets
+        .remove(json_target)
+        .ok_or_else(|| Error::TargetNotFound(json_target.to_string()))?;
+
+    state.root = root.version;
+    state.targets = targets.version;
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    write(&path, serde_json::to_vec(&state)?)?;
+
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(url: &str) -> String {
+        url.replace("{{target}}", "linux")
+            .replace("{{arch}}", "x86_64")
+    }
+
+    #[test]
+    fn metadata_url_variables() {
+        let root_url = UpdaterEndpoint(
+            Url::parse("https://example.com/{{target}}/root/{{version}}.json").unwrap(),
+        );
+        assert_eq!(
+            metadata_url(&root_url, &resolve),
+            "https://example.com/linux/root/{{version}}.json"
+        );
+
+        let targets_url = UpdaterEndpoint(
+            Url::parse("https://example.com/targets.json?target={{target}}&arch={{arch}}").unwrap(),
+        );
+        assert_eq!(
+            metadata_url(&targets_url, &resolve),
+            "https://example.com/targets.json?target=linux&arch=x86_64"
+        );
+    }
+}
@@ -5,62 +5,344 @@
 import { invoke, Channel } from "@tauri-apps/api/tauri";
 
 interface ProgressPayload {
+  /** The ID of the transfer that reported this progress. */
+  transferId: number;
+  /** The transfer URL. */
+  url: string;
+  /** The path of the file being transferred. */
+  filePath: string;
   progress: number;
   total: number;
+  /**
+   * The retry of the download the event belongs to, `0` for the first attempt.
+   * Every retry starts with an event without progress, after which the bytes resumed from the failed attempts are reported again.
+   */
+  attempt: number;
+  /** Whether the transfer was cancelled with {@link cancel}, set on its last event only. */
+  cancelled: boolean;
+  /** Whether the download completed, set on its last event only. */
+  completed: boolean;
+}
+
+interface DownloadResult {
+  /** The length of the file, counting the resumed bytes and the chunks written to it. */
+  bytesWritten: number;
+  /** The HTTP status of the response, `200` for `file://` and `data:` URLs. */
+  status: number;
+  /** The headers of the HTTP response, e.g. its `ETag`. */
+  headers: Record<string, string>;
+  /** The hex encoded SHA-256 digest of the file, counting the resumed bytes. */
+  sha256: string;
 }
 
 type ProgressHandler = (progress: ProgressPayload) => void;
 
-async function upload(
+/// Upload the file as the body of a `POST` request.
+/// Resolves with the JSON response of the server.
+///
+/// With `progressIntervalMs`, the progress of the chunks sent within the interval is summed in a single event.
+/// The `timeoutMs` overrides the timeout of the whole request configured on the Rust side.
+async function upload<T = unknown>(
   url: string,
   filePath: string,
   progressHandler?: ProgressHandler,
-  headers?: Map<string, string>
-): Promise<void> {
+  headers?: Map<string, string>,
+  transferId?: number,
+  progressIntervalMs?: number,
+  timeoutMs?: number
+): Promise<T> {
   const ids = new Uint32Array(1);
   window.crypto.getRandomValues(ids);
-  const id = ids[0];
+  const id = transferId ?? ids[0];
 
   const onProgress = new Channel<ProgressPayload>();
   if (progressHandler != null) {
     onProgress.onmessage = progressHandler;
   }
 
-  await invoke("plugin:upload|upload", {
-    id,
+  return await invoke<T>("plugin:upload|upload", {
+    transferId: id,
     url,
     filePath,
     headers: headers ?? {},
+    progressIntervalMs,
+    timeoutMs,
+    onProgress,
+  });
+}
+
+interface UploadMultipartOptions {
+  /** The name of the file part, the name of the file by default. */
+  fileName?: string;
+  /** The text fields sent with the file. */
+  fields?: Record<string, string>;
+  /** The request headers, without `Content-Type` which is set for the form. */
+  headers?: Map<string, string>;
+  progressHandler?: ProgressHandler;
+  transferId?: number;
+  /** The interval the progress of the chunks is summed over, sending every chunk by default. */
+  progressIntervalMs?: number;
+  /** The timeout of the whole request, overriding the one configured on the Rust side. */
+  timeoutMs?: number;
+}
+
+/// Upload the file as the `fieldName` part of a `multipart/form-data` form.
+///
+/// The progress events report the bytes of the file, not of the whole form.
+/// Resolves with the JSON response like {@link upload}.
+async function uploadMultipart<T = unknown>(
+  url: string,
+  filePath: string,
+  fieldName: string,
+  options: UploadMultipartOptions = {}
+): Promise<T> {
+  const ids = new Uint32Array(1);
+  window.crypto.getRandomValues(ids);
+  const id = options.transferId ?? ids[0];
+
+  const onProgress = new Channel<ProgressPayload>();
+  if (options.progressHandler != null) {
+    onProgress.onmessage = options.progressHandler;
+  }
+
+  return await invoke("plugin:upload|upload_multipart", {
+    transferId: id,
+    url,
+    filePath,
+    fieldName,
+    fileName: options.fileName,
+    fields: options.fields ?? {},
+    headers: options.headers ?? {},
+    progressIntervalMs: options.progressIntervalMs,
+    timeoutMs: options.timeoutMs,
+    onProgress,
+  });
+}
+
+interface UploadBytesOptions {
+  headers?: Map<string, string>;
+  progressHandler?: ProgressHandler;
+  transferId?: number;
+  /** The interval the progress of the chunks is summed over, sending every chunk by default. */
+  progressIntervalMs?: number;
+  /** The timeout of the whole request, overriding the one configured on the Rust side. */
+  timeoutMs?: number;
+}
+
+function toBase64(data: Uint8Array): string {
+  let binary = "";
+  // spreading a large buffer at once overflows the call stack
+  for (let i = 0; i < data.length; i += 0x8000) {
+    binary += String.fromCharCode(...data.subarray(i, i + 0x8000));
+  }
+  return window.btoa(binary);
+}
+
+/// Upload in-memory data as the body of a `POST` request, without writing it to a file first.
+///
+/// The progress events report the length of the data as their total and an empty `filePath`.
+/// Resolves with the JSON response like {@link upload}.
+async function uploadBytes<T = unknown>(
+  url: string,
+  data: Uint8Array | ArrayBuffer | Blob,
+  options: UploadBytesOptions = {}
+): Promise<T> {
+  const ids = new Uint32Array(1);
+  window.crypto.getRandomValues(ids);
+  const id = options.transferId ?? ids[0];
+
+  const onProgress = new Channel<ProgressPayload>();
+  if (options.progressHandler != null) {
+    onProgress.onmessage = options.progressHandler;
+  }
+
+  const bytes =
+    data instanceof Blob
+      ? new Uint8Array(await data.arrayBuffer())
+      : new Uint8Array(data);
+
+  return await invoke("plugin:upload|upload_bytes", {
+    transferId: id,
+    url,
+    data: toBase64(bytes),
+    headers: options.headers ?? {},
+    progressIntervalMs: options.progressIntervalMs,
+    timeoutMs: options.timeoutMs,
     onProgress,
   });
 }
 
 /// Download file from given url.
 ///
+/// The url can also be a `file://` URL, copying the local file, or a `data:` URL, decoding its payload.
+/// The headers are only sent with `http(s)` URLs, as the `method`, `GET` by default, and the `body` serialized to JSON.
+///
 /// Note that `filePath` currently must include the file name.
+/// Paths starting with a `scheme://` registered on the Rust side are written to that custom sink instead of a file.
+/// The `transferId`, random if not given, is included in every progress event.
 /// Furthermore the progress events will report a total length of 0 if the server did not sent a `Content-Length` header or if the file is compressed.
+///
+/// With `resume`, an existing file is completed with a `Range` request instead of downloaded again,
+/// and the first progress event reports the length of the existing file.
+/// The file is downloaded from the start if the server does not honor the range.
+///
+/// A download cancelled with {@link cancel} keeps the bytes written so far, so it can be resumed,
+/// unless `deletePartial` is set.
+///
+/// With `progressIntervalMs`, the progress of the chunks received within the interval is summed in a single event.
+/// The `timeoutMs` overrides the timeout of the whole request configured on the Rust side.
+///
+/// With `maxRetries`, a download whose connection failed, was reset or timed out is retried after an exponential backoff,
+/// resuming the bytes already written. The download rejects with the last error once the retries are exhausted.
+///
+/// Once the file is written, a last progress event with `completed` set is sent and the promise resolves
+/// with the number of bytes written, the response status and headers, and the SHA-256 digest of the file.
+/// The download rejects if fewer bytes than the announced total were written, and stops as soon as the server sends more.
+/// With `maxSize`, it also rejects without writing if the announced length is larger, and stops once it writes more.
+/// With `expectedSha256`, it also rejects and removes the file if its digest is not the expected one.
 async function download(
   url: string,
   filePath: string,
   progressHandler?: ProgressHandler,
-  headers?: Map<string, string>
-): Promise<void> {
+  headers?: Map<string, string>,
+  transferId?: number,
+  resume?: boolean,
+  deletePartial?: boolean,
+  progressIntervalMs?: number,
+  timeoutMs?: number,
+  expectedSha256?: string,
+  method?: string,
+  body?: unknown,
+  maxRetries?: number,
+  maxSize?: number
+): Promise<DownloadResult> {
   const ids = new Uint32Array(1);
   window.crypto.getRandomValues(ids);
-  const id = ids[0];
+  const id = transferId ?? ids[0];
 
   const onProgress = new Channel<ProgressPayload>();
   if (progressHandler != null) {
     onProgress.onmessage = progressHandler;
   }
 
-  await invoke("plugin:upload|download", {
-    id,
+  return await invoke("plugin:upload|download", {
+    transferId: id,
     url,
     filePath,
     headers: headers ?? {},
+    resume,
+    deletePartial,
+    progressIntervalMs,
+    timeoutMs,
+    expectedSha256,
+    method,
+    body,
+    maxRetries,
+    maxSize,
+    onProgress,
+  });
+}
+
+/// Cancel the download or upload started with the given `transferId`.
+///
+/// The transfer sends a last progress event with `cancelled` set, then rejects.
+async function cancel(transferId: number): Promise<void> {
+  await invoke("plugin:upload|cancel", { transferId });
+}
+
+/** A remote file listed for {@link downloadTree}. */
+interface RemoteFile {
+  /** The path of the file, relative to the base URL and the destination directory. */
+  path: string;
+  /** The size of the file in bytes. */
+  size?: number;
+  /** The last modification of the file, in milliseconds since the Unix epoch. */
+  modifiedAtMs?: number;
+  /** The hex encoded SHA-256 digest of the file. */
+  sha256?: string;
+}
+
+/**
+ * How {@link downloadTree} enumerates the remote files:
+ * - `webDav` walks the collections under the base URL with `PROPFIND` requests,
+ * - `index` fetches a JSON array of {@link RemoteFile}s from `url`, relative to the base URL,
+ * - `files` uses the given files.
+ */
+type Lister =
+  | { type: "webDav" }
+  | { type: "index"; url: string }
+  | { type: "files"; files: RemoteFile[] };
+
+interface TreeProgressPayload {
+  /** The file that made progress. */
+  path: string;
+  /** The number of files downloaded, skipped or failed so far. */
+  completedFiles: number;
+  totalFiles: number;
+  /** The bytes received so far, for all the files. */
+  progress: number;
+  /** The sum of the listed sizes of the files, 0 if any is unknown. */
+  total: number;
+}
+
+interface TreeSummary {
+  /** The files that were downloaded. */
+  downloaded: string[];
+  /** The files that already matched locally. */
+  skipped: string[];
+  /** The files that could not be downloaded, with their error. */
+  failed: Array<{ path: string; error: string }>;
+}
+
+interface DownloadTreeOptions {
+  headers?: Map<string, string>;
+  /** The number of files downloaded at the same time, 4 by default. */
+  concurrency?: number;
+  progressHandler?: (progress: TreeProgressPayload) => void;
+}
+
+/// Mirror the files listed under `baseUrl` into the `destDir` directory.
+///
+/// Files whose local copy matches the listed SHA-256 digest, or without digest has the listed size
+/// and was modified after the listed time, are skipped.
+/// A file that can not be downloaded is reported in the summary without stopping the other ones.
+async function downloadTree(
+  baseUrl: string,
+  destDir: string,
+  lister: Lister,
+  options: DownloadTreeOptions = {}
+): Promise<TreeSummary> {
+  const onProgress = new Channel<TreeProgressPayload>();
+  if (options.progressHandler != null) {
+    onProgress.onmessage = options.progressHandler;
+  }
+
+  return await invoke("plugin:upload|download_tree", {
+    baseUrl,
+    destDir,
+    lister,
+    headers: options.headers ?? {},
+    concurrency: options.concurrency,
     onProgress,
   });
 }
 
-export { download, upload };
+export {
+  download,
+  downloadTree,
+  upload,
+  uploadBytes,
+  uploadMultipart,
+  cancel,
+};
+export type {
+  ProgressPayload,
+  DownloadResult,
+  UploadMultipartOptions,
+  UploadBytesOptions,
+  RemoteFile,
+  Lister,
+  TreeProgressPayload,
+  TreeSummary,
+  DownloadTreeOptions,
+};
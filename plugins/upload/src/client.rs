@@ -0,0 +1,55 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The HTTP client shared by the transfers, configured with the [`Builder`](crate::Builder).
+
+use reqwest::Proxy;
+
+use std::time::Duration;
+
+use crate::Result;
+
+/// The configuration of the HTTP client.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ClientConfig {
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) proxy: Option<String>,
+    pub(crate) danger_accept_invalid_certs: bool,
+}
+
+impl ClientConfig {
+    pub(crate) fn build(&self) -> Result<HttpClient> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(Proxy::all(proxy)?);
+        }
+        if self.danger_accept_invalid_certs {
+            #[cfg(any(
+                feature = "native-tls",
+                feature = "native-tls-vendored",
+                feature = "rustls-tls"
+            ))]
+            {
+                builder = builder.danger_accept_invalid_certs(true);
+            }
+            #[cfg(not(any(
+                feature = "native-tls",
+                feature = "native-tls-vendored",
+                feature = "rustls-tls"
+            )))]
+            log::warn!("accepting invalid certificates has no effect without a TLS feature");
+        }
+        Ok(HttpClient(builder.build()?))
+    }
+}
+
+/// The HTTP client of the transfers, sharing its connection pool.
+pub(crate) struct HttpClient(pub(crate) reqwest::Client);
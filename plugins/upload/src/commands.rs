@@ -0,0 +1,224 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The commands of the plugin, sending the progress of the [`Upload`] functions to a channel.
+
+use base64::Engine;
+use serde::Serialize;
+use tauri::{api::ipc::Channel, command, Runtime, State};
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use crate::{
+    CancelSignal, DownloadOptions, DownloadResult, Error, Lister, MultipartForm, Result,
+    TransferId, TreeSummary, Upload, UploadOptions,
+};
+
+/// The number of files downloaded at the same time by [`download_tree`] by default.
+const DEFAULT_CONCURRENCY: usize = 4;
+/// The number of progress events in a row the channel may fail to deliver before the transfer
+/// is cancelled.
+const CLOSED_CHANNEL_FAILURES: u32 = 3;
+
+/// The progress channel of a transfer, cancelling it once the channel keeps failing, e.g.
+/// because the webview that listened to it is gone.
+struct ProgressChannel<R: Runtime> {
+    channel: Channel<R>,
+    failures: AtomicU32,
+    cancel_signal: CancelSignal,
+}
+
+impl<R: Runtime> ProgressChannel<R> {
+    fn new(channel: Channel<R>) -> Self {
+        Self {
+            channel,
+            failures: AtomicU32::new(0),
+            cancel_signal: CancelSignal::new(),
+        }
+    }
+
+    fn send<T: Serialize>(&self, payload: &T) {
+        if self.channel.send(payload).is_ok() {
+            self.failures.store(0, Ordering::Relaxed);
+        } else if self.failures.fetch_add(1, Ordering::Relaxed) + 1 >= CLOSED_CHANNEL_FAILURES {
+            self.cancel_signal.cancel();
+        }
+    }
+}
+
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn download<R: Runtime>(
+    upload: State<'_, Upload>,
+    transfer_id: Option<TransferId>,
+    url: &str,
+    file_path: &str,
+    headers: HashMap<String, String>,
+    resume: Option<bool>,
+    delete_partial: Option<bool>,
+    progress_interval_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    expected_sha256: Option<String>,
+    method: Option<String>,
+    body: Option<serde_json::Value>,
+    max_retries: Option<u32>,
+    max_size: Option<u64>,
+    on_progress: Channel<R>,
+) -> Result<DownloadResult> {
+    let on_progress = ProgressChannel::new(on_progress);
+    let options = DownloadOptions {
+        transfer_id,
+        headers,
+        resume: resume.unwrap_or_default(),
+        delete_partial: delete_partial.unwrap_or_default(),
+        progress_interval: progress_interval_ms.map(Duration::from_millis),
+        timeout: timeout_ms.map(Duration::from_millis),
+        expected_sha256,
+        method,
+        body,
+        max_retries: max_retries.unwrap_or_default(),
+        max_size,
+        cancel_signal: Some(on_progress.cancel_signal.clone()),
+    };
+    upload
+        .download(url, file_path, options, move |progress| {
+            on_progress.send(&progress)
+        })
+        .await
+}
+
+#[command]
+pub async fn download_tree<R: Runtime>(
+    upload: State<'_, Upload>,
+    base_url: &str,
+    dest_dir: PathBuf,
+    lister: Lister,
+    headers: HashMap<String, String>,
+    concurrency: Option<usize>,
+    on_progress: Channel<R>,
+) -> Result<TreeSummary> {
+    upload
+        .download_tree(
+            base_url,
+            &dest_dir,
+            lister,
+            headers,
+            concurrency.unwrap_or(DEFAULT_CONCURRENCY),
+            |progress| {
+                let _ = on_progress.send(&progress);
+            },
+        )
+        .await
+}
+
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn upload<R: Runtime>(
+    upload: State<'_, Upload>,
+    transfer_id: Option<TransferId>,
+    url: &str,
+    file_path: &str,
+    headers: HashMap<String, String>,
+    progress_interval_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    on_progress: Channel<R>,
+) -> Result<serde_json::Value> {
+    let on_progress = ProgressChannel::new(on_progress);
+    let options = UploadOptions {
+        transfer_id,
+        headers,
+        progress_interval: progress_interval_ms.map(Duration::from_millis),
+        timeout: timeout_ms.map(Duration::from_millis),
+        cancel_signal: Some(on_progress.cancel_signal.clone()),
+    };
+    upload
+        .upload(url, file_path, options, move |progress| {
+            on_progress.send(&progress)
+        })
+        .await
+}
+
+/// Uploads the base64 encoded `data` as the body of a `POST` request, without writing it to a file.
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_bytes<R: Runtime>(
+    upload: State<'_, Upload>,
+    transfer_id: Option<TransferId>,
+    url: &str,
+    data: String,
+    headers: HashMap<String, String>,
+    progress_interval_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    on_progress: Channel<R>,
+) -> Result<serde_json::Value> {
+    let on_progress = ProgressChannel::new(on_progress);
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| Error::InvalidData(e.to_string()))?;
+    let options = UploadOptions {
+        transfer_id,
+        headers,
+        progress_interval: progress_interval_ms.map(Duration::from_millis),
+        timeout: timeout_ms.map(Duration::from_millis),
+        cancel_signal: Some(on_progress.cancel_signal.clone()),
+    };
+    upload
+        .upload_bytes(url, data, options, move |progress| {
+            on_progress.send(&progress)
+        })
+        .await
+}
+
+/// Uploads the file as the `field_name` part of a `multipart/form-data` body, next to the text `fields`.
+///
+/// The part is named after the file unless a `file_name` is given. The `Content-Type` header with
+/// the form boundary is set by the request, so a `Content-Type` in `headers` is ignored.
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_multipart<R: Runtime>(
+    upload: State<'_, Upload>,
+    transfer_id: Option<TransferId>,
+    url: &str,
+    file_path: &str,
+    field_name: String,
+    file_name: Option<String>,
+    fields: HashMap<String, String>,
+    headers: HashMap<String, String>,
+    progress_interval_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    on_progress: Channel<R>,
+) -> Result<serde_json::Value> {
+    let on_progress = ProgressChannel::new(on_progress);
+    let form = MultipartForm {
+        field_name,
+        file_name,
+        fields,
+    };
+    let options = UploadOptions {
+        transfer_id,
+        headers,
+        progress_interval: progress_interval_ms.map(Duration::from_millis),
+        timeout: timeout_ms.map(Duration::from_millis),
+        cancel_signal: Some(on_progress.cancel_signal.clone()),
+    };
+    upload
+        .upload_multipart(url, file_path, form, options, move |progress| {
+            on_progress.send(&progress)
+        })
+        .await
+}
+
+/// Cancels the download or upload started with the given ID.
+///
+/// The transfer fails with [`Error::Cancelled`](crate::Error::Cancelled) after a last progress
+/// event flagged as cancelled.
+#[command]
+pub fn cancel(upload: State<'_, Upload>, transfer_id: TransferId) {
+    upload.cancel(transfer_id);
+}
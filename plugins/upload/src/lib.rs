@@ -4,20 +4,57 @@
 
 use futures_util::TryStreamExt;
 use serde::{ser::Serializer, Serialize};
+use sha2::{Digest, Sha256};
 use tauri::{
-    api::ipc::Channel,
-    command,
     plugin::{Builder as PluginBuilder, TauriPlugin},
-    Runtime,
+    Manager, Runtime,
+};
+use tokio::{
+    fs::{self, File, OpenOptions},
+    io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, SeekFrom},
 };
-use tokio::{fs::File, io::AsyncWriteExt};
 use tokio_util::codec::{BytesCodec, FramedRead};
 
 use read_progress_stream::ReadProgressStream;
+use reqwest::{
+    header::CONTENT_TYPE,
+    multipart::{Form, Part},
+    Method,
+};
+
+use std::{
+    collections::HashMap,
+    io::Cursor,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use std::collections::HashMap;
+mod client;
+mod commands;
+mod sink;
+mod source;
+mod throttle;
+mod transfer;
+mod tree;
+
+use client::{ClientConfig, HttpClient};
+pub use sink::{FileSink, Sink, SinkFactory, SinkFuture};
+
+use sink::Sinks;
+use source::Source;
+use throttle::Throttle;
+pub use transfer::CancelSignal;
+use transfer::Transfers;
+pub use tree::{FileError, Lister, RemoteFile, TreeProgress, TreeSummary};
 
 type Result<T> = std::result::Result<T, Error>;
+pub type TransferId = u32;
+
+/// The delay before the first retry of a download.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// The longest delay between two retries of a download.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -25,8 +62,34 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Request(#[from] reqwest::Error),
+    /// The download is longer than its `Content-Length` or its `max_size`.
     #[error("{0}")]
     ContentLength(String),
+    #[error("invalid URL: {0}")]
+    InvalidUrl(String),
+    /// The server answered an HTTP request with an error status.
+    #[error("the server responded with status {0}")]
+    HttpStatus(u16),
+    #[error("invalid base64 data: {0}")]
+    InvalidData(String),
+    #[error("invalid HTTP method `{0}`")]
+    InvalidMethod(String),
+    #[error("unsupported URL scheme `{0}`, expected `http`, `https`, `file` or `data`")]
+    UnsupportedScheme(String),
+    #[error("the remote path `{0}` is outside of the destination directory")]
+    InvalidPath(String),
+    /// The server has no bytes after the length of the resumed file, which may be complete.
+    #[error("range not satisfiable, the content is not longer than {0} bytes")]
+    RangeNotSatisfiable(u64),
+    /// The transfer was cancelled with the `cancel` command or its [`CancelSignal`].
+    #[error("transfer cancelled")]
+    Cancelled,
+    /// The download ended before the length announced by the server was written.
+    #[error("download truncated, {written} of {total} bytes written")]
+    Truncated { written: u64, total: u64 },
+    /// The SHA-256 digest of the downloaded file is not the expected one.
+    #[error("checksum mismatch, expected SHA-256 `{expected}` but the file has `{actual}`")]
+    ChecksumMismatch { expected: String, actual: String },
 }
 
 impl Serialize for Error {
@@ -38,84 +101,736 @@ impl Serialize for Error {
     }
 }
 
-#[derive(Clone, Serialize)]
-struct ProgressPayload {
-    progress: u64,
-    total: u64,
+/// A progress event of a download or upload.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressPayload<'a> {
+    /// The ID given by the caller, so concurrent transfers sharing a listener can be told apart.
+    pub transfer_id: Option<TransferId>,
+    pub url: &'a str,
+    pub file_path: &'a str,
+    /// The bytes transferred since the last event.
+    pub progress: u64,
+    pub total: u64,
+    /// The retry of the download the event belongs to, `0` for the first attempt.
+    ///
+    /// Every retry starts with an event without progress, after which the bytes resumed from
+    /// the failed attempts are reported again.
+    pub attempt: u32,
+    /// Whether the transfer was cancelled, set on its last event only.
+    pub cancelled: bool,
+    /// Whether the download completed, set on its last event only.
+    pub completed: bool,
+}
+
+/// The outcome of a completed download.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadResult {
+    /// The length of the file, counting the resumed bytes and the chunks written to it.
+    pub bytes_written: u64,
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    /// The hex encoded SHA-256 digest of the file, counting the resumed bytes.
+    pub sha256: String,
+}
+
+/// The options of [`Upload::download`].
+#[derive(Debug, Clone, Default)]
+pub struct DownloadOptions {
+    /// The ID to cancel the download with, see [`Upload::cancel`].
+    pub transfer_id: Option<TransferId>,
+    pub headers: HashMap<String, String>,
+    /// Appends to the existing file, requesting the bytes after its length.
+    pub resume: bool,
+    /// Removes the file once the download was cancelled.
+    pub delete_partial: bool,
+    /// The minimum interval between two progress events, one is sent per chunk without it.
+    pub progress_interval: Option<Duration>,
+    /// Overrides the timeout set with [`Builder::timeout`].
+    pub timeout: Option<Duration>,
+    /// The hex encoded SHA-256 digest the file must have, the download fails with
+    /// [`Error::ChecksumMismatch`] and the file is removed otherwise.
+    pub expected_sha256: Option<String>,
+    /// The HTTP method of the request, `GET` by default.
+    pub method: Option<String>,
+    /// The JSON body sent with the request.
+    pub body: Option<serde_json::Value>,
+    /// How many times the download is retried after a connection failure or a timeout,
+    /// resuming the written bytes after an exponential backoff.
+    pub max_retries: u32,
+    /// The largest file the download may write, in bytes. It fails with
+    /// [`Error::ContentLength`] before writing anything if the server announces a longer
+    /// content, and as soon as it writes more otherwise.
+    pub max_size: Option<u64>,
+    /// Cancels the download like [`Upload::cancel`], also without transfer ID.
+    pub cancel_signal: Option<CancelSignal>,
+}
+
+/// The options of [`Upload::upload`] and [`Upload::upload_multipart`].
+#[derive(Debug, Clone, Default)]
+pub struct UploadOptions {
+    /// The ID to cancel the upload with, see [`Upload::cancel`].
+    pub transfer_id: Option<TransferId>,
+    pub headers: HashMap<String, String>,
+    /// The minimum interval between two progress events, one is sent per chunk without it.
+    pub progress_interval: Option<Duration>,
+    /// Overrides the timeout set with [`Builder::timeout`].
+    pub timeout: Option<Duration>,
+    /// Cancels the upload like [`Upload::cancel`], also without transfer ID.
+    pub cancel_signal: Option<CancelSignal>,
+}
+
+/// The `multipart/form-data` body of [`Upload::upload_multipart`].
+#[derive(Debug, Clone, Default)]
+pub struct MultipartForm {
+    /// The name of the part holding the file.
+    pub field_name: String,
+    /// The file name of the part, the one of the uploaded file by default.
+    pub file_name: Option<String>,
+    /// The text parts sent next to the file.
+    pub fields: HashMap<String, String>,
+}
+
+/// The transfers of the plugin, for the Rust side of the app.
+///
+/// The commands are thin wrappers around these functions, which report their progress to a
+/// closure instead of a channel.
+pub struct Upload {
+    client: HttpClient,
+    sinks: Sinks,
+    transfers: Transfers,
+}
+
+impl Upload {
+    /// Downloads `url` to `file_path`, or to the sink registered for its scheme.
+    pub async fn download<F>(
+        &self,
+        url: &str,
+        file_path: &str,
+        options: DownloadOptions,
+        on_progress: F,
+    ) -> Result<DownloadResult>
+    where
+        F: Fn(ProgressPayload<'_>) + Send + Sync,
+    {
+        let transfer_id = options.transfer_id;
+        let cancel_signal = options.cancel_signal.clone();
+        let method = match options.method {
+            Some(method) => parse_method(&method)?,
+            None => Method::GET,
+        };
+        // only the files can be appended to or removed, custom sinks are always written from the
+        // start and handle their own partial writes
+        let is_file = !self.sinks.is_registered(file_path);
+        let on_progress = &on_progress;
+        let download = async {
+            let mut attempt = 0;
+            loop {
+                let on_attempt = move |mut progress: ProgressPayload<'_>| {
+                    progress.attempt = attempt;
+                    on_progress(progress);
+                };
+                // a retry resumes the bytes written by the failed attempts
+                let resume = (options.resume || attempt > 0) && is_file;
+                let result = async {
+                    let existing = if resume {
+                        fs::metadata(file_path).await.map(|m| m.len()).unwrap_or(0)
+                    } else {
+                        0
+                    };
+                    let source = Source::open_from(
+                        &self.client.0,
+                        url,
+                        options.headers.clone(),
+                        method.clone(),
+                        options.body.clone(),
+                        existing,
+                        options.timeout,
+                    )
+                    .await?;
+                    download_to(
+                        &self.sinks,
+                        source,
+                        transfer_id,
+                        url,
+                        file_path,
+                        Throttle::new(options.progress_interval),
+                        options.expected_sha256.as_deref(),
+                        options.max_size,
+                        &on_attempt,
+                    )
+                    .await
+                }
+                .await;
+
+                match result {
+                    Err(e) if attempt < options.max_retries && is_transient(&e) => {
+                        attempt += 1;
+                        on_progress(ProgressPayload {
+                            transfer_id,
+                            url,
+                            file_path,
+                            progress: 0,
+                            total: 0,
+                            attempt,
+                            cancelled: false,
+                            completed: false,
+                        });
+                        tokio::time::sleep(backoff(attempt)).await;
+                    }
+                    result => return result,
+                }
+            }
+        };
+        let result = self
+            .transfers
+            .run(transfer_id, cancel_signal.as_ref(), download)
+            .await;
+
+        match result {
+            Err(Error::Cancelled) => {
+                if options.delete_partial && is_file {
+                    let _ = fs::remove_file(file_path).await;
+                }
+                send_cancelled(on_progress, transfer_id, url, file_path);
+            }
+            // a corrupt or oversized file is never kept
+            Err(Error::ChecksumMismatch { .. } | Error::ContentLength(_)) if is_file => {
+                let _ = fs::remove_file(file_path).await;
+            }
+            _ => {}
+        }
+
+        result
+    }
+
+    /// Downloads the files listed under `base_url` into `dest_dir`, `concurrency` at a time,
+    /// skipping the ones whose local copy already matches.
+    pub async fn download_tree<F>(
+        &self,
+        base_url: &str,
+        dest_dir: &Path,
+        lister: Lister,
+        headers: HashMap<String, String>,
+        concurrency: usize,
+        on_progress: F,
+    ) -> Result<TreeSummary>
+    where
+        F: Fn(TreeProgress<'_>) + Sync,
+    {
+        tree::download_tree(
+            &self.client.0,
+            base_url,
+            dest_dir,
+            lister,
+            headers,
+            concurrency,
+            on_progress,
+        )
+        .await
+    }
+
+    /// Uploads the file as the body of a `POST` request, returning the JSON response.
+    pub async fn upload<F>(
+        &self,
+        url: &str,
+        file_path: &str,
+        options: UploadOptions,
+        on_progress: F,
+    ) -> Result<serde_json::Value>
+    where
+        F: Fn(ProgressPayload<'_>) + Send + Sync + 'static,
+    {
+        let transfer_id = options.transfer_id;
+        let cancel_signal = options.cancel_signal.clone();
+        let on_progress = Arc::new(on_progress);
+        let throttle = Arc::new(Mutex::new(Throttle::new(options.progress_interval)));
+        let upload = async {
+            // Read the file
+            let file = File::open(file_path).await?;
+
+            // Create the request and attach the file to the body
+            let mut request = self.client.0.post(url).body(reader_to_body(
+                on_progress.clone(),
+                file,
+                None,
+                transfer_id,
+                url.to_string(),
+                file_path.to_string(),
+                throttle.clone(),
+            ));
+
+            if let Some(timeout) = options.timeout {
+                request = request.timeout(timeout);
+            }
+
+            // Loop trought the headers keys and values
+            // and add them to the request object.
+            for (key, value) in options.headers {
+                request = request.header(&key, value);
+            }
+
+            let response = request.send().await?;
+            if let Some((progress, total)) = throttle.lock().unwrap().flush() {
+                send_progress(&*on_progress, transfer_id, url, file_path, progress, total);
+            }
+
+            response.json().await.map_err(Into::into)
+        };
+
+        let result = self
+            .transfers
+            .run(transfer_id, cancel_signal.as_ref(), upload)
+            .await;
+        if let Err(Error::Cancelled) = result {
+            send_cancelled(&*on_progress, transfer_id, url, file_path);
+        }
+        result
+    }
+
+    /// Uploads the in-memory `data` as the body of a `POST` request, returning the JSON response.
+    ///
+    /// The progress events report the length of `data` as their total and an empty file path.
+    pub async fn upload_bytes<F>(
+        &self,
+        url: &str,
+        data: Vec<u8>,
+        options: UploadOptions,
+        on_progress: F,
+    ) -> Result<serde_json::Value>
+    where
+        F: Fn(ProgressPayload<'_>) + Send + Sync + 'static,
+    {
+        let transfer_id = options.transfer_id;
+        let cancel_signal = options.cancel_signal.clone();
+        let on_progress = Arc::new(on_progress);
+        let throttle = Arc::new(Mutex::new(Throttle::new(options.progress_interval)));
+        let upload = async {
+            let length = data.len() as u64;
+            let mut request = self.client.0.post(url).body(reader_to_body(
+                on_progress.clone(),
+                Cursor::new(data),
+                Some(length),
+                transfer_id,
+                url.to_string(),
+                String::new(),
+                throttle.clone(),
+            ));
+            if let Some(timeout) = options.timeout {
+                request = request.timeout(timeout);
+            }
+            for (key, value) in options.headers {
+                request = request.header(&key, value);
+            }
+
+            let response = request.send().await?;
+            if let Some((progress, total)) = throttle.lock().unwrap().flush() {
+                send_progress(&*on_progress, transfer_id, url, "", progress, total);
+            }
+
+            response.json().await.map_err(Into::into)
+        };
+
+        let result = self
+            .transfers
+            .run(transfer_id, cancel_signal.as_ref(), upload)
+            .await;
+        if let Err(Error::Cancelled) = result {
+            send_cancelled(&*on_progress, transfer_id, url, "");
+        }
+        result
+    }
+
+    /// Uploads the file as a part of a `multipart/form-data` body, returning the JSON response.
+    ///
+    /// The `Content-Type` header with the form boundary is set by the request, so a
+    /// `Content-Type` in the headers is ignored.
+    pub async fn upload_multipart<F>(
+        &self,
+        url: &str,
+        file_path: &str,
+        form: MultipartForm,
+        options: UploadOptions,
+        on_progress: F,
+    ) -> Result<serde_json::Value>
+    where
+        F: Fn(ProgressPayload<'_>) + Send + Sync + 'static,
+    {
+        let transfer_id = options.transfer_id;
+        let cancel_signal = options.cancel_signal.clone();
+        let on_progress = Arc::new(on_progress);
+        let throttle = Arc::new(Mutex::new(Throttle::new(options.progress_interval)));
+        let upload = async {
+            let file = File::open(file_path).await?;
+            let length = file.metadata().await?.len();
+            let file_name = form.file_name.or_else(|| {
+                Path::new(file_path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+            });
+
+            let body = reader_to_body(
+                on_progress.clone(),
+                file,
+                None,
+                transfer_id,
+                url.to_string(),
+                file_path.to_string(),
+                throttle.clone(),
+            );
+            let mut part = Part::stream_with_length(body, length);
+            if let Some(file_name) = file_name {
+                part = part.file_name(file_name);
+            }
+            let multipart = form
+                .fields
+                .into_iter()
+                .fold(Form::new(), |multipart, (name, value)| {
+                    multipart.text(name, value)
+                })
+                .part(form.field_name, part);
+
+            let mut request = self.client.0.post(url).multipart(multipart);
+            if let Some(timeout) = options.timeout {
+                request = request.timeout(timeout);
+            }
+            for (key, value) in options.headers {
+                // the boundary of the form must be kept
+                if !key.eq_ignore_ascii_case(CONTENT_TYPE.as_str()) {
+                    request = request.header(&key, value);
+                }
+            }
+
+            let response = request.send().await?;
+            if let Some((progress, total)) = throttle.lock().unwrap().flush() {
+                send_progress(&*on_progress, transfer_id, url, file_path, progress, total);
+            }
+
+            response.json().await.map_err(Into::into)
+        };
+
+        let result = self
+            .transfers
+            .run(transfer_id, cancel_signal.as_ref(), upload)
+            .await;
+        if let Err(Error::Cancelled) = result {
+            send_cancelled(&*on_progress, transfer_id, url, file_path);
+        }
+        result
+    }
+
+    /// Cancels the download or upload started with the given ID.
+    ///
+    /// The transfer fails with [`Error::Cancelled`] after a last progress event flagged as cancelled.
+    pub fn cancel(&self, transfer_id: TransferId) {
+        self.transfers.cancel(transfer_id);
+    }
+}
+
+/// Extensions to [`tauri::App`], [`tauri::AppHandle`] and [`tauri::Window`] to access the
+/// transfers of the plugin.
+pub trait UploadExt<R: Runtime> {
+    fn upload(&self) -> &Upload;
+}
+
+impl<R: Runtime, T: Manager<R>> UploadExt<R> for T {
+    fn upload(&self) -> &Upload {
+        self.state::<Upload>().inner()
+    }
 }
 
-#[command]
-async fn download<R: Runtime>(
+#[allow(clippy::too_many_arguments)]
+async fn download_to(
+    sinks: &Sinks,
+    source: Source,
+    transfer_id: Option<TransferId>,
     url: &str,
     file_path: &str,
-    headers: HashMap<String, String>,
-    on_progress: Channel<R>,
-) -> Result<()> {
-    let client = reqwest::Client::new();
+    mut throttle: Throttle,
+    expected_sha256: Option<&str>,
+    max_size: Option<u64>,
+    on_progress: &(dyn Fn(ProgressPayload<'_>) + Send + Sync),
+) -> Result<DownloadResult> {
+    let Source {
+        offset,
+        total,
+        status,
+        headers,
+        mut stream,
+    } = source;
 
-    let mut request = client.get(url);
-    // Loop trought the headers keys and values
-    // and add them to the request object.
-    for (key, value) in headers {
-        request = request.header(&key, value);
+    if let Some(max_size) = max_size.filter(|max_size| total > *max_size) {
+        return Err(Error::ContentLength(format!(
+            "the content length {total} exceeds max_size {max_size}"
+        )));
     }
 
-    let response = request.send().await?;
-    let total = response.content_length().unwrap_or(0);
-
-    let mut file = File::create(file_path).await?;
-    let mut stream = response.bytes_stream();
+    let mut hasher = Sha256::new();
+    let mut sink = if offset > 0 {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(file_path)
+            .await?;
+        // drop anything past the resumed range, e.g. if the file changed since it was measured
+        file.set_len(offset).await?;
+        hash_file(&mut file, &mut hasher).await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        // the bytes already downloaded count towards the progress
+        send_progress(on_progress, transfer_id, url, file_path, offset, total);
+        Box::pin(file) as Sink
+    } else {
+        sinks.get(file_path).create(file_path).await?
+    };
 
+    let mut bytes_written = offset;
     while let Some(chunk) = stream.try_next().await? {
-        file.write_all(&chunk).await?;
-        let _ = on_progress.send(&ProgressPayload {
-            progress: chunk.len() as u64,
+        let length = bytes_written + chunk.len() as u64;
+        if total > 0 && length > total {
+            return Err(Error::ContentLength(format!(
+                "the server sent more than the content length {total}"
+            )));
+        }
+        if let Some(max_size) = max_size.filter(|max_size| length > *max_size) {
+            return Err(Error::ContentLength(format!(
+                "the download exceeds max_size {max_size}"
+            )));
+        }
+        sink.write_all(&chunk).await?;
+        hasher.update(&chunk);
+        bytes_written += chunk.len() as u64;
+        if let Some((progress, total)) = throttle.add(chunk.len() as u64, total) {
+            send_progress(on_progress, transfer_id, url, file_path, progress, total);
+        }
+    }
+    if let Some((progress, total)) = throttle.flush() {
+        send_progress(on_progress, transfer_id, url, file_path, progress, total);
+    }
+
+    // custom sinks may buffer or encrypt, so give them a chance to finish writing
+    sink.shutdown().await?;
+
+    if total > 0 && bytes_written != total {
+        return Err(Error::Truncated {
+            written: bytes_written,
             total,
         });
     }
 
-    Ok(())
-}
+    let sha256 = hex::encode(hasher.finalize());
+    if let Some(expected) = expected_sha256 {
+        if !expected.eq_ignore_ascii_case(&sha256) {
+            return Err(Error::ChecksumMismatch {
+                expected: expected.into(),
+                actual: sha256,
+            });
+        }
+    }
 
-#[command]
-async fn upload<R: Runtime>(
-    url: &str,
-    file_path: &str,
-    headers: HashMap<String, String>,
-    on_progress: Channel<R>,
-) -> Result<serde_json::Value> {
-    // Read the file
-    let file = File::open(file_path).await?;
+    on_progress(ProgressPayload {
+        transfer_id,
+        url,
+        file_path,
+        progress: 0,
+        total,
+        attempt: 0,
+        cancelled: false,
+        completed: true,
+    });
 
-    // Create the request and attach the file to the body
-    let client = reqwest::Client::new();
-    let mut request = client.post(url).body(file_to_body(on_progress, file));
+    Ok(DownloadResult {
+        bytes_written,
+        status,
+        headers,
+        sha256,
+    })
+}
 
-    // Loop trought the headers keys and values
-    // and add them to the request object.
-    for (key, value) in headers {
-        request = request.header(&key, value);
+/// Feeds the file to the hasher from its current position.
+async fn hash_file(file: &mut File, hasher: &mut Sha256) -> std::io::Result<()> {
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        hasher.update(&buffer[..read]);
     }
+}
 
-    let response = request.send().await?;
+/// The failures a retry may recover from: the connection failed, was reset or timed out.
+///
+/// An error response is written like any other, so it is never retried.
+fn is_transient(error: &Error) -> bool {
+    match error {
+        Error::Request(e) => e.is_connect() || e.is_timeout() || e.is_request() || e.is_body(),
+        Error::Truncated { .. } => true,
+        _ => false,
+    }
+}
 
-    response.json().await.map_err(Into::into)
+/// The delay before a retry, doubled on every attempt up to [`MAX_BACKOFF`].
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt - 1))
+        .min(MAX_BACKOFF)
 }
 
-fn file_to_body<R: Runtime>(channel: Channel<R>, file: File) -> reqwest::Body {
-    let stream = FramedRead::new(file, BytesCodec::new()).map_ok(|r| r.freeze());
+/// Parses a method name, case insensitively so `post` is not sent as an extension method.
+fn parse_method(method: &str) -> Result<Method> {
+    Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+        .map_err(|_| Error::InvalidMethod(method.into()))
+}
+
+fn send_progress(
+    on_progress: &(dyn Fn(ProgressPayload<'_>) + Send + Sync),
+    transfer_id: Option<TransferId>,
+    url: &str,
+    file_path: &str,
+    progress: u64,
+    total: u64,
+) {
+    on_progress(ProgressPayload {
+        transfer_id,
+        url,
+        file_path,
+        progress,
+        total,
+        attempt: 0,
+        cancelled: false,
+        completed: false,
+    });
+}
+
+/// Sends the last progress event of a cancelled transfer.
+fn send_cancelled(
+    on_progress: &(dyn Fn(ProgressPayload<'_>) + Send + Sync),
+    transfer_id: Option<TransferId>,
+    url: &str,
+    file_path: &str,
+) {
+    on_progress(ProgressPayload {
+        transfer_id,
+        url,
+        file_path,
+        progress: 0,
+        total: 0,
+        attempt: 0,
+        cancelled: true,
+        completed: false,
+    });
+}
+
+/// Streams the reader as a request body, reporting the progress of its chunks.
+///
+/// Without a `length`, the events report the bytes read so far as their total.
+fn reader_to_body<F, S>(
+    on_progress: Arc<F>,
+    reader: S,
+    length: Option<u64>,
+    transfer_id: Option<TransferId>,
+    url: String,
+    file_path: String,
+    throttle: Arc<Mutex<Throttle>>,
+) -> reqwest::Body
+where
+    F: Fn(ProgressPayload<'_>) + Send + Sync + 'static,
+    S: AsyncRead + Send + Sync + 'static,
+{
+    let stream = FramedRead::new(reader, BytesCodec::new()).map_ok(|r| r.freeze());
 
     reqwest::Body::wrap_stream(ReadProgressStream::new(
         stream,
-        Box::new(move |progress, total| {
-            let _ = channel.send(&ProgressPayload { progress, total });
+        Box::new(move |progress, read| {
+            let total = length.unwrap_or(read);
+            if let Some((progress, total)) = throttle.lock().unwrap().add(progress, total) {
+                send_progress(
+                    &*on_progress,
+                    transfer_id,
+                    &url,
+                    &file_path,
+                    progress,
+                    total,
+                );
+            }
         }),
     ))
 }
 
+#[derive(Default)]
+pub struct Builder {
+    sinks: Sinks,
+    client: ClientConfig,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the factory creating the sinks of the downloads to `scheme://` paths.
+    ///
+    /// Downloads to paths without a registered scheme are written to the file system with [`FileSink`].
+    pub fn sink(mut self, scheme: impl Into<String>, factory: impl SinkFactory) -> Self {
+        self.sinks.0.insert(scheme.into(), Arc::new(factory));
+        self
+    }
+
+    /// Sets the timeout for connecting to the servers.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.client.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout of the whole transfers, from connecting until the body is read.
+    ///
+    /// The `timeoutMs` argument of the transfers overrides it. There is no timeout by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.client.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends the `http` and `https` requests through the proxy at `url`.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.client.proxy = Some(url.into());
+        self
+    }
+
+    /// Accepts invalid TLS certificates, e.g. self-signed ones, for every server.
+    ///
+    /// This is dangerous, the connections can then be intercepted. It only applies with one
+    /// of the TLS features enabled.
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.client.danger_accept_invalid_certs = accept;
+        self
+    }
+
+    pub fn build<R: Runtime>(self) -> TauriPlugin<R> {
+        let sinks = self.sinks;
+        let client = self.client;
+        PluginBuilder::new("upload")
+            .js_init_script(include_str!("api-iife.js").to_string())
+            .invoke_handler(tauri::generate_handler![
+                commands::download,
+                commands::download_tree,
+                commands::upload,
+                commands::upload_bytes,
+                commands::upload_multipart,
+                commands::cancel
+            ])
+            .setup(move |app, _api| {
+                app.manage(Upload {
+                    client: client.build()?,
+                    sinks,
+                    transfers: Transfers::default(),
+                });
+                Ok(())
+            })
+            .build()
+    }
+}
+
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    PluginBuilder::new("upload")
-        .js_init_script(include_str!("api-iife.js").to_string())
-        .invoke_handler(tauri::generate_handler![download, upload])
-        .build()
+    Builder::default().build()
 }
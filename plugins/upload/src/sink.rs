@@ -0,0 +1,73 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Destinations of the downloaded bytes.
+
+use tokio::{fs::File, io::AsyncWrite};
+
+use std::{collections::HashMap, future::Future, io, pin::Pin, sync::Arc};
+
+/// A destination for the bytes of a download.
+pub type Sink = Pin<Box<dyn AsyncWrite + Send>>;
+
+/// The future returned by [`SinkFactory::create`].
+pub type SinkFuture = Pin<Box<dyn Future<Output = io::Result<Sink>> + Send>>;
+
+/// Creates the [`Sink`] of a download from the path given by the caller.
+///
+/// Implemented for closures, e.g.
+///
+/// ```rust,no_run
+/// use tauri_plugin_upload::{Sink, SinkFuture};
+///
+/// fn memory(_path: &str) -> SinkFuture {
+///     Box::pin(async { Ok(Box::pin(tokio::io::sink()) as Sink) })
+/// }
+///
+/// tauri::Builder::default()
+///     .plugin(tauri_plugin_upload::Builder::new().sink("memory", memory).build());
+/// ```
+pub trait SinkFactory: Send + Sync + 'static {
+    /// Creates the sink for the download to `path`.
+    ///
+    /// The path is given as is, including the scheme.
+    fn create(&self, path: &str) -> SinkFuture;
+}
+
+impl<F: Fn(&str) -> SinkFuture + Send + Sync + 'static> SinkFactory for F {
+    fn create(&self, path: &str) -> SinkFuture {
+        self(path)
+    }
+}
+
+/// The default sink factory, writing to the file at the given path.
+pub struct FileSink;
+
+impl SinkFactory for FileSink {
+    fn create(&self, path: &str) -> SinkFuture {
+        let path = path.to_string();
+        Box::pin(async move { Ok(Box::pin(File::create(path).await?) as Sink) })
+    }
+}
+
+/// The registered sink factories, indexed by scheme.
+#[derive(Default)]
+pub(crate) struct Sinks(pub(crate) HashMap<String, Arc<dyn SinkFactory>>);
+
+impl Sinks {
+    /// Gets the factory for the scheme of `path`, e.g. `vault` for `vault://secrets/key`,
+    /// defaulting to [`FileSink`] if the path has no registered scheme.
+    pub(crate) fn get(&self, path: &str) -> Arc<dyn SinkFactory> {
+        path.split_once("://")
+            .and_then(|(scheme, _)| self.0.get(scheme))
+            .cloned()
+            .unwrap_or_else(|| Arc::new(FileSink))
+    }
+
+    /// Whether `path` is written to a custom sink rather than to a file.
+    pub(crate) fn is_registered(&self, path: &str) -> bool {
+        path.split_once("://")
+            .map_or(false, |(scheme, _)| self.0.contains_key(scheme))
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Origins of the downloaded bytes.
+
+use base64::Engine;
+use bytes::{Bytes, BytesMut};
+use futures_util::{stream, Stream, TryStreamExt};
+use reqwest::{
+    header::{self, HeaderMap},
+    Method, StatusCode, Url,
+};
+use tokio::{
+    fs::File,
+    io::{AsyncSeekExt, SeekFrom},
+};
+use tokio_util::codec::{BytesCodec, FramedRead};
+
+use std::{collections::HashMap, pin::Pin, time::Duration};
+
+use crate::{Error, Result};
+
+pub(crate) type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>;
+
+/// The bytes of a download and their total length, `0` if unknown.
+pub(crate) struct Source {
+    /// The position of the first streamed byte, `0` unless a resumed download was honored.
+    pub(crate) offset: u64,
+    pub(crate) total: u64,
+    /// The HTTP status of the response, `200` for the other URLs.
+    pub(crate) status: u16,
+    /// The headers of the HTTP response, empty for the other URLs.
+    pub(crate) headers: HashMap<String, String>,
+    pub(crate) stream: ByteStream,
+}
+
+impl Source {
+    /// Opens `url`, which is either an `http(s)://`, a `file://` or a `data:` URL.
+    ///
+    /// The headers are only sent with HTTP requests, made with the `client`.
+    pub(crate) async fn open(
+        client: &reqwest::Client,
+        url: &str,
+        headers: HashMap<String, String>,
+    ) -> Result<Self> {
+        Self::open_from(client, url, headers, Method::GET, None, 0, None).await
+    }
+
+    /// Opens `url` from the byte at `offset`, with a `Range` request for HTTP URLs.
+    ///
+    /// Servers may ignore the range and send the whole content, which is reported with
+    /// an [`offset`](Self::offset) of `0`. The `method` and JSON `body` are only used by HTTP
+    /// requests, and the `timeout` overrides the one of the client.
+    pub(crate) async fn open_from(
+        client: &reqwest::Client,
+        url: &str,
+        headers: HashMap<String, String>,
+        method: Method,
+        body: Option<serde_json::Value>,
+        offset: u64,
+        timeout: Option<Duration>,
+    ) -> Result<Self> {
+        let parsed = Url::parse(url).map_err(|e| Error::InvalidUrl(e.to_string()))?;
+        match parsed.scheme() {
+            "http" | "https" => {
+                let mut request = client.request(method, parsed);
+                if let Some(body) = body {
+                    request = request.json(&body);
+                }
+                if let Some(timeout) = timeout {
+                    request = request.timeout(timeout);
+                }
+                // Loop trought the headers keys and values
+                // and add them to the request object.
+                for (key, value) in headers {
+                    request = request.header(&key, value);
+                }
+                if offset > 0 {
+                    request = request.header(header::RANGE, format!("bytes={offset}-"));
+                }
+                let response = request.send().await?;
+                let length = response.content_length().unwrap_or(0);
+                let (offset, total) = match response.status() {
+                    StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => {
+                        return Err(Error::RangeNotSatisfiable(offset))
+                    }
+                    StatusCode::PARTIAL_CONTENT if offset > 0 => {
+                        match content_range(response.headers()) {
+                            Some((start, Some(total))) => (start, total),
+                            Some((start, None)) => (start, 0),
+                            None => (offset, offset + length),
+                        }
+                    }
+                    _ => (0, length),
+                };
+                Ok(Self {
+                    offset,
+                    total,
+                    status: response.status().as_u16(),
+                    headers: header_map(response.headers()),
+                    stream: Box::pin(response.bytes_stream().map_err(Into::into)),
+                })
+            }
+            "file" => {
+                let path = parsed
+                    .to_file_path()
+                    .map_err(|_| Error::InvalidUrl(url.into()))?;
+                let mut file = File::open(path).await?;
+                let total = file.metadata().await?.len();
+                if offset > total {
+                    return Err(Error::RangeNotSatisfiable(offset));
+                }
+                file.seek(SeekFrom::Start(offset)).await?;
+                Ok(Self {
+                    offset,
+                    total,
+                    status: StatusCode::OK.as_u16(),
+                    headers: HashMap::new(),
+                    stream: Box::pin(
+                        FramedRead::new(file, BytesCodec::new())
+                            .map_ok(BytesMut::freeze)
+                            .map_err(Into::into),
+                    ),
+                })
+            }
+            "data" => {
+                let mut data = decode_data_url(url)?;
+                let total = data.len() as u64;
+                if offset > total {
+                    return Err(Error::RangeNotSatisfiable(offset));
+                }
+                data.drain(..offset as usize);
+                Ok(Self {
+                    offset,
+                    total,
+                    status: StatusCode::OK.as_u16(),
+                    headers: HashMap::new(),
+                    stream: Box::pin(stream::once(async move { Ok(Bytes::from(data)) })),
+                })
+            }
+            scheme => Err(Error::UnsupportedScheme(scheme.into())),
+        }
+    }
+}
+
+/// The headers with a text value, the values of a repeated header joined with `, `.
+fn header_map(headers: &HeaderMap) -> HashMap<String, String> {
+    let mut map = HashMap::<String, String>::new();
+    for (name, value) in headers {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        map.entry(name.as_str().into())
+            .and_modify(|values| {
+                values.push_str(", ");
+                values.push_str(value);
+            })
+            .or_insert_with(|| value.into());
+    }
+    map
+}
+
+/// The start and the total length of a `Content-Range: bytes <start>-<end>/<total>` header,
+/// where the total may be `*` if unknown.
+fn content_range(headers: &HeaderMap) -> Option<(u64, Option<u64>)> {
+    let value = headers.get(header::CONTENT_RANGE)?.to_str().ok()?;
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let (start, _) = range.split_once('-')?;
+    Some((start.trim().parse().ok()?, total.trim().parse().ok()))
+}
+
+/// Decodes the payload of a `data:[<mediatype>][;base64],<data>` URL.
+fn decode_data_url(url: &str) -> Result<Vec<u8>> {
+    let (header, data) = url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(','))
+        .ok_or_else(|| Error::InvalidUrl(url.into()))?;
+    let data = percent_encoding::percent_decode_str(data).collect::<Vec<u8>>();
+    if header.ends_with(";base64") {
+        // base64 payloads may be wrapped or padded with whitespace
+        let data: Vec<u8> = data
+            .into_iter()
+            .filter(|b| !b.is_ascii_whitespace())
+            .collect();
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| Error::InvalidUrl(format!("invalid base64 data URL: {e}")))
+    } else {
+        Ok(data)
+    }
+}
@@ -0,0 +1,49 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::time::{Duration, Instant};
+
+/// Sums the progress of the chunks transferred within an interval, so at most one progress
+/// event is sent per interval.
+pub(crate) struct Throttle {
+    interval: Duration,
+    last: Option<Instant>,
+    /// The progress of the chunks not reported yet.
+    pending: u64,
+    total: u64,
+}
+
+impl Throttle {
+    /// A throttle sending at most one event every `interval`, or one per chunk without it.
+    pub(crate) fn new(interval: Option<Duration>) -> Self {
+        Self {
+            interval: interval.unwrap_or_default(),
+            last: None,
+            pending: 0,
+            total: 0,
+        }
+    }
+
+    /// Adds the progress of a chunk, returning the progress and total to report once the
+    /// interval elapsed since the last report.
+    pub(crate) fn add(&mut self, progress: u64, total: u64) -> Option<(u64, u64)> {
+        self.pending += progress;
+        self.total = total;
+        let now = Instant::now();
+        let elapsed = self
+            .last
+            .map_or(true, |last| now.duration_since(last) >= self.interval);
+        if elapsed {
+            self.last = Some(now);
+            Some((std::mem::take(&mut self.pending), total))
+        } else {
+            None
+        }
+    }
+
+    /// The progress not reported yet, sent once the transfer completed.
+    pub(crate) fn flush(&mut self) -> Option<(u64, u64)> {
+        (self.pending > 0).then(|| (std::mem::take(&mut self.pending), self.total))
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Cancellation of the transfers started with an ID or a [`CancelSignal`].
+
+use tokio_util::sync::CancellationToken;
+
+use std::{collections::HashMap, future::Future, sync::Mutex};
+
+use crate::{Error, Result, TransferId};
+
+/// Cancels the transfers it is given to, e.g. once nothing listens to their progress anymore.
+///
+/// The clones of a signal share its state.
+#[derive(Debug, Clone, Default)]
+pub struct CancelSignal(CancellationToken);
+
+impl CancelSignal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the transfers, failing them with [`Error::Cancelled`].
+    pub fn cancel(&self) {
+        self.0.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+/// The cancellation tokens of the transfers in flight, indexed by transfer ID.
+#[derive(Default)]
+pub(crate) struct Transfers(Mutex<HashMap<TransferId, CancellationToken>>);
+
+impl Transfers {
+    /// Registers the token of a transfer, until [`Self::run`] removes it.
+    fn token(&self, id: TransferId) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.0.lock().unwrap().insert(id, token.clone());
+        token
+    }
+
+    /// Cancels the transfer, failing it with [`Error::Cancelled`].
+    ///
+    /// Does nothing if no transfer with this ID is in flight.
+    pub(crate) fn cancel(&self, id: TransferId) {
+        if let Some(token) = self.0.lock().unwrap().get(&id) {
+            token.cancel();
+        }
+    }
+
+    /// Runs the transfer until it completes, or is cancelled by its ID or its signal.
+    ///
+    /// Transfers without ID can only be cancelled by their signal.
+    pub(crate) async fn run<T>(
+        &self,
+        id: Option<TransferId>,
+        signal: Option<&CancelSignal>,
+        transfer: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let token = id.map(|id| self.token(id));
+        let cancelled = async {
+            match (&token, signal) {
+                (Some(token), Some(signal)) => tokio::select! {
+                    _ = token.cancelled() => {},
+                    _ = signal.0.cancelled() => {},
+                },
+                (Some(token), None) => token.cancelled().await,
+                (None, Some(signal)) => signal.0.cancelled().await,
+                (None, None) => std::future::pending().await,
+            }
+        };
+        // dropping the transfer future closes the connection and the file
+        let result = tokio::select! {
+            result = transfer => result,
+            _ = cancelled => Err(Error::Cancelled),
+        };
+        if let Some(id) = id {
+            self.0.lock().unwrap().remove(&id);
+        }
+        result
+    }
+}
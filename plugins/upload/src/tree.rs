@@ -0,0 +1,618 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Mirroring of a remote directory, listed by a [`Lister`].
+
+use futures_util::{stream, StreamExt, TryStreamExt};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{
+    fs::{self, File},
+    io::{AsyncReadExt, AsyncWriteExt},
+};
+
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::UNIX_EPOCH,
+};
+
+use crate::{source::Source, Error, Result};
+
+/// The characters escaped in a path segment of a file URL.
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<propfind xmlns="DAV:"><prop><resourcetype/><getcontentlength/><getlastmodified/></prop></propfind>"#;
+
+/// A remote file to mirror.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteFile {
+    /// The path of the file, relative to the base URL and the destination directory.
+    pub path: String,
+    /// The size of the file in bytes.
+    pub size: Option<u64>,
+    /// The last modification of the file, in milliseconds since the Unix epoch.
+    pub modified_at_ms: Option<u64>,
+    /// The hex encoded SHA-256 digest of the file.
+    pub sha256: Option<String>,
+}
+
+/// How the remote files are enumerated.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Lister {
+    /// Walks the collections under the base URL with WebDAV `PROPFIND` requests.
+    WebDav,
+    /// Fetches a JSON array of [`RemoteFile`]s from `url`, resolved against the base URL.
+    Index { url: String },
+    /// The files given by the caller.
+    Files { files: Vec<RemoteFile> },
+}
+
+impl Lister {
+    async fn list(
+        self,
+        client: &reqwest::Client,
+        base: &Url,
+        headers: &HashMap<String, String>,
+    ) -> Result<Vec<RemoteFile>> {
+        match self {
+            Self::WebDav => list_webdav(client, base, headers).await,
+            Self::Index { url } => {
+                let url = base
+                    .join(&url)
+                    .map_err(|e| Error::InvalidUrl(e.to_string()))?;
+                let mut request = client.get(url);
+                for (key, value) in headers {
+                    request = request.header(key, value);
+                }
+                let response = request.send().await?.error_for_status()?;
+                response.json().await.map_err(Into::into)
+            }
+            Self::Files { files } => Ok(files),
+        }
+    }
+}
+
+/// The aggregate progress of a tree download.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeProgress<'a> {
+    /// The file that made progress.
+    pub path: &'a str,
+    /// The number of files downloaded, skipped or failed so far.
+    pub completed_files: u64,
+    pub total_files: u64,
+    /// The bytes received so far, for all the files.
+    pub progress: u64,
+    /// The sum of the listed sizes of the files to download, `0` if any is unknown.
+    pub total: u64,
+}
+
+/// The error of a single file, which does not abort the other downloads.
+#[derive(Debug, Serialize)]
+pub struct FileError {
+    pub path: String,
+    pub error: String,
+}
+
+/// The outcome of each listed file.
+#[derive(Debug, Default, Serialize)]
+pub struct TreeSummary {
+    /// The files that were downloaded.
+    pub downloaded: Vec<String>,
+    /// The files that already matched locally.
+    pub skipped: Vec<String>,
+    /// The files that could not be downloaded.
+    pub failed: Vec<FileError>,
+}
+
+enum Outcome {
+    Downloaded,
+    Skipped,
+}
+
+/// Downloads the files listed under `base_url` into `dest_dir`, `concurrency` at a time,
+/// skipping the ones whose local copy already matches.
+pub(crate) async fn download_tree<F: Fn(TreeProgress<'_>) + Sync>(
+    client: &reqwest::Client,
+    base_url: &str,
+    dest_dir: &Path,
+    lister: Lister,
+    headers: HashMap<String, String>,
+    concurrency: usize,
+    on_progress: F,
+) -> Result<TreeSummary> {
+    let mut base = Url::parse(base_url).map_err(|e| Error::InvalidUrl(e.to_string()))?;
+    // the files are resolved relative to the base, which must be a directory
+    if !base.path().ends_with('/') {
+        base.set_path(&format!("{}/", base.path()));
+    }
+
+    let files = lister.list(client, &base, &headers).await?;
+    let total_files = files.len() as u64;
+    let total = files
+        .iter()
+        .map(|f| f.size)
+        .sum::<Option<u64>>()
+        .unwrap_or(0);
+    let completed = AtomicU64::new(0);
+    let progress = AtomicU64::new(0);
+    let report = |path: &str, received: u64| {
+        on_progress(TreeProgress {
+            path,
+            completed_files: completed.load(Ordering::Relaxed),
+            total_files,
+            progress: progress.fetch_add(received, Ordering::Relaxed) + received,
+            total,
+        })
+    };
+
+    let outcomes = stream::iter(files)
+        .map(|file| {
+            let (base, headers, report, completed) = (&base, &headers, &report, &completed);
+            async move {
+                let result = mirror_file(client, base, dest_dir, &file, headers, report).await;
+                completed.fetch_add(1, Ordering::Relaxed);
+                report(&file.path, 0);
+                (file.path, result)
+            }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut summary = TreeSummary::default();
+    for (path, outcome) in outcomes {
+        match outcome {
+            Ok(Outcome::Downloaded) => summary.downloaded.push(path),
+            Ok(Outcome::Skipped) => summary.skipped.push(path),
+            Err(e) => summary.failed.push(FileError {
+                path,
+                error: e.to_string(),
+            }),
+        }
+    }
+    Ok(summary)
+}
+
+async fn mirror_file(
+    client: &reqwest::Client,
+    base: &Url,
+    dest_dir: &Path,
+    file: &RemoteFile,
+    headers: &HashMap<String, String>,
+    report: &(dyn Fn(&str, u64) + Sync),
+) -> Result<Outcome> {
+    let destination = dest_dir.join(local_path(&file.path)?);
+    if matches_local(file, &destination).await {
+        return Ok(Outcome::Skipped);
+    }
+
+    let encoded = file
+        .path
+        .split('/')
+        .map(|segment| utf8_percent_encode(segment, SEGMENT).to_string())
+        .collect::<Vec<_>>()
+        .join("/");
+    let url = base
+        .join(&encoded)
+        .map_err(|e| Error::InvalidUrl(e.to_string()))?;
+
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    // a failed download must not leave a file that looks up to date
+    let mut partial = destination.clone().into_os_string();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+
+    let Source {
+        status, mut stream, ..
+    } = Source::open(client, url.as_str(), headers.clone()).await?;
+    // an error page must not replace the local copy
+    if !(200..300).contains(&status) {
+        return Err(Error::HttpStatus(status));
+    }
+    let written = async {
+        let mut out = File::create(&partial).await?;
+        while let Some(chunk) = stream.try_next().await? {
+            out.write_all(&chunk).await?;
+            report(&file.path, chunk.len() as u64);
+        }
+        out.shutdown().await?;
+        Ok::<_, Error>(())
+    }
+    .await;
+    if let Err(e) = written {
+        let _ = fs::remove_file(&partial).await;
+        return Err(e);
+    }
+    fs::rename(&partial, &destination).await?;
+    Ok(Outcome::Downloaded)
+}
+
+/// The relative local path of a remote file, which must stay in the destination directory.
+fn local_path(path: &str) -> Result<PathBuf> {
+    let local: PathBuf = path.split('/').filter(|s| !s.is_empty()).collect();
+    let contained = local
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)));
+    if contained && local.components().next().is_some() {
+        Ok(local)
+    } else {
+        Err(Error::InvalidPath(path.into()))
+    }
+}
+
+/// Whether the local copy matches the digest of the remote file or, without digest,
+/// has its size and was modified after it. Files without any of them are always downloaded.
+async fn matches_local(file: &RemoteFile, path: &Path) -> bool {
+    let Ok(metadata) = fs::metadata(path).await else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+    if let Some(expected) = &file.sha256 {
+        return sha256(path)
+            .await
+            .map_or(false, |digest| digest.eq_ignore_ascii_case(expected));
+    }
+    if file.size.is_none() && file.modified_at_ms.is_none() {
+        return false;
+    }
+    let size_matches = file.size.map_or(true, |size| size == metadata.len());
+    let up_to_date = file.modified_at_ms.map_or(true, |remote| {
+        metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map_or(false, |local| local.as_millis() >= u128::from(remote))
+    });
+    size_matches && up_to_date
+}
+
+async fn sha256(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Lists the files under `base`, one `Depth: 1` request per collection since
+/// servers commonly refuse `Depth: infinity`.
+async fn list_webdav(
+    client: &reqwest::Client,
+    base: &Url,
+    headers: &HashMap<String, String>,
+) -> Result<Vec<RemoteFile>> {
+    let propfind = Method::from_bytes(b"PROPFIND").expect("PROPFIND is a valid method");
+    let mut files = Vec::new();
+    let mut pending = vec![base.clone()];
+
+    while let Some(collection) = pending.pop() {
+        let mut request = client
+            .request(propfind.clone(), collection.clone())
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml")
+            .body(PROPFIND_BODY);
+        for (key, value) in headers {
+            request = request.header(key, value);
+        }
+        let xml = request.send().await?.error_for_status()?.text().await?;
+
+        for response in elements(&xml, "response") {
+            let Some(href) = elements(response, "href")
+                .first()
+                .map(|h| unescape(h.trim()))
+            else {
+                continue;
+            };
+            let url = collection
+                .join(&href)
+                .map_err(|e| Error::InvalidUrl(e.to_string()))?;
+            let Some(relative) = url.path().strip_prefix(base.path()) else {
+                continue;
+            };
+            let relative = percent_decode_str(relative).decode_utf8_lossy();
+
+            if !elements(response, "collection").is_empty() {
+                // the response of a collection includes itself
+                if url.path().trim_end_matches('/') != collection.path().trim_end_matches('/') {
+                    let mut url = url.clone();
+                    if !url.path().ends_with('/') {
+                        url.set_path(&format!("{}/", url.path()));
+                    }
+                    pending.push(url);
+                }
+                continue;
+            }
+
+            files.push(RemoteFile {
+                path: relative.into_owned(),
+                size: elements(response, "getcontentlength")
+                    .first()
+                    .and_then(|s| s.trim().parse().ok()),
+                modified_at_ms: elements(response, "getlastmodified")
+                    .first()
+                    .and_then(|s| httpdate::parse_http_date(s.trim()).ok())
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as u64),
+                sha256: None,
+            });
+        }
+    }
+    Ok(files)
+}
+
+/// The contents of the elements named `name` in any namespace, e.g. `<D:href>` for `href`.
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+
+        let qualified = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        let local = qualified.rsplit(':').next().unwrap_or_default();
+        if local != name || tag.starts_with(['/', '?', '!']) {
+            continue;
+        }
+        if tag.ends_with('/') {
+            found.push("");
+            continue;
+        }
+        let close = format!("</{qualified}>");
+        match rest.find(&close) {
+            Some(end) => {
+                found.push(&rest[..end]);
+                rest = &rest[end + close.len()..];
+            }
+            None => break,
+        }
+    }
+    found
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULTISTATUS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:">
+  <D:response>
+    <D:href>/files/</D:href>
+    <D:propstat><D:prop><D:resourcetype><D:collection/></D:resourcetype></D:prop></D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/files/docs/</D:href>
+    <D:propstat><D:prop><D:resourcetype><D:collection /></D:resourcetype></D:prop></D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/files/a%20&amp;%20b.txt</D:href>
+    <D:propstat>
+      <D:prop>
+        <D:resourcetype/>
+        <D:getcontentlength>12</D:getcontentlength>
+      </D:prop>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+
+    fn remote_file(size: Option<u64>, modified_at_ms: Option<u64>) -> RemoteFile {
+        RemoteFile {
+            path: "file.txt".into(),
+            size,
+            modified_at_ms,
+            sha256: None,
+        }
+    }
+
+    #[test]
+    fn elements_match_any_namespace() {
+        let responses = elements(MULTISTATUS, "response");
+        assert_eq!(responses.len(), 3);
+        assert_eq!(elements(responses[0], "href"), vec!["/files/"]);
+        assert_eq!(elements(responses[2], "getcontentlength"), vec!["12"]);
+        assert_eq!(
+            elements(
+                "<a:href>/x</a:href><b:href>/y</b:href><href>/z</href>",
+                "href"
+            ),
+            vec!["/x", "/y", "/z"]
+        );
+        // only the local name is matched, not a prefix of it
+        assert!(elements("<D:hrefs>/x</D:hrefs>", "href").is_empty());
+        assert_eq!(elements(r#"<href lang="en">/x</href>"#, "href"), vec!["/x"]);
+    }
+
+    #[test]
+    fn elements_find_nested_collections() {
+        let responses = elements(MULTISTATUS, "response");
+        assert_eq!(elements(responses[0], "collection"), vec![""]);
+        assert_eq!(elements(responses[1], "collection"), vec![""]);
+        assert!(elements(responses[2], "collection").is_empty());
+        // the resource type of a file is an empty element too
+        assert_eq!(elements(responses[2], "resourcetype"), vec![""]);
+    }
+
+    #[test]
+    fn elements_stop_at_an_unclosed_element() {
+        assert!(elements("<href>/x", "href").is_empty());
+        assert!(elements("<href", "href").is_empty());
+    }
+
+    #[test]
+    fn unescapes_entities() {
+        let href = elements(MULTISTATUS, "href")[2];
+        assert_eq!(unescape(href), "/files/a%20&%20b.txt");
+        assert_eq!(unescape("&lt;&gt;&quot;&apos;"), "<>\"'");
+        // an escaped ampersand is only unescaped once
+        assert_eq!(unescape("&amp;lt;"), "&lt;");
+    }
+
+    #[test]
+    fn local_path_stays_in_the_destination() {
+        assert_eq!(
+            local_path("docs/a.txt").unwrap(),
+            Path::new("docs").join("a.txt")
+        );
+        assert_eq!(
+            local_path("/docs//a.txt").unwrap(),
+            Path::new("docs").join("a.txt")
+        );
+        for path in ["", "/", "../a.txt", "docs/../../a.txt", "./a.txt"] {
+            assert!(
+                matches!(local_path(path), Err(Error::InvalidPath(_))),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn matches_local_copy() {
+        let dir = std::env::temp_dir().join(format!("tauri-upload-tree-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("file.txt");
+        std::fs::write(&path, "hello").unwrap();
+
+        tauri::async_runtime::block_on(async {
+            assert!(!matches_local(&remote_file(None, None), &path).await);
+            assert!(matches_local(&remote_file(Some(5), None), &path).await);
+            assert!(!matches_local(&remote_file(Some(4), None), &path).await);
+            assert!(matches_local(&remote_file(Some(5), Some(0)), &path).await);
+            assert!(!matches_local(&remote_file(Some(5), Some(u64::MAX)), &path).await);
+            assert!(!matches_local(&remote_file(Some(5), None), &dir.join("missing")).await);
+            assert!(!matches_local(&remote_file(Some(5), None), &dir).await);
+
+            let mut file = remote_file(Some(4), None);
+            // the digest takes precedence over the size
+            file.sha256 =
+                Some("2CF24DBA5FB0A30E26E83B2AC5B9E29E1B161E5C1FA7425E73043362938B9824".into());
+            assert!(matches_local(&file, &path).await);
+            file.sha256 = Some(hex::encode([0u8; 32]));
+            assert!(!matches_local(&file, &path).await);
+        });
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Serves `hello` at `/ok.txt` and a 404 error page at any other path.
+    fn serve() -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buffer) {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => request.extend_from_slice(&buffer[..read]),
+                    }
+                }
+                let (status, body) = if request.starts_with(b"GET /ok.txt ") {
+                    ("200 OK", "hello")
+                } else {
+                    ("404 Not Found", "not found")
+                };
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn error_status_fails_the_file() {
+        let dir =
+            std::env::temp_dir().join(format!("tauri-upload-tree-status-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("missing.txt"), "local").unwrap();
+        let file = |path: &str| RemoteFile {
+            path: path.into(),
+            size: None,
+            modified_at_ms: None,
+            sha256: None,
+        };
+
+        let summary = tauri::async_runtime::block_on(download_tree(
+            &reqwest::Client::new(),
+            &serve(),
+            &dir,
+            Lister::Files {
+                files: vec![file("ok.txt"), file("missing.txt")],
+            },
+            HashMap::new(),
+            1,
+            |_| {},
+        ))
+        .unwrap();
+
+        assert_eq!(summary.downloaded, vec!["ok.txt".to_string()]);
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(summary.failed[0].path, "missing.txt");
+        assert_eq!(
+            summary.failed[0].error,
+            "the server responded with status 404"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("ok.txt")).unwrap(),
+            "hello"
+        );
+        // the local copy is kept, without a partial file
+        assert_eq!(
+            std::fs::read_to_string(dir.join("missing.txt")).unwrap(),
+            "local"
+        );
+        assert!(!dir.join("missing.txt.part").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
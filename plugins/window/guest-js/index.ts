@@ -31,6 +31,12 @@ declare global {
 }
 
 type Theme = "light" | "dark";
+/**
+ * What double-clicking the title bar does, as configured in the system settings.
+ *
+ * @since 2.0.0
+ */
+type TitleBarDoubleClickAction = "maximize" | "minimize" | "none";
 type TitleBarStyle = "visible" | "transparent" | "overlay";
 
 /**
@@ -61,6 +67,157 @@ interface ScaleFactorChanged {
   size: PhysicalSize;
 }
 
+/**
+ * The payload for the `fullscreenChanged` event.
+ *
+ * @since 2.0.0
+ */
+interface FullscreenChanged {
+  /** Whether the window is now fullscreen. */
+  fullscreen: boolean;
+  /** The name of the monitor the window is on, if known. */
+  monitor: string | null;
+}
+
+/**
+ * The maximized and minimized state of a window.
+ *
+ * @since 2.0.0
+ */
+interface DisplayState {
+  maximized: boolean;
+  /**
+   * A minimized window can also be maximized,
+   * it is then maximized once restored.
+   */
+  minimized: boolean;
+}
+
+/**
+ * The payload for the `displayStateChanged` event.
+ *
+ * @since 2.0.0
+ */
+interface DisplayStateChanged extends DisplayState {
+  /** The state before the transition. */
+  previous: DisplayState;
+  /**
+   * `programmatic` when the transition was requested with the window API,
+   * `user` for anything else, e.g. the window controls or a keyboard shortcut.
+   */
+  cause: "programmatic" | "user";
+}
+
+/**
+ * The outer position and inner size of a window, in logical pixels.
+ *
+ * @since 2.0.0
+ */
+interface WindowRect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+/**
+ * How the progress of an animation accelerates.
+ *
+ * @since 2.0.0
+ */
+type Easing = "linear" | "easeIn" | "easeOut" | "easeInOut";
+
+/**
+ * The payload for the `animationFinished` event.
+ *
+ * @since 2.0.0
+ */
+interface AnimationFinished {
+  /** The geometry of the window once the animation stopped. */
+  rect: WindowRect;
+  /**
+   * Whether the animation stopped before reaching its target,
+   * because the window was moved by something else or another animation started.
+   */
+  cancelled: boolean;
+}
+
+/**
+ * The size constraints applied to a window, in physical pixels.
+ *
+ * @since 2.0.0
+ */
+interface SizeConstraints {
+  /** The scale factor the constraints were converted with. */
+  scaleFactor: number;
+  minSize: PhysicalSize | null;
+  maxSize: PhysicalSize | null;
+}
+
+/**
+ * The state of a webview document, captured with
+ * {@link WindowManager.captureState}.
+ *
+ * @since 2.0.0
+ */
+interface WebviewState {
+  /** The scroll position of the document, in CSS pixels. */
+  scrollX: number;
+  scrollY: number;
+  /** The entries of `sessionStorage`, if captured. */
+  sessionStorage: Record<string, string> | null;
+}
+
+/**
+ * The payload for the `geometryCommitted` event.
+ *
+ * @since 2.0.0
+ */
+interface GeometryCommitted {
+  /** The outer position of the window. */
+  position: PhysicalPosition;
+  /** The inner size of the window. */
+  size: PhysicalSize;
+  /** Whether the window is maximized. */
+  maximized: boolean;
+  /** Whether the window is fullscreen. */
+  fullscreen: boolean;
+  /** The monitor the window ended up on, if known. */
+  monitor: Monitor | null;
+}
+
+/**
+ * A window icon: a path to an icon file, the bytes of a PNG or ICO image,
+ * or raw RGBA pixels.
+ *
+ * @since 2.0.0
+ */
+type IconSource =
+  | string
+  | Uint8Array
+  | { rgba: Uint8Array; width: number; height: number };
+
+function serializeIcon(icon: IconSource): unknown {
+  if (typeof icon === "string") return icon;
+  if (icon instanceof Uint8Array) return Array.from(icon);
+  return {
+    rgba: Array.from(icon.rgba),
+    width: icon.width,
+    height: icon.height,
+  };
+}
+
+/**
+ * A step of an input method composition: `preedit` carries the text being
+ * composed and `commit` the final text, empty if the composition was cancelled.
+ *
+ * @since 2.0.0
+ */
+type ImeEvent =
+  | { type: "start" }
+  | { type: "preedit"; text: string }
+  | { type: "commit"; text: string };
+
 /** The file drop event types. */
 type FileDropEvent =
   | { type: "hover"; paths: string[] }
@@ -563,6 +720,24 @@ class WindowManager extends WebviewWindowHandle {
     });
   }
 
+  /**
+   * Gets the window's current maximized and minimized state at once,
+   * e.g. to initialize a custom title bar
+   * before listening to {@link WindowManager.onDisplayStateChanged}.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * const { maximized, minimized } = await appWindow.displayState();
+   * ```
+   *
+   * @since 2.0.0
+   * */
+  async displayState(): Promise<DisplayState> {
+    return window.__TAURI_INVOKE__("plugin:window|display_state", {
+      label: this.label,
+    });
+  }
+
   /**
    * Gets the window's current decorated state.
    * @example
@@ -656,6 +831,112 @@ class WindowManager extends WebviewWindowHandle {
     });
   }
 
+  /**
+   * Gets the action the system performs when the title bar is double-clicked.
+   *
+   * #### Platform-specific
+   *
+   * - **Windows:** Always `maximize`.
+   * - **Linux:** Read from the `gtk-titlebar-double-click` setting, `maximize` for the actions other than minimizing or doing nothing.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * const action = await appWindow.titleBarDoubleClickAction();
+   * ```
+   *
+   * @since 2.0.0
+   */
+  async titleBarDoubleClickAction(): Promise<TitleBarDoubleClickAction> {
+    return window.__TAURI_INVOKE__(
+      "plugin:window|title_bar_double_click_action",
+      {
+        label: this.label,
+      }
+    );
+  }
+
+  /**
+   * Gets the size of the rendered document, reported by the webview whenever it changes.
+   *
+   * Blocks fill the width of the window, so the width only shrinks with the content
+   * when the root element is sized by it, e.g. with `html { width: max-content }`.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * const size = await appWindow.contentSize();
+   * ```
+   *
+   * @returns The document size.
+   *
+   * @since 2.0.0
+   */
+  async contentSize(): Promise<LogicalSize> {
+    return window
+      .__TAURI_INVOKE__<{ width: number; height: number }>(
+        "plugin:window|content_size",
+        {
+          label: this.label,
+        }
+      )
+      .then(({ width, height }) => new LogicalSize(width, height));
+  }
+
+  /**
+   * Captures the scroll position of the document, and optionally its
+   * `sessionStorage` entries, to restore them with
+   * {@link WindowManager.restoreState}.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * const state = await appWindow.captureState({ sessionStorage: true });
+   * ```
+   *
+   * @returns The state of the document.
+   *
+   * @since 2.0.0
+   */
+  async captureState(
+    options: { sessionStorage?: boolean } = {}
+  ): Promise<WebviewState> {
+    return window.__TAURI_INVOKE__("plugin:window|capture_state", {
+      label: this.label,
+      sessionStorage: options.sessionStorage ?? false,
+    });
+  }
+
+  /**
+   * Restores a state captured with {@link WindowManager.captureState} once
+   * the document is loaded, e.g. in a window recreated after a crash.
+   *
+   * With `reload`, the document is reloaded first. The `sessionStorage`
+   * entries are restored before the reload, so the new document reads them
+   * on startup.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * const state = await appWindow.captureState({ sessionStorage: true });
+   * await appWindow.restoreState(state, { reload: true });
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async restoreState(
+    state: WebviewState,
+    options: { reload?: boolean } = {}
+  ): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|restore_state", {
+      label: this.label,
+      state,
+      reload: options.reload ?? false,
+    });
+  }
+
   // Setters
 
   /**
@@ -950,6 +1231,71 @@ class WindowManager extends WebviewWindowHandle {
     });
   }
 
+  /**
+   * Forces the window into the light or dark theme, regardless of the system theme.
+   * Pass `null` to follow the system theme again.
+   *
+   * A `tauri://theme-changed` event with the effective theme is emitted on the window.
+   *
+   * #### Platform-specific
+   *
+   * - **Linux:** The theme applies to all the windows of the application.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * await appWindow.setTheme('dark');
+   * ```
+   *
+   * @param theme The theme to use, or `null` to follow the system theme.
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async setTheme(theme: Theme | null): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|set_theme", {
+      label: this.label,
+      value: theme,
+    });
+  }
+
+  /**
+   * Resizes the window to the size of its document, e.g. for popovers hugging their content.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow, LogicalSize } from '@tauri-apps/window';
+   * await appWindow.fitToContent(new LogicalSize(400, 600));
+   * // follow the dynamic content
+   * await appWindow.onContentSizeChanged(() => appWindow.fitToContent());
+   * ```
+   *
+   * @param maxSize The size the window must not exceed.
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async fitToContent(maxSize?: LogicalSize | PhysicalSize): Promise<void> {
+    if (maxSize && maxSize.type !== "Logical" && maxSize.type !== "Physical") {
+      throw new Error(
+        "the `maxSize` argument must be either a LogicalSize or a PhysicalSize instance"
+      );
+    }
+
+    return window.__TAURI_INVOKE__("plugin:window|fit_to_content", {
+      label: this.label,
+      maxSize: maxSize
+        ? {
+            type: maxSize.type,
+            data: {
+              width: maxSize.width,
+              height: maxSize.height,
+            },
+          }
+        : null,
+    });
+  }
+
   /**
    * Whether the window should always be on top of other windows.
    * @example
@@ -1023,6 +1369,9 @@ class WindowManager extends WebviewWindowHandle {
 
   /**
    * Sets the window minimum inner size. If the `size` argument is not provided, the constraint is unset.
+   *
+   * A {@link LogicalSize} is kept when the window moves to a monitor with another scale factor,
+   * while a {@link PhysicalSize} stays the same number of pixels.
    * @example
    * ```typescript
    * import { appWindow, PhysicalSize } from '@tauri-apps/window';
@@ -1059,6 +1408,9 @@ class WindowManager extends WebviewWindowHandle {
 
   /**
    * Sets the window maximum inner size. If the `size` argument is undefined, the constraint is unset.
+   *
+   * A {@link LogicalSize} is kept when the window moves to a monitor with another scale factor,
+   * while a {@link PhysicalSize} stays the same number of pixels.
    * @example
    * ```typescript
    * import { appWindow, LogicalSize } from '@tauri-apps/window';
@@ -1094,118 +1446,353 @@ class WindowManager extends WebviewWindowHandle {
   }
 
   /**
-   * Sets the window outer position.
+   * Sets the window minimum inner size in logical pixels, kept for every scale factor.
+   * Use {@link WindowManager.setMinSize} with `null` to unset the constraint.
    * @example
    * ```typescript
-   * import { appWindow, LogicalPosition } from '@tauri-apps/window';
-   * await appWindow.setPosition(new LogicalPosition(600, 500));
+   * import { appWindow } from '@tauri-apps/window';
+   * await appWindow.setMinSizeLogical(600, 500);
    * ```
    *
-   * @param position The new position, in logical or physical pixels.
    * @returns A promise indicating the success or failure of the operation.
    *
    * @since 2.0.0
    */
-  async setPosition(
-    position: LogicalPosition | PhysicalPosition
-  ): Promise<void> {
-    if (
-      !position ||
-      (position.type !== "Logical" && position.type !== "Physical")
-    ) {
-      throw new Error(
-        "the `position` argument must be either a LogicalPosition or a PhysicalPosition instance"
-      );
-    }
-
-    return window.__TAURI_INVOKE__("plugin:window|set_position", {
+  async setMinSizeLogical(width: number, height: number): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|set_min_size_logical", {
       label: this.label,
-      value: {
-        type: position.type,
-        data: {
-          x: position.x,
-          y: position.y,
-        },
-      },
+      value: { width, height },
     });
   }
 
   /**
-   * Sets the window fullscreen state.
+   * Sets the window minimum inner size in physical pixels, regardless of the scale factor.
+   * Use {@link WindowManager.setMinSize} with `null` to unset the constraint.
    * @example
    * ```typescript
    * import { appWindow } from '@tauri-apps/window';
-   * await appWindow.setFullscreen(true);
+   * await appWindow.setMinSizePhysical(600, 500);
    * ```
    *
-   * @param fullscreen Whether the window should go to fullscreen or not.
    * @returns A promise indicating the success or failure of the operation.
    *
    * @since 2.0.0
    */
-  async setFullscreen(fullscreen: boolean): Promise<void> {
-    return window.__TAURI_INVOKE__("plugin:window|set_fullscreen", {
+  async setMinSizePhysical(width: number, height: number): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|set_min_size_physical", {
       label: this.label,
-      value: fullscreen,
+      value: { width, height },
     });
   }
 
   /**
-   * Bring the window to front and focus.
+   * Sets the window maximum inner size in logical pixels, kept for every scale factor.
+   * Use {@link WindowManager.setMaxSize} with `null` to unset the constraint.
    * @example
    * ```typescript
    * import { appWindow } from '@tauri-apps/window';
-   * await appWindow.setFocus();
+   * await appWindow.setMaxSizeLogical(600, 500);
    * ```
    *
    * @returns A promise indicating the success or failure of the operation.
    *
    * @since 2.0.0
    */
-  async setFocus(): Promise<void> {
-    return window.__TAURI_INVOKE__("plugin:window|set_focus", {
+  async setMaxSizeLogical(width: number, height: number): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|set_max_size_logical", {
       label: this.label,
+      value: { width, height },
     });
   }
 
   /**
-   * Sets the window icon.
+   * Sets the window maximum inner size in physical pixels, regardless of the scale factor.
+   * Use {@link WindowManager.setMaxSize} with `null` to unset the constraint.
    * @example
    * ```typescript
    * import { appWindow } from '@tauri-apps/window';
-   * await appWindow.setIcon('/tauri/awesome.png');
-   * ```
-   *
-   * Note that you need the `icon-ico` or `icon-png` Cargo features to use this API.
-   * To enable it, change your Cargo.toml file:
-   * ```toml
-   * [dependencies]
-   * tauri = { version = "...", features = ["...", "icon-png"] }
+   * await appWindow.setMaxSizePhysical(600, 500);
    * ```
    *
-   * @param icon Icon bytes or path to the icon file.
    * @returns A promise indicating the success or failure of the operation.
    *
    * @since 2.0.0
    */
-  async setIcon(icon: string | Uint8Array): Promise<void> {
-    return window.__TAURI_INVOKE__("plugin:window|set_icon", {
+  async setMaxSizePhysical(width: number, height: number): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|set_max_size_physical", {
       label: this.label,
-      value: typeof icon === "string" ? icon : Array.from(icon),
+      value: { width, height },
     });
   }
 
   /**
-   * Whether the window icon should be hidden from the taskbar or not.
-   *
-   * #### Platform-specific
-   *
-   * - **macOS:** Unsupported.
+   * Sets the window outer position.
    * @example
    * ```typescript
-   * import { appWindow } from '@tauri-apps/window';
-   * await appWindow.setSkipTaskbar(true);
-   * ```
+   * import { appWindow, LogicalPosition } from '@tauri-apps/window';
+   * await appWindow.setPosition(new LogicalPosition(600, 500));
+   * ```
+   *
+   * @param position The new position, in logical or physical pixels.
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async setPosition(
+    position: LogicalPosition | PhysicalPosition
+  ): Promise<void> {
+    if (
+      !position ||
+      (position.type !== "Logical" && position.type !== "Physical")
+    ) {
+      throw new Error(
+        "the `position` argument must be either a LogicalPosition or a PhysicalPosition instance"
+      );
+    }
+
+    return window.__TAURI_INVOKE__("plugin:window|set_position", {
+      label: this.label,
+      value: {
+        type: position.type,
+        data: {
+          x: position.x,
+          y: position.y,
+        },
+      },
+    });
+  }
+
+  /**
+   * Moves and resizes the window smoothly, cancelling its running animation.
+   *
+   * On macOS the animation is run by the system.
+   * Elsewhere it is cancelled when the window is moved by anything else, e.g. the user dragging it.
+   * Listen to {@link WindowManager.onAnimationFinished} to know when it stopped.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * await appWindow.animateTo({ x: 0, y: 0, width: 800, height: 600 }, 250);
+   * ```
+   *
+   * @param rect The outer position and inner size to reach, in logical pixels.
+   * @param durationMs The duration of the animation in milliseconds.
+   * @param easing How the animation accelerates, `easeInOut` by default.
+   * @returns A promise resolving once the animation started.
+   *
+   * @since 2.0.0
+   */
+  async animateTo(
+    rect: WindowRect,
+    durationMs: number,
+    easing?: Easing
+  ): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|animate_to", {
+      label: this.label,
+      rect,
+      durationMs,
+      easing,
+    });
+  }
+
+  /**
+   * Cancels the running animation of the window, leaving it where it is.
+   *
+   * On macOS the system completes the animation anyway, but it is reported as cancelled.
+   *
+   * @since 2.0.0
+   */
+  async cancelAnimation(): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|cancel_animation", {
+      label: this.label,
+    });
+  }
+
+  /**
+   * Sets the window fullscreen state.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * await appWindow.setFullscreen(true);
+   * ```
+   *
+   * @param fullscreen Whether the window should go to fullscreen or not.
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async setFullscreen(fullscreen: boolean): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|set_fullscreen", {
+      label: this.label,
+      value: fullscreen,
+    });
+  }
+
+  /**
+   * Enters fullscreen, optionally on the monitor with the given name.
+   *
+   * The windowed position, size and maximized state are recorded so {@link WindowManager.exitFullscreen} can restore them exactly.
+   * Calling this while already fullscreen moves the window to the requested monitor and keeps the originally recorded geometry.
+   * @example
+   * ```typescript
+   * import { appWindow, availableMonitors } from '@tauri-apps/window';
+   * const [, secondary] = await availableMonitors();
+   * await appWindow.enterFullscreen(secondary?.name ?? undefined);
+   * ```
+   *
+   * @param monitor The name of the monitor to go fullscreen on. Defaults to the current monitor.
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async enterFullscreen(monitor?: string): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|enter_fullscreen", {
+      label: this.label,
+      monitor,
+    });
+  }
+
+  /**
+   * Exits fullscreen and restores the geometry recorded by {@link WindowManager.enterFullscreen}.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * await appWindow.exitFullscreen();
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async exitFullscreen(): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|exit_fullscreen", {
+      label: this.label,
+    });
+  }
+
+  /**
+   * Starts emitting debounced move and resize events for this window.
+   *
+   * The events are debounced by the native event loop: they are emitted once the window
+   * stops moving or resizing for `debounce` milliseconds, followed by the final geometry.
+   * Calling this again replaces the previous debounce delay.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * await appWindow.observeGeometry(500);
+   * await appWindow.onGeometryCommitted(({ payload }) => saveGeometry(payload));
+   * ```
+   *
+   * @param debounce The delay, in milliseconds, without changes before the events are emitted.
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async observeGeometry(debounce: number): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|observe_geometry", {
+      label: this.label,
+      debounce,
+    });
+  }
+
+  /**
+   * Stops emitting the debounced events started by {@link WindowManager.observeGeometry}.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * await appWindow.unobserveGeometry();
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async unobserveGeometry(): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|unobserve_geometry", {
+      label: this.label,
+    });
+  }
+
+  /**
+   * Bring the window to front and focus.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * await appWindow.setFocus();
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async setFocus(): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|set_focus", {
+      label: this.label,
+    });
+  }
+
+  /**
+   * Sets the window icon.
+   *
+   * Given the same icon in several sizes, the size matching the scale factor
+   * of the window is used, and it is changed when the window moves to
+   * a monitor with another scale factor.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * await appWindow.setIcon('/tauri/awesome.png');
+   * await appWindow.setIcon(['/tauri/icon-32.png', '/tauri/icon-64.png']);
+   * ```
+   *
+   * Note that you need the `icon-ico` or `icon-png` Cargo features to use
+   * icon files and bytes. To enable it, change your Cargo.toml file:
+   * ```toml
+   * [dependencies]
+   * tauri = { version = "...", features = ["...", "icon-png"] }
+   * ```
+   *
+   * @param icon Icon bytes, path to the icon file or RGBA pixels, or a list of them in several sizes.
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async setIcon(icon: IconSource | IconSource[]): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|set_icon", {
+      label: this.label,
+      value: Array.isArray(icon)
+        ? icon.map(serializeIcon)
+        : serializeIcon(icon),
+    });
+  }
+
+  /**
+   * Restores the default window icon of the app.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * await appWindow.resetIcon();
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async resetIcon(): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|reset_icon", {
+      label: this.label,
+    });
+  }
+
+  /**
+   * Whether the window icon should be hidden from the taskbar or not.
+   *
+   * #### Platform-specific
+   *
+   * - **macOS:** Unsupported.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * await appWindow.setSkipTaskbar(true);
+   * ```
    *
    * @param skip true to hide window icon, false to show it.
    * @returns A promise indicating the success or failure of the operation.
@@ -1295,6 +1882,8 @@ class WindowManager extends WebviewWindowHandle {
 
   /**
    * Changes the position of the cursor in window coordinates.
+   *
+   * A {@link LogicalPosition} is converted with the window's scale factor, so it targets the same spot on any display.
    * @example
    * ```typescript
    * import { appWindow, LogicalPosition } from '@tauri-apps/window';
@@ -1330,6 +1919,66 @@ class WindowManager extends WebviewWindowHandle {
     });
   }
 
+  /**
+   * Enables or disables the input method of the caret input placed with
+   * {@link WindowManager.setImePosition}, e.g. while a terminal expects raw keys.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * await appWindow.setImeAllowed(false);
+   * ```
+   *
+   * @param allowed Whether the input method composes the typed text.
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async setImeAllowed(allowed: boolean): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|set_ime_allowed", {
+      label: this.label,
+      value: allowed,
+    });
+  }
+
+  /**
+   * Focuses an invisible caret input at this position of the window, so the
+   * input method places its candidate window next to the caret of a custom
+   * text area. The composed text is reported by {@link WindowManager.onImeEvent}.
+   * @example
+   * ```typescript
+   * import { appWindow, LogicalPosition } from '@tauri-apps/window';
+   * await appWindow.setImePosition(new LogicalPosition(120, 48));
+   * ```
+   *
+   * @param position The caret position, in window coordinates.
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async setImePosition(
+    position: LogicalPosition | PhysicalPosition
+  ): Promise<void> {
+    if (
+      !position ||
+      (position.type !== "Logical" && position.type !== "Physical")
+    ) {
+      throw new Error(
+        "the `position` argument must be either a LogicalPosition or a PhysicalPosition instance"
+      );
+    }
+
+    return window.__TAURI_INVOKE__("plugin:window|set_ime_position", {
+      label: this.label,
+      value: {
+        type: position.type,
+        data: {
+          x: position.x,
+          y: position.y,
+        },
+      },
+    });
+  }
+
   /**
    * Changes the cursor events behavior.
    *
@@ -1351,6 +2000,37 @@ class WindowManager extends WebviewWindowHandle {
     });
   }
 
+  /**
+   * Makes the transparent areas of a transparent window click-through,
+   * while the opaque content stays interactive.
+   *
+   * A point is transparent when it hits the document background and both the `html` and `body`
+   * backgrounds are fully transparent, or when it hits an element with the `data-tauri-click-through` attribute.
+   * The rendered pixels are not tested, so semi-transparent elements and the transparent pixels of images are opaque.
+   * The cursor events behavior set by {@link WindowManager.setIgnoreCursorEvents} is managed by the plugin while enabled.
+   *
+   * #### Platform-specific
+   *
+   * - **Linux / macOS:** Unsupported, the window keeps processing all the cursor events.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * await appWindow.setHitTest(true);
+   * ```
+   *
+   * @param enabled `true` to make the transparent areas click-through; `false` to process all cursor events again.
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async setHitTest(enabled: boolean): Promise<void> {
+    return window.__TAURI_INVOKE__("plugin:window|set_hit_test", {
+      label: this.label,
+      value: enabled,
+    });
+  }
+
   /**
    * Starts dragging the window.
    * @example
@@ -1369,6 +2049,30 @@ class WindowManager extends WebviewWindowHandle {
     });
   }
 
+  /**
+   * Performs the action of a double click on the title bar, as configured in the system settings.
+   * A window that is not resizable is not maximized.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/window';
+   * document
+   *   .getElementById('titlebar')
+   *   .addEventListener('dblclick', () => appWindow.performTitleBarDoubleClick());
+   * ```
+   *
+   * @returns A promise resolving to the action performed.
+   *
+   * @since 2.0.0
+   */
+  async performTitleBarDoubleClick(): Promise<TitleBarDoubleClickAction> {
+    return window.__TAURI_INVOKE__(
+      "plugin:window|perform_title_bar_double_click",
+      {
+        label: this.label,
+      }
+    );
+  }
+
   // Listeners
 
   /**
@@ -1614,7 +2318,7 @@ class WindowManager extends WebviewWindowHandle {
   }
 
   /**
-   * Listen to the system theme change.
+   * Listen to the theme changes of the window, either from the system or from `setTheme`.
    *
    * @example
    * ```typescript
@@ -1635,6 +2339,242 @@ class WindowManager extends WebviewWindowHandle {
   async onThemeChanged(handler: EventCallback<Theme>): Promise<UnlistenFn> {
     return this.listen<Theme>(TauriEvent.WINDOW_THEME_CHANGED, handler);
   }
+
+  /**
+   * Listen to the window entering or leaving fullscreen through {@link WindowManager.enterFullscreen} and {@link WindowManager.exitFullscreen}.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from "@tauri-apps/plugin-window";
+   * const unlisten = await appWindow.onFullscreenChanged(({ payload }) => {
+   *   console.log(`fullscreen: ${payload.fullscreen} on ${payload.monitor}`);
+   * });
+   *
+   * // you need to call unlisten if your handler goes out of scope e.g. the component is unmounted
+   * unlisten();
+   * ```
+   *
+   * @returns A promise resolving to a function to unlisten to the event.
+   * Note that removing the listener is required if your listener goes out of scope e.g. the component is unmounted.
+   *
+   * @since 2.0.0
+   */
+  async onFullscreenChanged(
+    handler: EventCallback<FullscreenChanged>
+  ): Promise<UnlistenFn> {
+    return this.listen<FullscreenChanged>("window://fullscreen", handler);
+  }
+
+  /**
+   * Listen to the window being maximized, minimized or restored,
+   * including by the window controls, a keyboard shortcut or the window manager.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from "@tauri-apps/plugin-window";
+   * const unlisten = await appWindow.onDisplayStateChanged(({ payload }) => {
+   *   maximizeButton.toggleAttribute("data-maximized", payload.maximized);
+   * });
+   *
+   * // you need to call unlisten if your handler goes out of scope e.g. the component is unmounted
+   * unlisten();
+   * ```
+   *
+   * @returns A promise resolving to a function to unlisten to the event.
+   * Note that removing the listener is required if your listener goes out of scope e.g. the component is unmounted.
+   *
+   * @since 2.0.0
+   */
+  async onDisplayStateChanged(
+    handler: EventCallback<DisplayStateChanged>
+  ): Promise<UnlistenFn> {
+    return this.listen<DisplayStateChanged>("window://display-state", handler);
+  }
+
+  /**
+   * Listen to the animations started with {@link WindowManager.animateTo}
+   * completing or being cancelled.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from "@tauri-apps/plugin-window";
+   * const unlisten = await appWindow.onAnimationFinished(({ payload }) => {
+   *   if (payload.cancelled) {
+   *     console.log("the window was moved during the animation");
+   *   }
+   * });
+   *
+   * // you need to call unlisten if your handler goes out of scope e.g. the component is unmounted
+   * unlisten();
+   * ```
+   *
+   * @returns A promise resolving to a function to unlisten to the event.
+   * Note that removing the listener is required if your listener goes out of scope e.g. the component is unmounted.
+   *
+   * @since 2.0.0
+   */
+  async onAnimationFinished(
+    handler: EventCallback<AnimationFinished>
+  ): Promise<UnlistenFn> {
+    return this.listen<AnimationFinished>(
+      "window://animation-finished",
+      handler
+    );
+  }
+
+  /**
+   * Listen to the size changes of the rendered document.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from "@tauri-apps/plugin-window";
+   * const unlisten = await appWindow.onContentSizeChanged(({ payload: size }) => {
+   *   console.log(`content is ${size.width}x${size.height}`);
+   * });
+   *
+   * // you need to call unlisten if your handler goes out of scope e.g. the component is unmounted
+   * unlisten();
+   * ```
+   *
+   * @returns A promise resolving to a function to unlisten to the event.
+   * Note that removing the listener is required if your listener goes out of scope e.g. the component is unmounted.
+   *
+   * @since 2.0.0
+   */
+  async onContentSizeChanged(
+    handler: EventCallback<LogicalSize>
+  ): Promise<UnlistenFn> {
+    return this.listen<LogicalSize>("window://content-size-changed", (e) => {
+      e.payload = new LogicalSize(e.payload.width, e.payload.height);
+      handler(e);
+    });
+  }
+
+  /**
+   * Listen to the input method compositions in the window, e.g. to render
+   * the preedit text in a custom text area.
+   * @example
+   * ```typescript
+   * import { appWindow } from "@tauri-apps/plugin-window";
+   * const unlisten = await appWindow.onImeEvent(({ payload: event }) => {
+   *   if (event.type === "preedit") {
+   *     console.log(`composing ${event.text}`);
+   *   }
+   * });
+   *
+   * // you need to call unlisten if your handler goes out of scope e.g. the component is unmounted
+   * unlisten();
+   * ```
+   *
+   * @returns A promise resolving to a function to unlisten to the event.
+   * Note that removing the listener is required if your listener goes out of scope e.g. the component is unmounted.
+   *
+   * @since 2.0.0
+   */
+  async onImeEvent(handler: EventCallback<ImeEvent>): Promise<UnlistenFn> {
+    return this.listen<ImeEvent>("window://ime", handler);
+  }
+
+  /**
+   * Listen to the size constraints applied to the window, when they are set
+   * and when the window moves to a monitor with another scale factor.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from "@tauri-apps/plugin-window";
+   * const unlisten = await appWindow.onSizeConstraintsChanged(({ payload }) => {
+   *   console.log(`minimum size: ${payload.minSize?.width}`);
+   * });
+   *
+   * // you need to call unlisten if your handler goes out of scope e.g. the component is unmounted
+   * unlisten();
+   * ```
+   *
+   * @returns A promise resolving to a function to unlisten to the event.
+   * Note that removing the listener is required if your listener goes out of scope e.g. the component is unmounted.
+   *
+   * @since 2.0.0
+   */
+  async onSizeConstraintsChanged(
+    handler: EventCallback<SizeConstraints>
+  ): Promise<UnlistenFn> {
+    return this.listen<SizeConstraints>("window://size-constraints", (e) => {
+      e.payload = {
+        ...e.payload,
+        minSize: e.payload.minSize && mapPhysicalSize(e.payload.minSize),
+        maxSize: e.payload.maxSize && mapPhysicalSize(e.payload.maxSize),
+      };
+      handler(e);
+    });
+  }
+
+  /**
+   * Listen to the window moves debounced by {@link WindowManager.observeGeometry}.
+   *
+   * @returns A promise resolving to a function to unlisten to the event.
+   * Note that removing the listener is required if your listener goes out of scope e.g. the component is unmounted.
+   *
+   * @since 2.0.0
+   */
+  async onMovedDebounced(
+    handler: EventCallback<PhysicalPosition>
+  ): Promise<UnlistenFn> {
+    return this.listen<PhysicalPosition>("window://moved-debounced", (e) => {
+      e.payload = mapPhysicalPosition(e.payload);
+      handler(e);
+    });
+  }
+
+  /**
+   * Listen to the window resizes debounced by {@link WindowManager.observeGeometry}.
+   *
+   * @returns A promise resolving to a function to unlisten to the event.
+   * Note that removing the listener is required if your listener goes out of scope e.g. the component is unmounted.
+   *
+   * @since 2.0.0
+   */
+  async onResizedDebounced(
+    handler: EventCallback<PhysicalSize>
+  ): Promise<UnlistenFn> {
+    return this.listen<PhysicalSize>("window://resized-debounced", (e) => {
+      e.payload = mapPhysicalSize(e.payload);
+      handler(e);
+    });
+  }
+
+  /**
+   * Listen to the final window geometry after a debounced move or resize.
+   *
+   * @example
+   * ```typescript
+   * import { appWindow } from "@tauri-apps/plugin-window";
+   * await appWindow.observeGeometry(500);
+   * const unlisten = await appWindow.onGeometryCommitted(({ payload }) => {
+   *   console.log(`window settled on ${payload.monitor?.name}`);
+   * });
+   *
+   * // you need to call unlisten if your handler goes out of scope e.g. the component is unmounted
+   * unlisten();
+   * ```
+   *
+   * @returns A promise resolving to a function to unlisten to the event.
+   * Note that removing the listener is required if your listener goes out of scope e.g. the component is unmounted.
+   *
+   * @since 2.0.0
+   */
+  async onGeometryCommitted(
+    handler: EventCallback<GeometryCommitted>
+  ): Promise<UnlistenFn> {
+    return this.listen<GeometryCommitted>("window://geometry-committed", (e) => {
+      e.payload = {
+        ...e.payload,
+        position: mapPhysicalPosition(e.payload.position),
+        size: mapPhysicalSize(e.payload.size),
+        monitor: mapMonitor(e.payload.monitor),
+      };
+      handler(e);
+    });
+  }
 }
 
 /**
@@ -1955,6 +2895,68 @@ async function availableMonitors(): Promise<Monitor[]> {
     .then((ms) => ms.map(mapMonitor) as Monitor[]);
 }
 
+/**
+ * Options of {@link registerDragRegion}.
+ *
+ * @since 2.0.0
+ */
+interface DragRegionOptions {
+  /**
+   * Whether a double click performs the title bar action configured in the
+   * system settings. Defaults to `true`.
+   */
+  doubleClick?: boolean;
+  /**
+   * A selector of the descendants that are not part of the drag region,
+   * e.g. the buttons of a title bar. Defaults to `button, input, a, select, textarea`.
+   */
+  exclude?: string;
+}
+
+/**
+ * Makes an element and its descendants drag the current window, like the
+ * `data-tauri-drag-region` attribute, which only applies to the element itself.
+ * A double click performs the title bar action configured in the system settings.
+ * @example
+ * ```typescript
+ * import { registerDragRegion } from '@tauri-apps/window';
+ * const unregister = registerDragRegion(document.getElementById('titlebar'));
+ * ```
+ *
+ * @returns A function removing the drag region.
+ *
+ * @since 2.0.0
+ */
+function registerDragRegion(
+  element: HTMLElement,
+  options: DragRegionOptions = {}
+): () => void {
+  const doubleClick = options.doubleClick ?? true;
+  const exclude = options.exclude ?? "button, input, a, select, textarea";
+  const current = getCurrent();
+
+  const onMouseDown = (e: MouseEvent): void => {
+    const target = e.target as Element | null;
+    const excluded = target?.closest(exclude);
+    if (e.buttons !== 1 || (excluded && element.contains(excluded))) {
+      return;
+    }
+    // prevents text cursor
+    e.preventDefault();
+    e.stopImmediatePropagation();
+    if (e.detail === 2) {
+      if (doubleClick) {
+        void current.performTitleBarDoubleClick();
+      }
+    } else {
+      void current.startDragging();
+    }
+  };
+
+  element.addEventListener("mousedown", onMouseDown);
+  return () => element.removeEventListener("mousedown", onMouseDown);
+}
+
 export {
   WebviewWindow,
   WebviewWindowHandle,
@@ -1971,13 +2973,27 @@ export {
   currentMonitor,
   primaryMonitor,
   availableMonitors,
+  registerDragRegion,
 };
 
 export type {
   Theme,
+  TitleBarDoubleClickAction,
+  DragRegionOptions,
   TitleBarStyle,
   Monitor,
   ScaleFactorChanged,
+  FullscreenChanged,
+  DisplayState,
+  DisplayStateChanged,
+  WindowRect,
+  Easing,
+  AnimationFinished,
+  GeometryCommitted,
+  SizeConstraints,
+  WebviewState,
   FileDropEvent,
+  ImeEvent,
+  IconSource,
   WindowOptions,
 };
@@ -0,0 +1,329 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Animated moves and resizes of the windows.
+//!
+//! On macOS the animation is run by AppKit with the window animator. Elsewhere the window is
+//! moved and resized on every frame, and the animation is cancelled when the window is moved
+//! by anything else, e.g. the user dragging it.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, Runtime, State, Window, WindowEvent};
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use crate::desktop_commands::{get_window, Result};
+
+/// Event emitted on a window with an [`AnimationFinished`] when its animation completed or
+/// was cancelled.
+pub const ANIMATION_FINISHED_EVENT: &str = "window://animation-finished";
+
+/// The interval between two frames of the animations run by the plugin.
+#[cfg(not(target_os = "macos"))]
+const FRAME: Duration = Duration::from_millis(16);
+/// How far a window may be from the position the animation gave it before the animation is
+/// considered interrupted, in logical pixels.
+#[cfg(not(target_os = "macos"))]
+const TOLERANCE: f64 = 4.0;
+
+/// The outer position and inner size of a window, in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    fn of<R: Runtime>(window: &Window<R>) -> Result<Self> {
+        let scale_factor = window.scale_factor()?;
+        let position = window.outer_position()?.to_logical::<f64>(scale_factor);
+        let size = window.inner_size()?.to_logical::<f64>(scale_factor);
+        Ok(Self {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        })
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn lerp(self, target: Self, t: f64) -> Self {
+        let lerp = |from: f64, to: f64| from + (to - from) * t;
+        Self {
+            x: lerp(self.x, target.x),
+            y: lerp(self.y, target.y),
+            width: lerp(self.width, target.width),
+            height: lerp(self.height, target.height),
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn is_near(&self, other: &Self) -> bool {
+        (self.x - other.x).abs() <= TOLERANCE && (self.y - other.y).abs() <= TOLERANCE
+    }
+}
+
+/// How the progress of an animation accelerates.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps the elapsed fraction of the animation to the fraction of the distance covered.
+    #[cfg(not(target_os = "macos"))]
+    fn apply(self, t: f64) -> f64 {
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t * t,
+            Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Self::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+        }
+    }
+}
+
+/// The payload of the [`ANIMATION_FINISHED_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimationFinished {
+    /// The geometry of the window once the animation stopped.
+    pub rect: Rect,
+    /// Whether the animation stopped before reaching its target, because the window was moved
+    /// by something else or another animation started.
+    pub cancelled: bool,
+}
+
+/// The cancellation flag of the running animation of each window, indexed by label.
+#[derive(Default)]
+pub struct Animations(Mutex<HashMap<String, Arc<AtomicBool>>>);
+
+impl Animations {
+    pub fn handle_event(&self, label: &str, event: &WindowEvent) {
+        if let WindowEvent::Destroyed = event {
+            if let Some(cancelled) = self.0.lock().unwrap().remove(label) {
+                cancelled.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Registers a new animation of the window, cancelling the running one.
+    fn start<R: Runtime>(&self, window: &Window<R>) -> Arc<AtomicBool> {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        if let Some(previous) = self
+            .0
+            .lock()
+            .unwrap()
+            .insert(window.label().into(), cancelled.clone())
+        {
+            previous.store(true, Ordering::Relaxed);
+        }
+        cancelled
+    }
+
+    fn cancel(&self, label: &str) {
+        if let Some(cancelled) = self.0.lock().unwrap().remove(label) {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Forgets the animation once it stopped, unless another one replaced it.
+    fn finish(&self, label: &str, cancelled: &Arc<AtomicBool>) {
+        let mut animations = self.0.lock().unwrap();
+        if animations
+            .get(label)
+            .map_or(false, |current| Arc::ptr_eq(current, cancelled))
+        {
+            animations.remove(label);
+        }
+    }
+}
+
+fn emit_finished<R: Runtime>(window: &Window<R>, cancelled: bool) -> Result<()> {
+    window.emit(
+        ANIMATION_FINISHED_EVENT,
+        AnimationFinished {
+            rect: Rect::of(window)?,
+            cancelled,
+        },
+    )?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn set_rect<R: Runtime>(window: &Window<R>, rect: Rect) -> Result<()> {
+    window.set_position(tauri::LogicalPosition::new(rect.x, rect.y))?;
+    window.set_size(tauri::LogicalSize::new(rect.width, rect.height))?;
+    Ok(())
+}
+
+/// Moves and resizes the window by interpolating its geometry on every frame.
+#[cfg(not(target_os = "macos"))]
+fn animate<R: Runtime>(
+    window: Window<R>,
+    cancelled: Arc<AtomicBool>,
+    target: Rect,
+    duration: Duration,
+    easing: Easing,
+) -> Result<()> {
+    let start = Rect::of(&window)?;
+    std::thread::spawn(move || {
+        let began = std::time::Instant::now();
+        // the platforms may apply a move a frame late, so the window can be at either position
+        let mut placed = [start, start];
+        loop {
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            let Ok(current) = Rect::of(&window) else {
+                return;
+            };
+            if !placed.iter().any(|rect| rect.is_near(&current)) {
+                cancelled.store(true, Ordering::Relaxed);
+                break;
+            }
+
+            let t = if duration.is_zero() {
+                1.0
+            } else {
+                (began.elapsed().as_secs_f64() / duration.as_secs_f64()).min(1.0)
+            };
+            let rect = start.lerp(target, easing.apply(t));
+            if set_rect(&window, rect).is_err() {
+                return;
+            }
+            placed = [placed[1], rect];
+            if t >= 1.0 {
+                break;
+            }
+            std::thread::sleep(FRAME);
+        }
+
+        window
+            .state::<Animations>()
+            .finish(window.label(), &cancelled);
+        let _ = emit_finished(&window, cancelled.load(Ordering::Relaxed));
+    });
+    Ok(())
+}
+
+/// Animates the window frame with the window animator of AppKit.
+#[cfg(target_os = "macos")]
+fn animate<R: Runtime>(
+    window: Window<R>,
+    cancelled: Arc<AtomicBool>,
+    target: Rect,
+    duration: Duration,
+    easing: Easing,
+) -> Result<()> {
+    use cocoa::{
+        appkit::NSScreen,
+        base::{id, nil, YES},
+        foundation::{NSArray, NSPoint, NSRect, NSSize, NSString},
+    };
+    use objc::*;
+
+    // the frame of a window includes its title bar, and its origin is the bottom left corner
+    let scale_factor = window.scale_factor()?;
+    let outer = window.outer_size()?.to_logical::<f64>(scale_factor);
+    let inner = window.inner_size()?.to_logical::<f64>(scale_factor);
+    let width = target.width + outer.width - inner.width;
+    let height = target.height + outer.height - inner.height;
+    let timing = match easing {
+        Easing::Linear => "linear",
+        Easing::EaseIn => "easeIn",
+        Easing::EaseOut => "easeOut",
+        Easing::EaseInOut => "easeInEaseOut",
+    };
+
+    let ns_window = window.ns_window()? as usize;
+    window.run_on_main_thread(move || {
+        // Safety: objc runtime calls are unsafe
+        unsafe {
+            let screens = NSScreen::screens(nil);
+            let screen_height = NSScreen::frame(screens.objectAtIndex(0)).size.height;
+            let frame = NSRect::new(
+                NSPoint::new(target.x, screen_height - target.y - height),
+                NSSize::new(width, height),
+            );
+
+            let _: () = msg_send![class!(NSAnimationContext), beginGrouping];
+            let context: id = msg_send![class!(NSAnimationContext), currentContext];
+            let _: () = msg_send![context, setDuration: duration.as_secs_f64()];
+            let name = NSString::alloc(nil).init_str(timing);
+            let function: id = msg_send![class!(CAMediaTimingFunction), functionWithName: name];
+            let _: () = msg_send![name, release];
+            let _: () = msg_send![context, setTimingFunction: function];
+            let animator: id = msg_send![ns_window as id, animator];
+            let _: () = msg_send![animator, setFrame: frame display: YES];
+            let _: () = msg_send![class!(NSAnimationContext), endGrouping];
+        }
+    })?;
+
+    std::thread::spawn(move || {
+        std::thread::sleep(duration);
+        window
+            .state::<Animations>()
+            .finish(window.label(), &cancelled);
+        // the animator can not tell whether the window was moved meanwhile
+        let reached = Rect::of(&window).map_or(false, |rect| {
+            (rect.x - target.x).abs() < 1.0 && (rect.y - target.y).abs() < 1.0
+        });
+        let _ = emit_finished(&window, cancelled.load(Ordering::Relaxed) || !reached);
+    });
+    Ok(())
+}
+
+/// Moves and resizes the window to `rect` over `duration_ms`, emitting the
+/// [`ANIMATION_FINISHED_EVENT`] once done.
+///
+/// An animation running on the window is cancelled first.
+#[tauri::command]
+pub async fn animate_to<R: Runtime>(
+    window: Window<R>,
+    animations: State<'_, Animations>,
+    label: Option<String>,
+    rect: Rect,
+    duration_ms: u64,
+    easing: Option<Easing>,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    let cancelled = animations.start(&window);
+    animate(
+        window,
+        cancelled,
+        rect,
+        Duration::from_millis(duration_ms),
+        easing.unwrap_or_default(),
+    )
+}
+
+/// Cancels the animation of the window, leaving it where it is.
+///
+/// On macOS, AppKit completes the animation anyway, but it is reported as cancelled.
+#[tauri::command]
+pub async fn cancel_animation<R: Runtime>(
+    window: Window<R>,
+    animations: State<'_, Animations>,
+    label: Option<String>,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    animations.cancel(window.label());
+    Ok(())
+}
@@ -0,0 +1,84 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The size of the rendered document, reported by the webview whenever it changes.
+//!
+//! Blocks fill the width of the viewport, so the width only shrinks with the content when the
+//! root element is sized by it, e.g. with `html { width: max-content }`.
+
+use tauri::{LogicalSize, Runtime, Size, State, Window};
+
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::desktop_commands::{get_window, Error, Result};
+
+/// Event emitted on a window when the size of its document changes.
+pub const CONTENT_SIZE_CHANGED_EVENT: &str = "window://content-size-changed";
+
+/// The last document size reported by each window, indexed by label.
+#[derive(Default)]
+pub struct ContentSizes(Mutex<HashMap<String, LogicalSize<f64>>>);
+
+impl ContentSizes {
+    pub fn remove(&self, label: &str) {
+        self.0.lock().unwrap().remove(label);
+    }
+
+    fn get<R: Runtime>(&self, window: &Window<R>) -> Result<LogicalSize<f64>> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(window.label())
+            .copied()
+            .ok_or(Error::ContentSizeUnavailable)
+    }
+}
+
+#[tauri::command]
+pub async fn internal_set_content_size<R: Runtime>(
+    window: Window<R>,
+    sizes: State<'_, ContentSizes>,
+    width: f64,
+    height: f64,
+) -> Result<()> {
+    let size = LogicalSize::new(width, height);
+    let previous = sizes.0.lock().unwrap().insert(window.label().into(), size);
+    if previous != Some(size) {
+        window.emit(CONTENT_SIZE_CHANGED_EVENT, size)?;
+    }
+    Ok(())
+}
+
+/// The size of the rendered document, in logical pixels.
+#[tauri::command]
+pub async fn content_size<R: Runtime>(
+    window: Window<R>,
+    sizes: State<'_, ContentSizes>,
+    label: Option<String>,
+) -> Result<LogicalSize<f64>> {
+    let window = get_window(window, label)?;
+    sizes.get(&window)
+}
+
+/// Resizes the window to its content, clamped to `max_size`.
+#[tauri::command]
+pub async fn fit_to_content<R: Runtime>(
+    window: Window<R>,
+    sizes: State<'_, ContentSizes>,
+    label: Option<String>,
+    max_size: Option<Size>,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    let mut size = sizes.get(&window)?;
+    if let Some(max_size) = max_size {
+        let max_size = match max_size {
+            Size::Logical(size) => size,
+            Size::Physical(size) => size.to_logical(window.scale_factor()?),
+        };
+        size.width = size.width.min(max_size.width);
+        size.height = size.height.min(max_size.height);
+    }
+    window.set_size(size)?;
+    Ok(())
+}
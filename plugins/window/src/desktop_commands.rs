@@ -5,13 +5,23 @@
 use serde::{Deserialize, Serialize, Serializer};
 use tauri::{
     utils::config::WindowConfig, AppHandle, CursorIcon, Icon, Manager, Monitor, PhysicalPosition,
-    PhysicalSize, Position, Runtime, Size, Theme, UserAttentionType, Window,
+    PhysicalSize, Position, Runtime, Size, UserAttentionType, Window,
 };
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("window not found")]
     WindowNotFound,
+    #[error("the window has not reported its content size yet")]
+    ContentSizeUnavailable,
+    #[error("monitor `{0}` not found")]
+    MonitorNotFound(String),
+    #[error("no icon given")]
+    EmptyIconSet,
+    #[error("the app has no default window icon")]
+    DefaultIconUnavailable,
+    #[error("the webview did not report its state")]
+    WebviewStateUnavailable,
     #[error(transparent)]
     Tauri(#[from] tauri::Error),
 }
@@ -25,7 +35,7 @@ impl Serialize for Error {
     }
 }
 
-type Result<T> = std::result::Result<T, Error>;
+pub(crate) type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Deserialize)]
 #[serde(untagged)]
@@ -67,7 +77,10 @@ pub async fn create<R: Runtime>(app: AppHandle<R>, options: WindowConfig) -> Res
     Ok(())
 }
 
-fn get_window<R: Runtime>(window: Window<R>, label: Option<String>) -> Result<Window<R>> {
+pub(crate) fn get_window<R: Runtime>(
+    window: Window<R>,
+    label: Option<String>,
+) -> Result<Window<R>> {
     match label {
         Some(l) if !l.is_empty() => window.get_window(&l).ok_or(Error::WindowNotFound),
         _ => Ok(window),
@@ -118,16 +131,11 @@ getter!(title, String);
 getter!(current_monitor, Option<Monitor>);
 getter!(primary_monitor, Option<Monitor>);
 getter!(available_monitors, Vec<Monitor>);
-getter!(theme, Theme);
 
 setter!(center);
 setter!(request_user_attention, Option<UserAttentionType>);
 setter!(set_resizable, bool);
 setter!(set_title, &str);
-setter!(maximize);
-setter!(unmaximize);
-setter!(minimize);
-setter!(unminimize);
 setter!(show);
 setter!(hide);
 setter!(close);
@@ -136,8 +144,6 @@ setter!(set_shadow, bool);
 setter!(set_always_on_top, bool);
 setter!(set_content_protected, bool);
 setter!(set_size, Size);
-setter!(set_min_size, Option<Size>);
-setter!(set_max_size, Option<Size>);
 setter!(set_position, Position);
 setter!(set_fullscreen, bool);
 setter!(set_focus);
@@ -150,42 +156,6 @@ setter!(set_ignore_cursor_events, bool);
 setter!(start_dragging);
 setter!(print);
 
-#[tauri::command]
-pub async fn set_icon<R: Runtime>(
-    window: Window<R>,
-    label: Option<String>,
-    value: IconDto,
-) -> Result<()> {
-    get_window(window, label)?
-        .set_icon(value.into())
-        .map_err(Into::into)
-}
-
-#[tauri::command]
-pub async fn toggle_maximize<R: Runtime>(window: Window<R>, label: Option<String>) -> Result<()> {
-    let window = get_window(window, label)?;
-    match window.is_maximized()? {
-        true => window.unmaximize()?,
-        false => window.maximize()?,
-    };
-    Ok(())
-}
-
-#[tauri::command]
-pub async fn internal_toggle_maximize<R: Runtime>(
-    window: Window<R>,
-    label: Option<String>,
-) -> Result<()> {
-    let window = get_window(window, label)?;
-    if window.is_resizable()? {
-        match window.is_maximized()? {
-            true => window.unmaximize()?,
-            false => window.maximize()?,
-        };
-    }
-    Ok(())
-}
-
 #[cfg(any(debug_assertions, feature = "devtools"))]
 #[tauri::command]
 pub async fn internal_toggle_devtools<R: Runtime>(
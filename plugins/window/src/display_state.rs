@@ -0,0 +1,253 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The maximized and minimized state of the windows, observed on every transition.
+//!
+//! The platforms do not report these transitions with an event of their own, and some of
+//! them, e.g. minimizing a window on macOS, are only followed by a focus change. The state is
+//! queried again on every event that may follow a transition, so a change made by a keyboard
+//! shortcut, the zoom button or the window manager is reported like the ones of the API.
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime, State, Window, WindowEvent};
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::desktop_commands::{get_window, Result};
+
+/// Event emitted on a window with a [`DisplayStateChanged`] when it is maximized, minimized
+/// or restored.
+pub const DISPLAY_STATE_EVENT: &str = "window://display-state";
+
+/// How long a transition requested through the plugin is waited for.
+const EXPECTED_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The maximized and minimized state of a window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayState {
+    pub maximized: bool,
+    /// A minimized window can also be maximized, it is then maximized once restored.
+    pub minimized: bool,
+}
+
+/// What caused a transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DisplayStateCause {
+    /// A command of the plugin, e.g. `maximize` or `toggleMaximize`.
+    Programmatic,
+    /// Anything else: the window controls, a keyboard shortcut, the window manager, or a
+    /// change made from Rust without the plugin.
+    User,
+}
+
+/// The payload of the [`DISPLAY_STATE_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayStateChanged {
+    #[serde(flatten)]
+    pub state: DisplayState,
+    pub previous: DisplayState,
+    pub cause: DisplayStateCause,
+}
+
+#[derive(Default)]
+struct Tracked {
+    state: DisplayState,
+    /// The state requested through the plugin, until the window reached it.
+    expected: Option<(DisplayState, Instant)>,
+}
+
+/// The last observed state of each window, indexed by label.
+#[derive(Default)]
+pub struct DisplayStates(Mutex<HashMap<String, Tracked>>);
+
+impl DisplayStates {
+    pub fn handle_event<R: Runtime>(&self, app: &AppHandle<R>, label: &str, event: &WindowEvent) {
+        match event {
+            WindowEvent::Resized(_)
+            | WindowEvent::Moved(_)
+            | WindowEvent::Focused(_)
+            | WindowEvent::ScaleFactorChanged { .. } => {
+                if let Some(window) = app.get_window(label) {
+                    let _ = self.refresh(&window);
+                }
+            }
+            WindowEvent::Destroyed => {
+                self.0.lock().unwrap().remove(label);
+            }
+            _ => {}
+        }
+    }
+
+    /// Records the state of a new window, so its first transition is reported.
+    pub fn track<R: Runtime>(&self, window: &Window<R>) {
+        if let Ok(state) = query(window) {
+            self.0
+                .lock()
+                .unwrap()
+                .entry(window.label().into())
+                .or_default()
+                .state = state;
+        }
+    }
+
+    /// Queries the state of the window, emitting the [`DISPLAY_STATE_EVENT`] if it changed.
+    fn refresh<R: Runtime>(&self, window: &Window<R>) -> Result<DisplayState> {
+        let state = query(window)?;
+        let mut states = self.0.lock().unwrap();
+        let tracked = states.entry(window.label().into()).or_default();
+        if tracked.state == state {
+            return Ok(state);
+        }
+
+        let expected = tracked
+            .expected
+            .take()
+            .filter(|(_, requested)| requested.elapsed() < EXPECTED_TIMEOUT);
+        let cause = match expected {
+            Some((expected, _)) if expected == state => DisplayStateCause::Programmatic,
+            _ => DisplayStateCause::User,
+        };
+        let previous = std::mem::replace(&mut tracked.state, state);
+        drop(states);
+
+        window.emit(
+            DISPLAY_STATE_EVENT,
+            DisplayStateChanged {
+                state,
+                previous,
+                cause,
+            },
+        )?;
+        Ok(state)
+    }
+
+    /// Applies a transition requested through the plugin.
+    fn request<R: Runtime>(
+        &self,
+        window: &Window<R>,
+        expected: impl FnOnce(DisplayState) -> DisplayState,
+        apply: impl FnOnce(&Window<R>) -> tauri::Result<()>,
+    ) -> Result<()> {
+        let current = self.refresh(window)?;
+        self.0
+            .lock()
+            .unwrap()
+            .entry(window.label().into())
+            .or_default()
+            .expected = Some((expected(current), Instant::now()));
+        apply(window)?;
+        // the platforms that apply the transition synchronously may not send any event
+        self.refresh(window)?;
+        Ok(())
+    }
+}
+
+fn query<R: Runtime>(window: &Window<R>) -> Result<DisplayState> {
+    Ok(DisplayState {
+        maximized: window.is_maximized()?,
+        minimized: window.is_minimized()?,
+    })
+}
+
+/// The maximized and minimized state of the window, queried at once.
+#[tauri::command]
+pub async fn display_state<R: Runtime>(
+    window: Window<R>,
+    states: State<'_, DisplayStates>,
+    label: Option<String>,
+) -> Result<DisplayState> {
+    states.refresh(&get_window(window, label)?)
+}
+
+#[tauri::command]
+pub async fn maximize<R: Runtime>(
+    window: Window<R>,
+    states: State<'_, DisplayStates>,
+    label: Option<String>,
+) -> Result<()> {
+    states.request(
+        &get_window(window, label)?,
+        |state| DisplayState {
+            maximized: true,
+            ..state
+        },
+        Window::maximize,
+    )
+}
+
+#[tauri::command]
+pub async fn unmaximize<R: Runtime>(
+    window: Window<R>,
+    states: State<'_, DisplayStates>,
+    label: Option<String>,
+) -> Result<()> {
+    states.request(
+        &get_window(window, label)?,
+        |state| DisplayState {
+            maximized: false,
+            ..state
+        },
+        Window::unmaximize,
+    )
+}
+
+#[tauri::command]
+pub async fn toggle_maximize<R: Runtime>(
+    window: Window<R>,
+    states: State<'_, DisplayStates>,
+    label: Option<String>,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    let maximized = window.is_maximized()?;
+    states.request(
+        &window,
+        |state| DisplayState {
+            maximized: !maximized,
+            ..state
+        },
+        |window| match maximized {
+            true => window.unmaximize(),
+            false => window.maximize(),
+        },
+    )
+}
+
+#[tauri::command]
+pub async fn minimize<R: Runtime>(
+    window: Window<R>,
+    states: State<'_, DisplayStates>,
+    label: Option<String>,
+) -> Result<()> {
+    states.request(
+        &get_window(window, label)?,
+        |state| DisplayState {
+            minimized: true,
+            ..state
+        },
+        Window::minimize,
+    )
+}
+
+#[tauri::command]
+pub async fn unminimize<R: Runtime>(
+    window: Window<R>,
+    states: State<'_, DisplayStates>,
+    label: Option<String>,
+) -> Result<()> {
+    states.request(
+        &get_window(window, label)?,
+        |state| DisplayState {
+            minimized: false,
+            ..state
+        },
+        Window::unminimize,
+    )
+}
@@ -0,0 +1,130 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Serialize;
+use tauri::{Monitor, PhysicalPosition, PhysicalSize, Position, Runtime, Size, State, Window};
+
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::desktop_commands::{get_window, Error, Result};
+
+/// Event emitted on a window when it enters or leaves fullscreen through the plugin.
+pub const FULLSCREEN_EVENT: &str = "window://fullscreen";
+
+/// The geometry a window had right before it entered fullscreen.
+struct WindowedGeometry {
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    maximized: bool,
+}
+
+#[derive(Default)]
+pub struct FullscreenCache(Mutex<HashMap<String, WindowedGeometry>>);
+
+impl FullscreenCache {
+    pub fn remove(&self, label: &str) {
+        self.0.lock().unwrap().remove(label);
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FullscreenPayload {
+    fullscreen: bool,
+    monitor: Option<String>,
+}
+
+fn monitor_name(monitor: Option<Monitor>) -> Option<String> {
+    monitor.and_then(|m| m.name().cloned())
+}
+
+#[tauri::command]
+pub async fn enter_fullscreen<R: Runtime>(
+    window: Window<R>,
+    cache: State<'_, FullscreenCache>,
+    label: Option<String>,
+    monitor: Option<String>,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+
+    let target = match monitor {
+        Some(name) => Some(
+            window
+                .available_monitors()?
+                .into_iter()
+                .find(|m| m.name() == Some(&name))
+                .ok_or(Error::MonitorNotFound(name))?,
+        ),
+        None => None,
+    };
+
+    // only record the windowed geometry once, so entering fullscreen twice
+    // (e.g. to switch displays) still restores the original rect on exit
+    if !window.is_fullscreen()? {
+        let geometry = WindowedGeometry {
+            position: window.outer_position()?,
+            size: window.inner_size()?,
+            maximized: window.is_maximized()?,
+        };
+        cache
+            .0
+            .lock()
+            .unwrap()
+            .insert(window.label().to_string(), geometry);
+    }
+
+    if let Some(target) = &target {
+        // the window must be moved while windowed, otherwise the OS keeps it
+        // fullscreen on the display it is currently on
+        if window.is_fullscreen()? {
+            window.set_fullscreen(false)?;
+        }
+        if window.is_maximized()? {
+            window.unmaximize()?;
+        }
+        window.set_position(Position::Physical(*target.position()))?;
+    }
+
+    window.set_fullscreen(true)?;
+
+    window.emit(
+        FULLSCREEN_EVENT,
+        FullscreenPayload {
+            fullscreen: true,
+            monitor: monitor_name(target.or(window.current_monitor()?)),
+        },
+    )?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn exit_fullscreen<R: Runtime>(
+    window: Window<R>,
+    cache: State<'_, FullscreenCache>,
+    label: Option<String>,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+
+    window.set_fullscreen(false)?;
+
+    let geometry = cache.0.lock().unwrap().remove(window.label());
+    if let Some(geometry) = geometry {
+        window.set_position(Position::Physical(geometry.position))?;
+        window.set_size(Size::Physical(geometry.size))?;
+        if geometry.maximized {
+            window.maximize()?;
+        }
+    }
+
+    window.emit(
+        FULLSCREEN_EVENT,
+        FullscreenPayload {
+            fullscreen: false,
+            monitor: monitor_name(window.current_monitor()?),
+        },
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Serialize;
+use tauri::{Monitor, PhysicalPosition, PhysicalSize, Runtime, State, Window, WindowEvent};
+
+use std::{
+    collections::HashMap,
+    sync::{
+        mpsc::{channel, RecvTimeoutError, Sender},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use crate::desktop_commands::{get_window, Result};
+
+/// Event emitted once a window stops moving for the observed debounce delay.
+pub const MOVED_DEBOUNCED_EVENT: &str = "window://moved-debounced";
+/// Event emitted once a window stops resizing for the observed debounce delay.
+pub const RESIZED_DEBOUNCED_EVENT: &str = "window://resized-debounced";
+/// Event emitted with the final geometry after a debounced move or resize.
+pub const GEOMETRY_COMMITTED_EVENT: &str = "window://geometry-committed";
+
+enum Change {
+    Moved,
+    Resized,
+}
+
+/// The debouncer of each observed window, indexed by label.
+///
+/// Dropping the sender stops the debouncer thread.
+#[derive(Default)]
+pub struct GeometryObservers(Mutex<HashMap<String, Sender<Change>>>);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeometryPayload {
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    maximized: bool,
+    fullscreen: bool,
+    monitor: Option<Monitor>,
+}
+
+fn emit_committed<R: Runtime>(window: &Window<R>, moved: bool, resized: bool) -> Result<()> {
+    let position = window.outer_position()?;
+    let size = window.inner_size()?;
+
+    if moved {
+        window.emit(MOVED_DEBOUNCED_EVENT, position)?;
+    }
+    if resized {
+        window.emit(RESIZED_DEBOUNCED_EVENT, size)?;
+    }
+
+    window.emit(
+        GEOMETRY_COMMITTED_EVENT,
+        GeometryPayload {
+            position,
+            size,
+            maximized: window.is_maximized()?,
+            fullscreen: window.is_fullscreen()?,
+            monitor: window.current_monitor()?,
+        },
+    )?;
+
+    Ok(())
+}
+
+fn spawn_debouncer<R: Runtime>(window: Window<R>, delay: Duration) -> Sender<Change> {
+    let (tx, rx) = channel();
+
+    std::thread::spawn(move || {
+        // wait for the first change of a burst, then until no change arrives for `delay`
+        while let Ok(change) = rx.recv() {
+            let (mut moved, mut resized) = (false, false);
+            let mut change = Some(change);
+            loop {
+                match change.take() {
+                    Some(Change::Moved) => moved = true,
+                    Some(Change::Resized) => resized = true,
+                    None => {}
+                }
+                match rx.recv_timeout(delay) {
+                    Ok(next) => change = Some(next),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            let _ = emit_committed(&window, moved, resized);
+        }
+    });
+
+    tx
+}
+
+impl GeometryObservers {
+    /// Feeds a native window event to the debouncer of the window, if it is observed.
+    pub fn handle_event(&self, label: &str, event: &WindowEvent) {
+        let mut observers = self.0.lock().unwrap();
+        let change = match event {
+            WindowEvent::Moved(_) => Change::Moved,
+            WindowEvent::Resized(_) => Change::Resized,
+            WindowEvent::Destroyed => {
+                observers.remove(label);
+                return;
+            }
+            _ => return,
+        };
+        if let Some(tx) = observers.get(label) {
+            let _ = tx.send(change);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn observe_geometry<R: Runtime>(
+    window: Window<R>,
+    observers: State<'_, GeometryObservers>,
+    label: Option<String>,
+    debounce: u64,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    let label = window.label().to_string();
+    let tx = spawn_debouncer(window, Duration::from_millis(debounce));
+    // replacing an existing observer drops its sender and stops its thread
+    observers.0.lock().unwrap().insert(label, tx);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unobserve_geometry<R: Runtime>(
+    window: Window<R>,
+    observers: State<'_, GeometryObservers>,
+    label: Option<String>,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    observers.0.lock().unwrap().remove(window.label());
+    Ok(())
+}
@@ -0,0 +1,200 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Click-through for the transparent areas of a window, on Windows.
+//!
+//! A window ignoring the cursor events does not receive the cursor moves either, so they are
+//! observed with a low-level mouse hook and the webview decides whether the content under the
+//! cursor is transparent, toggling [`Window::set_ignore_cursor_events`] as it crosses opaque
+//! content.
+//!
+//! The webview can not read its rendered pixels, so the transparency is decided from the
+//! document: a semi-transparent element, or the transparent pixels of an image, are opaque.
+
+use tauri::{Runtime, State, Window};
+
+#[cfg(windows)]
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::desktop_commands::{get_window, Result};
+
+/// The mouse hook registration of each hit-tested window, indexed by label.
+#[derive(Default)]
+pub struct HitTesters(#[cfg(windows)] Mutex<HashMap<String, hook::Registration>>);
+
+impl HitTesters {
+    pub fn stop(&self, _label: &str) {
+        #[cfg(windows)]
+        self.0.lock().unwrap().remove(_label);
+    }
+}
+
+#[cfg(windows)]
+mod hook {
+    //! A low-level mouse hook sending the cursor moves to the registered channels.
+    //!
+    //! The hook runs on its own thread, which only lives while a channel is registered.
+
+    use std::{
+        collections::HashMap,
+        sync::{
+            mpsc::{channel, Sender},
+            Mutex,
+        },
+        thread,
+    };
+
+    use windows_sys::Win32::{
+        Foundation::{LPARAM, LRESULT, WPARAM},
+        System::Threading::GetCurrentThreadId,
+        UI::WindowsAndMessaging::{
+            CallNextHookEx, GetMessageW, PostThreadMessageW, SetWindowsHookExW,
+            UnhookWindowsHookEx, MSG, MSLLHOOKSTRUCT, WH_MOUSE_LL, WM_MOUSEMOVE, WM_QUIT,
+        },
+    };
+
+    /// Receives the cursor position, in physical screen coordinates.
+    pub type CursorSender = Sender<(i32, i32)>;
+
+    struct Hook {
+        thread_id: u32,
+        next_id: u64,
+        senders: HashMap<u64, CursorSender>,
+    }
+
+    static HOOK: Mutex<Option<Hook>> = Mutex::new(None);
+
+    /// Removes the channel from the hook when dropped.
+    pub struct Registration(u64);
+
+    impl Drop for Registration {
+        fn drop(&mut self) {
+            let mut hook = HOOK.lock().unwrap();
+            let Some(running) = &mut *hook else {
+                return;
+            };
+            running.senders.remove(&self.0);
+            if running.senders.is_empty() {
+                unsafe { PostThreadMessageW(running.thread_id, WM_QUIT, 0, 0) };
+                *hook = None;
+            }
+        }
+    }
+
+    /// Sends the cursor moves to `sender` until the registration is dropped.
+    ///
+    /// Returns `None` if the hook could not be installed.
+    pub fn register(sender: CursorSender) -> Option<Registration> {
+        let mut hook = HOOK.lock().unwrap();
+        if hook.is_none() {
+            let (started, thread_id) = channel();
+            thread::spawn(move || run(started));
+            *hook = Some(Hook {
+                thread_id: thread_id.recv().ok()?,
+                next_id: 0,
+                senders: HashMap::new(),
+            });
+        }
+        let running = hook.as_mut().expect("the hook is running");
+        let id = running.next_id;
+        running.next_id += 1;
+        running.senders.insert(id, sender);
+        Some(Registration(id))
+    }
+
+    fn run(started: Sender<u32>) {
+        let hook = unsafe { SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_proc), 0, 0) };
+        if hook == 0 {
+            return;
+        }
+        let _ = started.send(unsafe { GetCurrentThreadId() });
+        // the hook is called by the message loop of the thread that installed it
+        let mut msg = unsafe { std::mem::zeroed::<MSG>() };
+        while unsafe { GetMessageW(&mut msg, 0, 0, 0) } > 0 {}
+        unsafe { UnhookWindowsHookEx(hook) };
+    }
+
+    unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        // the hook delays all the mouse input, so the moves are only forwarded
+        if code >= 0 && wparam == WM_MOUSEMOVE as WPARAM {
+            let info = &*(lparam as *const MSLLHOOKSTRUCT);
+            if let Some(hook) = &*HOOK.lock().unwrap() {
+                for sender in hook.senders.values() {
+                    let _ = sender.send((info.pt.x, info.pt.y));
+                }
+            }
+        }
+        CallNextHookEx(0, code, wparam, lparam)
+    }
+}
+
+/// Sends the cursor positions in the window to its hit test, until the window is gone.
+#[cfg(windows)]
+fn spawn_tester<R: Runtime>(window: Window<R>) -> hook::CursorSender {
+    let (sender, receiver) = std::sync::mpsc::channel::<(i32, i32)>();
+
+    std::thread::spawn(move || {
+        while let Ok(cursor) = receiver.recv() {
+            // only the latest position matters
+            let (x, y) = receiver.try_iter().last().unwrap_or(cursor);
+            let (Ok(position), Ok(size), Ok(scale_factor)) = (
+                window.inner_position(),
+                window.inner_size(),
+                window.scale_factor(),
+            ) else {
+                // the window is gone
+                break;
+            };
+
+            let (x, y) = (x - position.x, y - position.y);
+            let inside = x >= 0 && y >= 0 && (x as u32) < size.width && (y as u32) < size.height;
+            if !inside {
+                continue;
+            }
+
+            let _ = window.eval(&format!(
+                "window.__TAURI_HIT_TEST__ && window.__TAURI_HIT_TEST__({}, {})",
+                f64::from(x) / scale_factor,
+                f64::from(y) / scale_factor
+            ));
+        }
+    });
+
+    sender
+}
+
+/// Makes the transparent areas of the window click-through.
+///
+/// A point is transparent when it hits the document background and both the `html` and `body`
+/// backgrounds are fully transparent, or when it hits an element with the
+/// `data-tauri-click-through` attribute. The rendered pixels are not tested.
+///
+/// ## Platform-specific
+///
+/// - **Linux / macOS:** Unsupported, the window keeps processing all the cursor events.
+#[tauri::command]
+pub async fn set_hit_test<R: Runtime>(
+    window: Window<R>,
+    testers: State<'_, HitTesters>,
+    label: Option<String>,
+    value: bool,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    testers.stop(window.label());
+
+    if value {
+        #[cfg(windows)]
+        {
+            let label = window.label().to_string();
+            if let Some(registration) = hook::register(spawn_tester(window)) {
+                testers.0.lock().unwrap().insert(label, registration);
+            }
+        }
+    } else {
+        window.set_ignore_cursor_events(false)?;
+        window.eval("window.__TAURI_HIT_TEST__ && window.__TAURI_HIT_TEST__(null)")?;
+    }
+
+    Ok(())
+}
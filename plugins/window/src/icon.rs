@@ -0,0 +1,137 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Window icons set at runtime, in one or several sizes.
+
+use serde::Deserialize;
+use tauri::{AppHandle, Icon, Manager, Runtime, State, Window, WindowEvent};
+
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::desktop_commands::{get_window, Error, IconDto, Result};
+
+/// The logical size of the window icon the sizes are picked for.
+const ICON_SIZE: f64 = 32.0;
+
+/// An icon, or the same icon in several sizes.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum IconSet {
+    One(IconDto),
+    Many(Vec<IconDto>),
+}
+
+/// An icon and its largest dimension, when it is known.
+type SizedIcon = (Option<u32>, Icon);
+
+/// The icons set on each window with several sizes, indexed by label.
+#[derive(Default)]
+pub struct WindowIcons(Mutex<HashMap<String, Vec<SizedIcon>>>);
+
+impl WindowIcons {
+    pub fn handle_event<R: Runtime>(&self, app: &AppHandle<R>, label: &str, event: &WindowEvent) {
+        match event {
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                let icon = self
+                    .0
+                    .lock()
+                    .unwrap()
+                    .get(label)
+                    .and_then(|icons| pick(icons, *scale_factor));
+                if let (Some(icon), Some(window)) = (icon, app.get_window(label)) {
+                    let _ = window.set_icon(icon);
+                }
+            }
+            WindowEvent::Destroyed => {
+                self.0.lock().unwrap().remove(label);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Sets the window icon. With several sizes, the one matching the scale factor of the window
+/// is used, and changed when the window moves to a monitor with another scale factor.
+#[tauri::command]
+pub async fn set_icon<R: Runtime>(
+    window: Window<R>,
+    icons: State<'_, WindowIcons>,
+    label: Option<String>,
+    value: IconSet,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    let sizes = match value {
+        IconSet::One(icon) => vec![sized(icon)],
+        IconSet::Many(icons) => icons.into_iter().map(sized).collect(),
+    };
+    let icon = pick(&sizes, window.scale_factor()?).ok_or(Error::EmptyIconSet)?;
+    window.set_icon(icon)?;
+
+    let mut icons = icons.0.lock().unwrap();
+    if sizes.len() > 1 {
+        icons.insert(window.label().into(), sizes);
+    } else {
+        icons.remove(window.label());
+    }
+    Ok(())
+}
+
+/// Restores the default window icon of the app.
+#[tauri::command]
+pub async fn reset_icon<R: Runtime>(
+    window: Window<R>,
+    icons: State<'_, WindowIcons>,
+    label: Option<String>,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    let icon = window
+        .app_handle()
+        .default_window_icon()
+        .cloned()
+        .ok_or(Error::DefaultIconUnavailable)?;
+    window.set_icon(icon)?;
+    icons.0.lock().unwrap().remove(window.label());
+    Ok(())
+}
+
+fn sized(icon: IconDto) -> SizedIcon {
+    let size = match &icon {
+        IconDto::Rgba { width, height, .. } => Some(*width.max(height)),
+        #[cfg(any(feature = "icon-png", feature = "icon-ico"))]
+        IconDto::Raw(bytes) => png_size(bytes),
+        #[cfg(any(feature = "icon-png", feature = "icon-ico"))]
+        IconDto::File(_) => None,
+    };
+    (size, icon.into())
+}
+
+/// The largest dimension of a PNG image, read from its header.
+#[cfg(any(feature = "icon-png", feature = "icon-ico"))]
+fn png_size(bytes: &[u8]) -> Option<u32> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if !bytes.starts_with(SIGNATURE) || bytes.len() < 24 {
+        return None;
+    }
+    // the IHDR chunk always comes first
+    let width = u32::from_be_bytes(bytes[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(bytes[20..24].try_into().ok()?);
+    Some(width.max(height))
+}
+
+/// The smallest icon at least as large as the icon size at this scale factor,
+/// or the largest one if they are all smaller.
+fn pick(icons: &[SizedIcon], scale_factor: f64) -> Option<Icon> {
+    let target = (ICON_SIZE * scale_factor).round() as u32;
+    let known = icons
+        .iter()
+        .filter_map(|(size, icon)| Some((size.as_ref()?, icon)));
+    known
+        .clone()
+        .filter(|(size, _)| **size >= target)
+        .min_by_key(|(size, _)| **size)
+        .or_else(|| known.max_by_key(|(size, _)| **size))
+        .map(|(_, icon)| icon)
+        .or_else(|| icons.first().map(|(_, icon)| icon))
+        .cloned()
+}
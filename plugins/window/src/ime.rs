@@ -0,0 +1,68 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Input method control for the custom text areas, e.g. a terminal drawn on a canvas.
+//!
+//! The webview owns the input method of the platform, and places its candidate window at the
+//! caret of the focused input. So the script keeps an invisible input, focused and moved to
+//! the caret position given by the app. Disabling the input method turns it into a password
+//! input, which the platforms never compose into.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Position, Runtime, Window};
+
+use crate::desktop_commands::{get_window, Result};
+
+/// Event emitted on a window when the input method composes text in its document.
+pub const IME_EVENT: &str = "window://ime";
+
+/// A step of an input method composition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ImeEvent {
+    /// A composition started.
+    Start,
+    /// The text being composed changed, to be rendered in place until it is committed.
+    Preedit { text: String },
+    /// The composition ended with this text, empty if it was cancelled.
+    Commit { text: String },
+}
+
+#[tauri::command]
+pub async fn internal_ime_event<R: Runtime>(window: Window<R>, event: ImeEvent) -> Result<()> {
+    window.emit(IME_EVENT, event).map_err(Into::into)
+}
+
+/// Enables or disables the input method of the caret input placed with [`set_ime_position`].
+#[tauri::command]
+pub async fn set_ime_allowed<R: Runtime>(
+    window: Window<R>,
+    label: Option<String>,
+    value: bool,
+) -> Result<()> {
+    get_window(window, label)?
+        .eval(&format!("window.__TAURI_IME__.setAllowed({value})"))
+        .map_err(Into::into)
+}
+
+/// Focuses the caret input at this position of the window, where the input method
+/// places its candidate window.
+#[tauri::command]
+pub async fn set_ime_position<R: Runtime>(
+    window: Window<R>,
+    label: Option<String>,
+    value: Position,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    let position = match value {
+        Position::Logical(position) => position,
+        Position::Physical(position) => position.to_logical(window.scale_factor()?),
+    };
+    window
+        .eval(&format!(
+            "window.__TAURI_IME__.setPosition({}, {})",
+            position.x, position.y
+        ))
+        .map_err(Into::into)
+}
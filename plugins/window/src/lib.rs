@@ -7,8 +7,56 @@ use tauri::{
     Runtime,
 };
 
+#[cfg(desktop)]
+use tauri::Manager;
+
+#[cfg(desktop)]
+mod animation;
+#[cfg(desktop)]
+mod content_size;
 #[cfg(desktop)]
 mod desktop_commands;
+#[cfg(desktop)]
+mod display_state;
+#[cfg(desktop)]
+mod fullscreen;
+#[cfg(desktop)]
+mod geometry;
+#[cfg(desktop)]
+mod hit_test;
+#[cfg(desktop)]
+mod icon;
+#[cfg(desktop)]
+mod ime;
+#[cfg(desktop)]
+mod size_constraints;
+#[cfg(desktop)]
+mod theme;
+#[cfg(desktop)]
+mod titlebar;
+#[cfg(desktop)]
+mod webview_state;
+
+#[cfg(desktop)]
+pub use animation::{AnimationFinished, Easing, Rect, ANIMATION_FINISHED_EVENT};
+#[cfg(desktop)]
+pub use content_size::CONTENT_SIZE_CHANGED_EVENT;
+#[cfg(desktop)]
+pub use display_state::{
+    DisplayState, DisplayStateCause, DisplayStateChanged, DISPLAY_STATE_EVENT,
+};
+#[cfg(desktop)]
+pub use fullscreen::FULLSCREEN_EVENT;
+#[cfg(desktop)]
+pub use geometry::{GEOMETRY_COMMITTED_EVENT, MOVED_DEBOUNCED_EVENT, RESIZED_DEBOUNCED_EVENT};
+#[cfg(desktop)]
+pub use ime::{ImeEvent, IME_EVENT};
+#[cfg(desktop)]
+pub use size_constraints::{SizeConstraints, SIZE_CONSTRAINTS_EVENT};
+#[cfg(desktop)]
+pub use titlebar::TitleBarDoubleClickAction;
+#[cfg(desktop)]
+pub use webview_state::WebviewState;
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     let mut init_script = String::new();
@@ -18,6 +66,14 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
         init_script.push_str(include_str!("./scripts/print.js"));
     }
     init_script.push_str(include_str!("./scripts/drag.js"));
+    #[cfg(desktop)]
+    {
+        init_script.push_str(include_str!("./scripts/content-size.js"));
+        init_script.push_str(include_str!("./scripts/ime.js"));
+        init_script.push_str(include_str!("./scripts/webview-state.js"));
+    }
+    #[cfg(windows)]
+    init_script.push_str(include_str!("./scripts/hit-test.js"));
     #[cfg(any(debug_assertions, feature = "devtools"))]
     init_script.push_str(include_str!("./scripts/toggle-devtools.js"));
 
@@ -40,6 +96,7 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
                         desktop_commands::is_fullscreen,
                         desktop_commands::is_minimized,
                         desktop_commands::is_maximized,
+                        display_state::display_state,
                         desktop_commands::is_decorated,
                         desktop_commands::is_resizable,
                         desktop_commands::is_visible,
@@ -47,16 +104,17 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
                         desktop_commands::current_monitor,
                         desktop_commands::primary_monitor,
                         desktop_commands::available_monitors,
-                        desktop_commands::theme,
+                        theme::theme,
+                        titlebar::title_bar_double_click_action,
                         // setters
                         desktop_commands::center,
                         desktop_commands::request_user_attention,
                         desktop_commands::set_resizable,
                         desktop_commands::set_title,
-                        desktop_commands::maximize,
-                        desktop_commands::unmaximize,
-                        desktop_commands::minimize,
-                        desktop_commands::unminimize,
+                        display_state::maximize,
+                        display_state::unmaximize,
+                        display_state::minimize,
+                        display_state::unminimize,
                         desktop_commands::show,
                         desktop_commands::hide,
                         desktop_commands::close,
@@ -65,9 +123,15 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
                         desktop_commands::set_always_on_top,
                         desktop_commands::set_content_protected,
                         desktop_commands::set_size,
-                        desktop_commands::set_min_size,
-                        desktop_commands::set_max_size,
+                        size_constraints::set_min_size,
+                        size_constraints::set_min_size_logical,
+                        size_constraints::set_min_size_physical,
+                        size_constraints::set_max_size,
+                        size_constraints::set_max_size_logical,
+                        size_constraints::set_max_size_physical,
                         desktop_commands::set_position,
+                        animation::animate_to,
+                        animation::cancel_animation,
                         desktop_commands::set_fullscreen,
                         desktop_commands::set_focus,
                         desktop_commands::set_skip_taskbar,
@@ -76,11 +140,28 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
                         desktop_commands::set_cursor_icon,
                         desktop_commands::set_cursor_position,
                         desktop_commands::set_ignore_cursor_events,
+                        ime::set_ime_allowed,
+                        ime::set_ime_position,
+                        ime::internal_ime_event,
                         desktop_commands::start_dragging,
                         desktop_commands::print,
-                        desktop_commands::set_icon,
-                        desktop_commands::toggle_maximize,
-                        desktop_commands::internal_toggle_maximize,
+                        icon::set_icon,
+                        icon::reset_icon,
+                        display_state::toggle_maximize,
+                        titlebar::perform_title_bar_double_click,
+                        fullscreen::enter_fullscreen,
+                        fullscreen::exit_fullscreen,
+                        geometry::observe_geometry,
+                        geometry::unobserve_geometry,
+                        hit_test::set_hit_test,
+                        theme::set_theme,
+                        content_size::content_size,
+                        content_size::fit_to_content,
+                        content_size::internal_set_content_size,
+                        webview_state::capture_state,
+                        webview_state::restore_state,
+                        webview_state::internal_report_state,
+                        webview_state::internal_pending_state,
                         #[cfg(any(debug_assertions, feature = "devtools"))]
                         desktop_commands::internal_toggle_devtools,
                     ]);
@@ -93,5 +174,50 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
                 return true;
             }
         })
+        .setup(|_app, _api| {
+            #[cfg(desktop)]
+            {
+                _app.manage(fullscreen::FullscreenCache::default());
+                _app.manage(geometry::GeometryObservers::default());
+                _app.manage(hit_test::HitTesters::default());
+                _app.manage(theme::ThemeOverrides::default());
+                _app.manage(content_size::ContentSizes::default());
+                _app.manage(icon::WindowIcons::default());
+                _app.manage(size_constraints::WindowSizeConstraints::default());
+                _app.manage(webview_state::WebviewStates::default());
+                _app.manage(display_state::DisplayStates::default());
+                _app.manage(animation::Animations::default());
+            }
+            Ok(())
+        })
+        .on_webview_ready(|_window| {
+            #[cfg(desktop)]
+            _window
+                .state::<display_state::DisplayStates>()
+                .track(&_window);
+        })
+        .on_event(|_app, _event| {
+            #[cfg(desktop)]
+            if let tauri::RunEvent::WindowEvent { label, event, .. } = _event {
+                _app.state::<geometry::GeometryObservers>()
+                    .handle_event(label, event);
+                _app.state::<theme::ThemeOverrides>()
+                    .handle_event(_app, label, event);
+                _app.state::<icon::WindowIcons>()
+                    .handle_event(_app, label, event);
+                _app.state::<size_constraints::WindowSizeConstraints>()
+                    .handle_event(_app, label, event);
+                _app.state::<display_state::DisplayStates>()
+                    .handle_event(_app, label, event);
+                _app.state::<animation::Animations>()
+                    .handle_event(label, event);
+                if let tauri::WindowEvent::Destroyed = event {
+                    _app.state::<hit_test::HitTesters>().stop(label);
+                    _app.state::<content_size::ContentSizes>().remove(label);
+                    _app.state::<webview_state::WebviewStates>().remove(label);
+                    _app.state::<fullscreen::FullscreenCache>().remove(label);
+                }
+            }
+        })
         .build()
 }
@@ -0,0 +1,55 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+(function () {
+  let last = null;
+  let scheduled = false;
+
+  // the size of the root element, or of its content when it overflows the viewport
+  function measure() {
+    scheduled = false;
+    const root = document.documentElement;
+    const rect = root.getBoundingClientRect();
+    const size = {
+      width: Math.ceil(
+        Math.max(
+          rect.width,
+          root.scrollWidth > root.clientWidth ? root.scrollWidth : 0
+        )
+      ),
+      height: Math.ceil(
+        Math.max(
+          rect.height,
+          root.scrollHeight > root.clientHeight ? root.scrollHeight : 0
+        )
+      ),
+    };
+    if (last && last.width === size.width && last.height === size.height) {
+      return;
+    }
+    last = size;
+    window.__TAURI_INVOKE__("plugin:window|internal_set_content_size", size);
+  }
+
+  function schedule() {
+    if (!scheduled) {
+      scheduled = true;
+      requestAnimationFrame(measure);
+    }
+  }
+
+  function observe() {
+    const observer = new ResizeObserver(schedule);
+    observer.observe(document.documentElement);
+    if (document.body) {
+      observer.observe(document.body);
+    }
+  }
+
+  if (document.readyState === "loading") {
+    document.addEventListener("DOMContentLoaded", observe);
+  } else {
+    observe();
+  }
+})();
@@ -10,8 +10,10 @@ document.addEventListener("mousedown", (e) => {
     // https://github.com/tauri-apps/tauri/issues/2549#issuecomment-1250036908
     e.stopImmediatePropagation();
 
-    // start dragging if the element has a `tauri-drag-region` data attribute and maximize on double-clicking it
-    const cmd = e.detail === 2 ? "internal_toggle_maximize" : "start_dragging";
+    // start dragging if the element has a `tauri-drag-region` data attribute and perform the
+    // system title bar action on double-clicking it
+    const cmd =
+      e.detail === 2 ? "perform_title_bar_double_click" : "start_dragging";
     window.__TAURI_INVOKE__("plugin:window|" + cmd);
   }
 });
@@ -0,0 +1,50 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+(function () {
+  let ignoring = false;
+
+  function alpha(color) {
+    if (color === "transparent") {
+      return 0;
+    }
+    const values = /rgba?\(([^)]*)\)/.exec(color);
+    if (values == null) {
+      return 1;
+    }
+    const parts = values[1].split(/[\s,/]+/).filter(Boolean);
+    return parts.length > 3 ? parseFloat(parts[3]) : 1;
+  }
+
+  // the cursor is over a transparent pixel if it hits the document background
+  // and that background is fully transparent, or an element opting out of hit-testing
+  function isTransparent(x, y) {
+    const target = document.elementFromPoint(x, y);
+    if (target == null || target.closest("[data-tauri-click-through]")) {
+      return true;
+    }
+    return (
+      (target === document.documentElement || target === document.body) &&
+      alpha(getComputedStyle(document.documentElement).backgroundColor) === 0 &&
+      alpha(getComputedStyle(document.body).backgroundColor) === 0
+    );
+  }
+
+  Object.defineProperty(window, "__TAURI_HIT_TEST__", {
+    value: (x, y) => {
+      // hit-testing was disabled and the window already processes cursor events again
+      if (x == null) {
+        ignoring = false;
+        return;
+      }
+      const transparent = isTransparent(x, y);
+      if (transparent !== ignoring) {
+        ignoring = transparent;
+        window.__TAURI_INVOKE__("plugin:window|set_ignore_cursor_events", {
+          value: transparent,
+        });
+      }
+    },
+  });
+})();
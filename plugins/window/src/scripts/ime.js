@@ -0,0 +1,82 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+(function () {
+  let caret = null;
+  let allowed = true;
+
+  function report(event) {
+    window.__TAURI_INVOKE__("plugin:window|internal_ime_event", { event });
+  }
+
+  // the compositions of every input of the document are reported
+  document.addEventListener(
+    "compositionstart",
+    () => report({ type: "start" }),
+    true
+  );
+  document.addEventListener(
+    "compositionupdate",
+    (e) => report({ type: "preedit", text: e.data }),
+    true
+  );
+  document.addEventListener(
+    "compositionend",
+    (e) => {
+      report({ type: "commit", text: e.data });
+      if (caret && e.target === caret) {
+        caret.value = "";
+      }
+    },
+    true
+  );
+
+  function createCaret() {
+    const input = document.createElement("input");
+    input.setAttribute("autocomplete", "off");
+    input.setAttribute("autocorrect", "off");
+    input.setAttribute("autocapitalize", "off");
+    input.setAttribute("spellcheck", "false");
+    input.setAttribute("aria-hidden", "true");
+    Object.assign(input.style, {
+      position: "fixed",
+      width: "1px",
+      height: "1em",
+      margin: "0",
+      padding: "0",
+      border: "0",
+      outline: "none",
+      opacity: "0",
+      pointerEvents: "none",
+    });
+    input.addEventListener("input", (e) => {
+      // the text typed without composition is delivered by the key events
+      if (!e.isComposing) {
+        input.value = "";
+      }
+    });
+    document.body.appendChild(input);
+    return input;
+  }
+
+  window.__TAURI_IME__ = {
+    setAllowed(value) {
+      allowed = value;
+      if (caret) {
+        caret.type = allowed ? "text" : "password";
+      }
+    },
+    setPosition(x, y) {
+      if (!caret || !caret.isConnected) {
+        caret = createCaret();
+      }
+      caret.type = allowed ? "text" : "password";
+      caret.style.left = `${x}px`;
+      caret.style.top = `${y}px`;
+      if (document.activeElement !== caret) {
+        caret.focus({ preventScroll: true });
+      }
+    },
+  };
+})();
@@ -0,0 +1,66 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+(function () {
+  function pendingState(take) {
+    return window.__TAURI_INVOKE__("plugin:window|internal_pending_state", {
+      take,
+    });
+  }
+
+  function restoreSessionStorage(entries) {
+    if (entries) {
+      for (const [key, value] of Object.entries(entries)) {
+        window.sessionStorage.setItem(key, value);
+      }
+    }
+  }
+
+  function capture(id, sessionStorage) {
+    const state = {
+      scrollX: window.scrollX,
+      scrollY: window.scrollY,
+      sessionStorage: null,
+    };
+    if (sessionStorage) {
+      state.sessionStorage = {};
+      for (let i = 0; i < window.sessionStorage.length; i++) {
+        const key = window.sessionStorage.key(i);
+        state.sessionStorage[key] = window.sessionStorage.getItem(key);
+      }
+    }
+    window.__TAURI_INVOKE__("plugin:window|internal_report_state", {
+      id,
+      state,
+    });
+  }
+
+  // the scroll position is only kept once the document has its final layout
+  function restore() {
+    if (document.readyState !== "complete") {
+      window.addEventListener("load", restore, { once: true });
+      return;
+    }
+    pendingState(true).then((state) => {
+      if (state) {
+        restoreSessionStorage(state.sessionStorage);
+        window.scrollTo(state.scrollX, state.scrollY);
+      }
+    });
+  }
+
+  // `sessionStorage` outlives the reload, the new document takes the state
+  function reload() {
+    pendingState(false).then((state) => {
+      restoreSessionStorage(state && state.sessionStorage);
+      window.location.reload();
+    });
+  }
+
+  Object.defineProperty(window, "__TAURI_WEBVIEW_STATE__", {
+    value: { capture, restore, reload },
+  });
+
+  restore();
+})();
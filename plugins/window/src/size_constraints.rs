@@ -0,0 +1,173 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Minimum and maximum window sizes in explicit units.
+//!
+//! The platforms store the limits in physical pixels, so a logical limit would shrink or grow
+//! visually when the window moves to a monitor with another scale factor. The limits are kept
+//! in the unit they were set in and applied again on every scale factor change.
+
+use serde::Serialize;
+use tauri::{
+    AppHandle, LogicalSize, Manager, PhysicalSize, Runtime, Size, State, Window, WindowEvent,
+};
+
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::desktop_commands::{get_window, Result};
+
+/// Event emitted on a window with its [`SizeConstraints`] when they are set or applied again
+/// for a new scale factor.
+pub const SIZE_CONSTRAINTS_EVENT: &str = "window://size-constraints";
+
+/// The effective size limits of a window.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeConstraints {
+    /// The scale factor the limits were converted with.
+    pub scale_factor: f64,
+    pub min_size: Option<PhysicalSize<u32>>,
+    pub max_size: Option<PhysicalSize<u32>>,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Limits {
+    min: Option<Size>,
+    max: Option<Size>,
+}
+
+/// The size limits of each window, indexed by label.
+#[derive(Default)]
+pub struct WindowSizeConstraints(Mutex<HashMap<String, Limits>>);
+
+impl WindowSizeConstraints {
+    pub fn handle_event<R: Runtime>(&self, app: &AppHandle<R>, label: &str, event: &WindowEvent) {
+        match event {
+            WindowEvent::ScaleFactorChanged { .. } => {
+                let limits = self.0.lock().unwrap().get(label).copied();
+                if let (Some(limits), Some(window)) = (limits, app.get_window(label)) {
+                    let _ = apply(&window, limits);
+                }
+            }
+            WindowEvent::Destroyed => {
+                self.0.lock().unwrap().remove(label);
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&self, window: &Window<impl Runtime>, f: impl FnOnce(&mut Limits)) -> Limits {
+        let mut constraints = self.0.lock().unwrap();
+        let limits = constraints.entry(window.label().into()).or_default();
+        f(limits);
+        let limits = *limits;
+        if limits.min.is_none() && limits.max.is_none() {
+            constraints.remove(window.label());
+        }
+        limits
+    }
+}
+
+fn apply<R: Runtime>(window: &Window<R>, limits: Limits) -> Result<()> {
+    window.set_min_size(limits.min)?;
+    window.set_max_size(limits.max)?;
+    let scale_factor = window.scale_factor()?;
+    window.emit(
+        SIZE_CONSTRAINTS_EVENT,
+        SizeConstraints {
+            scale_factor,
+            min_size: limits.min.map(|size| size.to_physical(scale_factor)),
+            max_size: limits.max.map(|size| size.to_physical(scale_factor)),
+        },
+    )?;
+    Ok(())
+}
+
+fn set_min<R: Runtime>(
+    window: Window<R>,
+    constraints: State<'_, WindowSizeConstraints>,
+    label: Option<String>,
+    value: Option<Size>,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    let limits = constraints.update(&window, |limits| limits.min = value);
+    apply(&window, limits)
+}
+
+fn set_max<R: Runtime>(
+    window: Window<R>,
+    constraints: State<'_, WindowSizeConstraints>,
+    label: Option<String>,
+    value: Option<Size>,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    let limits = constraints.update(&window, |limits| limits.max = value);
+    apply(&window, limits)
+}
+
+/// Sets the minimum size, in the unit of the given size.
+#[tauri::command]
+pub async fn set_min_size<R: Runtime>(
+    window: Window<R>,
+    constraints: State<'_, WindowSizeConstraints>,
+    label: Option<String>,
+    value: Option<Size>,
+) -> Result<()> {
+    set_min(window, constraints, label, value)
+}
+
+/// Sets the minimum size in logical pixels, kept for every scale factor.
+#[tauri::command]
+pub async fn set_min_size_logical<R: Runtime>(
+    window: Window<R>,
+    constraints: State<'_, WindowSizeConstraints>,
+    label: Option<String>,
+    value: Option<LogicalSize<f64>>,
+) -> Result<()> {
+    set_min(window, constraints, label, value.map(Size::Logical))
+}
+
+/// Sets the minimum size in physical pixels, regardless of the scale factor.
+#[tauri::command]
+pub async fn set_min_size_physical<R: Runtime>(
+    window: Window<R>,
+    constraints: State<'_, WindowSizeConstraints>,
+    label: Option<String>,
+    value: Option<PhysicalSize<u32>>,
+) -> Result<()> {
+    set_min(window, constraints, label, value.map(Size::Physical))
+}
+
+/// Sets the maximum size, in the unit of the given size.
+#[tauri::command]
+pub async fn set_max_size<R: Runtime>(
+    window: Window<R>,
+    constraints: State<'_, WindowSizeConstraints>,
+    label: Option<String>,
+    value: Option<Size>,
+) -> Result<()> {
+    set_max(window, constraints, label, value)
+}
+
+/// Sets the maximum size in logical pixels, kept for every scale factor.
+#[tauri::command]
+pub async fn set_max_size_logical<R: Runtime>(
+    window: Window<R>,
+    constraints: State<'_, WindowSizeConstraints>,
+    label: Option<String>,
+    value: Option<LogicalSize<f64>>,
+) -> Result<()> {
+    set_max(window, constraints, label, value.map(Size::Logical))
+}
+
+/// Sets the maximum size in physical pixels, regardless of the scale factor.
+#[tauri::command]
+pub async fn set_max_size_physical<R: Runtime>(
+    window: Window<R>,
+    constraints: State<'_, WindowSizeConstraints>,
+    label: Option<String>,
+    value: Option<PhysicalSize<u32>>,
+) -> Result<()> {
+    set_max(window, constraints, label, value.map(Size::Physical))
+}
@@ -0,0 +1,180 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Per-window light or dark appearance, regardless of the system setting.
+
+use tauri::{AppHandle, Manager, Runtime, State, Theme, Window, WindowEvent};
+
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::desktop_commands::{get_window, Result};
+
+/// Event emitted on a window when its effective theme changes.
+const THEME_CHANGED_EVENT: &str = "tauri://theme-changed";
+
+/// The theme forced on each window, indexed by label.
+#[derive(Default)]
+pub struct ThemeOverrides(Mutex<HashMap<String, Theme>>);
+
+impl ThemeOverrides {
+    pub fn handle_event<R: Runtime>(&self, app: &AppHandle<R>, label: &str, event: &WindowEvent) {
+        match event {
+            WindowEvent::ThemeChanged(_) => {
+                let Some(theme) = self.0.lock().unwrap().get(label).copied() else {
+                    return;
+                };
+                // the system theme changed, the override must win again
+                if let Some(window) = app.get_window(label) {
+                    if apply(&window, Some(theme)).is_ok() {
+                        let _ = window.emit(THEME_CHANGED_EVENT, theme);
+                    }
+                }
+            }
+            WindowEvent::Destroyed => {
+                self.0.lock().unwrap().remove(label);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn theme<R: Runtime>(
+    window: Window<R>,
+    overrides: State<'_, ThemeOverrides>,
+    label: Option<String>,
+) -> Result<Theme> {
+    let window = get_window(window, label)?;
+    let theme = overrides.0.lock().unwrap().get(window.label()).copied();
+    match theme {
+        Some(theme) => Ok(theme),
+        None => window.theme().map_err(Into::into),
+    }
+}
+
+/// Forces the window into the light or dark theme, or follows the system theme again with `None`.
+///
+/// ## Platform-specific
+///
+/// - **Linux:** GTK only has an application-wide dark variant, so the theme applies to all windows.
+#[tauri::command]
+pub async fn set_theme<R: Runtime>(
+    window: Window<R>,
+    overrides: State<'_, ThemeOverrides>,
+    label: Option<String>,
+    value: Option<Theme>,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    apply(&window, value)?;
+
+    let mut overrides = overrides.0.lock().unwrap();
+    let effective = match value {
+        Some(theme) => {
+            overrides.insert(window.label().into(), theme);
+            theme
+        }
+        None => {
+            overrides.remove(window.label());
+            window.theme()?
+        }
+    };
+    drop(overrides);
+
+    window.emit(THEME_CHANGED_EVENT, effective)?;
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn apply<R: Runtime>(window: &Window<R>, theme: Option<Theme>) -> Result<()> {
+    use cocoa::{
+        base::{id, nil},
+        foundation::NSString,
+    };
+    use objc::*;
+
+    let ns_window = window.ns_window()? as usize;
+    window.run_on_main_thread(move || {
+        // Safety: objc runtime calls are unsafe
+        unsafe {
+            let appearance: id = match theme {
+                Some(theme) => {
+                    let name = NSString::alloc(nil).init_str(match theme {
+                        Theme::Dark => "NSAppearanceNameDarkAqua",
+                        _ => "NSAppearanceNameAqua",
+                    });
+                    let appearance: id = msg_send![class!(NSAppearance), appearanceNamed: name];
+                    let _: () = msg_send![name, release];
+                    appearance
+                }
+                // a nil appearance inherits the application's, i.e. the system theme
+                None => nil,
+            };
+            let _: () = msg_send![ns_window as id, setAppearance: appearance];
+        }
+    })?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn apply<R: Runtime>(window: &Window<R>, theme: Option<Theme>) -> Result<()> {
+    use std::{ffi::c_void, mem};
+    use windows_sys::Win32::{
+        Foundation::BOOL,
+        Graphics::Dwm::{DwmSetWindowAttribute, DWMWA_USE_IMMERSIVE_DARK_MODE},
+    };
+
+    // without an override the window goes back to the theme the runtime tracks for it
+    let theme = match theme {
+        Some(theme) => theme,
+        None => window.theme()?,
+    };
+    let dark = BOOL::from(theme == Theme::Dark);
+    unsafe {
+        DwmSetWindowAttribute(
+            window.hwnd()?.0,
+            DWMWA_USE_IMMERSIVE_DARK_MODE,
+            &dark as *const BOOL as *const c_void,
+            mem::size_of::<BOOL>() as u32,
+        )
+    };
+    Ok(())
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+))]
+fn apply<R: Runtime>(window: &Window<R>, theme: Option<Theme>) -> Result<()> {
+    use gtk::prelude::*;
+    use std::cell::Cell;
+
+    const PREFER_DARK: &str = "gtk-application-prefer-dark-theme";
+
+    thread_local! {
+        /// The value of the setting before the first override.
+        static SYSTEM_PREFERS_DARK: Cell<Option<bool>> = Cell::new(None);
+    }
+
+    window.run_on_main_thread(move || {
+        let Some(settings) = gtk::Settings::default() else {
+            return;
+        };
+        SYSTEM_PREFERS_DARK.with(|system| {
+            let system = match system.get() {
+                Some(value) => value,
+                None => {
+                    let value = settings.property::<bool>(PREFER_DARK);
+                    system.set(Some(value));
+                    value
+                }
+            };
+            let dark = theme.map(|theme| theme == Theme::Dark).unwrap_or(system);
+            settings.set_property(PREFER_DARK, dark);
+        });
+    })?;
+    Ok(())
+}
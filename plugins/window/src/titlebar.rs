@@ -0,0 +1,146 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The action of a double click on the title bar, as configured in the system settings.
+//!
+//! Custom title bars do not get the behavior of the native ones, so the drag regions ask the
+//! system what a double click does and perform it themselves.
+
+use serde::Serialize;
+use tauri::{Runtime, Window};
+
+use crate::desktop_commands::{get_window, Result};
+
+/// What double-clicking the title bar of a window does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TitleBarDoubleClickAction {
+    /// Toggles the maximized state of the window.
+    Maximize,
+    /// Minimizes the window.
+    Minimize,
+    /// Does nothing.
+    None,
+}
+
+/// The action configured in the system settings.
+#[cfg(target_os = "macos")]
+fn system_action<R: Runtime>(_window: &Window<R>) -> TitleBarDoubleClickAction {
+    use cocoa::{
+        base::{id, nil, BOOL, NO},
+        foundation::NSString,
+    };
+    use objc::*;
+    use std::ffi::CStr;
+
+    // Safety: objc runtime calls are unsafe, `NSUserDefaults` is thread-safe
+    unsafe {
+        let defaults: id = msg_send![class!(NSUserDefaults), standardUserDefaults];
+        let key = NSString::alloc(nil).init_str("AppleActionOnDoubleClick");
+        let value: id = msg_send![defaults, stringForKey: key];
+        let _: () = msg_send![key, release];
+        if value != nil {
+            let value = CStr::from_ptr(value.UTF8String()).to_string_lossy();
+            return match value.as_ref() {
+                "Minimize" => TitleBarDoubleClickAction::Minimize,
+                "None" => TitleBarDoubleClickAction::None,
+                _ => TitleBarDoubleClickAction::Maximize,
+            };
+        }
+
+        // the setting of macOS 10.x
+        let key = NSString::alloc(nil).init_str("AppleMiniaturizeOnDoubleClick");
+        let minimize: BOOL = msg_send![defaults, boolForKey: key];
+        let _: () = msg_send![key, release];
+        if minimize != NO {
+            TitleBarDoubleClickAction::Minimize
+        } else {
+            TitleBarDoubleClickAction::Maximize
+        }
+    }
+}
+
+/// The action configured in the system settings.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+))]
+fn system_action<R: Runtime>(window: &Window<R>) -> TitleBarDoubleClickAction {
+    use gtk::prelude::*;
+    use std::sync::mpsc::channel;
+
+    let (tx, rx) = channel();
+    let read = window.run_on_main_thread(move || {
+        let value = gtk::Settings::default()
+            .map(|settings| settings.property::<Option<String>>("gtk-titlebar-double-click"));
+        let _ = tx.send(value.flatten());
+    });
+    let value = read.ok().and_then(|_| rx.recv().ok()).flatten();
+
+    match value.as_deref() {
+        Some("minimize") => TitleBarDoubleClickAction::Minimize,
+        // `lower` and `menu` can not be performed from a custom title bar
+        Some("none" | "lower" | "menu") => TitleBarDoubleClickAction::None,
+        _ => TitleBarDoubleClickAction::Maximize,
+    }
+}
+
+/// The action configured in the system settings.
+///
+/// Windows has no such setting, its title bars maximize on double click.
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+)))]
+fn system_action<R: Runtime>(_window: &Window<R>) -> TitleBarDoubleClickAction {
+    TitleBarDoubleClickAction::Maximize
+}
+
+/// The action the system performs when the title bar is double-clicked.
+///
+/// ## Platform-specific
+///
+/// - **Windows:** Always `maximize`.
+/// - **Linux:** Read from the `gtk-titlebar-double-click` setting, `maximize` for the actions
+///   other than minimizing or doing nothing.
+#[tauri::command]
+pub async fn title_bar_double_click_action<R: Runtime>(
+    window: Window<R>,
+    label: Option<String>,
+) -> Result<TitleBarDoubleClickAction> {
+    let window = get_window(window, label)?;
+    Ok(system_action(&window))
+}
+
+/// Performs the action of a double click on the title bar, returning it.
+///
+/// A window that is not resizable is not maximized.
+#[tauri::command]
+pub async fn perform_title_bar_double_click<R: Runtime>(
+    window: Window<R>,
+    label: Option<String>,
+) -> Result<TitleBarDoubleClickAction> {
+    let window = get_window(window, label)?;
+    let action = system_action(&window);
+    match action {
+        TitleBarDoubleClickAction::Maximize => {
+            if window.is_resizable()? {
+                match window.is_maximized()? {
+                    true => window.unmaximize()?,
+                    false => window.maximize()?,
+                }
+            }
+        }
+        TitleBarDoubleClickAction::Minimize => window.minimize()?,
+        TitleBarDoubleClickAction::None => {}
+    }
+    Ok(action)
+}
@@ -0,0 +1,138 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The scroll position and session storage of a webview, captured to restore them after a
+//! reload or in a recreated window.
+//!
+//! The webview reports its state when asked through the init script. A restored state is kept
+//! until a loaded document of the window takes it, so a document that is still loading applies
+//! it once its `load` event fired.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, State, Window};
+use tokio::sync::oneshot;
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use crate::desktop_commands::{get_window, Error, Result};
+
+/// How long a webview has to report its state.
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The state of a webview document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebviewState {
+    /// The scroll position of the document, in CSS pixels.
+    pub scroll_x: f64,
+    pub scroll_y: f64,
+    /// The entries of `sessionStorage`, if captured.
+    pub session_storage: Option<HashMap<String, String>>,
+}
+
+/// The pending captures and the states waiting for a window to load, indexed by label.
+#[derive(Default)]
+pub struct WebviewStates {
+    next_id: AtomicU32,
+    captures: Mutex<HashMap<u32, oneshot::Sender<WebviewState>>>,
+    pending: Mutex<HashMap<String, WebviewState>>,
+}
+
+impl WebviewStates {
+    pub fn remove(&self, label: &str) {
+        self.pending.lock().unwrap().remove(label);
+    }
+}
+
+/// Captures the scroll position of the document and, with `session_storage`, its
+/// `sessionStorage` entries.
+#[tauri::command]
+pub async fn capture_state<R: Runtime>(
+    window: Window<R>,
+    states: State<'_, WebviewStates>,
+    label: Option<String>,
+    session_storage: Option<bool>,
+) -> Result<WebviewState> {
+    let window = get_window(window, label)?;
+    let id = states.next_id.fetch_add(1, Ordering::Relaxed);
+    let (tx, rx) = oneshot::channel();
+    states.captures.lock().unwrap().insert(id, tx);
+
+    let script = format!(
+        "window.__TAURI_WEBVIEW_STATE__?.capture({id}, {})",
+        session_storage.unwrap_or_default()
+    );
+    if let Err(e) = window.eval(&script) {
+        states.captures.lock().unwrap().remove(&id);
+        return Err(e.into());
+    }
+    let state = tokio::time::timeout(CAPTURE_TIMEOUT, rx).await;
+    states.captures.lock().unwrap().remove(&id);
+    match state {
+        Ok(Ok(state)) => Ok(state),
+        _ => Err(Error::WebviewStateUnavailable),
+    }
+}
+
+/// Restores a captured state once the document is loaded.
+///
+/// With `reload`, the `sessionStorage` entries are restored before reloading the document,
+/// so its scripts read them on startup, and the scroll position once the new document loaded.
+#[tauri::command]
+pub async fn restore_state<R: Runtime>(
+    window: Window<R>,
+    states: State<'_, WebviewStates>,
+    label: Option<String>,
+    state: WebviewState,
+    reload: Option<bool>,
+) -> Result<()> {
+    let window = get_window(window, label)?;
+    states
+        .pending
+        .lock()
+        .unwrap()
+        .insert(window.label().into(), state);
+    // a document that is not loaded yet takes the state from its init script
+    let script = if reload.unwrap_or_default() {
+        "window.__TAURI_WEBVIEW_STATE__?.reload()"
+    } else {
+        "window.__TAURI_WEBVIEW_STATE__?.restore()"
+    };
+    window.eval(script)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn internal_report_state(
+    states: State<'_, WebviewStates>,
+    id: u32,
+    state: WebviewState,
+) -> Result<()> {
+    if let Some(tx) = states.captures.lock().unwrap().remove(&id) {
+        let _ = tx.send(state);
+    }
+    Ok(())
+}
+
+/// The state waiting for the document of the window, removed with `take`.
+#[tauri::command]
+pub async fn internal_pending_state<R: Runtime>(
+    window: Window<R>,
+    states: State<'_, WebviewStates>,
+    take: bool,
+) -> Result<Option<WebviewState>> {
+    let mut pending = states.pending.lock().unwrap();
+    Ok(if take {
+        pending.remove(window.label())
+    } else {
+        pending.get(window.label()).cloned()
+    })
+}